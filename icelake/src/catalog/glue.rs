@@ -0,0 +1,471 @@
+//! A catalog of tables registered in the AWS Glue Data Catalog.
+//!
+//! Namespaces map to Glue databases, and every Iceberg table is a Glue table whose
+//! `metadata_location` parameter points to the table's current metadata file.
+//! Commits write a new metadata file and then update the Glue table, guarded by the
+//! version of the Glue table that was read, so concurrent commits can't overwrite
+//! each other.
+//!
+//! Access to Glue goes through the [`GlueClient`] trait, which is implemented for
+//! the AWS SDK client when the `glue` feature is enabled.
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use bytes::Bytes;
+use async_trait::async_trait;
+
+use crate::{
+    IcebergError, IcebergResult, IcebergTable, IcebergTableMetadata, NotFoundKind
+};
+use crate::iceberg::delete_metadata_files;
+use crate::schema::Schema;
+use crate::partition::PartitionSpec;
+use crate::sort::SortOrder;
+use crate::storage::IcebergStorage;
+use crate::catalog::{Catalog, Namespace, TableIdentifier, TableCommit};
+
+/// Glue table parameter holding the location of the current metadata file.
+pub const METADATA_LOCATION_PARAM: &str = "metadata_location";
+/// Glue table parameter holding the location of the previous metadata file.
+pub const PREVIOUS_METADATA_LOCATION_PARAM: &str = "previous_metadata_location";
+/// Glue table parameter identifying the table's format.
+pub const TABLE_TYPE_PARAM: &str = "table_type";
+const ICEBERG_TABLE_TYPE: &str = "ICEBERG";
+
+/// A table registered in Glue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlueTable {
+    pub name: String,
+    pub parameters: HashMap<String, String>,
+    /// Version of the Glue table, changing whenever the table is updated.
+    pub version_id: Option<String>,
+}
+
+/// The Glue operations used by [`GlueCatalog`].
+#[async_trait]
+pub trait GlueClient: Send + Sync {
+    /// Returns the names of all databases.
+    async fn get_databases(&self) -> IcebergResult<Vec<String>>;
+
+    /// Returns the table, or `None` if it does not exist.
+    async fn get_table(
+        &self,
+        database: &str,
+        name: &str
+    ) -> IcebergResult<Option<GlueTable>>;
+
+    /// Creates a table, failing with [`IcebergError::TableAlreadyExists`] if it
+    /// exists.
+    async fn create_table(&self, database: &str, table: GlueTable) -> IcebergResult<()>;
+
+    /// Updates a table only if its current version is `table.version_id`, failing
+    /// with [`IcebergError::CommitConflict`] otherwise.
+    async fn update_table(&self, database: &str, table: GlueTable) -> IcebergResult<()>;
+
+    async fn delete_table(&self, database: &str, name: &str) -> IcebergResult<()>;
+}
+
+/// An Iceberg catalog backed by the AWS Glue Data Catalog.
+pub struct GlueCatalog {
+    client: Arc<dyn GlueClient>,
+    warehouse: String,
+    storage_options: HashMap<String, String>,
+}
+
+impl GlueCatalog {
+    /// Creates a catalog using the given Glue client.
+    ///
+    /// New tables are created under the `warehouse` location, and `storage_options`
+    /// are used to access the tables' storage. See [`IcebergStorage::from_url()`].
+    pub fn new(
+        client: Arc<dyn GlueClient>,
+        warehouse: &str,
+        storage_options: HashMap<String, String>
+    ) -> Self {
+        Self {
+            client: client,
+            warehouse: warehouse.trim_end_matches('/').to_string(),
+            storage_options: storage_options,
+        }
+    }
+
+    /// Creates a catalog using an AWS SDK Glue client configured from the
+    /// environment.
+    #[cfg(feature = "glue")]
+    pub async fn from_env(
+        warehouse: &str,
+        storage_options: HashMap<String, String>
+    ) -> Self {
+        let config = aws_config::load_from_env().await;
+        let client = aws_sdk_glue::Client::new(&config);
+
+        Self::new(Arc::new(client), warehouse, storage_options)
+    }
+
+    /// Returns the location of new tables: `{warehouse}/{database}.db/{table}`.
+    pub fn default_table_location(
+        &self,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<String> {
+        Ok(format!(
+            "{}/{}.db/{}",
+            self.warehouse,
+            database(&identifier.namespace)?,
+            identifier.name
+        ))
+    }
+
+    /// Returns the Glue table of an Iceberg table and the location of its current
+    /// metadata file.
+    async fn get_iceberg_table(
+        &self,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<(GlueTable, String)> {
+        let table = self.client
+            .get_table(database(&identifier.namespace)?, &identifier.name)
+            .await?
+            .ok_or_else(|| IcebergError::NotFound {
+                kind: NotFoundKind::Table,
+                identifier: identifier.to_string(),
+            })?;
+
+        let metadata_location = table.parameters.get(METADATA_LOCATION_PARAM)
+            .cloned()
+            .ok_or_else(|| IcebergError::CatalogError {
+                message: format!("table {} is not an Iceberg table", identifier)
+            })?;
+
+        Ok((table, metadata_location))
+    }
+
+    fn storage(&self, location: &str) -> IcebergResult<IcebergStorage> {
+        IcebergStorage::from_url(location, self.storage_options.clone())
+    }
+
+    async fn read_metadata(&self, metadata_location: &str) -> IcebergResult<IcebergTable> {
+        let storage = self.storage(table_location(metadata_location)?)?;
+        let bytes = storage.get(&storage.create_path_from_url(metadata_location)?).await?;
+
+        let metadata = serde_json::from_slice::<IcebergTableMetadata>(&bytes)
+            .map_err(|e| IcebergError::InvalidMetadata { source: e })?;
+
+        IcebergTable::from_metadata(Arc::new(storage), metadata, metadata_location)
+    }
+
+    /// Writes a new metadata file for the table, numbered after the previous one.
+    async fn write_metadata(
+        &self,
+        metadata: IcebergTableMetadata,
+        previous_location: Option<&str>,
+    ) -> IcebergResult<IcebergTable> {
+        let version = previous_location
+            .and_then(|location| location.rsplit('/').next())
+            .and_then(|filename| filename.split('-').next())
+            .and_then(|version| version.parse::<u32>().ok())
+            .map_or(0, |version| version + 1);
+
+        let json = metadata.to_json()?;
+
+        let storage = Arc::new(self.storage(&metadata.location)?);
+        let file = IcebergTable::new(storage.clone()).new_metadata_file(
+            &format!("{:05}-{}.metadata.json", version, Uuid::new_v4()),
+            Bytes::from(json)
+        )?;
+        file.save().await?;
+
+        IcebergTable::from_metadata(storage, metadata, &file.url())
+    }
+}
+
+/// Returns the Glue database of a namespace, which must have a single level.
+fn database(namespace: &Namespace) -> IcebergResult<&str> {
+    match namespace.levels() {
+        [database] => Ok(database),
+        _ => Err(IcebergError::CatalogError {
+            message: format!("invalid Glue database name: '{}'", namespace)
+        })
+    }
+}
+
+/// Returns the location of a table from the location of one of its metadata files.
+fn table_location(metadata_location: &str) -> IcebergResult<&str> {
+    metadata_location.rsplit_once('/')
+        .and_then(|(directory, _)| directory.strip_suffix("/metadata"))
+        .ok_or_else(|| IcebergError::CatalogError {
+            message: format!("invalid metadata location {}", metadata_location)
+        })
+}
+
+#[async_trait]
+impl Catalog for GlueCatalog {
+    /// Lists the Glue databases. Glue databases can't be nested, so there are no
+    /// namespaces under any parent.
+    async fn list_namespaces(
+        &self,
+        parent: Option<&Namespace>
+    ) -> IcebergResult<Vec<Namespace>> {
+        if parent.map_or(false, |parent| !parent.is_empty()) {
+            return Ok(Vec::new());
+        }
+
+        Ok(self.client.get_databases().await?
+            .iter()
+            .map(|database| Namespace::new(&[database]))
+            .collect())
+    }
+
+    async fn create_table(
+        &self,
+        identifier: &TableIdentifier,
+        schema: Schema,
+        partition_spec: Option<PartitionSpec>,
+        sort_order: Option<SortOrder>,
+        properties: Option<HashMap<String, String>>,
+    ) -> IcebergResult<IcebergTable> {
+        let database = database(&identifier.namespace)?;
+        if self.client.get_table(database, &identifier.name).await?.is_some() {
+            return Err(IcebergError::TableAlreadyExists(identifier.to_string()));
+        }
+
+        let metadata = IcebergTableMetadata::try_new(
+            self.default_table_location(identifier)?,
+            schema,
+            partition_spec,
+            properties
+        )?.with_sort_order(sort_order.unwrap_or_else(SortOrder::new))?;
+        let table = self.write_metadata(metadata, None).await?;
+
+        self.client.create_table(database, GlueTable {
+            name: identifier.name.clone(),
+            parameters: HashMap::from([
+                (TABLE_TYPE_PARAM.to_string(), ICEBERG_TABLE_TYPE.to_string()),
+                (METADATA_LOCATION_PARAM.to_string(), table.current_metadata_uri()?),
+            ]),
+            version_id: None,
+        }).await?;
+
+        Ok(table)
+    }
+
+    async fn load_table(&self, identifier: &TableIdentifier) -> IcebergResult<IcebergTable> {
+        let (_, metadata_location) = self.get_iceberg_table(identifier).await?;
+
+        self.read_metadata(&metadata_location).await
+    }
+
+    /// Returns the metadata location registered in the table's Glue parameters.
+    async fn load_metadata_location(
+        &self,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<String> {
+        let (_, metadata_location) = self.get_iceberg_table(identifier).await?;
+
+        Ok(metadata_location)
+    }
+
+    /// Drops the table from Glue. If `purge` is set, all files under the table's
+    /// location are deleted as well.
+    async fn drop_table(&self, identifier: &TableIdentifier, purge: bool) -> IcebergResult<()> {
+        let (_, metadata_location) = self.get_iceberg_table(identifier).await?;
+
+        self.client
+            .delete_table(database(&identifier.namespace)?, &identifier.name)
+            .await?;
+
+        if purge {
+            let storage = self.storage(table_location(&metadata_location)?)?;
+            for object in storage.list(None).await? {
+                storage.delete(&object.location).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renames the table by registering it under the new name and removing the old
+    /// name. The table's files are not moved.
+    async fn rename_table(
+        &self,
+        source: &TableIdentifier,
+        destination: &TableIdentifier
+    ) -> IcebergResult<()> {
+        let (table, _) = self.get_iceberg_table(source).await?;
+
+        self.client.create_table(database(&destination.namespace)?, GlueTable {
+            name: destination.name.clone(),
+            parameters: table.parameters,
+            version_id: None,
+        }).await?;
+
+        self.client.delete_table(database(&source.namespace)?, &source.name).await
+    }
+
+    async fn commit_transaction(&self, commit: TableCommit) -> IcebergResult<IcebergTable> {
+        let identifier = &commit.identifier;
+        let (glue_table, base_location) = self.get_iceberg_table(identifier).await?;
+        let base_table = self.read_metadata(&base_location).await?;
+        let base = base_table.current_metadata()?;
+
+        let mut metadata = commit.apply(base)?;
+        let expired_metadata_files = metadata.add_previous_metadata_file(
+            &base_location,
+            base.last_updated_ms
+        )?;
+
+        let table = self.write_metadata(metadata, Some(&base_location)).await?;
+        let metadata_location = table.current_metadata_uri()?;
+
+        let mut parameters = glue_table.parameters;
+        parameters.insert(METADATA_LOCATION_PARAM.to_string(), metadata_location);
+        parameters.insert(PREVIOUS_METADATA_LOCATION_PARAM.to_string(), base_location);
+
+        // Fails if the Glue table changed since it was read, e.g. by a concurrent
+        // commit pointing it to another metadata file.
+        let updated_table = GlueTable {
+            name: glue_table.name,
+            parameters: parameters,
+            version_id: glue_table.version_id,
+        };
+        let result = self.client
+            .update_table(database(&identifier.namespace)?, updated_table)
+            .await;
+
+        if let Err(err) = result {
+            // The new metadata file was never referenced by the table.
+            let storage = table.storage();
+            let path = storage.create_path_from_url(&table.current_metadata_uri()?)?;
+            storage.delete(&path).await?;
+            return Err(err);
+        }
+
+        delete_metadata_files(&table.storage(), &expired_metadata_files).await;
+
+        Ok(table)
+    }
+}
+
+#[cfg(feature = "glue")]
+mod sdk {
+    use async_trait::async_trait;
+    use aws_sdk_glue::Client;
+    use aws_sdk_glue::types::TableInput;
+
+    use crate::{IcebergError, IcebergResult};
+    use crate::catalog::glue::{GlueClient, GlueTable};
+
+    fn glue_error(err: impl std::fmt::Display) -> IcebergError {
+        IcebergError::CatalogError { message: format!("Glue error: {}", err) }
+    }
+
+    fn table_input(table: GlueTable) -> TableInput {
+        TableInput::builder()
+            .name(table.name)
+            .table_type("EXTERNAL_TABLE")
+            .set_parameters(Some(table.parameters))
+            .build()
+    }
+
+    #[async_trait]
+    impl GlueClient for Client {
+        async fn get_databases(&self) -> IcebergResult<Vec<String>> {
+            let mut databases = Vec::new();
+            let mut next_token = None;
+            loop {
+                let output = self.get_databases()
+                    .set_next_token(next_token)
+                    .send()
+                    .await
+                    .map_err(glue_error)?;
+
+                databases.extend(
+                    output.database_list().unwrap_or_default()
+                        .iter()
+                        .filter_map(|database| database.name().map(str::to_string))
+                );
+
+                next_token = output.next_token().map(str::to_string);
+                if next_token.is_none() {
+                    return Ok(databases);
+                }
+            }
+        }
+
+        async fn get_table(
+            &self,
+            database: &str,
+            name: &str
+        ) -> IcebergResult<Option<GlueTable>> {
+            let result = self.get_table()
+                .database_name(database)
+                .name(name)
+                .send()
+                .await;
+
+            let output = match result {
+                Ok(output) => output,
+                Err(err) if err.as_service_error()
+                    .map_or(false, |err| err.is_entity_not_found_exception()) => {
+                    return Ok(None);
+                },
+                Err(err) => return Err(glue_error(err)),
+            };
+
+            Ok(output.table().map(|table| GlueTable {
+                name: table.name().unwrap_or(name).to_string(),
+                parameters: table.parameters().cloned().unwrap_or_default(),
+                version_id: table.version_id().map(str::to_string),
+            }))
+        }
+
+        async fn create_table(&self, database: &str, table: GlueTable) -> IcebergResult<()> {
+            let name = table.name.clone();
+            let result = self.create_table()
+                .database_name(database)
+                .table_input(table_input(table))
+                .send()
+                .await;
+
+            match result {
+                Ok(..) => Ok(()),
+                Err(err) if err.as_service_error()
+                    .map_or(false, |err| err.is_already_exists_exception()) => {
+                    Err(IcebergError::TableAlreadyExists(format!("{database}.{name}")))
+                },
+                Err(err) => Err(glue_error(err)),
+            }
+        }
+
+        async fn update_table(&self, database: &str, table: GlueTable) -> IcebergResult<()> {
+            let version_id = table.version_id.clone();
+            let result = self.update_table()
+                .database_name(database)
+                .set_version_id(version_id)
+                .table_input(table_input(table))
+                .send()
+                .await;
+
+            match result {
+                Ok(..) => Ok(()),
+                Err(err) if err.as_service_error()
+                    .map_or(false, |err| err.is_concurrent_modification_exception()) => {
+                    Err(IcebergError::CommitConflict(format!(
+                        "Glue table in database {} was changed concurrently", database
+                    )))
+                },
+                Err(err) => Err(glue_error(err)),
+            }
+        }
+
+        async fn delete_table(&self, database: &str, name: &str) -> IcebergResult<()> {
+            self.delete_table()
+                .database_name(database)
+                .name(name)
+                .send()
+                .await
+                .map_err(glue_error)?;
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,360 @@
+//! A catalog of tables stored in directories under a warehouse location.
+//!
+//! Tables are located at `{warehouse}/{namespace levels...}/{table name}`, and their
+//! metadata files are named `metadata/v{N}.metadata.json` with increasing versions.
+//! The current version is recorded in `metadata/version-hint.text`.
+//!
+//! Commits create the next metadata version only if no other writer created it
+//! first, which requires an object store supporting atomic renames without
+//! overwriting, like the local file system or HDFS.
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use bytes::Bytes;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use object_store::Error as ObjectStoreError;
+use regex::Regex;
+
+use crate::{
+    IcebergError, IcebergResult, IcebergTable, IcebergTableMetadata, NotFoundKind
+};
+use crate::iceberg::delete_metadata_files;
+use crate::schema::Schema;
+use crate::partition::PartitionSpec;
+use crate::sort::SortOrder;
+use crate::storage::{IcebergStorage, IcebergPath};
+use crate::catalog::{Catalog, Namespace, TableIdentifier, TableCommit};
+
+const VERSION_HINT_FILE: &str = "version-hint.text";
+
+/// A catalog that derives the locations of tables from a warehouse location.
+pub struct HadoopCatalog {
+    storage: IcebergStorage,
+    storage_options: HashMap<String, String>,
+}
+
+impl HadoopCatalog {
+    /// Creates a catalog of the tables under the given warehouse location.
+    ///
+    /// `storage_options` are used to access the warehouse and its tables. See
+    /// [`IcebergStorage::from_url()`].
+    pub fn new(
+        warehouse: &str,
+        storage_options: HashMap<String, String>
+    ) -> IcebergResult<Self> {
+        Ok(Self {
+            storage: IcebergStorage::from_url(warehouse, storage_options.clone())?,
+            storage_options: storage_options,
+        })
+    }
+
+    /// Returns the location of the warehouse.
+    pub fn warehouse(&self) -> &str {
+        self.storage.location()
+    }
+
+    /// Returns the location of the given table.
+    pub fn table_location(&self, identifier: &TableIdentifier) -> String {
+        let mut location = self.warehouse().to_string();
+        for part in identifier.namespace.levels().iter().chain([&identifier.name]) {
+            location.push('/');
+            location.push_str(part);
+        }
+
+        location
+    }
+
+    /// Returns the path of a file under the table's location, relative to the
+    /// warehouse.
+    fn table_path(&self, identifier: &TableIdentifier, parts: &[&str]) -> IcebergPath {
+        identifier.namespace.levels().iter()
+            .map(|level| level.as_str())
+            .chain([identifier.name.as_str()])
+            .chain(parts.iter().copied())
+            .collect()
+    }
+
+    fn metadata_path(&self, identifier: &TableIdentifier, filename: &str) -> IcebergPath {
+        self.table_path(identifier, &["metadata", filename])
+    }
+
+    fn version_path(&self, identifier: &TableIdentifier, version: i32) -> IcebergPath {
+        self.metadata_path(identifier, &format!("v{}.metadata.json", version))
+    }
+
+    /// Returns the current metadata version of the table, or `None` if the table
+    /// does not exist.
+    ///
+    /// The version hint may be behind if a writer failed after creating a new
+    /// metadata version, so newer versions are looked for as well.
+    async fn current_version(
+        &self,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<Option<i32>> {
+        let hint_path = self.metadata_path(identifier, VERSION_HINT_FILE);
+        let mut version = match self.storage.get(&hint_path).await {
+            Ok(bytes) => {
+                let hint = String::from_utf8_lossy(&bytes);
+                let version = hint.trim().parse::<i32>().map_err(|_| {
+                    IcebergError::CatalogError {
+                        message: format!("invalid version hint for {identifier}: {hint}")
+                    }
+                })?;
+                Some(version)
+            },
+            Err(IcebergError::ObjectStore { source: ObjectStoreError::NotFound { .. } }) => {
+                self.list_versions(identifier).await?.into_iter().max()
+            },
+            Err(err) => return Err(err),
+        };
+
+        while let Some(current) = version {
+            if !self.storage.exists(&self.version_path(identifier, current + 1)).await? {
+                break;
+            }
+            version = Some(current + 1);
+        }
+
+        Ok(version)
+    }
+
+    /// Lists the versions of all metadata files of the table.
+    async fn list_versions(
+        &self,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<Vec<i32>> {
+        lazy_static! {
+            static ref VERSION_FILE_REGEX: Regex =
+                Regex::new(r#"^v([0-9]+)\.metadata\.json$"#).unwrap();
+        }
+
+        let objects = self.storage
+            .list(Some(&self.table_path(identifier, &["metadata"])))
+            .await?;
+
+        Ok(objects.iter()
+            .filter_map(|object| object.location.filename())
+            .filter_map(|filename| VERSION_FILE_REGEX.captures(filename))
+            .filter_map(|captures| captures[1].parse().ok())
+            .collect())
+    }
+
+    async fn read_metadata(
+        &self,
+        identifier: &TableIdentifier,
+        version: i32
+    ) -> IcebergResult<IcebergTableMetadata> {
+        let bytes = self.storage.get(&self.version_path(identifier, version)).await?;
+
+        serde_json::from_slice(&bytes)
+            .map_err(|e| IcebergError::InvalidMetadata { source: e })
+    }
+
+    /// Writes the table's metadata as the version following `base_version`, and
+    /// points the version hint to it.
+    ///
+    /// Fails with [`IcebergError::CommitConflict`] if the current version is no
+    /// longer `base_version`.
+    async fn write_metadata(
+        &self,
+        identifier: &TableIdentifier,
+        base_version: Option<i32>,
+        metadata: IcebergTableMetadata,
+    ) -> IcebergResult<IcebergTable> {
+        let version = base_version.unwrap_or(0) + 1;
+
+        let json = metadata.to_json()?;
+
+        // Write to a temporary file first, then move it in place only if no other
+        // writer created the same version in the meantime.
+        let temp_path = self.metadata_path(
+            identifier,
+            &format!("{}.metadata.json", Uuid::new_v4())
+        );
+        self.storage.put(&temp_path, Bytes::from(json)).await?;
+
+        let conflict = || IcebergError::CommitConflict(format!(
+            "table {} was changed by another writer", identifier
+        ));
+
+        if self.current_version(identifier).await? != base_version {
+            self.storage.delete(&temp_path).await?;
+            return Err(conflict());
+        }
+
+        let version_path = self.version_path(identifier, version);
+        let result = self.storage.rename_if_not_exists(&temp_path, &version_path).await;
+        if let Err(err) = result {
+            self.storage.delete(&temp_path).await?;
+            return match err {
+                IcebergError::ObjectStore {
+                    source: ObjectStoreError::AlreadyExists { .. }
+                } => Err(conflict()),
+                err => Err(err),
+            };
+        }
+
+        // Replace the version hint atomically. Readers look for versions beyond the
+        // hint, so failing here leaves the table consistent.
+        let hint_path = self.metadata_path(identifier, VERSION_HINT_FILE);
+        let temp_hint_path = self.metadata_path(
+            identifier,
+            &format!("{}-{}", Uuid::new_v4(), VERSION_HINT_FILE)
+        );
+        self.storage.put(&temp_hint_path, Bytes::from(version.to_string())).await?;
+        self.storage.rename(&temp_hint_path, &hint_path).await?;
+
+        self.table(identifier, version, metadata)
+    }
+
+    fn table(
+        &self,
+        identifier: &TableIdentifier,
+        version: i32,
+        metadata: IcebergTableMetadata
+    ) -> IcebergResult<IcebergTable> {
+        let storage = IcebergStorage::from_url(
+            &metadata.location,
+            self.storage_options.clone()
+        )?;
+        let metadata_location = self.storage.to_uri(
+            &self.version_path(identifier, version)
+        );
+
+        IcebergTable::from_metadata(Arc::new(storage), metadata, &metadata_location)
+    }
+}
+
+#[async_trait]
+impl Catalog for HadoopCatalog {
+    /// Lists the directories under the namespace that aren't tables.
+    async fn list_namespaces(
+        &self,
+        parent: Option<&Namespace>
+    ) -> IcebergResult<Vec<Namespace>> {
+        let parent = parent.cloned().unwrap_or(Namespace::new(&[]));
+        let parent_path: Option<IcebergPath> = match parent.is_empty() {
+            true => None,
+            false => Some(parent.levels().iter().map(|level| level.as_str()).collect()),
+        };
+
+        let mut namespaces = Vec::new();
+        for directory in self.storage.list_directories(parent_path.as_ref()).await? {
+            let name = match directory.filename() {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+
+            // Directories of tables are not namespaces.
+            let table = TableIdentifier::new(parent.clone(), &name);
+            if self.current_version(&table).await?.is_none() {
+                let mut levels = parent.levels().to_vec();
+                levels.push(name);
+                namespaces.push(Namespace { levels: levels });
+            }
+        }
+
+        Ok(namespaces)
+    }
+
+    async fn create_table(
+        &self,
+        identifier: &TableIdentifier,
+        schema: Schema,
+        partition_spec: Option<PartitionSpec>,
+        sort_order: Option<SortOrder>,
+        properties: Option<HashMap<String, String>>,
+    ) -> IcebergResult<IcebergTable> {
+        if self.current_version(identifier).await?.is_some() {
+            return Err(IcebergError::TableAlreadyExists(
+                self.table_location(identifier)
+            ));
+        }
+
+        let metadata = IcebergTableMetadata::try_new(
+            self.table_location(identifier),
+            schema,
+            partition_spec,
+            properties
+        )?.with_sort_order(sort_order.unwrap_or_else(SortOrder::new))?;
+
+        self.write_metadata(identifier, None, metadata).await
+    }
+
+    async fn load_table(&self, identifier: &TableIdentifier) -> IcebergResult<IcebergTable> {
+        let version = self.current_version(identifier).await?
+            .ok_or_else(|| table_not_found(identifier))?;
+
+        let metadata = self.read_metadata(identifier, version).await?;
+
+        self.table(identifier, version, metadata)
+    }
+
+    /// Finds the current metadata file from the version hint, without reading it.
+    async fn load_metadata_location(
+        &self,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<String> {
+        let version = self.current_version(identifier).await?
+            .ok_or_else(|| table_not_found(identifier))?;
+
+        Ok(self.storage.to_uri(&self.version_path(identifier, version)))
+    }
+
+    /// Drops the table by deleting its metadata files. If `purge` is set, all other
+    /// files under the table's location are deleted as well.
+    async fn drop_table(&self, identifier: &TableIdentifier, purge: bool) -> IcebergResult<()> {
+        if self.current_version(identifier).await?.is_none() {
+            return Err(table_not_found(identifier));
+        }
+
+        let path = match purge {
+            true => self.table_path(identifier, &[]),
+            false => self.table_path(identifier, &["metadata"]),
+        };
+
+        for object in self.storage.list(Some(&path)).await? {
+            self.storage.delete(&object.location).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Not supported, since the table's location is derived from its identifier.
+    async fn rename_table(
+        &self,
+        _source: &TableIdentifier,
+        _destination: &TableIdentifier
+    ) -> IcebergResult<()> {
+        Err(IcebergError::Unsupported(
+            "renaming tables in a Hadoop catalog".to_string()
+        ))
+    }
+
+    async fn commit_transaction(&self, commit: TableCommit) -> IcebergResult<IcebergTable> {
+        let identifier = &commit.identifier;
+        let version = self.current_version(identifier).await?
+            .ok_or_else(|| table_not_found(identifier))?;
+
+        let base = self.read_metadata(identifier, version).await?;
+        let mut metadata = commit.apply(&base)?;
+        let expired_metadata_files = metadata.add_previous_metadata_file(
+            &self.storage.to_uri(&self.version_path(identifier, version)),
+            base.last_updated_ms
+        )?;
+
+        let table = self.write_metadata(identifier, Some(version), metadata).await?;
+        delete_metadata_files(&self.storage, &expired_metadata_files).await;
+
+        Ok(table)
+    }
+}
+
+fn table_not_found(identifier: &TableIdentifier) -> IcebergError {
+    IcebergError::NotFound {
+        kind: NotFoundKind::Table,
+        identifier: identifier.to_string(),
+    }
+}
@@ -0,0 +1,705 @@
+//! Iceberg catalogs.
+//!
+//! A catalog tracks the current metadata file of each table it manages and is
+//! responsible for atomically swapping it when a table is committed. The
+//! [`Catalog`] trait describes the operations supported by all catalog
+//! implementations.
+use std::fmt;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Serialize, Deserialize};
+
+use crate::{
+    IcebergError, IcebergResult, IcebergTable, IcebergTableMetadata, IcebergTableVersion,
+    NotFoundKind
+};
+use crate::utils;
+use crate::schema::Schema;
+use crate::partition::{PartitionSpec, PartitionSpecModel};
+use crate::sort::SortOrder;
+use crate::snapshot::{Snapshot, SnapshotLog, SnapshotReference};
+
+pub mod rest;
+pub mod hadoop;
+pub mod glue;
+
+pub use crate::catalog::rest::{RestCatalog, RestCatalogBuilder};
+pub use crate::catalog::hadoop::HadoopCatalog;
+pub use crate::catalog::glue::GlueCatalog;
+
+/// Name of the main branch of a table, which points to its current snapshot.
+pub const MAIN_BRANCH: &str = "main";
+
+/// A multi-level namespace containing tables, e.g. `db.schema`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Hash)]
+#[serde(transparent)]
+pub struct Namespace {
+    levels: Vec<String>,
+}
+
+impl Namespace {
+    pub fn new(levels: &[&str]) -> Self {
+        Self {
+            levels: levels.iter().map(|level| level.to_string()).collect()
+        }
+    }
+
+    /// Returns the levels of this namespace, from the outermost to the innermost.
+    pub fn levels(&self) -> &[String] {
+        &self.levels
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.levels.join("."))
+    }
+}
+
+/// Identifies a table by its namespace and name.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Hash)]
+pub struct TableIdentifier {
+    pub namespace: Namespace,
+    pub name: String,
+}
+
+impl TableIdentifier {
+    pub fn new(namespace: Namespace, name: &str) -> Self {
+        Self {
+            namespace: namespace,
+            name: name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TableIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.namespace.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{}.{}", self.namespace, self.name)
+        }
+    }
+}
+
+/// A requirement that must hold for the current table metadata in order for a
+/// commit to succeed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum TableRequirement {
+    /// The table must not already exist.
+    AssertCreate,
+    /// The table's UUID must match.
+    #[serde(rename_all = "kebab-case")]
+    AssertTableUuid { uuid: String },
+    /// The reference must point to the given snapshot, or must not exist if the
+    /// snapshot id is `None`.
+    #[serde(rename_all = "kebab-case")]
+    AssertRefSnapshotId {
+        r#ref: String,
+        snapshot_id: Option<i64>,
+    },
+    /// The table's current schema id must match.
+    #[serde(rename_all = "kebab-case")]
+    AssertCurrentSchemaId { current_schema_id: i32 },
+    /// The table's last assigned column id must match.
+    #[serde(rename_all = "kebab-case")]
+    AssertLastAssignedFieldId { last_assigned_field_id: i32 },
+    /// The table's default partition spec id must match.
+    #[serde(rename_all = "kebab-case")]
+    AssertDefaultSpecId { default_spec_id: i32 },
+    /// The table's last assigned partition field id must match.
+    #[serde(rename_all = "kebab-case")]
+    AssertLastAssignedPartitionId { last_assigned_partition_id: i32 },
+}
+
+impl TableRequirement {
+    /// Checks the requirement against the table's current metadata, or `None` if
+    /// the table does not exist.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::CommitConflict`] is returned if the requirement does not hold.
+    pub fn check(&self, metadata: Option<&IcebergTableMetadata>) -> IcebergResult<()> {
+        let metadata = match (self, metadata) {
+            (TableRequirement::AssertCreate, None) => return Ok(()),
+            (TableRequirement::AssertCreate, Some(..)) => {
+                return Err(IcebergError::CommitConflict(
+                    "table already exists".to_string()
+                ));
+            },
+            (_, None) => {
+                return Err(IcebergError::CommitConflict(
+                    "table does not exist".to_string()
+                ));
+            },
+            (_, Some(metadata)) => metadata,
+        };
+
+        let holds = match self {
+            TableRequirement::AssertCreate => true,
+            TableRequirement::AssertTableUuid { uuid } => metadata.table_uuid == *uuid,
+            TableRequirement::AssertRefSnapshotId { r#ref, snapshot_id } => {
+                ref_snapshot_id(metadata, r#ref) == *snapshot_id
+            },
+            TableRequirement::AssertCurrentSchemaId { current_schema_id } => {
+                metadata.current_schema_id == *current_schema_id
+            },
+            TableRequirement::AssertLastAssignedFieldId { last_assigned_field_id } => {
+                metadata.last_column_id == *last_assigned_field_id
+            },
+            TableRequirement::AssertDefaultSpecId { default_spec_id } => {
+                metadata.default_spec_id == *default_spec_id
+            },
+            TableRequirement::AssertLastAssignedPartitionId {
+                last_assigned_partition_id
+            } => {
+                metadata.last_partition_id == *last_assigned_partition_id
+            },
+        };
+
+        match holds {
+            true => Ok(()),
+            false => Err(IcebergError::CommitConflict(
+                format!("requirement failed: {:?}", self)
+            ))
+        }
+    }
+}
+
+/// Returns the id of the snapshot the reference points to, if it exists.
+fn ref_snapshot_id(metadata: &IcebergTableMetadata, name: &str) -> Option<i64> {
+    let reference = metadata.refs.as_ref()
+        .and_then(|refs| refs.get(name))
+        .map(|reference| reference.snapshot_id);
+
+    match name {
+        // The main branch always points to the current snapshot, even if the refs
+        // map is missing.
+        MAIN_BRANCH => {
+            reference.or(metadata.current_snapshot().map(|snapshot| snapshot.snapshot_id))
+        },
+        _ => reference
+    }
+}
+
+/// A change to apply to the table metadata when committing.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum TableUpdate {
+    #[serde(rename_all = "kebab-case")]
+    UpgradeFormatVersion { format_version: i32 },
+    #[serde(rename_all = "kebab-case")]
+    AddSchema {
+        schema: Schema,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        last_column_id: Option<i32>,
+    },
+    /// Sets the current schema, `-1` refers to the last added schema.
+    #[serde(rename_all = "kebab-case")]
+    SetCurrentSchema { schema_id: i32 },
+    #[serde(rename_all = "kebab-case")]
+    AddSpec { spec: PartitionSpecModel },
+    /// Sets the default partition spec, `-1` refers to the last added spec.
+    #[serde(rename_all = "kebab-case")]
+    SetDefaultSpec { spec_id: i32 },
+    #[serde(rename_all = "kebab-case")]
+    AddSortOrder { sort_order: SortOrder },
+    /// Sets the default sort order, `-1` refers to the last added sort order.
+    #[serde(rename_all = "kebab-case")]
+    SetDefaultSortOrder { sort_order_id: i32 },
+    #[serde(rename_all = "kebab-case")]
+    AddSnapshot { snapshot: Snapshot },
+    #[serde(rename_all = "kebab-case")]
+    SetSnapshotRef {
+        ref_name: String,
+        #[serde(flatten)]
+        reference: SnapshotReference,
+    },
+    #[serde(rename_all = "kebab-case")]
+    RemoveSnapshots { snapshot_ids: Vec<i64> },
+    #[serde(rename_all = "kebab-case")]
+    SetLocation { location: String },
+    #[serde(rename_all = "kebab-case")]
+    SetProperties { updates: HashMap<String, String> },
+    #[serde(rename_all = "kebab-case")]
+    RemoveProperties { removals: Vec<String> },
+}
+
+impl TableUpdate {
+    /// Applies the update to the table metadata.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::NotFound`] is returned if the update sets a schema, partition
+    /// spec, sort order or snapshot that does not exist.
+    /// [`IcebergError::CatalogError`] is returned if the update is otherwise invalid
+    /// for the metadata, e.g. downgrading the format version.
+    pub fn apply(&self, metadata: &mut IcebergTableMetadata) -> IcebergResult<()> {
+        match self {
+            TableUpdate::UpgradeFormatVersion { format_version } => {
+                let version: IcebergTableVersion = format_version.to_string().parse()?;
+                if (version.clone() as i32) < (metadata.format_version.clone() as i32) {
+                    return Err(IcebergError::CatalogError {
+                        message: format!(
+                            "can't downgrade format version from {} to {}",
+                            metadata.format_version, version
+                        )
+                    });
+                }
+                metadata.format_version = version;
+            },
+            TableUpdate::AddSchema { schema, last_column_id } => {
+                let last_column_id = last_column_id.unwrap_or(schema.max_field_id());
+                metadata.last_column_id = metadata.last_column_id.max(last_column_id);
+                metadata.schemas.push(schema.clone());
+            },
+            TableUpdate::SetCurrentSchema { schema_id: requested_id } => {
+                // -1 refers to the schema added last.
+                let schema_id = match *requested_id {
+                    -1 => metadata.schemas.last().map(|schema| schema.id()),
+                    schema_id => metadata.schema_by_id(schema_id).map(|schema| schema.id()),
+                };
+                metadata.current_schema_id = schema_id.ok_or_else(|| {
+                    IcebergError::NotFound {
+                        kind: NotFoundKind::Schema,
+                        identifier: requested_id.to_string(),
+                    }
+                })?;
+            },
+            TableUpdate::AddSpec { spec } => {
+                metadata.add_partition_spec(spec.clone());
+            },
+            TableUpdate::SetDefaultSpec { spec_id: requested_id } => {
+                // -1 refers to the spec added last.
+                let specs = metadata.partition_specs();
+                let spec_id = match *requested_id {
+                    -1 => specs.last().map(|spec| spec.spec_id),
+                    spec_id => specs.iter()
+                        .find(|spec| spec.spec_id == spec_id)
+                        .map(|spec| spec.spec_id),
+                };
+                metadata.default_spec_id = spec_id.ok_or_else(|| {
+                    IcebergError::NotFound {
+                        kind: NotFoundKind::PartitionSpec,
+                        identifier: requested_id.to_string(),
+                    }
+                })?;
+            },
+            TableUpdate::AddSortOrder { sort_order } => {
+                metadata.sort_orders.push(sort_order.clone());
+            },
+            TableUpdate::SetDefaultSortOrder { sort_order_id: requested_id } => {
+                // -1 refers to the sort order added last.
+                let sort_order_id = match *requested_id {
+                    -1 => {
+                        metadata.sort_orders.last().map(|sort_order| sort_order.order_id)
+                    },
+                    sort_order_id => metadata.sort_orders.iter()
+                        .find(|sort_order| sort_order.order_id == sort_order_id)
+                        .map(|sort_order| sort_order.order_id),
+                };
+                metadata.default_sort_order_id = sort_order_id.ok_or_else(|| {
+                    IcebergError::NotFound {
+                        kind: NotFoundKind::SortOrder,
+                        identifier: requested_id.to_string(),
+                    }
+                })?;
+            },
+            TableUpdate::AddSnapshot { snapshot } => {
+                metadata.last_sequence_number = metadata.last_sequence_number
+                    .max(snapshot.sequence_number);
+                metadata.snapshots
+                    .get_or_insert_with(Vec::new)
+                    .push(snapshot.clone());
+            },
+            TableUpdate::SetSnapshotRef { ref_name, reference } => {
+                let snapshot = metadata.snapshot_by_id(reference.snapshot_id)
+                    .ok_or_else(|| IcebergError::NotFound {
+                        kind: NotFoundKind::Snapshot,
+                        identifier: reference.snapshot_id.to_string(),
+                    })?;
+
+                if ref_name == MAIN_BRANCH {
+                    let log = SnapshotLog::new(snapshot.snapshot_id, snapshot.timestamp_ms);
+                    metadata.current_snapshot_id = Some(log.snapshot_id);
+                    metadata.snapshot_log.get_or_insert_with(Vec::new).push(log);
+                }
+                metadata.refs
+                    .get_or_insert_with(HashMap::new)
+                    .insert(ref_name.clone(), reference.clone());
+            },
+            TableUpdate::RemoveSnapshots { snapshot_ids } => {
+                if let Some(snapshots) = metadata.snapshots.as_mut() {
+                    snapshots.retain(|snapshot| !snapshot_ids.contains(&snapshot.snapshot_id));
+                }
+                if let Some(refs) = metadata.refs.as_mut() {
+                    refs.retain(|_, reference| !snapshot_ids.contains(&reference.snapshot_id));
+                }
+            },
+            TableUpdate::SetLocation { location } => {
+                metadata.location = location.clone();
+            },
+            TableUpdate::SetProperties { updates } => {
+                metadata.properties
+                    .get_or_insert_with(HashMap::new)
+                    .extend(updates.clone());
+            },
+            TableUpdate::RemoveProperties { removals } => {
+                if let Some(properties) = metadata.properties.as_mut() {
+                    for key in removals {
+                        properties.remove(key);
+                    }
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// A set of requirements and updates to commit atomically to a single table.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableCommit {
+    pub identifier: TableIdentifier,
+    pub requirements: Vec<TableRequirement>,
+    pub updates: Vec<TableUpdate>,
+}
+
+impl TableCommit {
+    pub fn new(identifier: TableIdentifier) -> Self {
+        Self {
+            identifier: identifier,
+            requirements: Vec::new(),
+            updates: Vec::new(),
+        }
+    }
+
+    pub fn with_requirement(mut self, requirement: TableRequirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    pub fn with_update(mut self, update: TableUpdate) -> Self {
+        self.updates.push(update);
+        self
+    }
+
+    /// Creates a commit changing the table's metadata from `base` to `metadata`.
+    ///
+    /// The commit requires the table's current snapshot, schema and last column id
+    /// to still be those of `base`, so it fails with a conflict if another writer
+    /// changed them in the meantime. If the partition spec changed, the default spec
+    /// and last partition field id are required to be unchanged as well. Its updates
+    /// add the schemas, partition specs, sort orders and snapshots that only exist in
+    /// `metadata`, and set the properties, current schema, default spec, default sort
+    /// order and current snapshot of `metadata`.
+    pub fn from_changes(
+        identifier: TableIdentifier,
+        base: &IcebergTableMetadata,
+        metadata: &IcebergTableMetadata
+    ) -> Self {
+        let mut commit = TableCommit::new(identifier)
+            .with_requirement(TableRequirement::AssertTableUuid {
+                uuid: base.table_uuid.clone()
+            })
+            .with_requirement(TableRequirement::AssertRefSnapshotId {
+                r#ref: MAIN_BRANCH.to_string(),
+                snapshot_id: ref_snapshot_id(base, MAIN_BRANCH),
+            })
+            .with_requirement(TableRequirement::AssertCurrentSchemaId {
+                current_schema_id: base.current_schema_id
+            })
+            .with_requirement(TableRequirement::AssertLastAssignedFieldId {
+                last_assigned_field_id: base.last_column_id
+            });
+
+        for schema in &metadata.schemas {
+            if base.schema_by_id(schema.id()).is_none() {
+                commit = commit.with_update(TableUpdate::AddSchema {
+                    schema: schema.clone(),
+                    last_column_id: Some(metadata.last_column_id),
+                });
+            }
+        }
+        if metadata.current_schema_id != base.current_schema_id {
+            commit = commit.with_update(TableUpdate::SetCurrentSchema {
+                schema_id: metadata.current_schema_id
+            });
+        }
+
+        if metadata.default_spec_id != base.default_spec_id
+            || metadata.partition_specs().len() != base.partition_specs().len() {
+            commit = commit
+                .with_requirement(TableRequirement::AssertDefaultSpecId {
+                    default_spec_id: base.default_spec_id
+                })
+                .with_requirement(TableRequirement::AssertLastAssignedPartitionId {
+                    last_assigned_partition_id: base.last_partition_id
+                });
+        }
+        for spec in metadata.partition_specs() {
+            let exists = base.partition_specs().iter()
+                .any(|existing| existing.spec_id == spec.spec_id);
+            if !exists {
+                commit = commit.with_update(TableUpdate::AddSpec { spec: spec.clone() });
+            }
+        }
+        if metadata.default_spec_id != base.default_spec_id {
+            commit = commit.with_update(TableUpdate::SetDefaultSpec {
+                spec_id: metadata.default_spec_id
+            });
+        }
+
+        for sort_order in &metadata.sort_orders {
+            let exists = base.sort_orders.iter()
+                .any(|existing| existing.order_id == sort_order.order_id);
+            if !exists {
+                commit = commit.with_update(TableUpdate::AddSortOrder {
+                    sort_order: sort_order.clone()
+                });
+            }
+        }
+        if metadata.default_sort_order_id != base.default_sort_order_id {
+            commit = commit.with_update(TableUpdate::SetDefaultSortOrder {
+                sort_order_id: metadata.default_sort_order_id
+            });
+        }
+
+        for snapshot in metadata.snapshots.iter().flatten() {
+            if base.snapshot_by_id(snapshot.snapshot_id).is_none() {
+                commit = commit.with_update(TableUpdate::AddSnapshot {
+                    snapshot: snapshot.clone()
+                });
+            }
+        }
+        if let Some(snapshot_id) = metadata.current_snapshot_id {
+            if base.current_snapshot_id != Some(snapshot_id) {
+                commit = commit.with_update(TableUpdate::SetSnapshotRef {
+                    ref_name: MAIN_BRANCH.to_string(),
+                    reference: SnapshotReference::branch(snapshot_id),
+                });
+            }
+        }
+
+        let empty = HashMap::new();
+        let base_properties = base.properties.as_ref().unwrap_or(&empty);
+        let properties = metadata.properties.as_ref().unwrap_or(&empty);
+        let updates: HashMap<String, String> = properties.iter()
+            .filter(|(key, value)| base_properties.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let removals: Vec<String> = base_properties.keys()
+            .filter(|key| !properties.contains_key(*key))
+            .cloned()
+            .collect();
+        if !updates.is_empty() {
+            commit = commit.with_update(TableUpdate::SetProperties { updates: updates });
+        }
+        if !removals.is_empty() {
+            commit = commit.with_update(
+                TableUpdate::RemoveProperties { removals: removals }
+            );
+        }
+
+        commit
+    }
+
+    /// Checks the commit's requirements against the table's current metadata and
+    /// returns the metadata with the commit's updates applied.
+    ///
+    /// Catalogs that don't delegate commits to a server use this to produce the
+    /// table's new metadata.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::CommitConflict`] is returned if a requirement does not hold.
+    /// Invalid updates fail as described in [`TableUpdate::apply()`].
+    pub fn apply(&self, metadata: &IcebergTableMetadata) -> IcebergResult<IcebergTableMetadata> {
+        for requirement in &self.requirements {
+            requirement.check(Some(metadata))?;
+        }
+
+        let mut metadata = metadata.clone();
+        for update in &self.updates {
+            update.apply(&mut metadata)?;
+        }
+        metadata.last_updated_ms = utils::current_time_ms()?;
+
+        Ok(metadata)
+    }
+}
+
+/// Operations supported by all Iceberg catalogs.
+#[async_trait]
+pub trait Catalog: Send + Sync {
+    /// Lists the namespaces under `parent`, or the top-level namespaces if `parent`
+    /// is `None`.
+    async fn list_namespaces(
+        &self,
+        parent: Option<&Namespace>
+    ) -> IcebergResult<Vec<Namespace>>;
+
+    /// Creates a new table in the catalog and returns it.
+    ///
+    /// The table is created with format version 2 metadata holding `schema`,
+    /// `partition_spec` and `sort_order`, or an unpartitioned and unsorted table if
+    /// they aren't given.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::TableAlreadyExists`] is returned if the table already exists.
+    async fn create_table(
+        &self,
+        identifier: &TableIdentifier,
+        schema: Schema,
+        partition_spec: Option<PartitionSpec>,
+        sort_order: Option<SortOrder>,
+        properties: Option<HashMap<String, String>>,
+    ) -> IcebergResult<IcebergTable>;
+
+    /// Loads an existing table from the catalog.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::NotFound`] is returned if the table does not exist.
+    async fn load_table(&self, identifier: &TableIdentifier) -> IcebergResult<IcebergTable>;
+
+    /// Returns the full URL of the table's current metadata file.
+    ///
+    /// By default the table is loaded to find its metadata file. Catalogs that can
+    /// find it without reading the table's metadata override this.
+    async fn load_metadata_location(
+        &self,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<String> {
+        self.load_table(identifier).await?.current_metadata_uri()
+    }
+
+    /// Drops a table from the catalog, optionally deleting its data and metadata.
+    async fn drop_table(&self, identifier: &TableIdentifier, purge: bool) -> IcebergResult<()>;
+
+    /// Renames a table, possibly moving it to another namespace.
+    async fn rename_table(
+        &self,
+        source: &TableIdentifier,
+        destination: &TableIdentifier
+    ) -> IcebergResult<()>;
+
+    /// Atomically applies the commit's updates to the table if all of its
+    /// requirements hold, and returns the updated table.
+    async fn commit_transaction(&self, commit: TableCommit) -> IcebergResult<IcebergTable>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::{IcebergError, IcebergTableMetadata};
+    use crate::schema::{Schema, Field, PrimitiveType};
+    use crate::catalog::{
+        Namespace, TableIdentifier, TableCommit, TableRequirement, TableUpdate
+    };
+    use crate::snapshot::SnapshotReference;
+
+    #[test]
+    fn table_identifier() {
+        let identifier = TableIdentifier::new(Namespace::new(&["db", "schema"]), "events");
+
+        assert_eq!(identifier.to_string(), "db.schema.events");
+        assert_eq!(
+            serde_json::to_value(&identifier).unwrap(),
+            json!({"namespace": ["db", "schema"], "name": "events"})
+        );
+    }
+
+    #[test]
+    fn commit_from_changes() {
+        let schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        ]);
+        let base = IcebergTableMetadata::try_new(
+            "s3://bucket/table".to_string(),
+            schema,
+            None,
+            Some(HashMap::from([
+                ("owner".to_string(), "a".to_string()),
+                ("comment".to_string(), "c".to_string()),
+            ]))
+        ).unwrap();
+
+        let mut metadata = base.clone();
+        let new_schema = Schema::new(1, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "name", false, PrimitiveType::String),
+        ]);
+        metadata.schemas.push(new_schema.clone());
+        metadata.current_schema_id = 1;
+        metadata.last_column_id = 2;
+        metadata.properties = Some(HashMap::from([
+            ("owner".to_string(), "b".to_string()),
+            ("comment".to_string(), "c".to_string()),
+        ]));
+
+        let identifier = TableIdentifier::new(Namespace::new(&["db"]), "events");
+        let commit = TableCommit::from_changes(identifier, &base, &metadata);
+
+        assert!(commit.requirements.contains(&TableRequirement::AssertRefSnapshotId {
+            r#ref: "main".to_string(),
+            snapshot_id: None,
+        }));
+        assert_eq!(commit.updates, vec![
+            TableUpdate::AddSchema { schema: new_schema, last_column_id: Some(2) },
+            TableUpdate::SetCurrentSchema { schema_id: 1 },
+            TableUpdate::SetProperties {
+                updates: HashMap::from([("owner".to_string(), "b".to_string())])
+            },
+        ]);
+
+        // Applying the commit to the base reproduces the changes.
+        let mut applied = commit.apply(&base).unwrap();
+        applied.last_updated_ms = metadata.last_updated_ms;
+        assert_eq!(applied, metadata);
+
+        // Once the base changed, the commit conflicts.
+        assert!(matches!(commit.apply(&metadata), Err(IcebergError::CommitConflict(..))));
+    }
+
+    #[test]
+    fn serialize_requirements_and_updates() {
+        let requirement = TableRequirement::AssertRefSnapshotId {
+            r#ref: "main".to_string(),
+            snapshot_id: Some(3),
+        };
+        assert_eq!(
+            serde_json::to_value(&requirement).unwrap(),
+            json!({"type": "assert-ref-snapshot-id", "ref": "main", "snapshot-id": 3})
+        );
+
+        let reference: SnapshotReference = serde_json::from_value(
+            json!({"snapshot-id": 3, "type": "branch"})
+        ).unwrap();
+        let update = TableUpdate::SetSnapshotRef {
+            ref_name: "main".to_string(),
+            reference: reference,
+        };
+        let value = serde_json::to_value(&update).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "action": "set-snapshot-ref",
+                "ref-name": "main",
+                "snapshot-id": 3,
+                "type": "branch"
+            })
+        );
+        assert_eq!(serde_json::from_value::<TableUpdate>(value).unwrap(), update);
+    }
+}
@@ -0,0 +1,510 @@
+//! A client for catalogs implementing the Iceberg REST catalog API.
+//!
+//! ```rust,no_run
+//! use icelake::IcebergResult;
+//! use icelake::catalog::{Catalog, RestCatalogBuilder, Namespace, TableIdentifier};
+//!
+//! #[tokio::main]
+//! async fn main() -> IcebergResult<()> {
+//!     let catalog = RestCatalogBuilder::new("http://localhost:8181")
+//!         .with_token("secret")
+//!         .with_warehouse("s3://warehouse")
+//!         .build()
+//!         .await?;
+//!
+//!     let table = catalog.load_table(
+//!         &TableIdentifier::new(Namespace::new(&["db"]), "events")
+//!     ).await?;
+//!     println!("Table location: {}", table.location());
+//!
+//!     Ok(())
+//! }
+//! ```
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use url::Url;
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use async_trait::async_trait;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+
+use crate::{
+    IcebergError, IcebergResult, IcebergTable, IcebergTableMetadata, NotFoundKind
+};
+use crate::schema::Schema;
+use crate::partition::{PartitionSpec, PartitionSpecModel};
+use crate::sort::SortOrder;
+use crate::storage::IcebergStorage;
+use crate::catalog::{
+    Catalog, Namespace, TableIdentifier, TableRequirement, TableUpdate, TableCommit
+};
+
+/// Separator of namespace levels when a namespace appears in a URL path.
+const NAMESPACE_SEPARATOR: &str = "\u{1f}";
+
+#[derive(Debug, Deserialize)]
+struct ConfigResponse {
+    #[serde(default)]
+    defaults: HashMap<String, String>,
+    #[serde(default)]
+    overrides: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListNamespacesResponse {
+    namespaces: Vec<Namespace>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct LoadTableResponse {
+    metadata_location: Option<String>,
+    metadata: IcebergTableMetadata,
+    #[serde(default)]
+    config: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CommitTableResponse {
+    metadata_location: String,
+    metadata: IcebergTableMetadata,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct CreateTableRequest {
+    name: String,
+    schema: Schema,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partition_spec: Option<PartitionSpecModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    write_order: Option<SortOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RenameTableRequest<'a> {
+    source: &'a TableIdentifier,
+    destination: &'a TableIdentifier,
+}
+
+#[derive(Debug, Serialize)]
+struct CommitTableRequest {
+    identifier: TableIdentifier,
+    requirements: Vec<TableRequirement>,
+    updates: Vec<TableUpdate>,
+}
+
+/// What a request refers to, reported by the error if it doesn't exist or already
+/// exists.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    Catalog,
+    Namespace(&'a Namespace),
+    Table(&'a TableIdentifier),
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: ErrorModel,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorModel {
+    message: String,
+    r#type: String,
+    code: u16,
+}
+
+/// Builds a [`RestCatalog`].
+///
+/// Building the catalog fetches its configuration from the server. The final
+/// catalog properties are the server's defaults, overridden by the properties
+/// given to the builder, overridden in turn by the server's overrides.
+pub struct RestCatalogBuilder {
+    uri: String,
+    token: Option<String>,
+    warehouse: Option<String>,
+    properties: HashMap<String, String>,
+    storage_options: HashMap<String, String>,
+}
+
+impl RestCatalogBuilder {
+    /// Creates a builder for the REST catalog served at `uri`.
+    pub fn new(uri: &str) -> Self {
+        Self {
+            uri: uri.to_string(),
+            token: None,
+            warehouse: None,
+            properties: HashMap::new(),
+            storage_options: HashMap::new(),
+        }
+    }
+
+    /// Sets a bearer token sent with every request.
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Sets the warehouse location requested from the server.
+    pub fn with_warehouse(mut self, warehouse: &str) -> Self {
+        self.warehouse = Some(warehouse.to_string());
+        self
+    }
+
+    pub fn with_properties(mut self, properties: HashMap<String, String>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Sets the options used to access the storage of tables loaded from the
+    /// catalog. See [`IcebergStorage::from_url()`].
+    pub fn with_storage_options(
+        mut self,
+        storage_options: HashMap<String, String>
+    ) -> Self {
+        self.storage_options = storage_options;
+        self
+    }
+
+    /// Fetches the catalog configuration from the server and builds the catalog.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::CatalogError`] is returned if the URI is invalid or if the
+    /// server responds with an error, and [`IcebergError::HttpError`] if the server
+    /// could not be reached.
+    pub async fn build(self) -> IcebergResult<RestCatalog> {
+        let client = Client::new();
+
+        let mut properties = self.properties;
+        if let Some(warehouse) = &self.warehouse {
+            properties.insert("warehouse".to_string(), warehouse.clone());
+        }
+
+        let mut config_url = endpoint(&parse_uri(&self.uri)?, None, &["config"])?;
+        if let Some(warehouse) = &self.warehouse {
+            config_url.query_pairs_mut().append_pair("warehouse", warehouse);
+        }
+
+        let mut request = client.get(config_url);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let config: ConfigResponse =
+            parse_response(request.send().await?, Target::Catalog).await?;
+
+        let mut merged = config.defaults;
+        merged.extend(properties);
+        merged.extend(config.overrides);
+
+        let uri = parse_uri(merged.get("uri").unwrap_or(&self.uri))?;
+        let token = merged.get("token").cloned().or(self.token);
+        let prefix = merged.get("prefix").cloned();
+
+        Ok(RestCatalog {
+            client: client,
+            uri: uri,
+            prefix: prefix,
+            token: token,
+            properties: merged,
+            storage_options: self.storage_options,
+        })
+    }
+}
+
+/// An Iceberg catalog accessed through the Iceberg REST catalog API.
+pub struct RestCatalog {
+    client: Client,
+    uri: Url,
+    prefix: Option<String>,
+    token: Option<String>,
+    properties: HashMap<String, String>,
+    storage_options: HashMap<String, String>,
+}
+
+impl RestCatalog {
+    /// Returns the catalog properties, merged with the server's configuration.
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.properties
+    }
+
+    fn url(&self, segments: &[&str]) -> IcebergResult<Url> {
+        endpoint(&self.uri, self.prefix.as_deref(), segments)
+    }
+
+    fn namespace_url(&self, namespace: &Namespace, segments: &[&str]) -> IcebergResult<Url> {
+        let namespace = namespace.levels().join(NAMESPACE_SEPARATOR);
+        let mut all_segments = vec!["namespaces", namespace.as_str()];
+        all_segments.extend_from_slice(segments);
+
+        self.url(&all_segments)
+    }
+
+    fn table_url(&self, identifier: &TableIdentifier) -> IcebergResult<Url> {
+        self.namespace_url(&identifier.namespace, &["tables", &identifier.name])
+    }
+
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request
+        }
+    }
+
+    /// Creates an [`IcebergTable`] from metadata returned by the server.
+    fn load_response_table(
+        &self,
+        metadata_location: Option<String>,
+        metadata: IcebergTableMetadata,
+        config: HashMap<String, String>,
+    ) -> IcebergResult<IcebergTable> {
+        let metadata_location = metadata_location.ok_or_else(|| {
+            IcebergError::CatalogError {
+                message: format!(
+                    "missing metadata location for table at {}", metadata.location
+                )
+            }
+        })?;
+
+        let mut storage_options = self.storage_options.clone();
+        storage_options.extend(config);
+
+        let storage = IcebergStorage::from_url(&metadata.location, storage_options)?;
+
+        IcebergTable::from_metadata(Arc::new(storage), metadata, &metadata_location)
+    }
+}
+
+#[async_trait]
+impl Catalog for RestCatalog {
+    async fn list_namespaces(
+        &self,
+        parent: Option<&Namespace>
+    ) -> IcebergResult<Vec<Namespace>> {
+        let mut url = self.url(&["namespaces"])?;
+        if let Some(parent) = parent {
+            url.query_pairs_mut()
+                .append_pair("parent", &parent.levels().join(NAMESPACE_SEPARATOR));
+        }
+        let target = parent.map_or(Target::Catalog, Target::Namespace);
+
+        let response = self.request(Method::GET, url).send().await?;
+        let response: ListNamespacesResponse = parse_response(response, target).await?;
+
+        Ok(response.namespaces)
+    }
+
+    async fn create_table(
+        &self,
+        identifier: &TableIdentifier,
+        schema: Schema,
+        partition_spec: Option<PartitionSpec>,
+        sort_order: Option<SortOrder>,
+        properties: Option<HashMap<String, String>>,
+    ) -> IcebergResult<IcebergTable> {
+        let url = self.namespace_url(&identifier.namespace, &["tables"])?;
+        let request = CreateTableRequest {
+            name: identifier.name.clone(),
+            schema: schema,
+            partition_spec: partition_spec.map(|spec| spec.model()),
+            write_order: sort_order,
+            properties: properties,
+        };
+
+        let response = self.request(Method::POST, url).json(&request).send().await?;
+        let response: LoadTableResponse =
+            parse_response(response, Target::Table(identifier)).await?;
+
+        self.load_response_table(
+            response.metadata_location,
+            response.metadata,
+            response.config
+        )
+    }
+
+    async fn load_table(&self, identifier: &TableIdentifier) -> IcebergResult<IcebergTable> {
+        let url = self.table_url(identifier)?;
+
+        let response = self.request(Method::GET, url).send().await?;
+        let response: LoadTableResponse =
+            parse_response(response, Target::Table(identifier)).await?;
+
+        self.load_response_table(
+            response.metadata_location,
+            response.metadata,
+            response.config
+        )
+    }
+
+    async fn drop_table(&self, identifier: &TableIdentifier, purge: bool) -> IcebergResult<()> {
+        let mut url = self.table_url(identifier)?;
+        url.query_pairs_mut().append_pair("purgeRequested", &purge.to_string());
+
+        let response = self.request(Method::DELETE, url).send().await?;
+        check_response(response, Target::Table(identifier)).await?;
+
+        Ok(())
+    }
+
+    async fn rename_table(
+        &self,
+        source: &TableIdentifier,
+        destination: &TableIdentifier
+    ) -> IcebergResult<()> {
+        let url = self.url(&["tables", "rename"])?;
+        let request = RenameTableRequest {
+            source: source,
+            destination: destination,
+        };
+
+        let response = self.request(Method::POST, url).json(&request).send().await?;
+        // A conflict is reported for the destination, which already exists.
+        check_response(response, Target::Table(source)).await
+            .map_err(|e| match e {
+                IcebergError::TableAlreadyExists(_) => {
+                    IcebergError::TableAlreadyExists(destination.to_string())
+                },
+                e => e,
+            })?;
+
+        Ok(())
+    }
+
+    async fn commit_transaction(&self, commit: TableCommit) -> IcebergResult<IcebergTable> {
+        let url = self.table_url(&commit.identifier)?;
+        let request = CommitTableRequest {
+            identifier: commit.identifier,
+            requirements: commit.requirements,
+            updates: commit.updates,
+        };
+
+        let response = self.request(Method::POST, url).json(&request).send().await?;
+        let response: CommitTableResponse =
+            parse_response(response, Target::Table(&request.identifier)).await?;
+
+        self.load_response_table(
+            Some(response.metadata_location),
+            response.metadata,
+            HashMap::new()
+        )
+    }
+}
+
+fn parse_uri(uri: &str) -> IcebergResult<Url> {
+    Url::parse(uri).map_err(|e| IcebergError::CatalogError {
+        message: format!("invalid catalog uri {}: {}", uri, e)
+    })
+}
+
+/// Returns the URL of a REST API endpoint, e.g. `{uri}/v1/{prefix}/namespaces`.
+fn endpoint(uri: &Url, prefix: Option<&str>, segments: &[&str]) -> IcebergResult<Url> {
+    let mut url = uri.clone();
+    url.path_segments_mut()
+        .map_err(|_| IcebergError::CatalogError {
+            message: format!("invalid catalog uri {}", uri)
+        })?
+        .pop_if_empty()
+        .push("v1")
+        .extend(prefix)
+        .extend(segments);
+
+    Ok(url)
+}
+
+/// Returns an error if the response does not have a success status code.
+///
+/// A missing namespace or table, reported with 404 Not Found, is returned as
+/// [`IcebergError::NotFound`] identifying the namespace or table of `target`, and
+/// an existing table, reported with 409 Conflict, as
+/// [`IcebergError::TableAlreadyExists`].
+async fn check_response(
+    response: Response,
+    target: Target<'_>
+) -> IcebergResult<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.bytes().await?;
+    let error = serde_json::from_slice::<ErrorResponse>(&body)
+        .ok()
+        .map(|response| response.error);
+    let message = match &error {
+        Some(error) => format!("{} ({} {})", error.message, error.code, error.r#type),
+        None => format!("unexpected response status {}", status)
+    };
+
+    // Commits whose requirements no longer hold are rejected with 409 Conflict, which
+    // is also used when creating a table that already exists.
+    let error_type = error.as_ref().map(|error| error.r#type.as_str());
+    if status == StatusCode::NOT_FOUND {
+        let not_found = match (target, error_type) {
+            (Target::Table(identifier), Some("NoSuchNamespaceException")) => {
+                Some((NotFoundKind::Namespace, identifier.namespace.to_string()))
+            },
+            (Target::Table(identifier), _) => {
+                Some((NotFoundKind::Table, identifier.to_string()))
+            },
+            (Target::Namespace(namespace), _) => {
+                Some((NotFoundKind::Namespace, namespace.to_string()))
+            },
+            (Target::Catalog, _) => None,
+        };
+        if let Some((kind, identifier)) = not_found {
+            return Err(IcebergError::NotFound { kind: kind, identifier: identifier });
+        }
+    }
+
+    match (status, error_type) {
+        (StatusCode::CONFLICT, Some("AlreadyExistsException")) => match target {
+            Target::Table(identifier) => {
+                Err(IcebergError::TableAlreadyExists(identifier.to_string()))
+            },
+            _ => Err(IcebergError::CatalogError { message: message }),
+        },
+        (StatusCode::CONFLICT, _) | (_, Some("CommitFailedException")) => {
+            Err(IcebergError::CommitConflict(message))
+        },
+        _ => Err(IcebergError::CatalogError { message: message }),
+    }
+}
+
+async fn parse_response<T: DeserializeOwned>(
+    response: Response,
+    target: Target<'_>
+) -> IcebergResult<T> {
+    let body = check_response(response, target).await?.bytes().await?;
+
+    serde_json::from_slice(&body)
+        .map_err(|e| IcebergError::SerializeJson { source: e })
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::catalog::rest::endpoint;
+
+    #[test]
+    fn endpoint_urls() {
+        let uri = Url::parse("http://localhost:8181/catalog/").unwrap();
+
+        assert_eq!(
+            endpoint(&uri, None, &["config"]).unwrap().as_str(),
+            "http://localhost:8181/catalog/v1/config"
+        );
+        assert_eq!(
+            endpoint(&uri, Some("ws"), &["namespaces", "a\u{1f}b", "tables"])
+                .unwrap()
+                .as_str(),
+            "http://localhost:8181/catalog/v1/ws/namespaces/a%1Fb/tables"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Pruning of Parquet row groups using their column bloom filters.
+use parquet::basic::Type as PhysicalType;
+use parquet::bloom_filter::Sbbf;
+
+use crate::value::Value;
+use crate::expr::{Operator, BoundReference, Predicate, Expression, BoundExpression};
+
+/// Decides whether a Parquet row group might contain rows matching a filter, based on
+/// the bloom filters of its columns.
+///
+/// Bloom filters can only rule out equality and `IN` predicates, whose literals are
+/// looked up in the filter of their column. The evaluation is inclusive: `false` is
+/// only returned if the row group is guaranteed to contain no matching rows. Columns
+/// without bloom filters never cause a row group to be pruned.
+pub struct BloomFilterEvaluator {
+    filter: BoundExpression,
+}
+
+impl BloomFilterEvaluator {
+    /// Creates an evaluator for a filter bound to the table's schema.
+    pub fn new(filter: &BoundExpression) -> Self {
+        Self {
+            filter: filter.clone().rewrite_not(),
+        }
+    }
+
+    /// Returns `false` if the row group is guaranteed to contain no rows matching the
+    /// filter, and `true` if it might contain matching rows.
+    ///
+    /// `bloom_filter` returns the bloom filter of the row group's column with the
+    /// given field id, along with the column's physical type, or `None` if the
+    /// column has no bloom filter.
+    pub fn eval<'a>(
+        &self,
+        bloom_filter: impl Fn(i32) -> Option<(&'a Sbbf, PhysicalType)>
+    ) -> bool {
+        eval_expression(&self.filter, &bloom_filter)
+    }
+}
+
+fn eval_expression<'a>(
+    expr: &BoundExpression,
+    bloom_filter: &impl Fn(i32) -> Option<(&'a Sbbf, PhysicalType)>
+) -> bool {
+    match expr {
+        Expression::AlwaysTrue => true,
+        Expression::AlwaysFalse => false,
+        Expression::And(left, right) => {
+            eval_expression(left, bloom_filter) && eval_expression(right, bloom_filter)
+        },
+        Expression::Or(left, right) => {
+            eval_expression(left, bloom_filter) || eval_expression(right, bloom_filter)
+        },
+        // Negations were rewritten away, so this can't be reached.
+        Expression::Not(_) => true,
+        Expression::Predicate(predicate) => eval_predicate(predicate, bloom_filter),
+    }
+}
+
+fn eval_predicate<'a>(
+    predicate: &Predicate<BoundReference>,
+    bloom_filter: &impl Fn(i32) -> Option<(&'a Sbbf, PhysicalType)>
+) -> bool {
+    if !matches!(predicate.op(), Operator::Eq | Operator::In) {
+        return true;
+    }
+
+    let (bloom_filter, physical_type) = match bloom_filter(predicate.term().field_id()) {
+        Some(bloom_filter) => bloom_filter,
+        None => return true,
+    };
+
+    predicate.literals().iter().any(|literal| {
+        match plain_bytes(literal, physical_type) {
+            Some(bytes) => bloom_filter.check(bytes.as_slice()),
+            // Values that can't be hashed like the column's values might be present.
+            None => true,
+        }
+    })
+}
+
+/// Returns the Parquet plain encoding of a literal compared with a column of the
+/// given physical type, which is what bloom filters hash, or `None` if the literal
+/// can't be encoded for the column.
+///
+/// For most types this matches the literal's single-value serialization. Literals
+/// of promoted columns are converted to the type of the column in the file.
+fn plain_bytes(literal: &Value, physical_type: PhysicalType) -> Option<Vec<u8>> {
+    match (physical_type, literal) {
+        (PhysicalType::INT32, Value::Int(_) | Value::Date(_))
+        | (PhysicalType::INT64, Value::Long(_) | Value::Time(_))
+        | (PhysicalType::INT64, Value::Timestamp(_) | Value::Timestamptz(_))
+        | (PhysicalType::FLOAT, Value::Float(_))
+        | (PhysicalType::DOUBLE, Value::Double(_))
+        | (PhysicalType::BYTE_ARRAY, Value::String(_) | Value::Binary(_))
+        | (PhysicalType::FIXED_LEN_BYTE_ARRAY, Value::Fixed(_) | Value::Uuid(_)) => {
+            Vec::<u8>::try_from(literal.clone()).ok()
+        },
+        // A long column written as int before being promoted.
+        (PhysicalType::INT32, Value::Long(l)) => {
+            i32::try_from(*l).ok().map(|i| i.to_le_bytes().to_vec())
+        },
+        // A double column written as float before being promoted.
+        (PhysicalType::FLOAT, Value::Double(d)) => {
+            let f = *d as f32;
+            (f as f64 == *d).then(|| f.to_le_bytes().to_vec())
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,522 @@
+//! Expression tree, column references and binding to a schema.
+use crate::{IcebergResult, IcebergError};
+use crate::schema::{Schema, SchemaType, PrimitiveType};
+use crate::value::Value;
+
+/// Comparison operators that can be used in a [`Predicate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    IsNull,
+    NotNull,
+    In,
+    NotIn,
+}
+
+impl Operator {
+    /// Returns the operator that matches exactly the rows this operator doesn't.
+    pub fn negate(self) -> Self {
+        match self {
+            Operator::Eq => Operator::NotEq,
+            Operator::NotEq => Operator::Eq,
+            Operator::Lt => Operator::GtEq,
+            Operator::LtEq => Operator::Gt,
+            Operator::Gt => Operator::LtEq,
+            Operator::GtEq => Operator::Lt,
+            Operator::IsNull => Operator::NotNull,
+            Operator::NotNull => Operator::IsNull,
+            Operator::In => Operator::NotIn,
+            Operator::NotIn => Operator::In,
+        }
+    }
+
+    /// Returns `true` if the operator takes no literals.
+    pub fn is_unary(self) -> bool {
+        matches!(self, Operator::IsNull | Operator::NotNull)
+    }
+
+    /// Returns `true` if the operator takes a set of literals.
+    pub fn is_set(self) -> bool {
+        matches!(self, Operator::In | Operator::NotIn)
+    }
+}
+
+/// A reference to a column by its name, not yet bound to a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    name: String,
+}
+
+/// Creates a reference to the column with the given name. Nested columns are
+/// referenced by their full dotted name, e.g. `"location.lat"`.
+pub fn col(name: &str) -> Reference {
+    Reference { name: name.to_string() }
+}
+
+impl Reference {
+    /// The name of the referenced column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn predicate(self, op: Operator, literals: Vec<Value>) -> Expression {
+        Expression::Predicate(Predicate {
+            term: self,
+            op: op,
+            literals: literals,
+        })
+    }
+
+    pub fn equal_to(self, value: impl Into<Value>) -> Expression {
+        self.predicate(Operator::Eq, vec![value.into()])
+    }
+
+    pub fn not_equal_to(self, value: impl Into<Value>) -> Expression {
+        self.predicate(Operator::NotEq, vec![value.into()])
+    }
+
+    pub fn less_than(self, value: impl Into<Value>) -> Expression {
+        self.predicate(Operator::Lt, vec![value.into()])
+    }
+
+    pub fn less_than_or_equal_to(self, value: impl Into<Value>) -> Expression {
+        self.predicate(Operator::LtEq, vec![value.into()])
+    }
+
+    pub fn greater_than(self, value: impl Into<Value>) -> Expression {
+        self.predicate(Operator::Gt, vec![value.into()])
+    }
+
+    pub fn greater_than_or_equal_to(self, value: impl Into<Value>) -> Expression {
+        self.predicate(Operator::GtEq, vec![value.into()])
+    }
+
+    pub fn is_null(self) -> Expression {
+        self.predicate(Operator::IsNull, Vec::new())
+    }
+
+    pub fn not_null(self) -> Expression {
+        self.predicate(Operator::NotNull, Vec::new())
+    }
+
+    /// Matches rows in which the column equals any of the given values. An empty
+    /// set of values matches no rows.
+    pub fn is_in<V: Into<Value>>(
+        self,
+        values: impl IntoIterator<Item = V>
+    ) -> Expression {
+        let values: Vec<Value> = values.into_iter().map(Into::into).collect();
+        if values.is_empty() {
+            Expression::AlwaysFalse
+        } else {
+            self.predicate(Operator::In, values)
+        }
+    }
+
+    /// Matches rows in which the column equals none of the given values. An empty
+    /// set of values matches all rows.
+    pub fn not_in<V: Into<Value>>(
+        self,
+        values: impl IntoIterator<Item = V>
+    ) -> Expression {
+        let values: Vec<Value> = values.into_iter().map(Into::into).collect();
+        if values.is_empty() {
+            Expression::AlwaysTrue
+        } else {
+            self.predicate(Operator::NotIn, values)
+        }
+    }
+}
+
+/// A reference to a primitive column that has been resolved against a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundReference {
+    field_id: i32,
+    name: String,
+    field_type: PrimitiveType,
+    required: bool,
+}
+
+impl BoundReference {
+    pub(crate) fn new(
+        field_id: i32,
+        name: &str,
+        field_type: PrimitiveType,
+        required: bool
+    ) -> Self {
+        Self {
+            field_id: field_id,
+            name: name.to_string(),
+            field_type: field_type,
+            required: required,
+        }
+    }
+
+    /// Id of the referenced field.
+    pub fn field_id(&self) -> i32 {
+        self.field_id
+    }
+
+    /// Full name of the referenced field.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Type of the referenced field.
+    pub fn field_type(&self) -> &PrimitiveType {
+        &self.field_type
+    }
+
+    /// Whether the referenced field is required, i.e. can't contain nulls.
+    pub fn required(&self) -> bool {
+        self.required
+    }
+}
+
+/// A predicate comparing a column against zero or more literals.
+///
+/// The number of literals depends on the operator: unary operators
+/// ([`Operator::IsNull`], [`Operator::NotNull`]) take none, set operators
+/// ([`Operator::In`], [`Operator::NotIn`]) take one or more, and all other operators
+/// take exactly one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate<R> {
+    term: R,
+    op: Operator,
+    literals: Vec<Value>,
+}
+
+impl<R> Predicate<R> {
+    pub(crate) fn new(term: R, op: Operator, literals: Vec<Value>) -> Self {
+        Self {
+            term: term,
+            op: op,
+            literals: literals,
+        }
+    }
+
+    /// The column the predicate applies to.
+    pub fn term(&self) -> &R {
+        &self.term
+    }
+
+    pub fn op(&self) -> Operator {
+        self.op
+    }
+
+    pub fn literals(&self) -> &[Value] {
+        &self.literals
+    }
+
+    /// The single literal of a comparison predicate, or `None` for unary and set
+    /// predicates.
+    pub fn literal(&self) -> Option<&Value> {
+        if self.op.is_unary() || self.op.is_set() {
+            None
+        } else {
+            self.literals.first()
+        }
+    }
+
+    /// Returns the predicate that matches exactly the rows this predicate doesn't.
+    pub fn negate(self) -> Self {
+        Self {
+            term: self.term,
+            op: self.op.negate(),
+            literals: self.literals,
+        }
+    }
+}
+
+/// A boolean expression tree.
+///
+/// `Expression` (with the default [`Reference`] terms) references columns by name,
+/// and is turned into a [`BoundExpression`] with [`Expression::bind`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression<R = Reference> {
+    AlwaysTrue,
+    AlwaysFalse,
+    And(Box<Expression<R>>, Box<Expression<R>>),
+    Or(Box<Expression<R>>, Box<Expression<R>>),
+    Not(Box<Expression<R>>),
+    Predicate(Predicate<R>),
+}
+
+/// An expression whose columns have been resolved against a schema.
+pub type BoundExpression = Expression<BoundReference>;
+
+impl<R> Expression<R> {
+    /// Combines two expressions with a logical and, simplifying away constant
+    /// operands.
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Expression::AlwaysFalse, _) | (_, Expression::AlwaysFalse) => {
+                Expression::AlwaysFalse
+            },
+            (Expression::AlwaysTrue, other) | (other, Expression::AlwaysTrue) => other,
+            (left, right) => Expression::And(Box::new(left), Box::new(right)),
+        }
+    }
+
+    /// Combines two expressions with a logical or, simplifying away constant
+    /// operands.
+    pub fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Expression::AlwaysTrue, _) | (_, Expression::AlwaysTrue) => {
+                Expression::AlwaysTrue
+            },
+            (Expression::AlwaysFalse, other) | (other, Expression::AlwaysFalse) => other,
+            (left, right) => Expression::Or(Box::new(left), Box::new(right)),
+        }
+    }
+
+    /// Returns the logical negation of the expression, with the negation pushed
+    /// down to the predicates so that the result contains no `Not` nodes.
+    pub fn negate(self) -> Self {
+        match self {
+            Expression::AlwaysTrue => Expression::AlwaysFalse,
+            Expression::AlwaysFalse => Expression::AlwaysTrue,
+            Expression::And(left, right) => {
+                Expression::Or(Box::new(left.negate()), Box::new(right.negate()))
+            },
+            Expression::Or(left, right) => {
+                Expression::And(Box::new(left.negate()), Box::new(right.negate()))
+            },
+            Expression::Not(child) => child.rewrite_not(),
+            Expression::Predicate(predicate) => {
+                Expression::Predicate(predicate.negate())
+            },
+        }
+    }
+
+    /// Rewrites the expression to an equivalent one without `Not` nodes, by
+    /// negating the predicates under them.
+    pub fn rewrite_not(self) -> Self {
+        match self {
+            Expression::And(left, right) => {
+                Expression::And(Box::new(left.rewrite_not()), Box::new(right.rewrite_not()))
+            },
+            Expression::Or(left, right) => {
+                Expression::Or(Box::new(left.rewrite_not()), Box::new(right.rewrite_not()))
+            },
+            Expression::Not(child) => child.negate(),
+            other => other,
+        }
+    }
+}
+
+impl<R> std::ops::Not for Expression<R> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        match self {
+            Expression::AlwaysTrue => Expression::AlwaysFalse,
+            Expression::AlwaysFalse => Expression::AlwaysTrue,
+            Expression::Not(child) => *child,
+            other => Expression::Not(Box::new(other)),
+        }
+    }
+}
+
+impl Expression {
+    /// Binds the expression to a schema, resolving every column name to its field
+    /// id and type.
+    ///
+    /// Literals are promoted where Iceberg allows it (`int` to `long`, `float` to
+    /// `double`).
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ExpressionError`] is returned if a column does not exist in
+    /// the schema or is not of a primitive type, or if a literal does not match the
+    /// type of its column.
+    pub fn bind(&self, schema: &Schema) -> IcebergResult<BoundExpression> {
+        match self {
+            Expression::AlwaysTrue => Ok(Expression::AlwaysTrue),
+            Expression::AlwaysFalse => Ok(Expression::AlwaysFalse),
+            Expression::And(left, right) => Ok(Expression::And(
+                Box::new(left.bind(schema)?),
+                Box::new(right.bind(schema)?)
+            )),
+            Expression::Or(left, right) => Ok(Expression::Or(
+                Box::new(left.bind(schema)?),
+                Box::new(right.bind(schema)?)
+            )),
+            Expression::Not(child) => {
+                Ok(Expression::Not(Box::new(child.bind(schema)?)))
+            },
+            Expression::Predicate(predicate) => {
+                Ok(Expression::Predicate(bind_predicate(predicate, schema)?))
+            },
+        }
+    }
+}
+
+fn bind_predicate(
+    predicate: &Predicate<Reference>,
+    schema: &Schema
+) -> IcebergResult<Predicate<BoundReference>> {
+    let name = predicate.term.name();
+    let field = schema.all_fields_by_name()
+        .find(|(field_name, _)| field_name == name)
+        .map(|(_, field)| field)
+        .ok_or_else(|| IcebergError::ExpressionError {
+            message: format!("column '{name}' not found in schema")
+        })?;
+
+    let field_type = match field.schema_type() {
+        SchemaType::Primitive(field_type) => field_type.clone(),
+        _ => {
+            return Err(IcebergError::ExpressionError {
+                message: format!("can't filter on non-primitive column '{name}'")
+            });
+        }
+    };
+
+    let literals = predicate.literals.iter()
+        .map(|literal| bind_literal(literal, &field_type, name))
+        .collect::<IcebergResult<Vec<Value>>>()?;
+
+    Ok(Predicate {
+        term: BoundReference {
+            field_id: field.id,
+            name: name.to_string(),
+            field_type: field_type,
+            required: field.required,
+        },
+        op: predicate.op,
+        literals: literals,
+    })
+}
+
+/// Converts a literal to the type of the column it is compared with.
+fn bind_literal(
+    literal: &Value,
+    field_type: &PrimitiveType,
+    name: &str
+) -> IcebergResult<Value> {
+    match (literal, field_type) {
+        (Value::Int(x), PrimitiveType::Long) => Ok(Value::Long(i64::from(*x))),
+        (Value::Float(x), PrimitiveType::Double) => Ok(Value::Double(f64::from(*x))),
+        _ => {
+            let literal_type = literal.get_type().ok();
+            if literal_type == Some(SchemaType::Primitive(field_type.clone())) {
+                Ok(literal.clone())
+            } else {
+                Err(IcebergError::ExpressionError {
+                    message: format!(
+                        "can't compare column '{name}' of type {field_type} with literal {literal:?}"
+                    )
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::IcebergError;
+    use crate::schema::{Schema, Field, PrimitiveType, SchemaType, StructType};
+    use crate::value::Value;
+    use crate::expr::{col, Expression, Reference, Operator};
+
+    fn create_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "name", false, PrimitiveType::String),
+            Field::new(3, "location", false, SchemaType::Struct(StructType::new(vec![
+                Field::new_primitive(4, "lat", false, PrimitiveType::Double),
+            ]))),
+        ])
+    }
+
+    #[test]
+    fn bind_expression() {
+        let schema = create_schema();
+        let bound = col("id").greater_than(5)
+            .and(col("location.lat").less_than(1.5f32))
+            .bind(&schema)
+            .unwrap();
+
+        let Expression::And(left, right) = bound else {
+            panic!("expected an and expression");
+        };
+
+        let Expression::Predicate(left) = *left else {
+            panic!("expected a predicate");
+        };
+        assert_eq!(left.term().field_id(), 1);
+        assert_eq!(left.term().field_type(), &PrimitiveType::Long);
+        assert!(left.term().required());
+        assert_eq!(left.op(), Operator::Gt);
+        // The int literal is promoted to the column's type.
+        assert_eq!(left.literal(), Some(&Value::Long(5)));
+
+        let Expression::Predicate(right) = *right else {
+            panic!("expected a predicate");
+        };
+        assert_eq!(right.term().field_id(), 4);
+        assert_eq!(right.term().name(), "location.lat");
+        assert_eq!(right.literal(), Some(&Value::Double(1.5)));
+    }
+
+    #[test]
+    fn bind_errors() {
+        let schema = create_schema();
+
+        assert!(matches!(
+            col("missing").is_null().bind(&schema),
+            Err(IcebergError::ExpressionError{..})
+        ));
+        assert!(matches!(
+            col("name").equal_to(5).bind(&schema),
+            Err(IcebergError::ExpressionError{..})
+        ));
+        assert!(matches!(
+            col("id").is_in(["a", "b"]).bind(&schema),
+            Err(IcebergError::ExpressionError{..})
+        ));
+        assert!(matches!(
+            col("location").not_null().bind(&schema),
+            Err(IcebergError::ExpressionError{..})
+        ));
+    }
+
+    #[test]
+    fn negation_normalization() {
+        let expr = !(col("id").less_than(5).or(!col("name").is_null()));
+
+        assert_eq!(
+            expr.rewrite_not(),
+            col("id").greater_than_or_equal_to(5).and(col("name").is_null())
+        );
+
+        assert_eq!(
+            col("id").is_in([1, 2]).negate(),
+            col("id").not_in([1, 2])
+        );
+        assert_eq!(!!col("id").equal_to(1), col("id").equal_to(1));
+        assert_eq!(
+            Expression::<Reference>::AlwaysTrue.negate(),
+            Expression::AlwaysFalse
+        );
+    }
+
+    #[test]
+    fn constant_simplification() {
+        assert_eq!(col("id").is_in(Vec::<i64>::new()), Expression::AlwaysFalse);
+        assert_eq!(
+            Expression::AlwaysTrue.and(col("id").is_null()),
+            col("id").is_null()
+        );
+        assert_eq!(
+            col("id").is_null().or(Expression::AlwaysTrue),
+            Expression::AlwaysTrue
+        );
+    }
+}
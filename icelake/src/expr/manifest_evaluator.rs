@@ -0,0 +1,269 @@
+//! Pruning of manifests using their partition field summaries.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::IcebergResult;
+use crate::partition::PartitionSpec;
+use crate::manifest::{ManifestFile, PartitionFieldSummary};
+use crate::value::Value;
+use crate::expr::{Operator, BoundReference, Predicate, Expression, BoundExpression};
+use crate::expr::projection::project;
+
+/// Decides whether a manifest might contain rows matching a filter, based on the
+/// partition field summaries in the manifest list.
+///
+/// The filter is projected onto the partition fields of the manifest's partition
+/// spec, taking the partition transforms into account. For example, a filter on a
+/// timestamp column is turned into a filter on the days partitioned by a `day`
+/// transform on that column.
+pub struct ManifestEvaluator {
+    partition_filter: BoundExpression,
+    /// Positions of the partition fields in the spec, and hence in the manifest's
+    /// partition summaries, by their partition field id.
+    positions: HashMap<i32, usize>,
+}
+
+impl ManifestEvaluator {
+    /// Creates an evaluator for manifests written with the partition spec `spec`,
+    /// given a filter bound to the table's schema.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::PartitionError`](crate::IcebergError::PartitionError) is
+    /// returned if a literal of the filter can't be transformed by the transform of
+    /// its partition field.
+    pub fn new(spec: &PartitionSpec, filter: &BoundExpression) -> IcebergResult<Self> {
+        Ok(Self {
+            partition_filter: project(filter, spec)?,
+            positions: spec.fields().iter()
+                .enumerate()
+                .map(|(position, field)| (field.field_id, position))
+                .collect(),
+        })
+    }
+
+    /// Returns `false` if the manifest is guaranteed to contain no rows matching the
+    /// filter, and `true` if it might contain matching rows.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`](crate::IcebergError::ValueError) is returned if
+    /// one of the manifest's partition bounds can't be decoded.
+    pub fn eval(&self, manifest_file: &ManifestFile) -> IcebergResult<bool> {
+        match &manifest_file.partitions {
+            Some(summaries) => self.eval_expression(&self.partition_filter, summaries),
+            // Nothing is known about the manifest's partitions.
+            None => Ok(true),
+        }
+    }
+
+    fn eval_expression(
+        &self,
+        expr: &BoundExpression,
+        summaries: &[PartitionFieldSummary]
+    ) -> IcebergResult<bool> {
+        match expr {
+            Expression::AlwaysTrue => Ok(true),
+            Expression::AlwaysFalse => Ok(false),
+            Expression::And(left, right) => {
+                Ok(self.eval_expression(left, summaries)?
+                    && self.eval_expression(right, summaries)?)
+            },
+            Expression::Or(left, right) => {
+                Ok(self.eval_expression(left, summaries)?
+                    || self.eval_expression(right, summaries)?)
+            },
+            // Projected filters contain no negations.
+            Expression::Not(_) => Ok(true),
+            Expression::Predicate(predicate) => {
+                let summary = self.positions.get(&predicate.term().field_id())
+                    .and_then(|position| summaries.get(*position));
+                match summary {
+                    Some(summary) => eval_predicate(predicate, summary),
+                    None => Ok(true),
+                }
+            }
+        }
+    }
+}
+
+fn eval_predicate(
+    predicate: &Predicate<BoundReference>,
+    summary: &PartitionFieldSummary
+) -> IcebergResult<bool> {
+    let field_type = predicate.term().field_type();
+    let lower = summary.lower_bound()
+        .map(|bytes| Value::from_bytes(bytes, field_type))
+        .transpose()?;
+    let upper = summary.upper_bound()
+        .map(|bytes| Value::from_bytes(bytes, field_type))
+        .transpose()?;
+
+    // Bounds are only missing if all values are null or NaN.
+    let (lower, upper) = match (predicate.op(), lower, upper) {
+        (Operator::IsNull, _, _) => return Ok(summary.contains_null()),
+        (Operator::NotNull, lower, _) => {
+            return Ok(lower.is_some() || summary.contains_nan() != Some(false)
+                || !summary.contains_null());
+        },
+        // Skipping would require every value within the bounds to be excluded,
+        // which the bounds can rarely prove.
+        (Operator::NotEq | Operator::NotIn, _, _) => return Ok(true),
+        (_, Some(lower), Some(upper)) => (lower, upper),
+        _ => return Ok(false),
+    };
+
+    // Incomparable values can't be used for pruning.
+    let cmp = |a: &Value, b: &Value| a.partial_cmp(b);
+    let in_range = |literal: &Value| {
+        !matches!(cmp(literal, &lower), Some(Ordering::Less))
+            && !matches!(cmp(literal, &upper), Some(Ordering::Greater))
+    };
+    let literals = predicate.literals();
+
+    let might_match = match predicate.op() {
+        Operator::Lt => literals.iter().all(|literal| {
+            matches!(cmp(&lower, literal), Some(Ordering::Less) | None)
+        }),
+        Operator::LtEq => literals.iter().all(|literal| {
+            !matches!(cmp(&lower, literal), Some(Ordering::Greater))
+        }),
+        Operator::Gt => literals.iter().all(|literal| {
+            matches!(cmp(&upper, literal), Some(Ordering::Greater) | None)
+        }),
+        Operator::GtEq => literals.iter().all(|literal| {
+            !matches!(cmp(&upper, literal), Some(Ordering::Less))
+        }),
+        Operator::Eq | Operator::In => literals.iter().any(in_range),
+        _ => true,
+    };
+
+    Ok(might_match)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::schema::{Schema, Field, PrimitiveType};
+    use crate::partition::{PartitionSpec, PartitionField, PartitionTransform};
+    use crate::manifest::{ManifestFile, ManifestFileType, PartitionFieldSummary};
+    use crate::value::Value;
+    use crate::expr::{col, Expression, ManifestEvaluator};
+
+    fn create_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "ts", false, PrimitiveType::Timestamp),
+        ])
+    }
+
+    fn create_spec() -> PartitionSpec {
+        PartitionSpec::try_new(0, vec![
+            PartitionField::new(2, 1000, "ts_day", PartitionTransform::Day),
+        ], create_schema()).unwrap()
+    }
+
+    fn day(month: u32, day: u32) -> Value {
+        Value::Date(NaiveDate::from_ymd_opt(2023, month, day).unwrap())
+    }
+
+    /// A manifest containing the partitions for the days between `lower` and `upper`.
+    fn manifest_file(
+        lower: Option<Value>,
+        upper: Option<Value>,
+        contains_null: bool
+    ) -> ManifestFile {
+        let day_ordinal = |value: Option<Value>| -> Option<Vec<u8>> {
+            let value = PartitionTransform::Day.apply(value).unwrap()?;
+            Some(value.try_into().unwrap())
+        };
+
+        ManifestFile {
+            manifest_path: "/tmp/manifest.avro".to_string(),
+            manifest_length: 4096,
+            partition_spec_id: 0,
+            content: ManifestFileType::Data,
+            sequence_number: 1,
+            min_sequence_number: 1,
+            added_snapshot_id: 1,
+            added_data_files_count: 1,
+            existing_data_files_count: 0,
+            deleted_data_files_count: 0,
+            added_rows_count: 100,
+            existing_rows_count: 0,
+            deleted_rows_count: 0,
+            partitions: Some(vec![PartitionFieldSummary::new(
+                contains_null,
+                Some(false),
+                day_ordinal(lower),
+                day_ordinal(upper)
+            )]),
+        }
+    }
+
+    fn timestamp(month: u32, day: u32, hour: u32) -> chrono::NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap()
+            .and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn might_match(filter: Expression, manifest_file: &ManifestFile) -> bool {
+        let filter = filter.bind(&create_schema()).unwrap();
+        ManifestEvaluator::new(&create_spec(), &filter).unwrap()
+            .eval(manifest_file)
+            .unwrap()
+    }
+
+    #[test]
+    fn prune_disjoint_date_range() {
+        // Partitions for March 2023.
+        let march = manifest_file(Some(day(3, 1)), Some(day(3, 31)), false);
+
+        // Filters entirely within April can't match.
+        let april = col("ts").greater_than_or_equal_to(timestamp(4, 1, 0))
+            .and(col("ts").less_than(timestamp(5, 1, 0)));
+        assert!(!might_match(april, &march));
+
+        // A range overlapping the last day of March might match.
+        let overlapping = col("ts").greater_than(timestamp(3, 31, 12))
+            .and(col("ts").less_than(timestamp(4, 15, 0)));
+        assert!(might_match(overlapping, &march));
+
+        assert!(!might_match(col("ts").less_than(timestamp(3, 1, 0)), &march));
+        assert!(might_match(col("ts").less_than(timestamp(3, 1, 6)), &march));
+        assert!(might_match(col("ts").equal_to(timestamp(3, 15, 6)), &march));
+        assert!(!might_match(
+            col("ts").is_in([timestamp(2, 1, 0), timestamp(4, 1, 0)]),
+            &march
+        ));
+        assert!(!might_match(
+            col("ts").less_than(timestamp(2, 1, 0))
+                .or(col("ts").greater_than(timestamp(4, 1, 0))),
+            &march
+        ));
+
+        // Negated predicates are pushed down before pruning.
+        let before_march = !col("ts").greater_than_or_equal_to(timestamp(3, 1, 0));
+        assert!(!might_match(before_march, &march));
+    }
+
+    #[test]
+    fn prune_nulls() {
+        let march = manifest_file(Some(day(3, 1)), Some(day(3, 31)), false);
+        let only_nulls = manifest_file(None, None, true);
+
+        assert!(!might_match(col("ts").is_null(), &march));
+        assert!(might_match(col("ts").is_null(), &only_nulls));
+        assert!(might_match(col("ts").not_null(), &march));
+        assert!(!might_match(col("ts").not_null(), &only_nulls));
+        assert!(!might_match(col("ts").equal_to(timestamp(3, 15, 0)), &only_nulls));
+    }
+
+    #[test]
+    fn unpartitioned_columns_are_not_pruned() {
+        let march = manifest_file(Some(day(3, 1)), Some(day(3, 31)), false);
+
+        assert!(might_match(col("id").equal_to(5i64), &march));
+        assert!(might_match(col("ts").not_equal_to(timestamp(3, 1, 0)), &march));
+    }
+}
@@ -0,0 +1,257 @@
+//! Pruning of data files using their column metrics.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::IcebergResult;
+use crate::manifest::DataFile;
+use crate::value::Value;
+use crate::expr::{Operator, BoundReference, Predicate, Expression, BoundExpression};
+
+/// Decides whether a data file might contain rows matching a filter, based on the
+/// column metrics stored for it in the manifest: value counts, null value counts,
+/// NaN value counts, and lower and upper bounds.
+///
+/// The evaluation is inclusive: `false` is only returned if the file is guaranteed to
+/// contain no matching rows. Missing metrics never cause a file to be pruned.
+pub struct InclusiveMetricsEvaluator {
+    filter: BoundExpression,
+}
+
+impl InclusiveMetricsEvaluator {
+    /// Creates an evaluator for a filter bound to the table's schema.
+    pub fn new(filter: &BoundExpression) -> Self {
+        Self {
+            filter: filter.clone().rewrite_not(),
+        }
+    }
+
+    /// Returns `false` if the data file is guaranteed to contain no rows matching the
+    /// filter, and `true` if it might contain matching rows.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`](crate::IcebergError::ValueError) is returned if
+    /// one of the file's bounds can't be decoded as a value of its column's type.
+    pub fn eval(&self, data_file: &DataFile) -> IcebergResult<bool> {
+        if data_file.record_count <= 0 {
+            return Ok(false);
+        }
+
+        eval_expression(&self.filter, data_file)
+    }
+}
+
+fn eval_expression(expr: &BoundExpression, data_file: &DataFile) -> IcebergResult<bool> {
+    match expr {
+        Expression::AlwaysTrue => Ok(true),
+        Expression::AlwaysFalse => Ok(false),
+        Expression::And(left, right) => {
+            Ok(eval_expression(left, data_file)? && eval_expression(right, data_file)?)
+        },
+        Expression::Or(left, right) => {
+            Ok(eval_expression(left, data_file)? || eval_expression(right, data_file)?)
+        },
+        // Negations were rewritten away, so this can't be reached.
+        Expression::Not(_) => Ok(true),
+        Expression::Predicate(predicate) => eval_predicate(predicate, data_file),
+    }
+}
+
+fn count(counts: &Option<HashMap<i32, i64>>, field_id: i32) -> Option<i64> {
+    counts.as_ref().and_then(|counts| counts.get(&field_id)).copied()
+}
+
+fn bound(
+    bounds: &Option<HashMap<i32, Vec<u8>>>,
+    term: &BoundReference
+) -> IcebergResult<Option<Value>> {
+    bounds.as_ref()
+        .and_then(|bounds| bounds.get(&term.field_id()))
+        .map(|bytes| Value::from_bytes(bytes, term.field_type()))
+        .transpose()
+}
+
+fn eval_predicate(
+    predicate: &Predicate<BoundReference>,
+    data_file: &DataFile
+) -> IcebergResult<bool> {
+    let term = predicate.term();
+    let field_id = term.field_id();
+
+    let value_count = count(&data_file.value_counts, field_id);
+    let null_count = count(&data_file.null_value_counts, field_id);
+    let nan_count = count(&data_file.nan_value_counts, field_id);
+
+    let nulls_only = value_count.is_some() && value_count == null_count;
+    let nans_only = value_count.is_some() && value_count == nan_count;
+
+    match predicate.op() {
+        Operator::IsNull => return Ok(null_count != Some(0)),
+        Operator::NotNull => return Ok(!nulls_only),
+        // A file could only be skipped if all of its values were excluded, which
+        // the bounds can rarely prove, so these always might match.
+        Operator::NotEq | Operator::NotIn => return Ok(true),
+        _ => {}
+    }
+
+    // Nulls and NaNs never match a comparison.
+    if nulls_only || nans_only {
+        return Ok(false);
+    }
+
+    let lower = bound(&data_file.lower_bounds, term)?;
+    let upper = bound(&data_file.upper_bounds, term)?;
+
+    // Incomparable values, such as NaNs, can't be used for pruning.
+    let cmp = |a: &Option<Value>, b: &Value| {
+        a.as_ref().and_then(|a| a.partial_cmp(b))
+    };
+    let in_range = |literal: &Value| {
+        !matches!(cmp(&lower, literal), Some(Ordering::Greater))
+            && !matches!(cmp(&upper, literal), Some(Ordering::Less))
+    };
+    let literals = predicate.literals();
+
+    let might_match = match predicate.op() {
+        Operator::Lt => literals.iter().all(|literal| {
+            !matches!(cmp(&lower, literal), Some(Ordering::Greater | Ordering::Equal))
+        }),
+        Operator::LtEq => literals.iter().all(|literal| {
+            !matches!(cmp(&lower, literal), Some(Ordering::Greater))
+        }),
+        Operator::Gt => literals.iter().all(|literal| {
+            !matches!(cmp(&upper, literal), Some(Ordering::Less | Ordering::Equal))
+        }),
+        Operator::GtEq => literals.iter().all(|literal| {
+            !matches!(cmp(&upper, literal), Some(Ordering::Less))
+        }),
+        Operator::Eq | Operator::In => literals.iter().any(in_range),
+        _ => true,
+    };
+
+    Ok(might_match)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::schema::{Schema, Field, PrimitiveType};
+    use crate::manifest::{DataFile, DataFileContent, DataFileFormat};
+    use crate::value::Value;
+    use crate::expr::{col, Expression, BoundExpression, InclusiveMetricsEvaluator};
+
+    fn create_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Int),
+            Field::new_primitive(2, "name", false, PrimitiveType::String),
+            Field::new_primitive(3, "all_nulls", false, PrimitiveType::Long),
+            Field::new_primitive(4, "no_stats", false, PrimitiveType::Long),
+            Field::new_primitive(5, "all_nans", false, PrimitiveType::Double),
+        ])
+    }
+
+    fn bytes(value: Value) -> Vec<u8> {
+        value.try_into().unwrap()
+    }
+
+    /// A file with 50 rows, with ids between 30 and 79 and names between "b" and "m".
+    fn create_data_file() -> DataFile {
+        DataFile::builder(
+            DataFileContent::Data,
+            "/tmp/data_file.parquet",
+            DataFileFormat::Parquet,
+            50,
+            5000
+        ).with_value_counts(HashMap::from([(1, 50), (2, 50), (3, 50), (5, 50)]))
+            .with_null_value_counts(HashMap::from([(1, 0), (2, 10), (3, 50), (5, 0)]))
+            .with_nan_value_counts(HashMap::from([(5, 50)]))
+            .with_lower_bounds(HashMap::from([
+                (1, bytes(Value::Int(30))),
+                (2, bytes(Value::String("b".to_string()))),
+            ]))
+            .with_upper_bounds(HashMap::from([
+                (1, bytes(Value::Int(79))),
+                (2, bytes(Value::String("m".to_string()))),
+            ]))
+            .build()
+    }
+
+    fn might_match(filter: Expression) -> bool {
+        let filter = filter.bind(&create_schema()).unwrap();
+        InclusiveMetricsEvaluator::new(&filter).eval(&create_data_file()).unwrap()
+    }
+
+    #[test]
+    fn null_counts() {
+        assert!(!might_match(col("id").is_null()));
+        assert!(might_match(col("name").is_null()));
+        assert!(might_match(col("no_stats").is_null()));
+
+        assert!(might_match(col("id").not_null()));
+        assert!(!might_match(col("all_nulls").not_null()));
+        assert!(might_match(col("no_stats").not_null()));
+
+        // Comparisons never match nulls or NaNs.
+        assert!(!might_match(col("all_nulls").equal_to(1i64)));
+        assert!(!might_match(col("all_nulls").greater_than(1i64)));
+        assert!(!might_match(col("all_nans").less_than(1.0f64)));
+        assert!(might_match(col("all_nulls").not_equal_to(1i64)));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(!might_match(col("id").less_than(30)));
+        assert!(might_match(col("id").less_than(31)));
+        assert!(!might_match(col("id").less_than_or_equal_to(29)));
+        assert!(might_match(col("id").less_than_or_equal_to(30)));
+
+        assert!(!might_match(col("id").greater_than(79)));
+        assert!(might_match(col("id").greater_than(78)));
+        assert!(!might_match(col("id").greater_than_or_equal_to(80)));
+        assert!(might_match(col("id").greater_than_or_equal_to(79)));
+
+        assert!(!might_match(col("name").less_than("b")));
+        assert!(might_match(col("name").greater_than("l")));
+
+        // No bounds are known for the column.
+        assert!(might_match(col("no_stats").less_than(0i64)));
+    }
+
+    #[test]
+    fn equality() {
+        assert!(!might_match(col("id").equal_to(29)));
+        assert!(might_match(col("id").equal_to(30)));
+        assert!(might_match(col("id").equal_to(79)));
+        assert!(!might_match(col("id").equal_to(80)));
+        assert!(might_match(col("id").not_equal_to(30)));
+        assert!(!might_match(col("name").equal_to("z")));
+
+        assert!(!might_match(col("id").is_in([1, 2, 100])));
+        assert!(might_match(col("id").is_in([1, 50])));
+        assert!(might_match(col("id").not_in([30, 31])));
+    }
+
+    #[test]
+    fn logical_operators() {
+        assert!(!might_match(col("id").less_than(30).and(col("name").is_null())));
+        assert!(might_match(col("id").less_than(30).or(col("name").is_null())));
+        // Negations are pushed down before evaluation.
+        assert!(!might_match(!col("id").greater_than_or_equal_to(30)));
+        assert!(might_match(!col("id").is_null()));
+    }
+
+    #[test]
+    fn empty_file() {
+        let data_file = DataFile::builder(
+            DataFileContent::Data,
+            "/tmp/data_file.parquet",
+            DataFileFormat::Parquet,
+            0,
+            0
+        ).build();
+        let filter: BoundExpression = Expression::AlwaysTrue;
+
+        assert!(!InclusiveMetricsEvaluator::new(&filter).eval(&data_file).unwrap());
+    }
+}
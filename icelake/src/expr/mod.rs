@@ -0,0 +1,30 @@
+//! Boolean expressions for filtering table scans.
+//!
+//! Expressions are built against column names with [`col`], and must be bound to a
+//! [`Schema`](crate::schema::Schema) with [`Expression::bind`] before they can be
+//! evaluated. Binding resolves every column name to its field id and type, and checks
+//! that the literals of each predicate match the type of the column.
+//!
+//! ```rust
+//! use icelake::expr::col;
+//!
+//! let filter = col("id").greater_than(5).and(col("name").not_null());
+//! ```
+
+pub mod expression;
+pub(crate) mod projection;
+mod manifest_evaluator;
+mod metrics_evaluator;
+mod bloom_filter_evaluator;
+mod row_evaluator;
+mod residual_evaluator;
+
+pub use crate::expr::expression::{
+    col, Operator, Reference, BoundReference, Predicate, Expression, BoundExpression
+};
+pub use crate::expr::manifest_evaluator::ManifestEvaluator;
+pub use crate::expr::metrics_evaluator::InclusiveMetricsEvaluator;
+pub use crate::expr::bloom_filter_evaluator::BloomFilterEvaluator;
+pub use crate::expr::row_evaluator::RowEvaluator;
+pub use crate::expr::residual_evaluator::ResidualEvaluator;
+pub(crate) use crate::expr::row_evaluator::literal_array;
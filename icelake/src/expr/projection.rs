@@ -0,0 +1,352 @@
+//! Projection of row filters onto partition values.
+//!
+//! An inclusive projection of a row filter is a filter on partition values that
+//! matches every partition that might contain a row matching the row filter.
+//! Partitions for which the projected filter is false are guaranteed to contain no
+//! matching rows.
+//!
+//! A strict projection only matches partitions whose rows all match the row filter.
+//! Partitions for which it is true are guaranteed to contain only matching rows.
+use chrono::Duration;
+
+use crate::IcebergResult;
+use crate::schema::SchemaType;
+use crate::partition::{PartitionSpec, PartitionField, PartitionTransform};
+use crate::value::Value;
+use crate::expr::{Operator, BoundReference, Predicate, Expression, BoundExpression};
+
+/// Projects a filter bound to the table's schema onto the partition fields of
+/// `spec`. The resulting expression references the partition fields by their
+/// partition field ids and result types.
+pub(crate) fn project(
+    filter: &BoundExpression,
+    spec: &PartitionSpec
+) -> IcebergResult<BoundExpression> {
+    project_inner(&filter.clone().rewrite_not(), spec)
+}
+
+fn project_inner(
+    filter: &BoundExpression,
+    spec: &PartitionSpec
+) -> IcebergResult<BoundExpression> {
+    match filter {
+        Expression::AlwaysTrue => Ok(Expression::AlwaysTrue),
+        Expression::AlwaysFalse => Ok(Expression::AlwaysFalse),
+        Expression::And(left, right) => {
+            Ok(project_inner(left, spec)?.and(project_inner(right, spec)?))
+        },
+        Expression::Or(left, right) => {
+            Ok(project_inner(left, spec)?.or(project_inner(right, spec)?))
+        },
+        // Negations were rewritten away, so this can't be reached. Keeping all
+        // partitions is always correct.
+        Expression::Not(_) => Ok(Expression::AlwaysTrue),
+        Expression::Predicate(predicate) => {
+            // Every partition field derived from the column restricts the matching
+            // partitions further.
+            let mut projected = Expression::AlwaysTrue;
+            for field in spec.fields() {
+                if field.source_id != predicate.term().field_id() {
+                    continue;
+                }
+
+                let projection =
+                    project_predicate(predicate, field, Projection::Inclusive)?;
+                if let Some(projection) = projection {
+                    projected = projected.and(Expression::Predicate(projection));
+                }
+            }
+            Ok(projected)
+        },
+    }
+}
+
+/// The kind of projection of a predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Projection {
+    /// Matches every partition that might contain matching rows.
+    Inclusive,
+    /// Matches only partitions whose rows all match.
+    Strict,
+}
+
+/// Projects a single predicate onto a partition field, or returns `None` if the
+/// predicate doesn't reference the partition field's source column or no sound
+/// projection exists.
+pub(crate) fn project_predicate(
+    predicate: &Predicate<BoundReference>,
+    field: &PartitionField,
+    projection: Projection
+) -> IcebergResult<Option<Predicate<BoundReference>>> {
+    if field.source_id != predicate.term().field_id() {
+        return Ok(None);
+    }
+
+    let result_type = field.transform.get_result_type(
+        SchemaType::Primitive(predicate.term().field_type().clone())
+    );
+    let result_type = match result_type {
+        Ok(SchemaType::Primitive(result_type)) => result_type,
+        // Transforms that aren't supported yet can't be used for pruning.
+        _ => return Ok(None),
+    };
+
+    let term = BoundReference::new(field.field_id, &field.name, result_type, false);
+    let op = predicate.op();
+
+    // Null values are always transformed to nulls, and non-null values to non-nulls.
+    if op.is_unary() {
+        return match field.transform {
+            PartitionTransform::Void => Ok(None),
+            _ => Ok(Some(Predicate::new(term, op, Vec::new()))),
+        };
+    }
+
+    // The remaining transforms preserve order but not equality, so distinct values
+    // may share a partition. Bounds are moved to the adjacent value where one exists,
+    // which keeps the projection as tight as possible; without one, e.g. for
+    // truncated strings, the bound on the value itself is still sound.
+    let (op, offset) = match (&field.transform, projection) {
+        (PartitionTransform::Void, _) => return Ok(None),
+        (PartitionTransform::Identity, _) => (op, 0),
+        // Buckets don't preserve order, and a bucket that contains a value may also
+        // contain other values.
+        (PartitionTransform::Bucket(_), Projection::Inclusive) => {
+            match op {
+                Operator::Eq | Operator::In => (op, 0),
+                _ => return Ok(None),
+            }
+        },
+        (PartitionTransform::Bucket(_), Projection::Strict) => {
+            match op {
+                Operator::NotEq | Operator::NotIn => (op, 0),
+                _ => return Ok(None),
+            }
+        },
+        (_, Projection::Inclusive) => {
+            match op {
+                Operator::Eq | Operator::In | Operator::LtEq | Operator::GtEq => (op, 0),
+                Operator::Lt => (Operator::LtEq, -1),
+                Operator::Gt => (Operator::GtEq, 1),
+                _ => return Ok(None),
+            }
+        },
+        (_, Projection::Strict) => {
+            match op {
+                Operator::NotEq | Operator::NotIn
+                | Operator::Lt | Operator::Gt => (op, 0),
+                Operator::LtEq => (Operator::Lt, 1),
+                Operator::GtEq => (Operator::Gt, -1),
+                _ => return Ok(None),
+            }
+        },
+    };
+
+    let mut literals = Vec::with_capacity(predicate.literals().len());
+    for literal in predicate.literals() {
+        let literal = adjacent(literal, offset).unwrap_or_else(|| literal.clone());
+        if let Some(value) = field.transform.apply(Some(literal))? {
+            if !literals.contains(&value) {
+                literals.push(value);
+            }
+        }
+    }
+
+    Ok(Some(Predicate::new(term, op, literals)))
+}
+
+/// Returns the integer, date or timestamp `offset` units, days or microseconds away
+/// from `value`, or `None` if it can't be computed.
+fn adjacent(value: &Value, offset: i64) -> Option<Value> {
+    if offset == 0 {
+        return Some(value.clone());
+    }
+
+    match value {
+        Value::Int(i) => {
+            i.checked_add(i32::try_from(offset).ok()?).map(Value::Int)
+        },
+        Value::Long(l) => l.checked_add(offset).map(Value::Long),
+        Value::Date(date) => {
+            date.checked_add_signed(Duration::days(offset)).map(Value::Date)
+        },
+        Value::Timestamp(timestamp) => {
+            timestamp.checked_add_signed(Duration::microseconds(offset))
+                .map(Value::Timestamp)
+        },
+        Value::Timestamptz(timestamptz) => {
+            timestamptz.checked_add_signed(Duration::microseconds(offset))
+                .map(Value::Timestamptz)
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::schema::{Schema, Field, PrimitiveType};
+    use crate::partition::{PartitionSpec, PartitionField, PartitionTransform};
+    use crate::value::Value;
+    use crate::expr::{col, Expression, Operator, BoundReference, Predicate};
+    use crate::expr::projection::project;
+
+    fn create_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "ts", false, PrimitiveType::Timestamp),
+            Field::new_primitive(3, "name", false, PrimitiveType::String),
+        ])
+    }
+
+    fn bind_predicate(filter: Expression) -> Predicate<BoundReference> {
+        match filter.bind(&create_schema()).unwrap() {
+            Expression::Predicate(predicate) => predicate,
+            _ => panic!("expected a predicate"),
+        }
+    }
+
+    fn create_spec() -> PartitionSpec {
+        PartitionSpec::try_new(0, vec![
+            PartitionField::new(2, 1000, "ts_day", PartitionTransform::Day),
+            PartitionField::new(1, 1001, "id_bucket", PartitionTransform::Bucket(16)),
+        ], create_schema()).unwrap()
+    }
+
+    #[test]
+    fn project_day_transform() {
+        let ts = NaiveDate::from_ymd_opt(1970, 1, 3).unwrap()
+            .and_hms_opt(12, 0, 0).unwrap();
+        let filter = col("ts").greater_than(ts).bind(&create_schema()).unwrap();
+
+        let Expression::Predicate(projected) = project(&filter, &create_spec()).unwrap()
+        else {
+            panic!("expected a predicate");
+        };
+
+        assert_eq!(projected.term().field_id(), 1000);
+        assert_eq!(projected.term().field_type(), &PrimitiveType::Int);
+        // The day of the timestamp may still contain matching rows.
+        assert_eq!(projected.op(), Operator::GtEq);
+        assert_eq!(projected.literals(), &[Value::Int(2)]);
+    }
+
+    #[test]
+    fn project_bucket_transform() {
+        let schema = create_schema();
+        let spec = create_spec();
+
+        let filter = col("id").equal_to(5i64).bind(&schema).unwrap();
+        let Expression::Predicate(projected) = project(&filter, &spec).unwrap() else {
+            panic!("expected a predicate");
+        };
+        assert_eq!(projected.term().field_id(), 1001);
+        let bucket = PartitionTransform::Bucket(16).bucket_of(&Value::Long(5)).unwrap();
+        assert_eq!(projected.literals(), &[Value::Int(bucket)]);
+
+        // Range predicates can't be projected onto buckets.
+        let filter = col("id").less_than(5i64).bind(&schema).unwrap();
+        assert_eq!(project(&filter, &spec).unwrap(), Expression::AlwaysTrue);
+
+        // Negations are pushed down before projecting.
+        let filter = (!col("id").not_equal_to(5i64)).bind(&schema).unwrap();
+        assert!(matches!(
+            project(&filter, &spec).unwrap(),
+            Expression::Predicate(p) if p.op() == Operator::Eq
+        ));
+    }
+
+    #[test]
+    fn project_identity_field() {
+        let field = PartitionField::new(1, 1000, "id", PartitionTransform::Identity);
+
+        let predicate = bind_predicate(col("id").less_than(5i64));
+        let projected = field.project(&predicate).unwrap();
+        assert_eq!(projected.term().field_id(), 1000);
+        assert_eq!(projected.op(), Operator::Lt);
+        assert_eq!(projected.literals(), &[Value::Long(5)]);
+        assert_eq!(field.project_strict(&predicate), Some(projected));
+
+        // Predicates on other columns aren't projected.
+        let predicate = bind_predicate(col("name").equal_to("abc"));
+        assert_eq!(field.project(&predicate), None);
+    }
+
+    #[test]
+    fn project_bucket_field() {
+        let transform = PartitionTransform::Bucket(16);
+        let bucket = Value::Int(transform.bucket_of(&Value::Long(5)).unwrap());
+        let field = PartitionField::new(1, 1000, "id_bucket", transform);
+
+        let predicate = bind_predicate(col("id").equal_to(5i64));
+        let projected = field.project(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::Eq);
+        assert_eq!(projected.literals(), &[bucket.clone()]);
+        // Other ids may share the bucket.
+        assert_eq!(field.project_strict(&predicate), None);
+
+        // A row outside the bucket of 5 can't be 5.
+        let predicate = bind_predicate(col("id").not_equal_to(5i64));
+        assert_eq!(field.project(&predicate), None);
+        let projected = field.project_strict(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::NotEq);
+        assert_eq!(projected.literals(), &[bucket]);
+    }
+
+    #[test]
+    fn project_day_range() {
+        let field = PartitionField::new(2, 1000, "ts_day", PartitionTransform::Day);
+        let start = NaiveDate::from_ymd_opt(1970, 1, 2).unwrap()
+            .and_hms_opt(0, 0, 0).unwrap();
+        let end = NaiveDate::from_ymd_opt(1970, 1, 5).unwrap()
+            .and_hms_opt(0, 0, 0).unwrap();
+
+        // ts >= 1970-01-02: day 1 may contain matching rows, and all its rows match.
+        let predicate = bind_predicate(col("ts").greater_than_or_equal_to(start));
+        let projected = field.project(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::GtEq);
+        assert_eq!(projected.literals(), &[Value::Int(1)]);
+        let projected = field.project_strict(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::Gt);
+        assert_eq!(projected.literals(), &[Value::Int(0)]);
+
+        // ts < 1970-01-05: day 4 contains no matching rows.
+        let predicate = bind_predicate(col("ts").less_than(end));
+        let projected = field.project(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::LtEq);
+        assert_eq!(projected.literals(), &[Value::Int(3)]);
+        let projected = field.project_strict(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::Lt);
+        assert_eq!(projected.literals(), &[Value::Int(4)]);
+
+        // Days contain other timestamps too.
+        let predicate = bind_predicate(col("ts").equal_to(start));
+        assert!(field.project(&predicate).is_some());
+        assert_eq!(field.project_strict(&predicate), None);
+    }
+
+    #[test]
+    fn project_truncate_field() {
+        let transform = PartitionTransform::Truncate(2);
+        let field = PartitionField::new(3, 1000, "name_trunc", transform);
+
+        let predicate = bind_predicate(col("name").equal_to("abc"));
+        let projected = field.project(&predicate).unwrap();
+        assert_eq!(projected.literals(), &[Value::String("ab".to_string())]);
+        // "abd" is in the same partition.
+        assert_eq!(field.project_strict(&predicate), None);
+
+        // Partitions before "ab" only contain names before "abc", but partition "ab"
+        // contains names on both sides of "abc".
+        let predicate = bind_predicate(col("name").less_than_or_equal_to("abc"));
+        let projected = field.project(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::LtEq);
+        let projected = field.project_strict(&predicate).unwrap();
+        assert_eq!(projected.op(), Operator::Lt);
+        assert_eq!(projected.literals(), &[Value::String("ab".to_string())]);
+
+        let predicate = bind_predicate(col("name").not_equal_to("abc"));
+        assert_eq!(field.project(&predicate), None);
+    }
+}
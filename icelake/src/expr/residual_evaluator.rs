@@ -0,0 +1,233 @@
+//! Residual filters of data files after partition pruning.
+use std::cmp::Ordering;
+
+use crate::IcebergResult;
+use crate::partition::{PartitionSpec, PartitionValues};
+use crate::value::Value;
+use crate::expr::{Operator, BoundReference, Predicate, Expression, BoundExpression};
+use crate::expr::projection::{project_predicate, Projection};
+
+/// Computes the residual of a filter for the rows of a partition: the part of the
+/// filter that isn't already decided by the partition values.
+///
+/// A predicate whose strict projection matches the partition holds for every row of
+/// the partition and is simplified to `AlwaysTrue`, and a predicate whose inclusive
+/// projection doesn't match holds for none of them and is simplified to
+/// `AlwaysFalse`. For example, with a `day` partition on `ts`, the filter
+/// `ts >= '2023-04-01T00:00:00' AND name = 'a'` has the residual `name = 'a'` in
+/// April partitions, and is always false in March partitions.
+pub struct ResidualEvaluator {
+    spec: PartitionSpec,
+    filter: BoundExpression,
+}
+
+impl ResidualEvaluator {
+    /// Creates an evaluator for partitions of the partition spec `spec`, given a
+    /// filter bound to the table's schema.
+    pub fn new(spec: &PartitionSpec, filter: &BoundExpression) -> Self {
+        Self {
+            spec: spec.clone(),
+            filter: filter.clone().rewrite_not(),
+        }
+    }
+
+    /// Returns the residual filter for the rows of the partition with the given
+    /// values.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::PartitionError`](crate::IcebergError::PartitionError) is
+    /// returned if a literal of the filter can't be transformed by the transform of
+    /// its partition field.
+    pub fn residual_for(
+        &self,
+        partition: &PartitionValues
+    ) -> IcebergResult<BoundExpression> {
+        self.residual(&self.filter, partition)
+    }
+
+    fn residual(
+        &self,
+        expr: &BoundExpression,
+        partition: &PartitionValues
+    ) -> IcebergResult<BoundExpression> {
+        match expr {
+            Expression::AlwaysTrue => Ok(Expression::AlwaysTrue),
+            Expression::AlwaysFalse => Ok(Expression::AlwaysFalse),
+            Expression::And(left, right) => {
+                Ok(self.residual(left, partition)?.and(self.residual(right, partition)?))
+            },
+            Expression::Or(left, right) => {
+                Ok(self.residual(left, partition)?.or(self.residual(right, partition)?))
+            },
+            // Negations were rewritten away, so this can't be reached. Keeping the
+            // expression is always correct.
+            Expression::Not(_) => Ok(expr.clone()),
+            Expression::Predicate(predicate) => {
+                self.residual_predicate(predicate, partition)
+            },
+        }
+    }
+
+    fn residual_predicate(
+        &self,
+        predicate: &Predicate<BoundReference>,
+        partition: &PartitionValues
+    ) -> IcebergResult<BoundExpression> {
+        for field in self.spec.fields() {
+            if field.source_id != predicate.term().field_id() {
+                continue;
+            }
+
+            let value = partition.values().iter()
+                .find(|(name, _)| *name == field.name)
+                .map(|(_, value)| value.as_ref());
+            let value = match value {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let strict = project_predicate(predicate, field, Projection::Strict)?;
+            if strict.is_some_and(|strict| eval_predicate(&strict, value) == Some(true)) {
+                return Ok(Expression::AlwaysTrue);
+            }
+
+            let inclusive = project_predicate(predicate, field, Projection::Inclusive)?;
+            if inclusive.is_some_and(|inclusive| {
+                eval_predicate(&inclusive, value) == Some(false)
+            }) {
+                return Ok(Expression::AlwaysFalse);
+            }
+        }
+
+        Ok(Expression::Predicate(predicate.clone()))
+    }
+}
+
+/// Evaluates a projected predicate on a partition value, or returns `None` if the
+/// value can't be compared with the predicate's literals.
+fn eval_predicate(
+    predicate: &Predicate<BoundReference>,
+    value: Option<&Value>
+) -> Option<bool> {
+    let value = match (predicate.op(), value) {
+        (Operator::IsNull, value) => return Some(value.is_none()),
+        (Operator::NotNull, value) => return Some(value.is_some()),
+        (_, Some(value)) => value,
+        // Comparisons with null partitions are left to the rows.
+        (_, None) => return None,
+    };
+
+    let orderings = predicate.literals().iter()
+        .map(|literal| value.partial_cmp(literal))
+        .collect::<Option<Vec<Ordering>>>()?;
+    let ordering = orderings.first().copied();
+
+    match predicate.op() {
+        Operator::Eq => ordering.map(|ordering| ordering == Ordering::Equal),
+        Operator::NotEq => ordering.map(|ordering| ordering != Ordering::Equal),
+        Operator::Lt => ordering.map(|ordering| ordering == Ordering::Less),
+        Operator::LtEq => ordering.map(|ordering| ordering != Ordering::Greater),
+        Operator::Gt => ordering.map(|ordering| ordering == Ordering::Greater),
+        Operator::GtEq => ordering.map(|ordering| ordering != Ordering::Less),
+        Operator::In => Some(orderings.contains(&Ordering::Equal)),
+        Operator::NotIn => Some(!orderings.contains(&Ordering::Equal)),
+        Operator::IsNull | Operator::NotNull => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::schema::{Schema, Field, PrimitiveType};
+    use crate::partition::{
+        PartitionSpec, PartitionField, PartitionTransform, PartitionValues
+    };
+    use crate::value::Value;
+    use crate::expr::{col, Expression, ResidualEvaluator};
+
+    fn create_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "ts", false, PrimitiveType::Timestamp),
+            Field::new_primitive(3, "name", false, PrimitiveType::String),
+        ])
+    }
+
+    fn create_spec() -> PartitionSpec {
+        PartitionSpec::try_new(0, vec![
+            PartitionField::new(2, 1000, "ts_day", PartitionTransform::Day),
+        ], create_schema()).unwrap()
+    }
+
+    fn partition(month: u32, day: u32) -> PartitionValues {
+        let day = PartitionTransform::Day
+            .apply(Some(Value::Timestamp(timestamp(month, day))))
+            .unwrap();
+        PartitionValues::from_iter([("ts_day".to_string(), day)])
+    }
+
+    fn timestamp(month: u32, day: u32) -> chrono::NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap().and_hms_opt(0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn residual_for_partition() {
+        let schema = create_schema();
+        let filter = col("ts").greater_than_or_equal_to(timestamp(4, 1))
+            .and(col("name").equal_to("a"))
+            .bind(&schema)
+            .unwrap();
+        let evaluator = ResidualEvaluator::new(&create_spec(), &filter);
+
+        // Every row of an April partition matches the predicate on `ts`.
+        assert_eq!(
+            evaluator.residual_for(&partition(4, 15)).unwrap(),
+            col("name").equal_to("a").bind(&schema).unwrap()
+        );
+
+        // No row of a March partition does.
+        assert_eq!(
+            evaluator.residual_for(&partition(3, 15)).unwrap(),
+            Expression::AlwaysFalse
+        );
+
+        // A timestamp within the day can't be decided by the day alone.
+        let filter = col("ts").greater_than(timestamp(4, 1)).bind(&schema).unwrap();
+        let evaluator = ResidualEvaluator::new(&create_spec(), &filter);
+        assert_eq!(evaluator.residual_for(&partition(4, 1)).unwrap(), filter);
+        assert_eq!(
+            evaluator.residual_for(&partition(4, 2)).unwrap(),
+            Expression::AlwaysTrue
+        );
+
+        // Negated and unpartitioned predicates.
+        let filter = (!col("ts").less_than(timestamp(4, 1)))
+            .or(col("id").equal_to(5))
+            .bind(&schema)
+            .unwrap();
+        let evaluator = ResidualEvaluator::new(&create_spec(), &filter);
+        assert_eq!(
+            evaluator.residual_for(&partition(4, 15)).unwrap(),
+            Expression::AlwaysTrue
+        );
+        assert_eq!(
+            evaluator.residual_for(&partition(3, 15)).unwrap(),
+            col("id").equal_to(5).bind(&schema).unwrap()
+        );
+
+        // Null partitions only decide null checks.
+        let filter = col("ts").is_null().bind(&schema).unwrap();
+        let evaluator = ResidualEvaluator::new(&create_spec(), &filter);
+        let null_partition = PartitionValues::from_iter([("ts_day".to_string(), None)]);
+        assert_eq!(
+            evaluator.residual_for(&null_partition).unwrap(),
+            Expression::AlwaysTrue
+        );
+        assert_eq!(
+            evaluator.residual_for(&partition(4, 15)).unwrap(),
+            Expression::AlwaysFalse
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Evaluation of filters on the rows of Arrow record batches.
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveTime, NaiveDateTime, DateTime, Utc};
+use arrow_schema::{Schema as ArrowSchema, DataType as ArrowDataType};
+use arrow_array::{
+    Array, ArrayRef, RecordBatch, BooleanArray, Int32Array, Int64Array, Float32Array,
+    Float64Array, Date32Array, Time64MicrosecondArray, TimestampMicrosecondArray,
+    StringArray, BinaryArray, FixedSizeBinaryArray
+};
+use arrow_ord::comparison::{eq_dyn, neq_dyn, lt_dyn, lt_eq_dyn, gt_dyn, gt_eq_dyn};
+use arrow_arith::boolean::{and, or, not, is_null, is_not_null};
+use arrow_select::filter::filter_record_batch;
+
+use crate::{IcebergResult, IcebergError};
+use crate::value::Value;
+use crate::expr::{Operator, BoundReference, Predicate, Expression, BoundExpression};
+
+/// Evaluates a filter on every row of Arrow record batches, using Arrow compute
+/// kernels.
+///
+/// Columns are matched to the filter's references by the field ids stored in the
+/// `"ICEBERG:field_id"` metadata of the batch's fields, or by name if the fields
+/// carry no ids. Only top-level columns can be referenced.
+///
+/// Comparisons never match null values, so `Eq`, `In`, `Lt` and the other
+/// comparisons are false for nulls, while `NotEq` and `NotIn` are true for them: a
+/// null is never equal to a literal, nor contained in a set of literals.
+pub struct RowEvaluator {
+    filter: BoundExpression,
+}
+
+impl RowEvaluator {
+    /// Creates an evaluator for a filter bound to the table's schema.
+    pub fn new(filter: &BoundExpression) -> Self {
+        Self {
+            filter: filter.clone(),
+        }
+    }
+
+    /// Returns for each row of the batch whether it matches the filter.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ExpressionError`] is returned if a column referenced by the
+    /// filter is missing from the batch, or if a literal can't be compared with its
+    /// column.
+    pub fn eval(&self, batch: &RecordBatch) -> IcebergResult<BooleanArray> {
+        eval_expression(&self.filter, batch)
+    }
+
+    /// Returns a batch containing only the rows of `batch` matching the filter.
+    ///
+    /// # Errors
+    ///
+    /// See [`RowEvaluator::eval()`].
+    pub fn filter(&self, batch: &RecordBatch) -> IcebergResult<RecordBatch> {
+        let selection = self.eval(batch)?;
+        Ok(filter_record_batch(batch, &selection)?)
+    }
+}
+
+fn eval_expression(
+    expr: &BoundExpression,
+    batch: &RecordBatch
+) -> IcebergResult<BooleanArray> {
+    // Results of predicates contain no nulls, so the kernels below never need to
+    // apply three-valued logic.
+    let result = match expr {
+        Expression::AlwaysTrue => BooleanArray::from(vec![true; batch.num_rows()]),
+        Expression::AlwaysFalse => BooleanArray::from(vec![false; batch.num_rows()]),
+        Expression::And(left, right) => {
+            and(&eval_expression(left, batch)?, &eval_expression(right, batch)?)?
+        },
+        Expression::Or(left, right) => {
+            or(&eval_expression(left, batch)?, &eval_expression(right, batch)?)?
+        },
+        Expression::Not(child) => not(&eval_expression(child, batch)?)?,
+        Expression::Predicate(predicate) => eval_predicate(predicate, batch)?,
+    };
+
+    Ok(result)
+}
+
+fn eval_predicate(
+    predicate: &Predicate<BoundReference>,
+    batch: &RecordBatch
+) -> IcebergResult<BooleanArray> {
+    let column = find_column(batch, predicate.term())?;
+    let to_array = |literal: &Value| {
+        literal_array(literal, column.data_type(), batch.num_rows())
+    };
+
+    let matches = match predicate.op() {
+        Operator::IsNull => return Ok(is_null(column.as_ref())?),
+        Operator::NotNull => return Ok(is_not_null(column.as_ref())?),
+        Operator::In | Operator::NotIn => {
+            let mut matches = BooleanArray::from(vec![false; batch.num_rows()]);
+            for literal in predicate.literals() {
+                let literal = to_array(literal)?;
+                let equal = eq_dyn(column.as_ref(), literal.as_ref())?;
+                matches = or(&matches, &without_nulls(equal, false))?;
+            }
+
+            match predicate.op() {
+                Operator::NotIn => not(&matches)?,
+                _ => matches,
+            }
+        },
+        op => {
+            let literal = predicate.literal().ok_or_else(|| {
+                IcebergError::ExpressionError {
+                    message: format!("predicate {op:?} requires a literal")
+                }
+            })?;
+            let literal = to_array(literal)?;
+            let (column, literal) = (column.as_ref(), literal.as_ref());
+
+            match op {
+                Operator::Eq => without_nulls(eq_dyn(column, literal)?, false),
+                Operator::NotEq => without_nulls(neq_dyn(column, literal)?, true),
+                Operator::Lt => without_nulls(lt_dyn(column, literal)?, false),
+                Operator::LtEq => without_nulls(lt_eq_dyn(column, literal)?, false),
+                Operator::Gt => without_nulls(gt_dyn(column, literal)?, false),
+                Operator::GtEq => without_nulls(gt_eq_dyn(column, literal)?, false),
+                // Unary and set operators were handled above.
+                _ => unreachable!(),
+            }
+        },
+    };
+
+    Ok(matches)
+}
+
+/// Replaces the nulls of a comparison's result with `value`.
+fn without_nulls(array: BooleanArray, value: bool) -> BooleanArray {
+    if array.null_count() == 0 {
+        return array;
+    }
+
+    array.iter().map(|matches| Some(matches.unwrap_or(value))).collect()
+}
+
+fn find_column(batch: &RecordBatch, term: &BoundReference) -> IcebergResult<ArrayRef> {
+    let schema: &ArrowSchema = &batch.schema();
+    let field_id = term.field_id().to_string();
+
+    let position = schema.fields().iter()
+        .position(|field| field.metadata().get("ICEBERG:field_id") == Some(&field_id))
+        .or_else(|| {
+            let has_ids = schema.fields().iter()
+                .any(|field| field.metadata().contains_key("ICEBERG:field_id"));
+            match has_ids {
+                true => None,
+                false => schema.index_of(term.name()).ok(),
+            }
+        });
+
+    position.map(|position| batch.column(position).clone())
+        .ok_or_else(|| IcebergError::ExpressionError {
+            message: format!("column {} not found in record batch", term.name())
+        })
+}
+
+/// Creates an array of `len` copies of a literal, cast to `data_type`.
+pub(crate) fn literal_array(
+    literal: &Value,
+    data_type: &ArrowDataType,
+    len: usize
+) -> IcebergResult<ArrayRef> {
+    let array: ArrayRef = match literal {
+        Value::Boolean(b) => Arc::new(BooleanArray::from(vec![*b; len])),
+        Value::Int(i) => Arc::new(Int32Array::from_value(*i, len)),
+        Value::Long(l) => Arc::new(Int64Array::from_value(*l, len)),
+        Value::Float(f) => Arc::new(Float32Array::from_value(*f, len)),
+        Value::Double(d) => Arc::new(Float64Array::from_value(*d, len)),
+        Value::Date(date) => {
+            let days = (*date - NaiveDate::default()).num_days();
+            let days = i32::try_from(days).map_err(|_| IcebergError::ExpressionError {
+                message: format!("date {date} is too far from 1970-01-01")
+            })?;
+            Arc::new(Date32Array::from_value(days, len))
+        },
+        Value::Time(time) => {
+            // Can't overflow, a day has less than 2^63 microseconds.
+            let micros = (*time - NaiveTime::default()).num_microseconds().unwrap();
+            Arc::new(Time64MicrosecondArray::from_value(micros, len))
+        },
+        Value::Timestamp(timestamp) => {
+            let micros = (*timestamp - NaiveDateTime::default()).num_microseconds()
+                .ok_or_else(|| IcebergError::ExpressionError {
+                    message: format!("timestamp {timestamp} is too far from 1970-01-01")
+                })?;
+            Arc::new(TimestampMicrosecondArray::from_value(micros, len))
+        },
+        Value::Timestamptz(timestamptz) => {
+            let micros = (*timestamptz - DateTime::<Utc>::default()).num_microseconds()
+                .ok_or_else(|| IcebergError::ExpressionError {
+                    message: format!("timestamp {timestamptz} is too far from 1970-01-01")
+                })?;
+            Arc::new(
+                TimestampMicrosecondArray::from_value(micros, len).with_timezone("UTC")
+            )
+        },
+        Value::String(s) => {
+            Arc::new(StringArray::from_iter_values(std::iter::repeat(s).take(len)))
+        },
+        Value::Binary(bytes) => {
+            Arc::new(BinaryArray::from_iter_values(std::iter::repeat(bytes).take(len)))
+        },
+        Value::Uuid(uuid) => fixed_size_array(uuid.as_bytes(), len)?,
+        Value::Fixed(bytes) => fixed_size_array(bytes, len)?,
+        _ => {
+            return Err(IcebergError::ExpressionError {
+                message: format!("literal {literal:?} can't be compared with a column")
+            });
+        },
+    };
+
+    if array.data_type() == data_type {
+        Ok(array)
+    } else {
+        arrow_cast::cast(&array, data_type).map_err(|e| IcebergError::ExpressionError {
+            message: format!("literal {literal:?} can't be compared with a column: {e}")
+        })
+    }
+}
+
+fn fixed_size_array(bytes: &[u8], len: usize) -> IcebergResult<ArrayRef> {
+    let size = i32::try_from(bytes.len()).map_err(|_| IcebergError::ExpressionError {
+        message: format!("fixed literal of length {} is too long", bytes.len())
+    })?;
+
+    Ok(Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+        std::iter::repeat(Some(bytes)).take(len),
+        size
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_schema::{Schema as ArrowSchema, Field as ArrowField, DataType as ArrowDataType};
+    use arrow_array::{Array, RecordBatch, Int32Array, StringArray};
+
+    use crate::schema::{Schema, Field, PrimitiveType};
+    use crate::expr::{col, Expression, RowEvaluator};
+
+    fn create_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", false, PrimitiveType::Int),
+            Field::new_primitive(2, "name", false, PrimitiveType::String),
+        ])
+    }
+
+    fn create_batch() -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![
+                ArrowField::new("id", ArrowDataType::Int32, true),
+                ArrowField::new("name", ArrowDataType::Utf8, true),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), Some(2), None, Some(4), Some(5)])),
+                Arc::new(StringArray::from(vec![
+                    Some("a"), None, Some("c"), Some("d"), Some("e")
+                ])),
+            ]
+        ).unwrap()
+    }
+
+    fn eval(filter: Expression) -> Vec<bool> {
+        let filter = filter.bind(&create_schema()).unwrap();
+        RowEvaluator::new(&filter).eval(&create_batch()).unwrap()
+            .iter()
+            .map(|matches| matches.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn not_in() {
+        assert_eq!(eval(col("id").not_in([2, 5])), [true, false, true, true, false]);
+        assert_eq!(eval(col("id").is_in([2, 5])), [false, true, false, false, true]);
+        // Nulls are never contained in the set.
+        assert_eq!(eval(col("name").not_in(["a", "z"])), [false, true, true, true, true]);
+        assert_eq!(eval(!col("id").is_in([1])), [false, true, true, true, true]);
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(eval(col("id").less_than(4)), [true, true, false, false, false]);
+        assert_eq!(
+            eval(col("id").greater_than_or_equal_to(4)),
+            [false, false, false, true, true]
+        );
+        assert_eq!(eval(col("id").equal_to(2)), [false, true, false, false, false]);
+        assert_eq!(eval(col("id").not_equal_to(2)), [true, false, true, true, true]);
+        assert_eq!(eval(col("name").is_null()), [false, true, false, false, false]);
+        assert_eq!(
+            eval(col("id").less_than(2).or(col("name").equal_to("d"))),
+            [true, false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn filter_batch() {
+        let filter = col("id").not_in([1, 4]).bind(&create_schema()).unwrap();
+        let filtered = RowEvaluator::new(&filter).filter(&create_batch()).unwrap();
+
+        assert_eq!(filtered.num_rows(), 3);
+        assert_eq!(filtered.column(0).null_count(), 1);
+    }
+}
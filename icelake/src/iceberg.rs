@@ -1,7 +1,7 @@
 //! Iceberg table implementation.
 use std::fmt;
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use uuid::Uuid;
 use bytes::Bytes;
@@ -12,15 +12,53 @@ use serde_json;
 use lazy_static::lazy_static;
 use murmur3::murmur3_32;
 
-use crate::{IcebergError, IcebergResult};
+use crate::{IcebergError, IcebergResult, NotFoundKind};
 use crate::utils;
-use crate::schema::Schema;
-use crate::partition::{PartitionSpecModel, PartitionSpec, PartitionValues};
+use crate::schema::{Schema, SchemaType};
+use crate::value::Value;
+use crate::partition::{
+    PartitionSpecModel, PartitionSpec, PartitionSpecUpdate, PartitionValues,
+    PartitionField, PartitionTransform, UNPARTITIONED_LAST_ASSIGNED_FIELD_ID
+};
 use crate::sort::SortOrder;
-use crate::transaction::Transaction;
+use crate::puffin::StatisticsFile;
+use crate::transaction::{Transaction, RewriteManifestsOperation};
+use crate::scan::TableScan;
+use crate::catalog::{Catalog, TableIdentifier};
 use crate::storage::{IcebergStorage, IcebergPath};
 use crate::snapshot::{Snapshot, SnapshotLog, SnapshotReference};
-use crate::manifest::{ManifestList};
+use crate::manifest::{
+    ManifestList, ManifestFile, ManifestFileType, ManifestReader, DataFile, DataFileFormat
+};
+use crate::writer::{
+    TARGET_FILE_SIZE_BYTES_PROPERTY, DEFAULT_TARGET_FILE_SIZE_BYTES,
+    PARQUET_COMPRESSION_CODEC_PROPERTY, PARQUET_COMPRESSION_LEVEL_PROPERTY,
+    DEFAULT_FILE_FORMAT_PROPERTY, CompressionCodec, MetricsConfig, BloomFilterConfig
+};
+
+/// Table property controlling whether metadata files are written as pretty-printed
+/// JSON.
+pub const METADATA_JSON_PRETTY_PROPERTY: &str = "write.metadata.json-pretty";
+/// By default, metadata files are written as compact JSON.
+pub const DEFAULT_METADATA_JSON_PRETTY: bool = false;
+/// Table property controlling whether old metadata files are deleted after each
+/// commit, keeping only the number set by [`METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY`].
+pub const METADATA_DELETE_AFTER_COMMIT_PROPERTY: &str =
+    "write.metadata.delete-after-commit.enabled";
+/// By default, old metadata files are kept.
+pub const DEFAULT_METADATA_DELETE_AFTER_COMMIT: bool = false;
+/// Table property controlling the number of previous metadata files tracked in the
+/// metadata log.
+pub const METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY: &str =
+    "write.metadata.previous-versions-max";
+/// Default number of previous metadata files tracked.
+pub const DEFAULT_METADATA_PREVIOUS_VERSIONS_MAX: usize = 100;
+/// Table property setting the target size of manifests, below which manifests are
+/// merged by [`IcebergTable::rewrite_manifests()`].
+pub const MANIFEST_TARGET_SIZE_BYTES_PROPERTY: &str =
+    "commit.manifest.target-size-bytes";
+/// Default target size of manifests, 8 MB.
+pub const DEFAULT_MANIFEST_TARGET_SIZE_BYTES: u64 = 8 * 1024 * 1024;
 
 #[derive(Debug, Serialize_repr, Deserialize_repr, PartialEq, Clone)]
 #[repr(i32)]
@@ -45,18 +83,25 @@ impl std::str::FromStr for IcebergTableVersion {
         match s {
             "1" => Ok(IcebergTableVersion::V1),
             "2" => Ok(IcebergTableVersion::V2),
-            _ => Err(IcebergError::CustomError {
-                message: format!("invalid iceberg table version '{}'", s)
-            })
+            _ => match s.parse::<u8>() {
+                Ok(version) => Err(IcebergError::UnsupportedFormatVersion(version)),
+                Err(..) => Err(IcebergError::CustomError {
+                    message: format!("invalid iceberg table version '{}'", s)
+                }),
+            }
         }
     }
 }
 
+/// Metadata of an Iceberg table, as stored in the table's metadata files.
+///
+/// Metadata of both format versions is read into the same version 2 representation,
+/// with fields missing from version 1 metadata set to their defaults. The format
+/// version is retained, and version 1 metadata is written back as such.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-#[serde(rename_all = "kebab-case")]
+#[serde(try_from = "IcebergTableMetadataModel", into = "IcebergTableMetadataModel")]
 pub struct IcebergTableMetadata {
     /// An integer version number for the format.
-    /// Should always be set to 2.
     pub format_version: IcebergTableVersion,
     /// A UUID that identifies the table
     pub table_uuid: String,
@@ -119,7 +164,231 @@ pub struct IcebergTableMetadata {
     /// always a main branch reference pointing to the current-snapshot-id even if the
     /// refs map is null.
     pub refs: Option<HashMap<String, SnapshotReference>>,
-    // TODO: Table statistics
+    /// A list (optional) of table statistics files, at most one per snapshot. Each
+    /// references a Puffin file with statistics computed from the snapshot's data.
+    pub statistics: Option<Vec<StatisticsFile>>,
+}
+
+/// A partition field of the single partition spec of version 1 metadata, whose
+/// field id is optional.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionFieldV1 {
+    source_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field_id: Option<i32>,
+    name: String,
+    transform: PartitionTransform,
+}
+
+/// Table metadata as serialized in metadata files of either format version.
+///
+/// Version 1 metadata may hold a single `schema` and `partition-spec` instead of the
+/// `schemas` and `partition-specs` lists, and lacks sequence numbers. When read, the
+/// single schema and spec become the current ones, keeping the schema's id or 0, and
+/// using spec id 0 and partition field ids from 1000. Other fields missing from
+/// version 1 metadata get their defaults, e.g. sequence number 0 and an unsorted
+/// sort order.
+///
+/// Version 1 metadata is written with both the single schema and spec, as required
+/// by the spec, and the lists.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct IcebergTableMetadataModel {
+    format_version: IcebergTableVersion,
+    table_uuid: Option<String>,
+    location: String,
+    last_sequence_number: Option<i64>,
+    last_updated_ms: i64,
+    last_column_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema: Option<Schema>,
+    schemas: Option<Vec<Schema>>,
+    current_schema_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    partition_spec: Option<Vec<PartitionFieldV1>>,
+    partition_specs: Option<Vec<PartitionSpecModel>>,
+    default_spec_id: Option<i32>,
+    last_partition_id: Option<i32>,
+    properties: Option<HashMap<String, String>>,
+    current_snapshot_id: Option<i64>,
+    snapshots: Option<Vec<Snapshot>>,
+    snapshot_log: Option<Vec<SnapshotLog>>,
+    metadata_log: Option<Vec<MetadataLog>>,
+    sort_orders: Option<Vec<SortOrder>>,
+    default_sort_order_id: Option<i32>,
+    refs: Option<HashMap<String, SnapshotReference>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    statistics: Option<Vec<StatisticsFile>>,
+}
+
+impl TryFrom<IcebergTableMetadataModel> for IcebergTableMetadata {
+    type Error = IcebergError;
+
+    fn try_from(model: IcebergTableMetadataModel) -> Result<Self, Self::Error> {
+        let v1 = model.format_version == IcebergTableVersion::V1;
+
+        // Fields required by version 2 are optional in version 1, where they default
+        // to the given value.
+        fn required<T>(
+            value: Option<T>,
+            v1_default: impl FnOnce() -> Option<T>,
+            v1: bool,
+            name: &str
+        ) -> IcebergResult<T> {
+            value.or_else(|| v1.then(v1_default).flatten())
+                .ok_or_else(|| IcebergError::CustomError {
+                    message: format!("table metadata is missing {name}")
+                })
+        }
+
+        // The lists take precedence over the single schema and spec of version 1.
+        let single_schema_id = model.schema.as_ref().map(|schema| schema.id());
+        let schemas = required(
+            model.schemas.filter(|schemas| !schemas.is_empty()),
+            || model.schema.map(|schema| vec![schema]),
+            v1,
+            "schemas"
+        )?;
+        let current_schema_id = required(
+            model.current_schema_id,
+            || single_schema_id.or(schemas.last().map(|schema| schema.id())),
+            v1,
+            "current-schema-id"
+        )?;
+
+        let partition_specs = required(
+            model.partition_specs.filter(|specs| !specs.is_empty()),
+            || model.partition_spec.map(|fields| vec![PartitionSpecModel {
+                spec_id: 0,
+                fields: fields.into_iter()
+                    .enumerate()
+                    .map(|(index, field)| PartitionField {
+                        source_id: field.source_id,
+                        field_id: field.field_id.unwrap_or(
+                            UNPARTITIONED_LAST_ASSIGNED_FIELD_ID + 1 + index as i32
+                        ),
+                        name: field.name,
+                        transform: field.transform,
+                    })
+                    .collect(),
+            }]),
+            v1,
+            "partition-specs"
+        )?;
+        let default_spec_id = required(
+            model.default_spec_id,
+            || partition_specs.last().map(|spec| spec.spec_id),
+            v1,
+            "default-spec-id"
+        )?;
+        let last_partition_id = required(
+            model.last_partition_id,
+            || {
+                let max_field_id = partition_specs.iter()
+                    .flat_map(|spec| spec.fields.iter().map(|field| field.field_id))
+                    .max();
+                Some(max_field_id.unwrap_or(UNPARTITIONED_LAST_ASSIGNED_FIELD_ID))
+            },
+            v1,
+            "last-partition-id"
+        )?;
+
+        let sort_orders = required(
+            model.sort_orders,
+            || Some(vec![SortOrder::new()]),
+            v1,
+            "sort-orders"
+        )?;
+        let default_sort_order_id = required(
+            model.default_sort_order_id,
+            || Some(sort_orders.first().map_or(0, |sort_order| sort_order.order_id)),
+            v1,
+            "default-sort-order-id"
+        )?;
+
+        Ok(Self {
+            format_version: model.format_version,
+            table_uuid: required(
+                model.table_uuid,
+                || Some(Uuid::new_v4().to_string()),
+                v1,
+                "table-uuid"
+            )?,
+            location: model.location,
+            last_sequence_number: required(
+                model.last_sequence_number,
+                || Some(0),
+                v1,
+                "last-sequence-number"
+            )?,
+            last_updated_ms: model.last_updated_ms,
+            last_column_id: model.last_column_id,
+            schemas: schemas,
+            current_schema_id: current_schema_id,
+            partition_specs: partition_specs,
+            default_spec_id: default_spec_id,
+            last_partition_id: last_partition_id,
+            properties: model.properties,
+            current_snapshot_id: model.current_snapshot_id,
+            snapshots: model.snapshots,
+            snapshot_log: model.snapshot_log,
+            metadata_log: model.metadata_log,
+            sort_orders: sort_orders,
+            default_sort_order_id: default_sort_order_id,
+            refs: model.refs,
+            statistics: model.statistics,
+        })
+    }
+}
+
+impl From<IcebergTableMetadata> for IcebergTableMetadataModel {
+    fn from(metadata: IcebergTableMetadata) -> Self {
+        let v1 = metadata.format_version == IcebergTableVersion::V1;
+
+        let schema = metadata.schemas.iter()
+            .find(|schema| schema.id() == metadata.current_schema_id)
+            .filter(|_| v1)
+            .cloned();
+        let partition_spec = metadata.partition_specs.iter()
+            .find(|spec| spec.spec_id == metadata.default_spec_id)
+            .filter(|_| v1)
+            .map(|spec| {
+                spec.fields.iter()
+                    .map(|field| PartitionFieldV1 {
+                        source_id: field.source_id,
+                        field_id: Some(field.field_id),
+                        name: field.name.clone(),
+                        transform: field.transform.clone(),
+                    })
+                    .collect()
+            });
+
+        Self {
+            format_version: metadata.format_version,
+            table_uuid: Some(metadata.table_uuid),
+            location: metadata.location,
+            last_sequence_number: Some(metadata.last_sequence_number),
+            last_updated_ms: metadata.last_updated_ms,
+            last_column_id: metadata.last_column_id,
+            schema: schema,
+            schemas: Some(metadata.schemas),
+            current_schema_id: Some(metadata.current_schema_id),
+            partition_spec: partition_spec,
+            partition_specs: Some(metadata.partition_specs),
+            default_spec_id: Some(metadata.default_spec_id),
+            last_partition_id: Some(metadata.last_partition_id),
+            properties: metadata.properties,
+            current_snapshot_id: metadata.current_snapshot_id,
+            snapshots: metadata.snapshots,
+            snapshot_log: metadata.snapshot_log,
+            metadata_log: metadata.metadata_log,
+            sort_orders: Some(metadata.sort_orders),
+            default_sort_order_id: Some(metadata.default_sort_order_id),
+            refs: metadata.refs,
+            statistics: metadata.statistics,
+        }
+    }
 }
 
 impl IcebergTableMetadata {
@@ -138,7 +407,7 @@ impl IcebergTableMetadata {
 
         let current_schema_id = schema.id();
         // Infer the maximum field id of the current schema.
-        let last_column_id = schema.max_field_id() + 1;
+        let last_column_id = schema.max_field_id();
 
         Ok(Self {
             format_version: IcebergTableVersion::V2,
@@ -160,9 +429,35 @@ impl IcebergTableMetadata {
             sort_orders: vec![sort_order],
             default_sort_order_id: sort_order_id,
             refs: Some(HashMap::new()),
+            statistics: None,
         })
     }
 
+    /// Replaces the default sort order of new table metadata with `sort_order`.
+    ///
+    /// An unsorted `sort_order` leaves the metadata unchanged.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SortOrderError`] is returned if `sort_order` has fields but
+    /// the id `0` reserved for the unsorted order, or if it isn't valid for the
+    /// current schema.
+    pub fn with_sort_order(mut self, sort_order: SortOrder) -> IcebergResult<Self> {
+        if sort_order.is_unsorted() {
+            return Ok(self);
+        }
+        if sort_order.order_id == 0 {
+            return Err(IcebergError::SortOrderError {
+                message: "sort order id 0 is reserved for the unsorted order".to_string()
+            });
+        }
+        sort_order.validate(self.current_schema())?;
+
+        self.default_sort_order_id = sort_order.order_id;
+        self.sort_orders = vec![sort_order];
+        Ok(self)
+    }
+
     // TODO: This function will panic if the metadata object is invalid, which
     // can happen if it was deserialized directly.
     pub fn current_schema(&self) -> &Schema {
@@ -171,6 +466,11 @@ impl IcebergTableMetadata {
             .expect("current_schema_id does not match any schema")
     }
 
+    /// Returns the schema with the given id, or `None` if there's no such schema.
+    pub fn schema_by_id(&self, schema_id: i32) -> Option<&Schema> {
+        self.schemas.iter().find(|schema| schema.id() == schema_id)
+    }
+
     /// Return the latest snapshot of the table. May return `None` if the table has no
     /// snapshots, or if its `current_snapshot_id` is invalid.
     pub fn current_snapshot(&self) -> Option<&Snapshot> {
@@ -183,6 +483,114 @@ impl IcebergTableMetadata {
         })
     }
 
+    /// Returns the snapshot with the given id, or `None` if there's no such snapshot.
+    pub fn snapshot_by_id(&self, snapshot_id: i64) -> Option<&Snapshot> {
+        self.snapshots.as_ref().and_then(|snapshots| {
+            snapshots.iter().find(|snapshot| snapshot.snapshot_id == snapshot_id)
+        })
+    }
+
+    /// Returns the statistics file of the snapshot with the given id, or `None` if
+    /// the snapshot has no statistics.
+    pub fn statistics_for(&self, snapshot_id: i64) -> Option<&StatisticsFile> {
+        self.statistics.as_ref().and_then(|statistics| {
+            statistics.iter().find(|file| file.snapshot_id == snapshot_id)
+        })
+    }
+
+    /// Sets the statistics file of its snapshot, replacing any existing statistics
+    /// file of the same snapshot.
+    pub fn set_statistics(&mut self, statistics_file: StatisticsFile) {
+        let statistics = self.statistics.get_or_insert_with(Vec::new);
+        statistics.retain(|file| file.snapshot_id != statistics_file.snapshot_id);
+        statistics.push(statistics_file);
+    }
+
+    /// Returns the snapshot that was current at the given time, i.e. the snapshot of
+    /// the latest entry in the snapshot log with a timestamp at or before
+    /// `timestamp_ms`.
+    ///
+    /// Returns `None` if `timestamp_ms` predates the first snapshot, or if the
+    /// snapshot that was current at that time no longer exists.
+    pub fn snapshot_as_of_timestamp(&self, timestamp_ms: i64) -> Option<&Snapshot> {
+        self.snapshot_log.as_ref()
+            .and_then(|snapshot_log| {
+                snapshot_log.iter()
+                    .filter(|entry| entry.timestamp_ms <= timestamp_ms)
+                    // Returns the last of several entries with the same timestamp.
+                    .max_by_key(|entry| entry.timestamp_ms)
+            })
+            .and_then(|entry| self.snapshot_by_id(entry.snapshot_id))
+    }
+
+    /// Returns the lineage of the snapshot with the given id, starting with the
+    /// snapshot itself and following its parents back to the root snapshot.
+    ///
+    /// The lineage is truncated at the first parent that no longer exists in the
+    /// metadata, e.g. because it was expired. Returns an empty list if the snapshot
+    /// itself doesn't exist.
+    pub fn ancestors_of(&self, snapshot_id: i64) -> Vec<&Snapshot> {
+        let mut ancestors: Vec<&Snapshot> = Vec::new();
+        let mut visited: HashSet<i64> = HashSet::new();
+
+        let mut next_snapshot_id = Some(snapshot_id);
+        while let Some(snapshot_id) = next_snapshot_id {
+            // Malformed metadata may contain a cycle of parents.
+            if !visited.insert(snapshot_id) {
+                break;
+            }
+
+            match self.snapshot_by_id(snapshot_id) {
+                Some(snapshot) => {
+                    ancestors.push(snapshot);
+                    next_snapshot_id = snapshot.parent_snapshot_id;
+                },
+                None => break
+            }
+        }
+
+        ancestors
+    }
+
+    /// Returns the ids of the snapshots that may be expired: snapshots older than
+    /// `older_than_ms` that are not among the `retain_last` most recent snapshots.
+    ///
+    /// Ancestors of the current snapshot and snapshots referenced by a branch or tag
+    /// are never expired.
+    fn snapshots_to_expire(&self, older_than_ms: i64, retain_last: usize) -> HashSet<i64> {
+        let mut retained: HashSet<i64> = self.current_ancestors()
+            .iter()
+            .map(|snapshot| snapshot.snapshot_id)
+            .collect();
+
+        if let Some(refs) = &self.refs {
+            retained.extend(refs.values().map(|reference| reference.snapshot_id));
+        }
+
+        let mut snapshots: Vec<&Snapshot> = self.snapshots.iter().flatten().collect();
+        snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.timestamp_ms));
+        retained.extend(
+            snapshots.iter().take(retain_last).map(|snapshot| snapshot.snapshot_id)
+        );
+
+        snapshots.iter()
+            .filter(|snapshot| {
+                snapshot.timestamp_ms < older_than_ms
+                    && !retained.contains(&snapshot.snapshot_id)
+            })
+            .map(|snapshot| snapshot.snapshot_id)
+            .collect()
+    }
+
+    /// Returns the lineage of the current snapshot, see
+    /// [`ancestors_of()`](Self::ancestors_of).
+    pub fn current_ancestors(&self) -> Vec<&Snapshot> {
+        match self.current_snapshot() {
+            Some(snapshot) => self.ancestors_of(snapshot.snapshot_id),
+            None => Vec::new()
+        }
+    }
+
     // TODO: This function will panic if the metadata object is invalid, which
     // can happen if it was deserialized directly.
     pub fn current_partition_spec(&self) -> PartitionSpec {
@@ -196,6 +604,306 @@ impl IcebergTableMetadata {
             self.current_schema().clone()
         ).unwrap()
     }
+
+    /// Parses the value of a table property, or returns `default` if the property
+    /// is not set.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned if the value can't be parsed.
+    fn parse_property<T: std::str::FromStr>(
+        &self,
+        key: &str,
+        default: T
+    ) -> IcebergResult<T> {
+        let value = match self.properties.as_ref().and_then(|props| props.get(key)) {
+            Some(value) => value,
+            None => return Ok(default),
+        };
+
+        value.parse().map_err(|_| IcebergError::InvalidProperty {
+            key: key.to_string(),
+            value: value.clone(),
+        })
+    }
+
+    /// Target size in bytes of data files written to the table, set by the
+    /// `write.target-file-size-bytes` property.
+    pub fn write_target_file_size_bytes(&self) -> IcebergResult<u64> {
+        self.parse_property(
+            TARGET_FILE_SIZE_BYTES_PROPERTY,
+            DEFAULT_TARGET_FILE_SIZE_BYTES as u64
+        )
+    }
+
+    /// Compression codec of Parquet data files written to the table, set by the
+    /// `write.parquet.compression-codec` property. Defaults to ZSTD.
+    pub fn parquet_compression_codec(&self) -> IcebergResult<CompressionCodec> {
+        self.parse_property(
+            PARQUET_COMPRESSION_CODEC_PROPERTY,
+            CompressionCodec::default()
+        )
+    }
+
+    /// Compression level of Parquet data files written to the table, set by the
+    /// `write.parquet.compression-level` property. If not set, the codec's default
+    /// level is used.
+    pub fn parquet_compression_level(&self) -> IcebergResult<Option<i32>> {
+        let is_set = self.properties.as_ref().map_or(false, |props| {
+            props.contains_key(PARQUET_COMPRESSION_LEVEL_PROPERTY)
+        });
+
+        match is_set {
+            true => self.parse_property(PARQUET_COMPRESSION_LEVEL_PROPERTY, 0).map(Some),
+            false => Ok(None),
+        }
+    }
+
+    /// Modes choosing the metrics recorded for each column of written data files,
+    /// set by the `write.metadata.metrics.*` properties.
+    pub fn metrics_config(&self) -> IcebergResult<MetricsConfig> {
+        match &self.properties {
+            Some(properties) => MetricsConfig::from_properties(properties),
+            None => Ok(MetricsConfig::default()),
+        }
+    }
+
+    /// File format of data files written to the table, set by the
+    /// `write.format.default` property. Defaults to Parquet.
+    pub fn default_file_format(&self) -> IcebergResult<DataFileFormat> {
+        self.parse_property(DEFAULT_FILE_FORMAT_PROPERTY, DataFileFormat::Parquet)
+    }
+
+    /// Columns of Parquet data files written to the table that have bloom filters,
+    /// set by the `write.parquet.bloom-filter-enabled.column.<name>` and
+    /// `write.parquet.bloom-filter-fpp.column.<name>` properties.
+    pub fn bloom_filter_config(&self) -> IcebergResult<BloomFilterConfig> {
+        match &self.properties {
+            Some(properties) => BloomFilterConfig::from_properties(properties),
+            None => Ok(BloomFilterConfig::default()),
+        }
+    }
+
+    /// Target size in bytes of the table's manifests, set by the
+    /// [`MANIFEST_TARGET_SIZE_BYTES_PROPERTY`].
+    pub fn manifest_target_size_bytes(&self) -> IcebergResult<u64> {
+        self.parse_property(
+            MANIFEST_TARGET_SIZE_BYTES_PROPERTY,
+            DEFAULT_MANIFEST_TARGET_SIZE_BYTES
+        )
+    }
+
+    /// The table's format version as a number.
+    pub fn format_version(&self) -> u8 {
+        self.format_version.clone() as u8
+    }
+
+    /// Whether old metadata files should be deleted after each commit, set by the
+    /// [`METADATA_DELETE_AFTER_COMMIT_PROPERTY`].
+    pub fn metadata_delete_after_commit(&self) -> IcebergResult<bool> {
+        self.parse_property(
+            METADATA_DELETE_AFTER_COMMIT_PROPERTY,
+            DEFAULT_METADATA_DELETE_AFTER_COMMIT
+        )
+    }
+
+    /// Number of previous metadata files to track, set by the
+    /// [`METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY`].
+    pub fn metadata_previous_versions_max(&self) -> IcebergResult<usize> {
+        self.parse_property(
+            METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY,
+            DEFAULT_METADATA_PREVIOUS_VERSIONS_MAX
+        )
+    }
+
+    /// Whether metadata files should be written as pretty-printed JSON, set by the
+    /// [`METADATA_JSON_PRETTY_PROPERTY`].
+    pub fn metadata_json_pretty(&self) -> IcebergResult<bool> {
+        self.parse_property(METADATA_JSON_PRETTY_PROPERTY, DEFAULT_METADATA_JSON_PRETTY)
+    }
+
+    /// Records the table's previous metadata file in the metadata log, dropping the
+    /// oldest entries beyond the [`METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY`].
+    ///
+    /// Returns the URIs of the dropped metadata files, which should be deleted
+    /// after the new metadata is committed. These are only returned if the
+    /// [`METADATA_DELETE_AFTER_COMMIT_PROPERTY`] is enabled.
+    pub fn add_previous_metadata_file(
+        &mut self,
+        metadata_file: &str,
+        timestamp_ms: i64
+    ) -> IcebergResult<Vec<String>> {
+        // At least the previous file is always kept.
+        let max = self.metadata_previous_versions_max()?.max(1);
+        let delete_after_commit = self.metadata_delete_after_commit()?;
+
+        let metadata_log = self.metadata_log.get_or_insert_with(Vec::new);
+        metadata_log.push(MetadataLog::new(metadata_file, timestamp_ms));
+
+        let expired = metadata_log.len().saturating_sub(max);
+        let removed = metadata_log.drain(..expired)
+            .map(|entry| entry.metadata_file)
+            .collect();
+
+        Ok(match delete_after_commit {
+            true => removed,
+            false => Vec::new(),
+        })
+    }
+
+    /// Checks that the metadata references existing entities: the current schema id
+    /// must be in `schemas`, the default spec id in the partition specs and the
+    /// default sort order id in `sort_orders`. The source fields of the default
+    /// partition spec and sort order must exist in the current schema.
+    ///
+    /// Older partition specs and sort orders aren't checked, as they may reference
+    /// fields since dropped from the schema.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::Validation`] is returned if the current schema, the default
+    /// partition spec or the default sort order is missing or invalid.
+    pub fn validate(&self) -> IcebergResult<()> {
+        let invalid = |message: String| IcebergError::Validation { message: message };
+
+        let schema = self.schema_by_id(self.current_schema_id).ok_or_else(|| {
+            invalid(format!(
+                "current schema id {} not found in schemas",
+                self.current_schema_id
+            ))
+        })?;
+
+        let spec = self.partition_specs.iter()
+            .find(|spec| spec.spec_id == self.default_spec_id)
+            .ok_or_else(|| {
+                invalid(format!(
+                    "default partition spec id {} not found in partition specs",
+                    self.default_spec_id
+                ))
+            })?;
+        PartitionSpec::try_new(spec.spec_id, spec.fields.clone(), schema.clone())
+            .map_err(|e| invalid(format!("invalid default partition spec: {e}")))?;
+
+        let sort_order = self.sort_orders.iter()
+            .find(|sort_order| sort_order.order_id == self.default_sort_order_id)
+            .ok_or_else(|| {
+                invalid(format!(
+                    "default sort order id {} not found in sort orders",
+                    self.default_sort_order_id
+                ))
+            })?;
+        sort_order.validate(schema)
+            .map_err(|e| invalid(format!("invalid default sort order: {e}")))
+    }
+
+    /// Serializes the metadata to JSON, pretty-printed if the table's
+    /// [`METADATA_JSON_PRETTY_PROPERTY`] is `true`, and compact otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the metadata isn't valid, see [`validate()`](Self::validate).
+    pub fn to_json(&self) -> IcebergResult<String> {
+        self.validate()?;
+
+        let json = match self.metadata_json_pretty()? {
+            true => serde_json::to_string_pretty(self),
+            false => serde_json::to_string(self),
+        };
+
+        json.map_err(|e| IcebergError::SerializeMetadataJson { source: e })
+    }
+
+    /// Returns all of the table's partition specs, including the default one.
+    pub fn partition_specs(&self) -> &[PartitionSpecModel] {
+        &self.partition_specs
+    }
+
+    /// Adds a partition spec to the table, unless a spec with the same id exists,
+    /// and updates the last assigned partition field id.
+    pub(crate) fn add_partition_spec(&mut self, spec: PartitionSpecModel) {
+        if let Some(last_partition_id) = spec.fields.iter().map(|f| f.field_id).max() {
+            self.last_partition_id = self.last_partition_id.max(last_partition_id);
+        }
+        if !self.partition_specs.iter().any(|existing| existing.spec_id == spec.spec_id) {
+            self.partition_specs.push(spec);
+        }
+    }
+
+    /// Returns the partition spec with the given id, or `None` if there's no such
+    /// spec or it's not valid for the current schema.
+    pub fn partition_spec(&self, spec_id: i32) -> Option<PartitionSpec> {
+        self.partition_specs.iter()
+            .find(|spec| spec.spec_id == spec_id)
+            .and_then(|model| {
+                PartitionSpec::try_new(
+                    model.spec_id,
+                    model.fields.clone(),
+                    self.current_schema().clone()
+                ).ok()
+            })
+    }
+}
+
+/// Aggregates the partition field summaries of the given manifests into the
+/// minimum and maximum values of a partition field.
+///
+/// Returns `None` if any of the manifests tracking the partition field is missing
+/// its summary, or if no manifest contains non-null values for the field.
+fn aggregate_partition_bounds(
+    metadata: &IcebergTableMetadata,
+    manifest_files: &[ManifestFile],
+    partition_field_id: i32
+) -> IcebergResult<Option<(Value, Value)>> {
+    let mut bounds: Option<(Value, Value)> = None;
+
+    for manifest_file in manifest_files {
+        let spec = metadata.partition_spec(manifest_file.partition_spec_id)
+            .ok_or_else(|| {
+                IcebergError::PartitionError {
+                    message: format!(
+                        "partition spec {} not found in table metadata",
+                        manifest_file.partition_spec_id
+                    )
+                }
+            })?;
+
+        // Manifests written with specs that don't include the partition field can't
+        // contribute to its bounds.
+        let field_index = match spec.fields()
+            .iter()
+            .position(|field| field.field_id == partition_field_id) {
+            Some(field_index) => field_index,
+            None => continue
+        };
+
+        let field_type = match spec.as_struct_type().fields[field_index].schema_type() {
+            SchemaType::Primitive(field_type) => field_type.clone(),
+            _ => return Ok(None)
+        };
+
+        let summary = match manifest_file.partitions
+            .as_ref()
+            .and_then(|partitions| partitions.get(field_index)) {
+            Some(summary) => summary,
+            None => return Ok(None)
+        };
+
+        // Bounds are missing if all values of the field are null.
+        if let (Some(lower), Some(upper)) = (summary.lower_bound(), summary.upper_bound()) {
+            let lower = Value::from_bytes(lower, &field_type)?;
+            let upper = Value::from_bytes(upper, &field_type)?;
+
+            bounds = match bounds {
+                None => Some((lower, upper)),
+                Some((min, max)) => Some((
+                    if lower < min { lower } else { min },
+                    if upper > max { upper } else { max }
+                ))
+            };
+        }
+    }
+
+    Ok(bounds)
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -217,6 +925,31 @@ impl MetadataLog {
     }
 }
 
+/// Deletes metadata files dropped from the metadata log of a committed table.
+///
+/// Failures are ignored, since the commit has already succeeded and the files are
+/// no longer referenced.
+pub(crate) async fn delete_metadata_files(storage: &IcebergStorage, uris: &[String]) {
+    for uri in uris {
+        if let Ok(path) = storage.create_path_from_url(uri) {
+            let _ = storage.delete(&path).await;
+        }
+    }
+}
+
+/// Returns the version of a metadata file from its name.
+///
+/// Hadoop tables name metadata files `v{N}.metadata.json`, other tables
+/// `{N}-{uuid}.metadata.json`.
+fn metadata_file_version(filename: &str) -> Option<i64> {
+    lazy_static! {
+        static ref VERSION_REGEX: Regex =
+            Regex::new(r#"^v?([0-9]+)(-.*)?\.metadata\.json$"#).unwrap();
+    }
+
+    VERSION_REGEX.captures(filename)?.get(1)?.as_str().parse().ok()
+}
+
 /// Holds the current state of the Iceberg table, changing with each commit.
 pub struct IcebergTableState {
     /// UUID identifying the latest snapshot of the table.
@@ -233,6 +966,8 @@ pub struct IcebergTable {
     metadata: Option<IcebergTableMetadata>,
     /// Used to access data and metadata files
     storage: Arc<IcebergStorage>,
+    /// Whether the table was loaded without a catalog, and can't be committed to.
+    read_only: bool,
 }
 
 /// The main interface for working with Iceberg tables.
@@ -246,9 +981,112 @@ impl IcebergTable {
             state: None,
             metadata: None,
             storage: storage,
+            read_only: false,
         }
     }
 
+    /// Creates an Iceberg table from metadata that was already loaded, e.g. by a
+    /// catalog, given the full URL of its metadata file.
+    pub(crate) fn from_metadata(
+        storage: Arc<IcebergStorage>,
+        metadata: IcebergTableMetadata,
+        metadata_location: &str,
+    ) -> IcebergResult<Self> {
+        let metadata_path = storage.create_path_from_url(metadata_location)?;
+
+        // Metadata files written by this crate are named with the version's UUID,
+        // files written by other implementations may not be.
+        let version_uuid = metadata_path.filename()
+            .and_then(|filename| filename.strip_suffix(".metadata.json"))
+            .and_then(|stem| stem.get(stem.len().saturating_sub(36)..))
+            .and_then(|uuid| Uuid::parse_str(uuid).ok())
+            .unwrap_or_else(Uuid::new_v4);
+
+        Ok(Self {
+            state: Some(IcebergTableState {
+                version_uuid: version_uuid,
+                metadata_path: metadata_path,
+            }),
+            metadata: Some(metadata),
+            storage: storage,
+            read_only: false,
+        })
+    }
+
+    /// Loads a read-only table directly from its location, e.g.
+    /// `s3://bucket/path/to/table`, without a catalog.
+    ///
+    /// `storage` is used to access the location's object store, see
+    /// [`IcebergStorage::with_location()`]. The current metadata file is the version
+    /// named by `metadata/version-hint.text`, as written by Hadoop tables. Without a
+    /// version hint, the metadata file with the highest version under `metadata/` is
+    /// loaded.
+    ///
+    /// The table can be read and scanned, but commits require a catalog and fail with
+    /// [`IcebergError::Unsupported`].
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::NotFound`] is returned if there are no metadata files under
+    /// the location, or if the version hint is invalid.
+    /// [`IcebergError::InvalidMetadata`] is returned if the metadata file could not be
+    /// parsed.
+    pub async fn from_location(
+        storage: Arc<IcebergStorage>,
+        location: &str
+    ) -> IcebergResult<Self> {
+        let storage = match storage.location() == location.trim_end_matches('/') {
+            true => storage,
+            false => Arc::new(storage.with_location(location)?),
+        };
+
+        let metadata_path = Self::locate_metadata_file(&storage).await?;
+        let bytes = storage.get(&metadata_path).await?;
+        let metadata = serde_json::from_slice::<IcebergTableMetadata>(&bytes)
+            .map_err(|e| IcebergError::InvalidMetadata { source: e })?;
+
+        let metadata_location = storage.to_uri(&metadata_path);
+        let mut table = Self::from_metadata(storage, metadata, &metadata_location)?;
+        table.read_only = true;
+
+        Ok(table)
+    }
+
+    /// Finds the current metadata file of a table from its version hint, or the
+    /// metadata file with the highest version.
+    async fn locate_metadata_file(
+        storage: &IcebergStorage
+    ) -> IcebergResult<IcebergPath> {
+        let not_found = || IcebergError::NotFound {
+            kind: NotFoundKind::Table,
+            identifier: storage.location().to_string(),
+        };
+
+        match storage.get(&IcebergPath::from("metadata/version-hint.text")).await {
+            Ok(bytes) => {
+                let hint = String::from_utf8_lossy(&bytes);
+                let version = hint.trim().parse::<i32>().map_err(|_| not_found())?;
+                return Ok(IcebergPath::from(
+                    format!("metadata/v{version}.metadata.json")
+                ));
+            },
+            Err(IcebergError::ObjectStore {
+                source: object_store::Error::NotFound { .. }
+            }) => {},
+            Err(err) => return Err(err),
+        }
+
+        let objects = storage.list(Some(&IcebergPath::from("metadata"))).await?;
+        objects.into_iter()
+            .filter_map(|object| {
+                let version = metadata_file_version(object.location.filename()?)?;
+                Some((version, object.last_modified, object.location))
+            })
+            .max_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)))
+            .map(|(_, _, location)| location)
+            .ok_or_else(not_found)
+    }
+
     /// Returns the full URL location of this table.
     pub fn location(&self) -> &str {
         self.storage.location()
@@ -271,6 +1109,31 @@ impl IcebergTable {
             .ok_or(IcebergError::TableNotInitialized)
     }
 
+    /// Returns all the schemas the table ever had, including the current one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub fn schemas(&self) -> IcebergResult<&[Schema]> {
+        self.metadata.as_ref()
+            .map(|metadata| metadata.schemas.as_slice())
+            .ok_or(IcebergError::TableNotInitialized)
+    }
+
+    /// Returns the schema with the given id, or `None` if the table has no such
+    /// schema.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub fn schema_by_id(&self, schema_id: i32) -> IcebergResult<Option<&Schema>> {
+        self.metadata.as_ref()
+            .map(|metadata| metadata.schema_by_id(schema_id))
+            .ok_or(IcebergError::TableNotInitialized)
+    }
+
     /// Returns the latest snapshot of the table, or `None` if the table has no
     /// snapshots.
     ///
@@ -284,6 +1147,53 @@ impl IcebergTable {
             .ok_or(IcebergError::TableNotInitialized)
     }
 
+    /// Returns the snapshot with the given id, or `None` if the table has no such
+    /// snapshot, e.g. because it was expired.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub fn snapshot_by_id(&self, snapshot_id: i64) -> IcebergResult<Option<&Snapshot>> {
+        self.metadata.as_ref()
+            .map(|metadata| metadata.snapshot_by_id(snapshot_id))
+            .ok_or(IcebergError::TableNotInitialized)
+    }
+
+    /// Returns the statistics file of the snapshot with the given id, or `None` if
+    /// the snapshot has no statistics.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub fn statistics_for(
+        &self,
+        snapshot_id: i64
+    ) -> IcebergResult<Option<&StatisticsFile>> {
+        self.metadata.as_ref()
+            .map(|metadata| metadata.statistics_for(snapshot_id))
+            .ok_or(IcebergError::TableNotInitialized)
+    }
+
+    /// Returns the snapshot that was current at the given time, in milliseconds since
+    /// the unix epoch, or `None` if the time predates the table's first snapshot.
+    ///
+    /// See [`IcebergTableMetadata::snapshot_as_of_timestamp()`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub fn snapshot_as_of_timestamp(
+        &self,
+        timestamp_ms: i64
+    ) -> IcebergResult<Option<&Snapshot>> {
+        self.metadata.as_ref()
+            .map(|metadata| metadata.snapshot_as_of_timestamp(timestamp_ms))
+            .ok_or(IcebergError::TableNotInitialized)
+    }
+
     /// Returns a reference to the current table's metadata.
     ///
     /// # Errors
@@ -316,10 +1226,31 @@ impl IcebergTable {
         &self,
         snapshot: &Snapshot
     ) -> IcebergResult<ManifestList> {
-        let path = self.storage.create_path_from_url(&snapshot.manifest_list)?;
-        let bytes = self.storage.get(&path).await?;
+        snapshot.read_manifest_list(&self.storage).await
+    }
+
+    /// Returns the minimum and maximum values of a partition field in the current
+    /// snapshot, without opening any data or manifest files.
+    ///
+    /// The bounds are aggregated from the partition field summaries stored in the
+    /// manifest list. Returns `None` if the table has no snapshots, or if the summaries
+    /// are unavailable.
+    pub async fn partition_bounds(
+        &self,
+        partition_field_id: i32
+    ) -> IcebergResult<Option<(Value, Value)>> {
+        let snapshot = match self.current_snapshot()? {
+            Some(snapshot) => snapshot,
+            None => return Ok(None)
+        };
+
+        let manifest_list = self.read_manifest_list(snapshot).await?;
 
-        ManifestList::decode(bytes.as_ref())
+        aggregate_partition_bounds(
+            self.current_metadata()?,
+            manifest_list.manifest_files(),
+            partition_field_id
+        )
     }
 
     /// Commits the given metadata to the table, replacing the existing metadata.
@@ -330,28 +1261,40 @@ impl IcebergTable {
         &mut self,
         mut metadata: IcebergTableMetadata,
     ) -> IcebergResult<()> {
+        if self.read_only {
+            return Err(IcebergError::Unsupported(format!(
+                "table at {} was loaded from its location and is read-only, commits \
+                require a catalog",
+                self.location()
+            )));
+        }
+
         // Add a log entry to the new metadata about the previous metadata.
+        let mut expired_metadata_files = Vec::new();
         if let Some(current_metadata) = &self.metadata {
            if let Some(state) = &self.state {
-               metadata.metadata_log
-                   .get_or_insert(Vec::new())
-                   .push(MetadataLog::new(
-                       &self.storage.to_uri(&state.metadata_path),
-                       current_metadata.last_updated_ms
-                   ));
+               expired_metadata_files = metadata.add_previous_metadata_file(
+                   &self.storage.to_uri(&state.metadata_path),
+                   current_metadata.last_updated_ms
+               )?;
            }
        }
 
-        let json = serde_json::to_string_pretty(&metadata)
-            .map_err(|e| IcebergError::SerializeMetadataJson { source: e })?;
+        let json = metadata.to_json()?;
 
         // Generate a new UUID for this version, and set it only after the new metadata
         // file has been committed.
         let new_version_uuid = Uuid::new_v4();
+
+        // Metadata files are numbered one higher than the version they replace.
+        let version = self.state.as_ref()
+            .and_then(|state| state.metadata_path.filename())
+            .and_then(metadata_file_version)
+            .map_or(0, |version| version + 1);
         let metadata_file = self.new_metadata_file(
             &format!(
                 "{:05}-{}.metadata.json",
-                metadata.last_sequence_number,
+                version,
                 new_version_uuid.to_string()
             ),
             Bytes::from(json)
@@ -367,6 +1310,8 @@ impl IcebergTable {
             metadata_path: metadata_file.path().clone(),
         });
 
+        delete_metadata_files(&self.storage, &expired_metadata_files).await;
+
         Ok(())
     }
 
@@ -395,6 +1340,11 @@ impl IcebergTable {
                 .unwrap();
         }
 
+        let not_found = || IcebergError::NotFound {
+            kind: NotFoundKind::Table,
+            identifier: self.location().to_string(),
+        };
+
         let objects = self.storage.list(Some(&IcebergPath::from("metadata"))).await?;
 
         // Find the highest numbered metadata file.
@@ -404,19 +1354,13 @@ impl IcebergTable {
                     .then(|| obj_meta.location)
             })
             .max_by(|p, q| p.as_ref().cmp(q.as_ref()))
-            .ok_or_else(|| {
-                IcebergError::MetadataNotFound(self.location().to_string())
-            })?;
+            .ok_or_else(not_found)?;
 
         let captures = METADATA_FILE_REGEX.captures(metadata_path.as_ref())
-            .ok_or_else(|| {
-                IcebergError::MetadataNotFound(self.location().to_string())
-            })?;
+            .ok_or_else(not_found)?;
 
         let uuid = Uuid::parse_str(captures.get(1).unwrap().as_str())
-            .map_err(|_| {
-                IcebergError::MetadataNotFound(self.location().to_string())
-            })?;
+            .map_err(|_| not_found())?;
 
         Ok(IcebergTableState {
             version_uuid: uuid,
@@ -436,8 +1380,8 @@ impl IcebergTable {
     ///
     /// # Errors
     ///
-    /// This function returns [`IcebergError::MetadataNotFound`] if the table's metadata
-    /// could not be located. In this case the table's state is left untouched and
+    /// This function returns [`IcebergError::NotFound`] if the table's metadata could
+    /// not be located. In this case the table's state is left untouched and
     /// [`IcebergTable::create()`] can be called instead.
     /// [`IcebergError::InvalidMetadata`] is returned if the table's metadata could be
     /// found but could not be correctly parsed.
@@ -455,6 +1399,304 @@ impl IcebergTable {
         Ok(())
     }
 
+    /// Refreshes the table to the latest version committed to the catalog, e.g. by
+    /// other writers, and returns whether the table changed.
+    ///
+    /// The metadata is only reloaded if the catalog's metadata location differs from
+    /// the table's. Scans started after refreshing see the latest snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the table can't be loaded from the catalog, in which case the table
+    /// is left unchanged.
+    pub async fn refresh(
+        &mut self,
+        catalog: &dyn Catalog,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<bool> {
+        let metadata_location = catalog.load_metadata_location(identifier).await?;
+        if self.current_metadata_uri().ok().as_ref() == Some(&metadata_location) {
+            return Ok(false);
+        }
+
+        let table = catalog.load_table(identifier).await?;
+        // The state's metadata path is relative to the loaded table's storage.
+        self.state = table.state;
+        self.metadata = table.metadata;
+        self.storage = table.storage;
+
+        Ok(true)
+    }
+
+    /// Returns the live data files of the current snapshot, i.e. the files of its
+    /// manifest entries that weren't deleted. The delete files of the snapshot are
+    /// included as well if `include_delete_files` is set.
+    ///
+    /// Returns an empty list if the table has no snapshots.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub async fn data_files(
+        &self,
+        include_delete_files: bool
+    ) -> IcebergResult<Vec<DataFile>> {
+        let snapshot = match self.current_snapshot()? {
+            Some(snapshot) => snapshot,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut data_files = Vec::new();
+        let manifest_list = self.read_manifest_list(snapshot).await?;
+        for manifest_file in manifest_list.manifest_files() {
+            if !include_delete_files
+                && manifest_file.content == ManifestFileType::Delete {
+                continue;
+            }
+
+            let entries = ManifestReader::for_manifest_file(manifest_file)
+                .read_entries(&manifest_file.manifest_path, &self.storage)
+                .await?;
+            data_files.extend(
+                entries.into_iter()
+                    .filter(|entry| !entry.deleted())
+                    .map(|entry| entry.data_file)
+            );
+        }
+
+        Ok(data_files)
+    }
+
+    /// Returns the URLs of the manifest list, manifests and live data files of the
+    /// given snapshot.
+    async fn snapshot_files(&self, snapshot: &Snapshot) -> IcebergResult<HashSet<String>> {
+        let mut files = HashSet::from([snapshot.manifest_list.clone()]);
+
+        let manifest_list = self.read_manifest_list(snapshot).await?;
+        for manifest_file in manifest_list.manifest_files() {
+            let entries = ManifestReader::for_manifest_file(manifest_file)
+                .read_entries(&manifest_file.manifest_path, &self.storage)
+                .await?;
+
+            files.insert(manifest_file.manifest_path.clone());
+            files.extend(
+                entries.into_iter()
+                    .filter(|entry| !entry.deleted())
+                    .map(|entry| entry.data_file.file_path)
+            );
+        }
+
+        Ok(files)
+    }
+
+    /// Lists the files under the table's location that aren't referenced by the
+    /// table, and were last modified before `older_than_ms`, returning their URLs.
+    ///
+    /// Referenced files are the current and previous metadata files, statistics
+    /// files, and the manifest lists, manifests, data and delete files of all
+    /// snapshots. Such orphan files are left behind by failed writes, or by
+    /// expiring snapshots. Files modified since `older_than_ms` are never listed,
+    /// since they may belong to writes still in progress.
+    ///
+    /// The files are not deleted.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub async fn list_orphan_files(
+        &self,
+        older_than_ms: i64
+    ) -> IcebergResult<Vec<String>> {
+        let metadata = self.current_metadata()?;
+
+        let mut referenced = HashSet::from([
+            self.current_metadata_uri()?,
+            self.storage.to_uri(&IcebergPath::from("metadata/version-hint.text")),
+        ]);
+        referenced.extend(
+            metadata.metadata_log.iter().flatten()
+                .map(|entry| entry.metadata_file.clone())
+        );
+        referenced.extend(
+            metadata.statistics.iter().flatten()
+                .map(|statistics| statistics.statistics_path.clone())
+        );
+        for snapshot in metadata.snapshots.iter().flatten() {
+            referenced.extend(self.snapshot_files(snapshot).await?);
+        }
+
+        // Compare paths relative to the table's location, since URLs of the same file
+        // may differ, e.g. by a trailing slash of the location.
+        let referenced: HashSet<String> = referenced.iter()
+            .filter_map(|url| self.storage.create_path_from_url(url).ok())
+            .map(|path| path.to_string())
+            .collect();
+
+        let mut orphans: Vec<String> = self.storage.list(None).await?
+            .into_iter()
+            .filter(|object| object.last_modified.timestamp_millis() < older_than_ms)
+            .filter(|object| !referenced.contains(&object.location.to_string()))
+            .map(|object| self.storage.to_uri(&object.location))
+            .collect();
+        orphans.sort();
+
+        Ok(orphans)
+    }
+
+    /// Expires snapshots older than `older_than_ms` and commits the new metadata,
+    /// while always keeping the `retain_last` most recent snapshots.
+    ///
+    /// Ancestors of the current snapshot and snapshots referenced by a branch or tag
+    /// are never expired.
+    ///
+    /// Returns the URLs of the manifest lists, manifests and data files that are no
+    /// longer referenced by any of the remaining snapshots. The files are not deleted,
+    /// so the caller can delete them once no readers use the expired snapshots.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub async fn expire_snapshots(
+        &mut self,
+        older_than_ms: i64,
+        retain_last: usize
+    ) -> IcebergResult<HashSet<String>> {
+        let metadata = self.current_metadata()?;
+        let expired_ids = metadata.snapshots_to_expire(older_than_ms, retain_last);
+        if expired_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let mut expired_files = HashSet::new();
+        let mut retained_files = HashSet::new();
+        for snapshot in metadata.snapshots.iter().flatten() {
+            let files = self.snapshot_files(snapshot).await?;
+            if expired_ids.contains(&snapshot.snapshot_id) {
+                expired_files.extend(files);
+            } else {
+                retained_files.extend(files);
+            }
+        }
+
+        let mut new_metadata = metadata.clone();
+        new_metadata.last_updated_ms = utils::current_time_ms()?;
+        if let Some(snapshots) = new_metadata.snapshots.as_mut() {
+            snapshots.retain(|snapshot| !expired_ids.contains(&snapshot.snapshot_id));
+        }
+        if let Some(snapshot_log) = new_metadata.snapshot_log.as_mut() {
+            snapshot_log.retain(|entry| !expired_ids.contains(&entry.snapshot_id));
+        }
+
+        self.commit(new_metadata).await?;
+
+        Ok(expired_files.difference(&retained_files).cloned().collect())
+    }
+
+    /// Upgrades the table to the given format version and commits the new metadata.
+    ///
+    /// Upgrading version 1 metadata writes it in version 2 form: the current schema
+    /// and partition spec are only written in the `schemas` and `partition-specs`
+    /// lists, and snapshots without sequence numbers have sequence number 0, the
+    /// sequence number of all data in the version 1 table.
+    ///
+    /// Returns `false` without committing if the table is already at the given
+    /// version.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`] is returned if the version is older than the
+    /// table's, since format versions can't be downgraded.
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    pub async fn upgrade_format_version(
+        &mut self,
+        format_version: IcebergTableVersion
+    ) -> IcebergResult<bool> {
+        let metadata = self.current_metadata()?;
+        if format_version == metadata.format_version {
+            return Ok(false);
+        }
+        if (format_version.clone() as i32) < (metadata.format_version.clone() as i32) {
+            return Err(IcebergError::ValueError(format!(
+                "can't downgrade format version from {} to {}",
+                metadata.format_version, format_version
+            )));
+        }
+
+        let mut new_metadata = metadata.clone();
+        new_metadata.format_version = format_version;
+        new_metadata.last_updated_ms = utils::current_time_ms()?;
+        // The last sequence number must cover the sequence numbers of all snapshots.
+        let max_sequence_number = new_metadata.snapshots.iter()
+            .flatten()
+            .map(|snapshot| snapshot.sequence_number)
+            .max()
+            .unwrap_or(0);
+        new_metadata.last_sequence_number =
+            new_metadata.last_sequence_number.max(max_sequence_number) + 1;
+
+        self.commit(new_metadata).await?;
+
+        Ok(true)
+    }
+
+    /// Merges the small data manifests of the current snapshot into fewer, larger
+    /// ones and commits them in a new `replace` snapshot.
+    ///
+    /// Manifests smaller than the table's [`MANIFEST_TARGET_SIZE_BYTES_PROPERTY`]
+    /// are merged, up to that size, with other small manifests of the same partition
+    /// spec. See [`RewriteManifestsOperation`] for details.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    ///
+    /// [`RewriteManifestsOperation`]: crate::transaction::RewriteManifestsOperation
+    pub async fn rewrite_manifests(&mut self) -> IcebergResult<()> {
+        let target_size_bytes = self.current_metadata()?.manifest_target_size_bytes()?;
+
+        let mut transaction = self.new_transaction();
+        transaction.add_operation(Box::new(
+            RewriteManifestsOperation::new(target_size_bytes)
+        ));
+        transaction.commit().await
+    }
+
+    /// Creates a scan over the current snapshot of this table.
+    pub fn scan(&self) -> TableScan {
+        TableScan::new(self)
+    }
+
+    /// Creates a builder for evolving the table's current partition spec.
+    ///
+    /// The spec it builds is committed with an [`UpdatePartitionSpecOperation`]
+    /// as part of a transaction.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`IcebergError::TableNotInitialized`] if the table has not been
+    /// initialized with either [`IcebergTable::create()`] or [`IcebergTable::load()`].
+    ///
+    /// [`UpdatePartitionSpecOperation`]: crate::transaction::UpdatePartitionSpecOperation
+    pub fn update_spec(&self) -> IcebergResult<PartitionSpecUpdate> {
+        let metadata = self.current_metadata()?;
+        let spec_id = metadata.partition_specs.iter()
+            .map(|spec| spec.spec_id)
+            .max()
+            .unwrap_or(0) + 1;
+
+        Ok(PartitionSpecUpdate::new(
+            spec_id,
+            &metadata.current_partition_spec(),
+            metadata.current_schema().clone(),
+            metadata.last_partition_id
+        ))
+    }
+
     /// Initiates a new transaction on this table. Only a single transaction can be
     /// created at any given time.
     pub fn new_transaction(&mut self) -> Transaction {
@@ -619,8 +1861,8 @@ impl IcebergTableLoader {
     ///
     /// # Errors
     ///
-    /// This function returns [`IcebergError::MetadataNotFound`] if the table's metadata
-    /// could not be located.  [`IcebergError::InvalidMetadata`] is returned if the
+    /// This function returns [`IcebergError::NotFound`] if the table's metadata could
+    /// not be located.  [`IcebergError::InvalidMetadata`] is returned if the
     /// table's metadata could be found but could not be correctly parsed.
     /// [`IcebergError::ObjectStore`] could be returned if there was an error reading
     /// from the object storage.
@@ -688,7 +1930,7 @@ impl IcebergTableLoader {
             Ok(..) => Ok(table),
             Err(err) => {
                 match err {
-                    IcebergError::MetadataNotFound(..) => {
+                    IcebergError::NotFound { kind: NotFoundKind::Table, .. } => {
                         let schema = self.schema.ok_or(
                             IcebergError::SchemaError {
                                 message: "missing schema".to_string()
@@ -762,8 +2004,25 @@ impl IcebergFile {
 
 #[cfg(test)]
 mod tests {
-    use crate::IcebergTableMetadata;
+    use std::collections::HashMap;
+
+    use crate::{IcebergTableMetadata, IcebergTableVersion};
+    use crate::snapshot::{Snapshot, SnapshotSummary, SnapshotLog};
     use crate::schema::{Schema, Field, SchemaType, PrimitiveType};
+    use crate::value::Value;
+    use crate::partition::{
+        PartitionSpec, PartitionSpecModel, PartitionField, PartitionTransform
+    };
+    use crate::sort::{SortOrder, SortField, SortDirection, NullOrder};
+    use crate::manifest::{ManifestFile, ManifestFileType, PartitionFieldSummary};
+    use crate::IcebergError;
+    use crate::writer::TARGET_FILE_SIZE_BYTES_PROPERTY;
+    use super::{
+        aggregate_partition_bounds,
+        METADATA_JSON_PRETTY_PROPERTY,
+        METADATA_DELETE_AFTER_COMMIT_PROPERTY,
+        METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY,
+    };
 
     fn create_schema(schema_id: i32) -> Schema {
         Schema::new(schema_id, vec![
@@ -800,6 +2059,422 @@ mod tests {
         assert_eq!(metadata.current_schema().id(), 0);
     }
 
+    #[test]
+    fn schema_history() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        let schema = Schema::new(1, vec![
+            Field::new_primitive(0, "id", true, PrimitiveType::Long),
+            Field::new_primitive(3, "comment", false, PrimitiveType::String),
+        ]);
+        metadata.schemas.push(schema.clone());
+        metadata.current_schema_id = 1;
+
+        assert_eq!(metadata.schemas.len(), 2);
+        assert_eq!(metadata.schema_by_id(0), Some(&create_schema(0)));
+        assert_eq!(metadata.schema_by_id(1), Some(&schema));
+        assert_eq!(metadata.schema_by_id(2), None);
+        assert_eq!(metadata.current_schema(), &schema);
+    }
+
+    #[test]
+    fn metadata_json_formatting() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        // Compact by default.
+        let compact = metadata.to_json().unwrap();
+        assert!(!compact.contains('\n'));
+
+        metadata.properties = Some(HashMap::from([
+            (METADATA_JSON_PRETTY_PROPERTY.to_string(), "true".to_string())
+        ]));
+        let pretty = metadata.to_json().unwrap();
+        assert!(pretty.contains('\n'));
+
+        for json in [compact, pretty] {
+            let parsed: IcebergTableMetadata = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed.current_schema(), metadata.current_schema());
+        }
+    }
+
+    #[test]
+    fn validate_metadata() {
+        let metadata = || IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+        assert!(metadata().validate().is_ok());
+
+        let mut missing_schema = metadata();
+        missing_schema.current_schema_id = 1;
+        assert!(matches!(
+            missing_schema.validate(),
+            Err(IcebergError::Validation { .. })
+        ));
+        assert!(missing_schema.to_json().is_err());
+
+        let mut missing_spec = metadata();
+        missing_spec.default_spec_id = 1;
+        assert!(matches!(
+            missing_spec.validate(),
+            Err(IcebergError::Validation { .. })
+        ));
+
+        let mut missing_sort_order = metadata();
+        missing_sort_order.default_sort_order_id = 1;
+        assert!(matches!(
+            missing_sort_order.validate(),
+            Err(IcebergError::Validation { .. })
+        ));
+
+        let mut dangling_partition_source = metadata();
+        dangling_partition_source.add_partition_spec(PartitionSpecModel {
+            spec_id: 1,
+            fields: vec![
+                PartitionField::new(10, 1000, "missing", PartitionTransform::Identity)
+            ]
+        });
+        dangling_partition_source.default_spec_id = 1;
+        assert!(matches!(
+            dangling_partition_source.validate(),
+            Err(IcebergError::Validation { .. })
+        ));
+
+        let mut dangling_sort_source = metadata();
+        dangling_sort_source.sort_orders.push(SortOrder {
+            order_id: 1,
+            fields: vec![SortField {
+                source_id: 10,
+                transform: PartitionTransform::Identity,
+                direction: SortDirection::Ascending,
+                null_order: NullOrder::First,
+            }]
+        });
+        dangling_sort_source.default_sort_order_id = 1;
+        assert!(matches!(
+            dangling_sort_source.validate(),
+            Err(IcebergError::Validation { .. })
+        ));
+    }
+
+    #[test]
+    fn typed_properties() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        assert_eq!(metadata.format_version(), 2);
+        assert_eq!(metadata.write_target_file_size_bytes().unwrap(), 536870912);
+        assert!(!metadata.metadata_delete_after_commit().unwrap());
+        assert_eq!(metadata.metadata_previous_versions_max().unwrap(), 100);
+
+        metadata.properties = Some(HashMap::from([
+            (TARGET_FILE_SIZE_BYTES_PROPERTY.to_string(), "1048576".to_string()),
+            (METADATA_DELETE_AFTER_COMMIT_PROPERTY.to_string(), "true".to_string()),
+            (METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY.to_string(), "5".to_string()),
+        ]));
+        assert_eq!(metadata.write_target_file_size_bytes().unwrap(), 1048576);
+        assert!(metadata.metadata_delete_after_commit().unwrap());
+        assert_eq!(metadata.metadata_previous_versions_max().unwrap(), 5);
+
+        metadata.properties = Some(HashMap::from([
+            (TARGET_FILE_SIZE_BYTES_PROPERTY.to_string(), "big".to_string()),
+            (METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY.to_string(), "-1".to_string()),
+        ]));
+        assert!(matches!(
+            metadata.write_target_file_size_bytes(),
+            Err(IcebergError::InvalidProperty { key, value })
+                if key == TARGET_FILE_SIZE_BYTES_PROPERTY && value == "big"
+        ));
+        assert!(matches!(
+            metadata.metadata_previous_versions_max(),
+            Err(IcebergError::InvalidProperty { .. })
+        ));
+    }
+
+    #[test]
+    fn trim_metadata_log() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+        metadata.properties = Some(HashMap::from([
+            (METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY.to_string(), "2".to_string()),
+        ]));
+
+        let file = |version: i64| format!("s3://bucket/path/to/table/v{version}.json");
+        for version in 1..=3 {
+            // Old files are only dropped from the log, not deleted.
+            let expired = metadata.add_previous_metadata_file(&file(version), version);
+            assert!(expired.unwrap().is_empty());
+        }
+        assert_eq!(metadata.metadata_log.as_ref().unwrap().len(), 2);
+
+        metadata.properties.as_mut().unwrap().insert(
+            METADATA_DELETE_AFTER_COMMIT_PROPERTY.to_string(),
+            "true".to_string()
+        );
+        let expired = metadata.add_previous_metadata_file(&file(4), 4).unwrap();
+        assert_eq!(expired, [file(2)]);
+
+        let log: Vec<&str> = metadata.metadata_log.as_ref().unwrap().iter()
+            .map(|entry| entry.metadata_file.as_str())
+            .collect();
+        assert_eq!(log, [file(3), file(4)]);
+    }
+
+    fn create_manifest_file(partitions: Option<Vec<PartitionFieldSummary>>) -> ManifestFile {
+        ManifestFile {
+            manifest_path: "s3://bucket/path/to/table/metadata/manifest.avro".to_string(),
+            manifest_length: 1024,
+            partition_spec_id: 0,
+            content: ManifestFileType::Data,
+            sequence_number: 1,
+            min_sequence_number: 1,
+            added_snapshot_id: 1,
+            added_data_files_count: 1,
+            existing_data_files_count: 0,
+            deleted_data_files_count: 0,
+            added_rows_count: 100,
+            existing_rows_count: 0,
+            deleted_rows_count: 0,
+            partitions: partitions,
+        }
+    }
+
+    fn int_summary(lower: i32, upper: i32) -> PartitionFieldSummary {
+        PartitionFieldSummary::new(
+            false,
+            None,
+            Some(lower.to_le_bytes().to_vec()),
+            Some(upper.to_le_bytes().to_vec())
+        )
+    }
+
+    #[test]
+    fn partition_bounds() {
+        let schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "user_id", true, PrimitiveType::Int),
+        ]);
+        let spec = PartitionSpec::builder(0, schema.clone())
+            .add_identity_field("user_id").unwrap()
+            .build();
+        let partition_field_id = spec.fields()[0].field_id;
+
+        let metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            schema,
+            Some(spec),
+            None
+        ).unwrap();
+
+        let manifest_files = vec![
+            create_manifest_file(Some(vec![int_summary(10, 20)])),
+            create_manifest_file(Some(vec![int_summary(-5, 15)])),
+            // Contains only nulls.
+            create_manifest_file(Some(vec![
+                PartitionFieldSummary::new(true, None, None, None)
+            ])),
+            create_manifest_file(Some(vec![int_summary(0, 42)])),
+        ];
+
+        assert_eq!(
+            aggregate_partition_bounds(&metadata, &manifest_files, partition_field_id)
+                .unwrap(),
+            Some((Value::Int(-5), Value::Int(42)))
+        );
+
+        // Unknown partition field.
+        assert_eq!(
+            aggregate_partition_bounds(&metadata, &manifest_files, 2000).unwrap(),
+            None
+        );
+
+        // Missing summaries.
+        let manifest_files = vec![
+            create_manifest_file(Some(vec![int_summary(10, 20)])),
+            create_manifest_file(None),
+        ];
+        assert_eq!(
+            aggregate_partition_bounds(&metadata, &manifest_files, partition_field_id)
+                .unwrap(),
+            None
+        );
+    }
+
+    fn create_snapshot(snapshot_id: i64, parent_snapshot_id: Option<i64>) -> Snapshot {
+        Snapshot {
+            snapshot_id: snapshot_id,
+            parent_snapshot_id: parent_snapshot_id,
+            sequence_number: snapshot_id,
+            timestamp_ms: 1681727363902 + snapshot_id,
+            manifest_list: format!("s3://bucket/table/metadata/snap-{snapshot_id}.avro"),
+            summary: SnapshotSummary::default(),
+            schema_id: Some(0),
+        }
+    }
+
+    fn snapshot_ids(snapshots: Vec<&Snapshot>) -> Vec<i64> {
+        snapshots.iter().map(|snapshot| snapshot.snapshot_id).collect()
+    }
+
+    #[test]
+    fn snapshot_ancestors() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        assert!(metadata.current_ancestors().is_empty());
+
+        metadata.snapshots = Some(vec![
+            create_snapshot(1, None),
+            create_snapshot(2, Some(1)),
+            create_snapshot(3, Some(2)),
+        ]);
+        metadata.current_snapshot_id = Some(3);
+
+        assert_eq!(snapshot_ids(metadata.current_ancestors()), vec![3, 2, 1]);
+        assert_eq!(snapshot_ids(metadata.ancestors_of(2)), vec![2, 1]);
+        assert!(metadata.ancestors_of(4).is_empty());
+    }
+
+    #[test]
+    fn snapshot_ancestors_with_missing_parent() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        // Snapshot 1 was expired.
+        metadata.snapshots = Some(vec![
+            create_snapshot(2, Some(1)),
+            create_snapshot(3, Some(2)),
+        ]);
+
+        assert_eq!(snapshot_ids(metadata.ancestors_of(3)), vec![3, 2]);
+    }
+
+    #[test]
+    fn snapshot_ancestors_with_cycle() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        metadata.snapshots = Some(vec![
+            create_snapshot(1, Some(1)),
+            create_snapshot(2, Some(3)),
+            create_snapshot(3, Some(2)),
+        ]);
+
+        assert_eq!(snapshot_ids(metadata.ancestors_of(1)), vec![1]);
+        assert_eq!(snapshot_ids(metadata.ancestors_of(3)), vec![3, 2]);
+    }
+
+    #[test]
+    fn snapshots_to_expire() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        // Snapshots 3 and 4 were committed on top of snapshot 2, but the table was
+        // then rolled back to snapshot 2 and snapshot 5 was committed on top of it.
+        metadata.snapshots = Some(vec![
+            create_snapshot(1, None),
+            create_snapshot(2, Some(1)),
+            create_snapshot(3, Some(2)),
+            create_snapshot(4, Some(3)),
+            create_snapshot(5, Some(2)),
+        ]);
+        metadata.current_snapshot_id = Some(5);
+
+        let expired = |older_than_ms, retain_last| {
+            let mut ids: Vec<i64> = metadata.snapshots_to_expire(older_than_ms, retain_last)
+                .into_iter()
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        // Ancestors of the current snapshot are never expired.
+        assert_eq!(expired(i64::MAX, 0), vec![3, 4]);
+        // Retains the last snapshots, even if they're old.
+        assert_eq!(expired(i64::MAX, 2), vec![3]);
+        assert_eq!(expired(i64::MAX, 5), Vec::<i64>::new());
+        // Only snapshots older than the cutoff are expired.
+        assert_eq!(
+            expired(create_snapshot(4, None).timestamp_ms, 0),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn snapshot_as_of_timestamp() {
+        let mut metadata = IcebergTableMetadata::try_new(
+            "s3://bucket/path/to/table".to_string(),
+            create_schema(0),
+            None,
+            None
+        ).unwrap();
+
+        assert!(metadata.snapshot_as_of_timestamp(2000).is_none());
+
+        metadata.snapshots = Some(vec![
+            create_snapshot(1, None),
+            create_snapshot(2, Some(1)),
+            create_snapshot(3, Some(2)),
+        ]);
+        metadata.current_snapshot_id = Some(3);
+        metadata.snapshot_log = Some(vec![
+            SnapshotLog::new(1, 1000),
+            SnapshotLog::new(2, 2000),
+            SnapshotLog::new(3, 3000),
+        ]);
+
+        let snapshot_id = |timestamp_ms| {
+            metadata.snapshot_as_of_timestamp(timestamp_ms)
+                .map(|snapshot| snapshot.snapshot_id)
+        };
+
+        // Before the first snapshot.
+        assert_eq!(snapshot_id(999), None);
+        // Exact boundaries.
+        assert_eq!(snapshot_id(1000), Some(1));
+        assert_eq!(snapshot_id(2000), Some(2));
+        assert_eq!(snapshot_id(3000), Some(3));
+        // In between and after the last snapshot.
+        assert_eq!(snapshot_id(2999), Some(2));
+        assert_eq!(snapshot_id(i64::MAX), Some(3));
+    }
+
     #[test]
     fn deserialize_metadata() {
         let metadata_json = r#"
@@ -893,4 +2568,99 @@ mod tests {
         assert_eq!(metadata.snapshot_log.unwrap().len(), 1);
         assert_eq!(metadata.metadata_log.unwrap().len(), 1);
     }
+
+    #[test]
+    fn deserialize_metadata_v1() {
+        let metadata_json = r#"
+            {
+              "format-version" : 1,
+              "table-uuid" : "d20125c8-7284-442c-9aea-15fee620737c",
+              "location" : "s3://bucket/test/location",
+              "last-updated-ms" : 1602638573874,
+              "last-column-id" : 3,
+              "schema" : {
+                "type" : "struct",
+                "fields" : [ {
+                  "id" : 1,
+                  "name" : "x",
+                  "required" : true,
+                  "type" : "long"
+                }, {
+                  "id" : 2,
+                  "name" : "y",
+                  "required" : true,
+                  "type" : "long",
+                  "doc" : "comment"
+                }, {
+                  "id" : 3,
+                  "name" : "z",
+                  "required" : true,
+                  "type" : "long"
+                } ]
+              },
+              "partition-spec" : [ {
+                "name" : "x",
+                "transform" : "identity",
+                "source-id" : 1
+              } ],
+              "properties" : { },
+              "current-snapshot-id" : 3051729675574597004,
+              "snapshots" : [ {
+                "snapshot-id" : 3051729675574597004,
+                "timestamp-ms" : 1515100955770,
+                "summary" : {
+                  "operation" : "append"
+                },
+                "manifest-list" : "s3://bucket/test/location/metadata/snap-3051729675574597004.avro"
+              } ],
+              "snapshot-log" : [ {
+                "timestamp-ms" : 1515100955770,
+                "snapshot-id" : 3051729675574597004
+              } ],
+              "metadata-log" : [ ]
+            }"#;
+
+        let metadata = serde_json::from_str::<IcebergTableMetadata>(metadata_json)
+            .unwrap();
+
+        assert_eq!(metadata.format_version, IcebergTableVersion::V1);
+        assert_eq!(metadata.table_uuid, "d20125c8-7284-442c-9aea-15fee620737c");
+        assert_eq!(metadata.last_sequence_number, 0);
+        assert_eq!(metadata.schemas.len(), 1);
+        assert_eq!(metadata.current_schema_id, 0);
+        assert_eq!(metadata.current_schema().fields().len(), 3);
+        assert_eq!(metadata.default_spec_id, 0);
+        assert_eq!(
+            metadata.current_partition_spec().fields(),
+            [PartitionField::new(1, 1000, "x", PartitionTransform::Identity)]
+        );
+        assert_eq!(metadata.last_partition_id, 1000);
+        assert_eq!(metadata.sort_orders, vec![SortOrder::new()]);
+        assert_eq!(metadata.default_sort_order_id, 0);
+        let snapshots = metadata.snapshots.as_ref().unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].sequence_number, 0);
+
+        // Version 1 metadata is written with the single schema and spec.
+        let json: serde_json::Value = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["format-version"], 1);
+        assert_eq!(json["schema"]["fields"].as_array().unwrap().len(), 3);
+        assert_eq!(json["partition-spec"][0]["field-id"], 1000);
+        assert_eq!(json["schemas"].as_array().unwrap().len(), 1);
+
+        let parsed: IcebergTableMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, metadata);
+
+        // Version 2 metadata is written without them.
+        let mut v2 = metadata.clone();
+        v2.format_version = IcebergTableVersion::V2;
+        let json: serde_json::Value = serde_json::to_value(&v2).unwrap();
+        assert!(json.get("schema").is_none());
+        assert!(json.get("partition-spec").is_none());
+
+        // Fields optional in version 1 are required in version 2.
+        let mut json: serde_json::Value = serde_json::from_str(metadata_json).unwrap();
+        json["format-version"] = 2.into();
+        assert!(serde_json::from_value::<IcebergTableMetadata>(json).is_err());
+    }
 }
@@ -70,10 +70,14 @@ pub mod value;
 pub mod snapshot;
 pub mod partition;
 pub mod sort;
+pub mod expr;
 pub mod manifest;
 pub mod storage;
+pub mod scan;
 pub mod transaction;
 pub mod writer;
+pub mod puffin;
+pub mod catalog;
 
 pub use crate::iceberg::{
     IcebergTable, IcebergTableVersion, IcebergTableMetadata,
@@ -83,6 +87,34 @@ pub use crate::iceberg::{
 /// A result type returned by functions in this crate.
 pub type IcebergResult<T> = Result<T, IcebergError>;
 
+/// The kind of entity that was not found, see [`IcebergError::NotFound`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NotFoundKind {
+    Namespace,
+    Table,
+    Snapshot,
+    Schema,
+    PartitionSpec,
+    SortOrder,
+    Field,
+    File,
+}
+
+impl std::fmt::Display for NotFoundKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            NotFoundKind::Namespace => "namespace",
+            NotFoundKind::Table => "table",
+            NotFoundKind::Snapshot => "snapshot",
+            NotFoundKind::Schema => "schema",
+            NotFoundKind::PartitionSpec => "partition spec",
+            NotFoundKind::SortOrder => "sort order",
+            NotFoundKind::Field => "field",
+            NotFoundKind::File => "file",
+        })
+    }
+}
+
 /// An Iceberg table error.
 #[derive(thiserror::Error, Debug)]
 pub enum IcebergError {
@@ -92,11 +124,6 @@ pub enum IcebergError {
     #[error("unsupported: {0}")]
     Unsupported(String),
 
-    /// The table's metadata file could not be located. Usually this means the table
-    /// does not exist at the specified location.
-    #[error("Iceberg table metadata not found at {0}")]
-    MetadataNotFound(String),
-
     /// The URL location specified for the table is invalid: It might have an invalid
     /// URL scheme, point to an invalid path or path that is not a directory when using
     /// local file systems.
@@ -106,6 +133,14 @@ pub enum IcebergError {
     #[error("Iceberg table already exists at: {0}")]
     TableAlreadyExists(String),
 
+    /// An entity, e.g. a table or a snapshot, does not exist.
+    #[error("{kind} {identifier} not found")]
+    NotFound { kind: NotFoundKind, identifier: String },
+
+    /// Table metadata or a manifest has a format version this crate can't handle.
+    #[error("unsupported table format version {0}")]
+    UnsupportedFormatVersion(u8),
+
     /// An operation has been attempted on an Iceberg table that was not initialized
     /// and therefore has no [IcebergTableMetadata] associated with it.
     #[error("Iceberg table not initialized")]
@@ -118,6 +153,11 @@ pub enum IcebergError {
     #[error("value error: {0}")]
     ValueError(String),
 
+    /// Data or metadata that violates a rule of the Iceberg spec or a constraint of
+    /// the table.
+    #[error("validation error: {message}")]
+    Validation { message: String },
+
     /// An error with manifest files.
     #[error("manifest error: {0}")]
     ManifestError(String),
@@ -126,6 +166,32 @@ pub enum IcebergError {
     #[error("partition error: {message}")]
     PartitionError { message: String },
 
+    /// Error with Iceberg table sort orders.
+    #[error("sort order error: {message}")]
+    SortOrderError { message: String },
+
+    /// An invalid filter expression, e.g. one that can't be bound to a schema.
+    #[error("expression error: {message}")]
+    ExpressionError { message: String },
+
+    /// A table property has a value that can't be parsed.
+    #[error("invalid value '{value}' for table property {key}")]
+    InvalidProperty { key: String, value: String },
+
+    /// Error with planning a table scan.
+    #[error("scan error: {message}")]
+    ScanError { message: String },
+
+    /// An error returned by an Iceberg catalog, e.g. a table that does not exist or
+    /// a commit whose requirements were not met.
+    #[error("catalog error: {message}")]
+    CatalogError { message: String },
+
+    /// A commit failed because the table was changed concurrently since its
+    /// metadata was read. The commit may be retried against the new metadata.
+    #[error("commit conflict: {0}")]
+    CommitConflict(String),
+
     /// Failed serializing the table's metadata to json.
     #[error("error serializing table metadata to json: {source}")]
     SerializeMetadataJson {source: serde_json::Error},
@@ -160,10 +226,56 @@ pub enum IcebergError {
     ParquetError {#[from] source: parquet::errors::ParquetError},
 
     /// A system I/O error
-    #[error("I/O error")]
+    #[error("I/O error: {source}")]
     IoError {#[from] source: std::io::Error},
 
+    /// An HTTP error while communicating with a REST catalog.
+    #[error("HTTP error")]
+    HttpError {#[from] source: reqwest::Error},
+
     /// Apache Arrow error
     #[error("Arrow error")]
     ArrowError{#[from] source: arrow_schema::ArrowError},
 }
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn error_messages() {
+        let error = IcebergError::from(
+            std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied")
+        );
+        assert_eq!(error.to_string(), "I/O error: access denied");
+        assert!(error.source().is_some());
+
+        let error = IcebergError::CommitConflict("table uuid changed".to_string());
+        assert_eq!(error.to_string(), "commit conflict: table uuid changed");
+
+        let error = IcebergError::NotFound {
+            kind: NotFoundKind::PartitionSpec,
+            identifier: "3".to_string(),
+        };
+        assert_eq!(error.to_string(), "partition spec 3 not found");
+        assert!(error.source().is_none());
+
+        let error = IcebergError::UnsupportedFormatVersion(3);
+        assert_eq!(error.to_string(), "unsupported table format version 3");
+
+        let error = IcebergError::Validation {
+            message: "sequence number 4 is older than 5".to_string()
+        };
+        assert_eq!(
+            error.to_string(),
+            "validation error: sequence number 4 is older than 5"
+        );
+
+        assert!(matches!(
+            "3".parse::<IcebergTableVersion>(),
+            Err(IcebergError::UnsupportedFormatVersion(3))
+        ));
+    }
+}
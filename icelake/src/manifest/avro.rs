@@ -428,6 +428,12 @@ impl ManifestEntrySerializer {
                 "type": [ "null", "int" ],
                 "default": null,
                 "field-id": 140
+            },
+            {
+                "name": "first_row_id",
+                "type": [ "null", "long" ],
+                "default": null,
+                "field-id": 142
             }
         ]);
 
@@ -604,6 +610,10 @@ impl ManifestEntrySerializer {
             "sort_order_id",
             Self::serialize_opt(&data_file.sort_order_id)
         );
+        record.put(
+            "first_row_id",
+            Self::serialize_opt(&data_file.first_row_id)
+        );
 
         Ok(record)
     }
@@ -750,9 +760,7 @@ where
     )?;
 
     if format_version != IcebergTableVersion::V2 {
-        Err(IcebergError::Unsupported(
-            format!("table version {format_version} not supported")
-        ))
+        Err(IcebergError::UnsupportedFormatVersion(format_version as u8))
     } else {
         Ok(Manifest::new(
             schema.clone(),
@@ -1052,6 +1060,7 @@ fn deserialize_data_file(value: AvroValue) -> IcebergResult<DataFile> {
         split_offsets: deserialize_opt_key(&record, "split_offsets")?,
         equality_ids: deserialize_opt_key(&record, "equality_ids")?,
         sort_order_id: deserialize_opt_key(&record, "sort_order_id")?,
+        first_row_id: deserialize_opt_key(&record, "first_row_id")?,
     })
 }
 
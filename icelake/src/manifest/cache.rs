@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::IcebergResult;
+use crate::storage::IcebergStorage;
+use super::manifest::{ManifestEntry, ManifestFile};
+use super::reader::ManifestReader;
+
+/// Default number of manifests held by a [`ManifestCache`].
+pub const DEFAULT_MANIFEST_CACHE_CAPACITY: usize = 128;
+
+#[derive(Default)]
+struct CacheState {
+    /// Entries of the cached manifests by path, with the time they were last used.
+    manifests: HashMap<String, (u64, Arc<Vec<ManifestEntry>>)>,
+    /// Incremented on every access, ordering the manifests by recency of use.
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// A least-recently-used cache of decoded manifest entries, keyed by the paths of
+/// the manifest files.
+///
+/// Manifest files are never modified once written, so cached manifests never need
+/// to be invalidated. The cache can be shared between scans, and between tables.
+pub struct ManifestCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ManifestCache {
+    /// Creates a cache holding the entries of up to `capacity` manifests. A cache
+    /// with no capacity holds nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        // The state is consistent even if a thread panicked while holding the lock.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Maximum number of cached manifests.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached manifests.
+    pub fn len(&self) -> usize {
+        self.state().manifests.len()
+    }
+
+    /// Whether no manifests are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of manifests found in the cache by
+    /// [`read_entries()`](Self::read_entries).
+    pub fn hits(&self) -> u64 {
+        self.state().hits
+    }
+
+    /// Number of manifests read from storage by
+    /// [`read_entries()`](Self::read_entries).
+    pub fn misses(&self) -> u64 {
+        self.state().misses
+    }
+
+    /// Returns the cached entries of the manifest at the given path, if any.
+    pub fn get(&self, manifest_path: &str) -> Option<Arc<Vec<ManifestEntry>>> {
+        let mut state = self.state();
+        state.clock += 1;
+        let clock = state.clock;
+
+        state.manifests.get_mut(manifest_path).map(|(last_used, entries)| {
+            *last_used = clock;
+            entries.clone()
+        })
+    }
+
+    /// Caches the entries of the manifest at the given path, evicting the least
+    /// recently used manifest if the cache is full.
+    pub fn insert(&self, manifest_path: &str, entries: Arc<Vec<ManifestEntry>>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.state();
+        state.clock += 1;
+        let clock = state.clock;
+
+        if !state.manifests.contains_key(manifest_path)
+            && state.manifests.len() >= self.capacity {
+            let oldest = state.manifests.iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(path, _)| path.clone());
+            if let Some(oldest) = oldest {
+                state.manifests.remove(&oldest);
+            }
+        }
+
+        state.manifests.insert(manifest_path.to_string(), (clock, entries));
+    }
+
+    /// Returns the entries of the manifest file from the cache, or reads them from
+    /// storage and caches them.
+    ///
+    /// Entries inherit metadata from the `ManifestFile` like in
+    /// [`ManifestReader::for_manifest_file()`].
+    pub async fn read_entries(
+        &self,
+        manifest_file: &ManifestFile,
+        storage: &IcebergStorage
+    ) -> IcebergResult<Arc<Vec<ManifestEntry>>> {
+        if let Some(entries) = self.get(&manifest_file.manifest_path) {
+            self.state().hits += 1;
+            return Ok(entries);
+        }
+
+        let entries = Arc::new(
+            ManifestReader::for_manifest_file(manifest_file)
+                .read_entries(&manifest_file.manifest_path, storage)
+                .await?
+        );
+        self.state().misses += 1;
+        self.insert(&manifest_file.manifest_path, entries.clone());
+
+        Ok(entries)
+    }
+}
+
+impl Default for ManifestCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MANIFEST_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = ManifestCache::new(2);
+        cache.insert("a", Arc::new(Vec::new()));
+        cache.insert("b", Arc::new(Vec::new()));
+
+        // Using "a" makes "b" the least recently used.
+        assert!(cache.get("a").is_some());
+        cache.insert("c", Arc::new(Vec::new()));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+
+        let empty = ManifestCache::new(0);
+        empty.insert("a", Arc::new(Vec::new()));
+        assert!(empty.is_empty());
+    }
+}
@@ -88,6 +88,10 @@ pub struct DataFile {
     pub equality_ids: Option<Vec<i32>>,
     /// ID representing sort order for this file.
     pub sort_order_id: Option<i32>,
+    /// The `_row_id` of the first row in the data file, used for row lineage in
+    /// format version 3. The row id of every other row is the first row id plus the
+    /// row's position in the file.
+    pub first_row_id: Option<i64>,
 }
 
 impl DataFile {
@@ -108,6 +112,67 @@ impl DataFile {
     }
 }
 
+/// Formats data files as an aligned text table, listing the path, record count,
+/// size in bytes and partition of each file, one file per line.
+///
+/// Partitions are formatted as partition paths, e.g. `date=2020-01-01/user=111`.
+///
+/// # Examples
+///
+/// ```rust
+/// use icelake::manifest::{
+///     DataFile, DataFileContent, DataFileFormat, format_data_files
+/// };
+///
+/// let data_file = DataFile::builder(
+///     DataFileContent::Data,
+///     "/data/00000.parquet",
+///     DataFileFormat::Parquet,
+///     100,
+///     2048
+/// ).build();
+///
+/// assert_eq!(
+///     format_data_files(&[data_file]),
+///     "path                 records  size  partition\n\
+///      /data/00000.parquet      100  2048\n"
+/// );
+/// ```
+pub fn format_data_files(files: &[DataFile]) -> String {
+    let header = ["path", "records", "size", "partition"];
+    let rows: Vec<[String; 4]> = files.iter()
+        .map(|file| [
+            file.file_path.clone(),
+            file.record_count.to_string(),
+            file.file_size_in_bytes.to_string(),
+            file.partition.to_string(),
+        ])
+        .collect();
+
+    let mut widths = header.map(|column| column.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: [&str; 4]| {
+        // Numeric columns are aligned to the right.
+        let line = format!(
+            "{:<w0$}  {:>w1$}  {:>w2$}  {}",
+            cells[0], cells[1], cells[2], cells[3],
+            w0 = widths[0], w1 = widths[1], w2 = widths[2]
+        );
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut output = format_row(header);
+    for row in &rows {
+        output.push_str(&format_row([&row[0], &row[1], &row[2], &row[3]]));
+    }
+    output
+}
+
 pub struct DataFileBuilder {
     data_file: DataFile
 }
@@ -139,6 +204,7 @@ impl DataFileBuilder {
                 split_offsets: None,
                 equality_ids: None,
                 sort_order_id: None,
+                first_row_id: None,
             }
         }
     }
@@ -155,7 +221,111 @@ impl DataFileBuilder {
         self
     }
 
+    /// Add value counts to the `DataFile`.
+    ///
+    /// `value_counts` should map field ids to the number of values in the column,
+    /// including nulls and NaNs.
+    pub fn with_value_counts(mut self, value_counts: HashMap<i32, i64>) -> Self {
+        self.data_file.value_counts = Some(value_counts);
+        self
+    }
+
+    /// Add null value counts to the `DataFile`.
+    ///
+    /// `null_value_counts` should map field ids to the number of nulls in the column.
+    pub fn with_null_value_counts(mut self, null_value_counts: HashMap<i32, i64>) -> Self {
+        self.data_file.null_value_counts = Some(null_value_counts);
+        self
+    }
+
+    /// Add NaN value counts to the `DataFile`.
+    ///
+    /// `nan_value_counts` should map field ids to the number of NaNs in the column.
+    pub fn with_nan_value_counts(mut self, nan_value_counts: HashMap<i32, i64>) -> Self {
+        self.data_file.nan_value_counts = Some(nan_value_counts);
+        self
+    }
+
+    /// Add lower bounds to the `DataFile`.
+    ///
+    /// `lower_bounds` should map field ids to their lower bound, serialized using
+    /// the Iceberg single-value binary serialization.
+    pub fn with_lower_bounds(mut self, lower_bounds: HashMap<i32, Vec<u8>>) -> Self {
+        self.data_file.lower_bounds = Some(lower_bounds);
+        self
+    }
+
+    /// Add upper bounds to the `DataFile`.
+    ///
+    /// `upper_bounds` should map field ids to their upper bound, serialized using
+    /// the Iceberg single-value binary serialization.
+    pub fn with_upper_bounds(mut self, upper_bounds: HashMap<i32, Vec<u8>>) -> Self {
+        self.data_file.upper_bounds = Some(upper_bounds);
+        self
+    }
+
+    /// Add the field ids used for equality comparison by an equality delete file.
+    pub fn with_equality_ids(mut self, equality_ids: Vec<i32>) -> Self {
+        self.data_file.equality_ids = Some(equality_ids);
+        self
+    }
+
+    /// Set the `_row_id` of the first row in the `DataFile`.
+    pub fn with_first_row_id(mut self, first_row_id: i64) -> Self {
+        self.data_file.first_row_id = Some(first_row_id);
+        self
+    }
+
     pub fn build(self) -> DataFile {
         self.data_file
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::value::Value;
+    use crate::partition::PartitionValues;
+    use crate::manifest::datafile::{
+        DataFile, DataFileContent, DataFileFormat, format_data_files
+    };
+
+    #[test]
+    fn format_partitioned_data_files() {
+        let data_file = |path: &str, record_count: i64, day: u32| {
+            DataFile::builder(
+                DataFileContent::Data,
+                path,
+                DataFileFormat::Parquet,
+                record_count,
+                record_count * 10
+            ).with_partition_values(PartitionValues::from_iter([
+                (
+                    "date".to_string(),
+                    Some(Value::Date(NaiveDate::from_ymd_opt(2023, 1, day).unwrap()))
+                ),
+                ("user".to_string(), None),
+            ])).build()
+        };
+
+        let output = format_data_files(&[
+            data_file("s3://bucket/table/data/a.parquet", 5, 1),
+            data_file("s3://bucket/table/data/bb.parquet", 12000, 2),
+        ]);
+
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            [
+                "path                               records    size  partition",
+                "s3://bucket/table/data/a.parquet         5      50  date=2023-01-01/user=null",
+                "s3://bucket/table/data/bb.parquet    12000  120000  date=2023-01-02/user=null",
+            ]
+        );
+    }
+
+    #[test]
+    fn format_no_data_files() {
+        assert_eq!(format_data_files(&[]), "path  records  size  partition\n");
+    }
+}
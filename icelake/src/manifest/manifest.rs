@@ -69,6 +69,93 @@ static MANIFEST_FILE_SCHEMA: &str = r#"
 }
 "#;
 
+/// Avro schema for the manifest_file struct in v1 tables, which lacks the content
+/// type and sequence numbers and has optional file and row counts.
+static MANIFEST_FILE_V1_SCHEMA: &str = r#"
+{
+  "type": "record",
+  "name": "manifest_file",
+  "fields": [
+    { "name": "manifest_path", "type": "string", "field-id": 500 },
+    { "name": "manifest_length", "type": "long", "field-id": 501 },
+    { "name": "partition_spec_id", "type": "int", "field-id": 502 },
+    { "name": "added_snapshot_id", "type": "long", "field-id": 503 },
+    {
+      "name": "added_data_files_count",
+      "type": [ "null", "int" ],
+      "default": null,
+      "field-id": 504
+    },
+    {
+      "name": "existing_data_files_count",
+      "type": [ "null", "int" ],
+      "default": null,
+      "field-id": 505
+    },
+    {
+      "name": "deleted_data_files_count",
+      "type": [ "null", "int" ],
+      "default": null,
+      "field-id": 506
+    },
+    { "name": "partitions",
+      "type": [
+        "null",
+        {
+          "type": "array",
+          "items": {
+            "type": "record",
+            "name": "field_summary",
+            "fields": [
+              { "name": "contains_null", "type": "boolean", "field-id": 509 },
+              {
+                "name": "contains_nan",
+                "type": [ "null", "boolean" ],
+                "default": null,
+                "field-id": 518
+              },
+              {
+                "name": "lower_bound",
+                "type": [ "null", "bytes" ],
+                "default": null,
+                "field-id": 510
+              },
+              {
+                "name": "upper_bound",
+                "type": [ "null", "bytes" ],
+                "default": null,
+                "field-id": 511
+              }
+            ]
+          },
+          "element-id": 508
+        }
+      ],
+      "default": null,
+      "field-id": 507
+    },
+    {
+      "name": "added_rows_count",
+      "type": [ "null", "long" ],
+      "default": null,
+      "field-id": 512
+    },
+    {
+      "name": "existing_rows_count",
+      "type": [ "null", "long" ],
+      "default": null,
+      "field-id": 513
+    },
+    {
+      "name": "deleted_rows_count",
+      "type": [ "null", "long" ],
+      "default": null,
+      "field-id": 514
+    }
+  ]
+}
+"#;
+
 /// Indicates the status of the [`ManifestEntry`] at the time the manifest was written.
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize_repr)]
 #[repr(u8)]
@@ -330,7 +417,7 @@ impl Manifest {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct PartitionFieldSummary {
     /// Whether the manifest contains at least one partition with a null value for the
     /// field.
@@ -340,12 +427,50 @@ pub struct PartitionFieldSummary {
     contains_nan: Option<bool>,
     /// Lower bound for the non-null, non-NaN values in the partition field, or null if
     /// all values are null or NaN.
+    #[serde(default, with = "opt_bytes_serde")]
     lower_bound: Option<Vec<u8>>,
     /// Upper bound for the non-null, non-NaN values in the partition field, or null if
     /// all values are null or NaN.
+    #[serde(default, with = "opt_bytes_serde")]
     upper_bound: Option<Vec<u8>>,
 }
 
+impl PartitionFieldSummary {
+    pub fn new(
+        contains_null: bool,
+        contains_nan: Option<bool>,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>
+    ) -> Self {
+        Self {
+            contains_null: contains_null,
+            contains_nan: contains_nan,
+            lower_bound: lower_bound,
+            upper_bound: upper_bound
+        }
+    }
+
+    pub fn contains_null(&self) -> bool {
+        self.contains_null
+    }
+
+    pub fn contains_nan(&self) -> Option<bool> {
+        self.contains_nan
+    }
+
+    /// Returns the lower bound serialized using the Iceberg single-value binary
+    /// serialization.
+    pub fn lower_bound(&self) -> Option<&[u8]> {
+        self.lower_bound.as_deref()
+    }
+
+    /// Returns the upper bound serialized using the Iceberg single-value binary
+    /// serialization.
+    pub fn upper_bound(&self) -> Option<&[u8]> {
+        self.upper_bound.as_deref()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize_repr, Deserialize_repr)]
 #[repr(i32)]
 pub enum ManifestFileType {
@@ -353,7 +478,7 @@ pub enum ManifestFileType {
     Delete = 1,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ManifestFile {
     /// Location of the manifest file.
     pub manifest_path: String,
@@ -389,6 +514,75 @@ pub struct ManifestFile {
     pub partitions: Option<Vec<PartitionFieldSummary>>,
 }
 
+/// A [`ManifestFile`] in the layout of v1 manifest lists.
+///
+/// Older writers named the file counts `added_files_count`, etc. which are accepted
+/// as aliases when reading.
+#[derive(Serialize, Deserialize)]
+struct ManifestFileV1 {
+    manifest_path: String,
+    manifest_length: i64,
+    partition_spec_id: i32,
+    added_snapshot_id: i64,
+    #[serde(default, alias = "added_files_count")]
+    added_data_files_count: Option<i32>,
+    #[serde(default, alias = "existing_files_count")]
+    existing_data_files_count: Option<i32>,
+    #[serde(default, alias = "deleted_files_count")]
+    deleted_data_files_count: Option<i32>,
+    #[serde(default)]
+    partitions: Option<Vec<PartitionFieldSummary>>,
+    #[serde(default)]
+    added_rows_count: Option<i64>,
+    #[serde(default)]
+    existing_rows_count: Option<i64>,
+    #[serde(default)]
+    deleted_rows_count: Option<i64>,
+}
+
+impl From<ManifestFile> for ManifestFileV1 {
+    fn from(manifest_file: ManifestFile) -> Self {
+        Self {
+            manifest_path: manifest_file.manifest_path,
+            manifest_length: manifest_file.manifest_length,
+            partition_spec_id: manifest_file.partition_spec_id,
+            added_snapshot_id: manifest_file.added_snapshot_id,
+            added_data_files_count: Some(manifest_file.added_data_files_count),
+            existing_data_files_count: Some(manifest_file.existing_data_files_count),
+            deleted_data_files_count: Some(manifest_file.deleted_data_files_count),
+            partitions: manifest_file.partitions,
+            added_rows_count: Some(manifest_file.added_rows_count),
+            existing_rows_count: Some(manifest_file.existing_rows_count),
+            deleted_rows_count: Some(manifest_file.deleted_rows_count),
+        }
+    }
+}
+
+impl From<ManifestFileV1> for ManifestFile {
+    /// Converts a v1 manifest file to the v2 layout. v1 manifests can only track
+    /// data files, and have an implicit sequence number of 0.
+    fn from(manifest_file: ManifestFileV1) -> Self {
+        Self {
+            manifest_path: manifest_file.manifest_path,
+            manifest_length: manifest_file.manifest_length,
+            partition_spec_id: manifest_file.partition_spec_id,
+            content: ManifestFileType::Data,
+            sequence_number: 0,
+            min_sequence_number: 0,
+            added_snapshot_id: manifest_file.added_snapshot_id,
+            added_data_files_count: manifest_file.added_data_files_count.unwrap_or(0),
+            existing_data_files_count: manifest_file.existing_data_files_count
+                .unwrap_or(0),
+            deleted_data_files_count: manifest_file.deleted_data_files_count
+                .unwrap_or(0),
+            added_rows_count: manifest_file.added_rows_count.unwrap_or(0),
+            existing_rows_count: manifest_file.existing_rows_count.unwrap_or(0),
+            deleted_rows_count: manifest_file.deleted_rows_count.unwrap_or(0),
+            partitions: manifest_file.partitions,
+        }
+    }
+}
+
 /// An Iceberg manifest list file contains entries of ManifestFile serialized
 /// to Avro. Each ManifestFile points to the path of the actual manifest file on the
 /// underlying storage.
@@ -403,6 +597,11 @@ impl ManifestList {
         self.manifests.push(manifest);
     }
 
+    /// Inserts a manifest at the given position, shifting the following manifests.
+    pub fn insert(&mut self, index: usize, manifest: ManifestFile) {
+        self.manifests.insert(index, manifest);
+    }
+
     pub fn is_empty(&self) -> bool {
         self.manifests.is_empty()
     }
@@ -411,22 +610,107 @@ impl ManifestList {
         &self.manifests
     }
 
-    /// Encodes the manifest list to an Avro manifest list file.
+    /// Encodes the manifest list to an Avro manifest list file of a v2 table.
     /// Consumes the ManifestList object.
     pub fn encode(self) -> IcebergResult<Vec<u8>> {
-        let schema = apache_avro::Schema::parse_str(MANIFEST_FILE_SCHEMA)?;
+        self.encode_version(IcebergTableVersion::V2)
+    }
+
+    /// Encodes the manifest list to an Avro manifest list file using the layout of
+    /// the given table format version. Consumes the ManifestList object.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ManifestError`] is returned when encoding a v1 manifest list
+    /// that references delete manifests, which are not supported by v1 tables.
+    pub fn encode_version(
+        self,
+        format_version: IcebergTableVersion
+    ) -> IcebergResult<Vec<u8>> {
+        let schema = apache_avro::Schema::parse_str(match format_version {
+            IcebergTableVersion::V1 => MANIFEST_FILE_V1_SCHEMA,
+            IcebergTableVersion::V2 => MANIFEST_FILE_SCHEMA
+        })?;
         let mut writer = apache_avro::Writer::new(&schema, Vec::<u8>::new());
-        writer.extend_ser(self.manifests)?;
+        writer.add_user_metadata(
+            "format-version".to_string(),
+            format_version.to_string()
+        )?;
+
+        match format_version {
+            IcebergTableVersion::V1 => {
+                let manifests = self.manifests.into_iter()
+                    .map(|manifest| {
+                        if manifest.content != ManifestFileType::Data {
+                            return Err(IcebergError::ManifestError(format!(
+                                "v1 manifest lists can't contain delete manifest {}",
+                                manifest.manifest_path
+                            )));
+                        }
+
+                        Ok(ManifestFileV1::from(manifest))
+                    })
+                    .collect::<IcebergResult<Vec<_>>>()?;
+
+                writer.extend_ser(manifests)?;
+            },
+            IcebergTableVersion::V2 => {
+                writer.extend_ser(self.manifests)?;
+            }
+        }
+
         Ok(writer.into_inner()?)
     }
 
     /// Decodes a ManifestList from an Avro-encoded manifest list file.
+    ///
+    /// Both v1 and v2 manifest lists are supported. The format version is determined
+    /// from the file's metadata, or from its schema if the metadata is missing. v1
+    /// manifest files are converted to the v2 layout.
+    ///
+    /// A manifest list referencing no manifests, such as the one of a snapshot in
+    /// which all data was deleted, decodes to an empty list.
     pub fn decode(data: &[u8]) -> IcebergResult<Self> {
-        let schema = apache_avro::Schema::parse_str(MANIFEST_FILE_SCHEMA)?;
-        let reader = apache_avro::Reader::with_schema(&schema, data)?;
+        // Avro writers may omit the file header when no records were written.
+        if data.is_empty() {
+            return Ok(Self::new());
+        }
+
+        let reader = apache_avro::Reader::new(data)?;
+
+        let format_version = match reader.user_metadata().get("format-version") {
+            Some(bytes) => {
+                std::str::from_utf8(bytes)
+                    .map_err(|_| {
+                        IcebergError::ManifestError(
+                            "manifest list's metadata does not contain a valid \
+                            utf-8 string for key 'format-version'".to_string()
+                        )
+                    })?
+                    .parse::<IcebergTableVersion>()?
+            },
+            None => {
+                // v1 manifest lists lack the sequence number fields.
+                match reader.writer_schema() {
+                    apache_avro::Schema::Record { fields, .. }
+                        if fields.iter().any(|field| field.name == "sequence_number") => {
+                        IcebergTableVersion::V2
+                    },
+                    _ => IcebergTableVersion::V1
+                }
+            }
+        };
 
         let manifests: Result<Vec<ManifestFile>, _> = reader.into_iter().map(|res| {
-            res.and_then(|value| apache_avro::from_value::<ManifestFile>(&value))
+            res.and_then(|value| match format_version {
+                IcebergTableVersion::V1 => {
+                    apache_avro::from_value::<ManifestFileV1>(&value)
+                        .map(ManifestFile::from)
+                },
+                IcebergTableVersion::V2 => {
+                    apache_avro::from_value::<ManifestFile>(&value)
+                }
+            })
         }).collect();
 
         Ok(Self { manifests: manifests? })
@@ -441,3 +725,99 @@ impl IntoIterator for ManifestList {
         self.manifests.into_iter()
     }
 }
+
+/// Custom serializer and deserializer for optional binary fields, encoding them as
+/// Avro `bytes` rather than as an array of integers.
+mod opt_bytes_serde {
+    use std::fmt;
+
+    use serde::{self, Serializer, Deserializer};
+    use serde::de::{Visitor, SeqAccess};
+
+    struct BytesRef<'a>(&'a [u8]);
+
+    impl<'a> serde::Serialize for BytesRef<'a> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a byte array")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::new();
+            while let Some(byte) = seq.next_element::<u8>()? {
+                bytes.push(byte);
+            }
+            Ok(bytes)
+        }
+    }
+
+    struct OptBytesVisitor;
+
+    impl<'de> Visitor<'de> for OptBytesVisitor {
+        type Value = Option<Vec<u8>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an optional byte array")
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_bytes(BytesVisitor).map(Some)
+        }
+    }
+
+    pub fn serialize<S>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&BytesRef(bytes)),
+            None => serializer.serialize_none()
+        }
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(OptBytesVisitor)
+    }
+}
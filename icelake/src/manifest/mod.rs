@@ -1,17 +1,21 @@
 //! Interface to Iceberg table manifest lists and manifest files.
 
 mod avro;
+mod cache;
 mod writer;
 mod reader;
 
 pub mod datafile;
 pub mod manifest;
 
-pub use crate::manifest::datafile::{DataFile, DataFileContent, DataFileFormat};
+pub use crate::manifest::datafile::{
+    DataFile, DataFileContent, DataFileFormat, format_data_files
+};
 pub use crate::manifest::manifest::{
     ManifestEntry, ManifestEntryStatus,
     Manifest, ManifestFile, ManifestFileType, ManifestContentType,
-    ManifestList
+    ManifestList, PartitionFieldSummary
 };
+pub use crate::manifest::cache::{ManifestCache, DEFAULT_MANIFEST_CACHE_CAPACITY};
 pub use crate::manifest::reader::ManifestReader;
 pub use crate::manifest::writer::ManifestWriter;
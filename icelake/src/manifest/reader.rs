@@ -1,6 +1,7 @@
 use crate::IcebergResult;
+use crate::storage::IcebergStorage;
 use super::avro::deserialize_manifest;
-use super::manifest::{Manifest, ManifestFile};
+use super::manifest::{Manifest, ManifestEntry, ManifestFile};
 
 /// Decodes [`Manifest`]s from binary avro format.
 pub struct ManifestReader {
@@ -55,4 +56,19 @@ impl ManifestReader {
 
         Ok(manifest)
     }
+
+    /// Reads the manifest file at the given URL from storage and returns its entries.
+    ///
+    /// The manifest is decoded using the Avro schema embedded in the file, and
+    /// inherits metadata the same way as [`ManifestReader::read()`].
+    pub async fn read_entries(
+        &self,
+        manifest_path: &str,
+        storage: &IcebergStorage
+    ) -> IcebergResult<Vec<ManifestEntry>> {
+        let path = storage.create_path_from_url(manifest_path)?;
+        let bytes = storage.get(&path).await?;
+
+        Ok(self.read(&bytes)?.into_entries().collect())
+    }
 }
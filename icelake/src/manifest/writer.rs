@@ -1,8 +1,14 @@
+use std::cmp::Ordering;
+
 use bytes::Bytes;
 
-use crate::IcebergResult;
+use crate::{IcebergResult, IcebergError};
+use crate::value::Value;
 use super::avro::serialize_manifest;
-use super::manifest::{Manifest, ManifestContentType, ManifestFile, ManifestFileType};
+use super::manifest::{
+    Manifest, ManifestContentType, ManifestFile, ManifestFileType,
+    PartitionFieldSummary
+};
 
 /// Encodes [`Manifest`]s to binary avro format.
 pub struct ManifestWriter {
@@ -48,9 +54,85 @@ impl ManifestWriter {
             added_rows_count: manifest.added_rows_count(),
             existing_rows_count: manifest.existing_rows_count(),
             deleted_rows_count: manifest.deleted_rows_count(),
-            partitions: None,
+            partitions: Some(partition_summaries(manifest)?),
         };
-        
+
         Ok((Bytes::from(encoded), manifest_file))
     }
 }
+
+/// Computes the summary of each partition field in the manifest's partition spec
+/// over the partition values of all the manifest's entries.
+///
+/// The bounds are serialized using the Iceberg single-value binary serialization.
+fn partition_summaries(manifest: &Manifest) -> IcebergResult<Vec<PartitionFieldSummary>> {
+    manifest.partition_spec()
+        .fields()
+        .iter()
+        .map(|field| {
+            let mut contains_null = false;
+            let mut contains_nan = false;
+            let mut bounds: Option<(Value, Value)> = None;
+
+            let values = manifest.entries()
+                .iter()
+                .map(|entry| {
+                    entry.data_file.partition
+                        .values()
+                        .iter()
+                        .find(|(name, _)| *name == field.name)
+                        .and_then(|(_, value)| value.as_ref())
+                });
+
+            for value in values {
+                let value = match value {
+                    None => {
+                        contains_null = true;
+                        continue;
+                    },
+                    Some(Value::Float(f)) if f.is_nan() => {
+                        contains_nan = true;
+                        continue;
+                    },
+                    Some(Value::Double(d)) if d.is_nan() => {
+                        contains_nan = true;
+                        continue;
+                    },
+                    Some(value) => value
+                };
+
+                bounds = match bounds {
+                    None => Some((value.clone(), value.clone())),
+                    Some((lower, upper)) => {
+                        let cmp = |other: &Value| {
+                            value.partial_cmp(other).ok_or_else(|| {
+                                IcebergError::ValueError(format!(
+                                    "can't compare values {value} and {other} \
+                                    of partition field '{}'",
+                                    field.name
+                                ))
+                            })
+                        };
+
+                        Some((
+                            if cmp(&lower)? == Ordering::Less { value.clone() } else { lower },
+                            if cmp(&upper)? == Ordering::Greater { value.clone() } else { upper }
+                        ))
+                    }
+                };
+            }
+
+            let (lower_bound, upper_bound) = match bounds {
+                Some((lower, upper)) => (Some(lower.try_into()?), Some(upper.try_into()?)),
+                None => (None, None)
+            };
+
+            Ok(PartitionFieldSummary::new(
+                contains_null,
+                Some(contains_nan),
+                lower_bound,
+                upper_bound
+            ))
+        })
+        .collect()
+}
@@ -50,11 +50,10 @@
 use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
-use serde::{
-    de::{self, IntoDeserializer},
-    Deserialize, Deserializer, Serialize,
-};
-use chrono::{Datelike, NaiveDate, NaiveDateTime, DateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use lazy_static::lazy_static;
+use murmur3::murmur3_32;
+use chrono::{Datelike, NaiveDate, NaiveTime, NaiveDateTime, DateTime, Utc};
 
 use crate::{IcebergResult, IcebergError};
 use crate::schema::{
@@ -62,9 +61,10 @@ use crate::schema::{
     StructType, PrimitiveType
 };
 use crate::value::Value;
+use crate::expr::{BoundReference, Predicate};
+use crate::expr::projection::{project_predicate, Projection};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
-#[serde(rename_all = "lowercase", remote = "Self")]
+#[derive(Debug, PartialEq, Eq, Clone)]
 /// A Transformation applied to a source column to produce a partition value.
 pub enum PartitionTransform {
     /// Always produces `null`
@@ -144,6 +144,91 @@ impl Transform for YearTransform {
     }
 }
 
+struct MonthTransform;
+impl Transform for MonthTransform {
+    fn get_result_type(field_type: SchemaType) -> IcebergResult<SchemaType> {
+        match field_type {
+            SchemaType::Primitive(PrimitiveType::Date)
+            | SchemaType::Primitive(PrimitiveType::Timestamp)
+            | SchemaType::Primitive(PrimitiveType::Timestamptz) => {
+                Ok(SchemaType::Primitive(PrimitiveType::Int))
+            },
+            _ => {
+                Err(IcebergError::PartitionError {
+                    message: format!(
+                        "can't apply month transform to field of type {}",
+                        field_type
+                    )
+                })
+            }
+        }
+    }
+
+    fn apply(value: &Value) -> IcebergResult<Value> {
+        let (year, month0) = match value {
+            Value::Date(date) => (date.year(), date.month0()),
+            Value::Timestamp(timestamp) => (timestamp.year(), timestamp.month0()),
+            Value::Timestamptz(timestamptz) => {
+                (timestamptz.year(), timestamptz.month0())
+            },
+            _ => {
+                return Err(IcebergError::PartitionError {
+                    message: format!(
+                        "can't apply month transform to value {}",
+                        value
+                    )
+                });
+            }
+        };
+
+        Ok(Value::Int((year - 1970) * 12 + month0 as i32))
+    }
+}
+
+struct HourTransform;
+impl Transform for HourTransform {
+    fn get_result_type(field_type: SchemaType) -> IcebergResult<SchemaType> {
+        match field_type {
+            SchemaType::Primitive(PrimitiveType::Timestamp)
+            | SchemaType::Primitive(PrimitiveType::Timestamptz) => {
+                Ok(SchemaType::Primitive(PrimitiveType::Int))
+            },
+            _ => {
+                Err(IcebergError::PartitionError {
+                    message: format!(
+                        "can't apply hour transform to field of type {}",
+                        field_type
+                    )
+                })
+            }
+        }
+    }
+
+    fn apply(value: &Value) -> IcebergResult<Value> {
+        let seconds = match value {
+            Value::Timestamp(timestamp) => timestamp.timestamp(),
+            Value::Timestamptz(timestamptz) => timestamptz.timestamp(),
+            _ => {
+                return Err(IcebergError::PartitionError {
+                    message: format!(
+                        "can't apply hour transform to value {}",
+                        value
+                    )
+                });
+            }
+        };
+
+        // Hours before the epoch are rounded down, like days.
+        let hours = seconds.div_euclid(3600).try_into().map_err(|_| {
+            IcebergError::PartitionError {
+                message: format!("timestamp {value} is too far from 1970-01-01")
+            }
+        })?;
+
+        Ok(Value::Int(hours))
+    }
+}
+
 struct DayTransform;
 impl Transform for DayTransform {
     fn get_result_type(field_type: SchemaType) -> IcebergResult<SchemaType> {
@@ -220,6 +305,129 @@ impl Transform for DayTransform {
     }
 }
 
+struct BucketTransform;
+impl BucketTransform {
+    /// Hashes the value with 32-bit Murmur3 according to the
+    /// [bucket transform spec](https://iceberg.apache.org/spec/#bucket-transform-details),
+    /// so that the resulting buckets match those of other Iceberg implementations.
+    fn hash(value: &Value) -> IcebergResult<i32> {
+        let bytes: Vec<u8> = match value {
+            // Integers, dates and times are all hashed as 64-bit longs.
+            Value::Int(i) => i64::from(*i).to_le_bytes().to_vec(),
+            Value::Long(l) => l.to_le_bytes().to_vec(),
+            Value::Date(date) => {
+                // Days since 1970-01-01
+                date.signed_duration_since(NaiveDate::default())
+                    .num_days()
+                    .to_le_bytes()
+                    .to_vec()
+            },
+            Value::Time(time) => {
+                // Microseconds since midnight
+                time.signed_duration_since(NaiveTime::default())
+                    .num_microseconds()
+                    .unwrap()
+                    .to_le_bytes()
+                    .to_vec()
+            },
+            Value::Timestamp(_) | Value::Timestamptz(_) => {
+                // Microseconds since 1970-01-01 00:00:00
+                value.to_iceberg_bytes()?
+            },
+            // Minimal two's-complement bytes of the unscaled value
+            Value::Decimal { .. } => value.to_iceberg_bytes()?,
+            Value::String(s) => s.as_bytes().to_vec(),
+            Value::Uuid(uuid) => uuid.as_bytes().to_vec(),
+            Value::Fixed(bytes) | Value::Binary(bytes) => bytes.clone(),
+            _ => {
+                return Err(IcebergError::PartitionError {
+                    message: format!("can't apply bucket transform to value {value}")
+                });
+            }
+        };
+
+        let hash = murmur3_32(&mut std::io::Cursor::new(bytes), 0)?;
+
+        Ok(hash as i32)
+    }
+
+    fn apply(num_buckets: u32, value: &Value) -> IcebergResult<Value> {
+        if num_buckets == 0 || num_buckets > i32::MAX as u32 {
+            return Err(IcebergError::PartitionError {
+                message: format!("invalid number of buckets {num_buckets}")
+            });
+        }
+
+        let hash = Self::hash(value)?;
+
+        Ok(Value::Int((hash & i32::MAX) % num_buckets as i32))
+    }
+}
+
+struct TruncateTransform;
+impl TruncateTransform {
+    fn get_result_type(field_type: SchemaType) -> IcebergResult<SchemaType> {
+        match field_type {
+            SchemaType::Primitive(PrimitiveType::Int)
+            | SchemaType::Primitive(PrimitiveType::Long)
+            | SchemaType::Primitive(PrimitiveType::Decimal { .. })
+            | SchemaType::Primitive(PrimitiveType::String)
+            | SchemaType::Primitive(PrimitiveType::Binary) => Ok(field_type),
+            _ => {
+                Err(IcebergError::PartitionError {
+                    message: format!(
+                        "can't apply truncate transform to field of type {}",
+                        field_type
+                    )
+                })
+            }
+        }
+    }
+
+    /// Truncates the value according to the
+    /// [truncate transform spec](https://iceberg.apache.org/spec/#truncate-transform-details).
+    /// Integers and the unscaled values of decimals are rounded down to a multiple of
+    /// the width, strings are truncated to `width` code points and binary values to
+    /// `width` bytes.
+    fn apply(width: u32, value: &Value) -> IcebergResult<Value> {
+        if width == 0 || width > i32::MAX as u32 {
+            return Err(IcebergError::PartitionError {
+                message: format!("invalid truncate width {width}")
+            });
+        }
+
+        match value {
+            Value::Int(i) => {
+                Ok(Value::Int(i - i.rem_euclid(width as i32)))
+            },
+            Value::Long(l) => {
+                Ok(Value::Long(l - l.rem_euclid(i64::from(width))))
+            },
+            Value::Decimal { unscaled, precision, scale } => {
+                Ok(Value::Decimal {
+                    unscaled: unscaled - unscaled.rem_euclid(i128::from(width)),
+                    precision: *precision,
+                    scale: *scale
+                })
+            },
+            Value::String(s) => {
+                Ok(Value::String(s.chars().take(width as usize).collect()))
+            },
+            Value::Binary(bytes) => {
+                Ok(Value::Binary(bytes.iter().take(width as usize).cloned().collect()))
+            },
+            _ => {
+                Err(IcebergError::PartitionError {
+                    message: format!(
+                        "can't apply truncate transform to value {}",
+                        value
+                    )
+                })
+            }
+        }
+    }
+}
+
 impl PartitionTransform {
     /// Returns the field type resulting from applying this transform to the input
     /// type.
@@ -240,14 +448,36 @@ impl PartitionTransform {
             PartitionTransform::Year => {
                 YearTransform::get_result_type(field_type)
             }
+            PartitionTransform::Month => {
+                MonthTransform::get_result_type(field_type)
+            },
             PartitionTransform::Day => {
                 DayTransform::get_result_type(field_type)
             },
-            _ => {
-                Err(IcebergError::PartitionError {
-                    message: format!("transform {self:?} not yet supported")
-                })
-            }
+            PartitionTransform::Hour => {
+                HourTransform::get_result_type(field_type)
+            },
+            PartitionTransform::Bucket(_) => {
+                match field_type {
+                    SchemaType::Primitive(PrimitiveType::Boolean)
+                    | SchemaType::Primitive(PrimitiveType::Float)
+                    | SchemaType::Primitive(PrimitiveType::Double)
+                    | SchemaType::Struct(_)
+                    | SchemaType::List(_)
+                    | SchemaType::Map(_) => {
+                        Err(IcebergError::PartitionError {
+                            message: format!(
+                                "can't apply bucket transform to field of type {}",
+                                field_type
+                            )
+                        })
+                    },
+                    _ => Ok(SchemaType::Primitive(PrimitiveType::Int))
+                }
+            },
+            PartitionTransform::Truncate(_) => {
+                TruncateTransform::get_result_type(field_type)
+            },
         }
     }
 
@@ -271,21 +501,187 @@ impl PartitionTransform {
                 PartitionTransform::Year => {
                     Ok(Some(YearTransform::apply(&value)?))
                 },
+                PartitionTransform::Month => {
+                    Ok(Some(MonthTransform::apply(&value)?))
+                },
                 PartitionTransform::Day => {
                     Ok(Some(DayTransform::apply(&value)?))
                 },
-                // TODO
-                _ => {
-                    Err(IcebergError::PartitionError {
-                        message: format!("transform {self:?} not yet supported")
-                    })
-                }
+                PartitionTransform::Hour => {
+                    Ok(Some(HourTransform::apply(&value)?))
+                },
+                PartitionTransform::Bucket(n) => {
+                    Ok(Some(BucketTransform::apply(*n, &value)?))
+                },
+                PartitionTransform::Truncate(width) => {
+                    Ok(Some(TruncateTransform::apply(*width, &value)?))
+                },
             }
         } else {
             // All transforms must return null for a null input value.
             Ok(None)
         }
     }
+
+    /// Returns the field type resulting from applying this transform to the source
+    /// field, like [`get_result_type()`](Self::get_result_type).
+    ///
+    /// If the transform can't be applied, the error names the source field and lists
+    /// the transforms that can be applied to its type instead, e.g. `identity`,
+    /// `bucket[N]` and `truncate[W]` for strings.
+    pub fn get_result_type_for(&self, source_field: &Field) -> IcebergResult<SchemaType> {
+        self.get_result_type(source_field.r#type.clone()).map_err(|_| {
+            let applicable = Self::applicable_transforms(&source_field.r#type);
+            IcebergError::PartitionError {
+                message: format!(
+                    "can't partition by {self} of source field '{}' of type {}, valid \
+                    transforms for {} are: {}",
+                    source_field.name,
+                    source_field.r#type,
+                    source_field.r#type,
+                    applicable.join(", ")
+                )
+            }
+        })
+    }
+
+    /// Returns the names of the transforms that can be applied to the given type,
+    /// with `N` and `W` standing for the parameters of `bucket` and `truncate`.
+    fn applicable_transforms(field_type: &SchemaType) -> Vec<&'static str> {
+        [
+            (PartitionTransform::Identity, "identity"),
+            (PartitionTransform::Bucket(1), "bucket[N]"),
+            (PartitionTransform::Truncate(1), "truncate[W]"),
+            (PartitionTransform::Year, "year"),
+            (PartitionTransform::Month, "month"),
+            (PartitionTransform::Day, "day"),
+            (PartitionTransform::Hour, "hour"),
+            (PartitionTransform::Void, "void"),
+        ].into_iter()
+            .filter(|(transform, _)| {
+                transform.get_result_type(field_type.clone()).is_ok()
+            })
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// Returns the bucket the value is assigned to by a `Bucket` transform.
+    ///
+    /// Buckets are computed exactly as by other Iceberg implementations, such as
+    /// Spark, so this can be used to verify partition assignments across engines.
+    /// Returns `None` if this is not a `Bucket` transform or the value can't be
+    /// bucketed, e.g. a float.
+    pub fn bucket_of(&self, value: &Value) -> Option<i32> {
+        match self {
+            PartitionTransform::Bucket(n) => {
+                match BucketTransform::apply(*n, value) {
+                    Ok(Value::Int(bucket)) => Some(bucket),
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }
+
+    /// Returns the human-readable string of a value produced by this transform, as
+    /// used in partition paths.
+    ///
+    /// Date and time transforms produce ordinals, which are formatted back into
+    /// dates, e.g. a `Month` value of `636` is formatted as `2023-01`. A `None`
+    /// value is formatted as `null`.
+    pub fn to_human_string(&self, value: Option<&Value>) -> String {
+        let value = match value {
+            Some(value) => value,
+            None => return "null".to_string()
+        };
+
+        match (self, value) {
+            (PartitionTransform::Year, Value::Int(years)) => {
+                format!("{:04}", 1970 + years)
+            },
+            (PartitionTransform::Month, Value::Int(months)) => {
+                format!(
+                    "{:04}-{:02}",
+                    1970 + months.div_euclid(12),
+                    months.rem_euclid(12) + 1
+                )
+            },
+            (PartitionTransform::Day, Value::Int(days)) => {
+                NaiveDate::default()
+                    .checked_add_signed(chrono::Duration::days(i64::from(*days)))
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| days.to_string())
+            },
+            (PartitionTransform::Hour, Value::Int(hours)) => {
+                NaiveDateTime::default()
+                    .checked_add_signed(chrono::Duration::hours(i64::from(*hours)))
+                    .map(|ts| ts.format("%Y-%m-%d-%H").to_string())
+                    .unwrap_or_else(|| hours.to_string())
+            },
+            (_, Value::Timestamptz(ts)) => {
+                ts.format("%Y-%m-%dT%H:%M:%S%.6f+00:00").to_string()
+            },
+            (_, value) => value.to_string()
+        }
+    }
+}
+
+impl std::fmt::Display for PartitionTransform {
+    /// Formats the transform as its Iceberg string representation, e.g. `"identity"`
+    /// or `"bucket[16]"`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartitionTransform::Void => write!(f, "void"),
+            PartitionTransform::Identity => write!(f, "identity"),
+            PartitionTransform::Year => write!(f, "year"),
+            PartitionTransform::Month => write!(f, "month"),
+            PartitionTransform::Day => write!(f, "day"),
+            PartitionTransform::Hour => write!(f, "hour"),
+            PartitionTransform::Bucket(n) => write!(f, "bucket[{n}]"),
+            PartitionTransform::Truncate(width) => write!(f, "truncate[{width}]"),
+        }
+    }
+}
+
+impl std::str::FromStr for PartitionTransform {
+    type Err = IcebergError;
+
+    /// Parses a transform from its Iceberg string representation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref PARAM_REGEX: Regex =
+                Regex::new(r#"^(?P<name>bucket|truncate)\[(?P<param>\d+)\]$"#).unwrap();
+        }
+
+        match s {
+            "void" => Ok(PartitionTransform::Void),
+            "identity" => Ok(PartitionTransform::Identity),
+            "year" => Ok(PartitionTransform::Year),
+            "month" => Ok(PartitionTransform::Month),
+            "day" => Ok(PartitionTransform::Day),
+            "hour" => Ok(PartitionTransform::Hour),
+            _ => {
+                let caps = PARAM_REGEX.captures(s).ok_or_else(|| {
+                    IcebergError::PartitionError {
+                        message: format!("invalid partition transform '{s}'")
+                    }
+                })?;
+
+                let param: u32 = caps["param"].parse().map_err(|_| {
+                    IcebergError::PartitionError {
+                        message: format!(
+                            "invalid parameter for partition transform '{s}'"
+                        )
+                    }
+                })?;
+
+                match &caps["name"] {
+                    "bucket" => Ok(PartitionTransform::Bucket(param)),
+                    _ => Ok(PartitionTransform::Truncate(param)),
+                }
+            }
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for PartitionTransform {
@@ -294,13 +690,7 @@ impl<'de> Deserialize<'de> for PartitionTransform {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        if s.starts_with("bucket") {
-            deserialize_bucket(s.into_deserializer())
-        } else if s.starts_with("truncate") {
-            deserialize_truncate(s.into_deserializer())
-        } else {
-            PartitionTransform::deserialize(s.into_deserializer())
-        }
+        s.parse().map_err(de::Error::custom)
     }
 }
 
@@ -309,59 +699,10 @@ impl Serialize for PartitionTransform {
     where
         S: serde::Serializer,
     {
-        use PartitionTransform::*;
-        match self {
-            Bucket(mod_n) => serializer.serialize_str(&format!("bucket[{mod_n}]")),
-            Truncate(width) => serializer.serialize_str(&format!("truncate[{width}]")),
-            _ => PartitionTransform::serialize(self, serializer),
-        }
+        serializer.serialize_str(&self.to_string())
     }
 }
 
-fn deserialize_bucket<'de, D>(deserializer: D) -> Result<PartitionTransform, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let this = String::deserialize(deserializer)?;
-    let re: Regex = Regex::new(r#"^bucket\[(?P<n>\d+)\]$"#).unwrap();
-    let err_msg = format!("Invalid bucket format {}", this);
-
-    let caps = re
-        .captures(&this)
-        .ok_or_else(|| de::Error::custom(&err_msg))?;
-    let bucket: u32 = caps
-        .name("n")
-        .ok_or_else(|| de::Error::custom(&err_msg))
-        .and_then(|p| {
-            p.as_str()
-                .parse()
-                .map_err(|_| de::Error::custom("bucket not u32"))
-        })?;
-    Ok(PartitionTransform::Bucket(bucket))
-}
-
-fn deserialize_truncate<'de, D>(deserializer: D) -> Result<PartitionTransform, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let this = String::deserialize(deserializer)?;
-    let re: Regex = Regex::new(r#"^truncate\[(?P<w>\d+)\]$"#).unwrap();
-    let err_msg = format!("Invalid truncate format {}", this);
-
-    let caps = re
-        .captures(&this)
-        .ok_or_else(|| de::Error::custom(&err_msg))?;
-    let width: u32 = caps
-        .name("w")
-        .ok_or_else(|| de::Error::custom(&err_msg))
-        .and_then(|p| {
-            p.as_str()
-                .parse()
-                .map_err(|_| de::Error::custom("bucket not u32"))
-        })?;
-    Ok(PartitionTransform::Truncate(width))
-}
-
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 #[serde(rename_all = "kebab-case")]
 /// Specification of a single partition field within a `PartitionSpec`.
@@ -391,13 +732,44 @@ impl PartitionField {
             transform: transform
         }
     }
+
+    /// Projects a predicate on this field's source column to an inclusive predicate
+    /// on the partition values: every partition that might contain a row matching
+    /// `predicate` matches the projected predicate.
+    ///
+    /// For example, `ts > '2023-01-01T10:00:00'` is projected by a `Day` field to
+    /// `ts_day >= 19358`, and `id = 5` is projected by a `Bucket` field to
+    /// `id_bucket = <bucket of 5>`. Returns `None` if the predicate doesn't reference
+    /// the source column or no projection restricts the partitions, e.g. `id < 5`
+    /// with a `Bucket` field.
+    pub fn project(
+        &self,
+        predicate: &Predicate<BoundReference>
+    ) -> Option<Predicate<BoundReference>> {
+        project_predicate(predicate, self, Projection::Inclusive).ok().flatten()
+    }
+
+    /// Projects a predicate on this field's source column to a strict predicate on
+    /// the partition values: every row of a partition matching the projected
+    /// predicate matches `predicate`.
+    ///
+    /// For example, `ts >= '2023-01-02T00:00:00'` is projected by a `Day` field to
+    /// `ts_day > 19358`. Returns `None` if the predicate doesn't reference the source
+    /// column or no sound projection exists, e.g. `name = 'abc'` with a `Truncate`
+    /// field, whose partitions may also contain other values.
+    pub fn project_strict(
+        &self,
+        predicate: &Predicate<BoundReference>
+    ) -> Option<Predicate<BoundReference>> {
+        project_predicate(predicate, self, Projection::Strict).ok().flatten()
+    }
 }
 
 /// Partition spec struct that can be directly serialized or deserialized
 /// but may contain integrity errors.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 #[serde(rename_all = "kebab-case")]
-pub(crate) struct PartitionSpecModel {
+pub struct PartitionSpecModel {
     /// Unique identifier for this partition spec within an Iceberg table.
     pub spec_id: i32,
     /// The partitioning fields.
@@ -467,7 +839,7 @@ impl PartitionSpec {
             match field_by_id.get(&field.source_id) {
                 Some(source_field) => {
                     // Will fail if the transform can't be applied to the source field.
-                    field.transform.get_result_type(source_field.r#type.clone())?;
+                    field.transform.get_result_type_for(source_field)?;
                 },
                 None => {
                     return Err(IcebergError::PartitionError {
@@ -620,6 +992,38 @@ impl PartitionSpec {
                 Ok((field.name.clone(), value))
             }).collect()
     }
+
+    /// Returns the Hive-style path of the partition with the given values, e.g.
+    /// `event_date=2023-01-01/bucket=5`.
+    ///
+    /// Each partition field is formatted as `name=value`, where the value is the
+    /// human-readable string of its transform (see
+    /// [`PartitionTransform::to_human_string()`]). Both the name and the value are
+    /// URL-encoded, so special characters such as `/` can't break the path structure.
+    /// Null values are formatted as `name=null`.
+    pub fn partition_path(&self, values: &PartitionValues) -> String {
+        values.values()
+            .iter()
+            .map(|(name, value)| {
+                let transform = self.fields()
+                    .iter()
+                    .find(|field| field.name == *name)
+                    .map(|field| &field.transform)
+                    .unwrap_or(&PartitionTransform::Identity);
+
+                format!(
+                    "{}={}",
+                    url_encode(name),
+                    url_encode(&transform.to_human_string(value.as_ref()))
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+}
+
+fn url_encode(s: &str) -> String {
+    url::form_urlencoded::byte_serialize(s.as_bytes()).collect()
 }
 
 /// Builder struct to create new `PartitionSpec`s easily.
@@ -656,7 +1060,7 @@ impl PartitionSpecBuilder {
             })?;
 
         // Fail if this transform can't be applied to the source field.
-        transform.get_result_type(source_field.r#type.clone())?;
+        transform.get_result_type_for(source_field)?;
 
         if self.fields.iter().any(|field| field.source_id == source_field.id) {
             Err(IcebergError::PartitionError {
@@ -707,6 +1111,135 @@ impl PartitionSpecBuilder {
     }
 }
 
+/// Builder for evolving a table's partition spec into a new spec, created with
+/// [`IcebergTable::update_spec()`](crate::IcebergTable::update_spec).
+///
+/// Removed fields are kept in the new spec with a `void` transform, which always
+/// produces `null`, so their field ids are never reused and data files written with
+/// older specs can still be read. New fields are assigned ids after the table's
+/// last assigned partition field id, while existing fields keep their ids.
+///
+/// The resulting spec is committed with an
+/// [`UpdatePartitionSpecOperation`](crate::transaction::UpdatePartitionSpecOperation),
+/// which makes it the table's default spec.
+pub struct PartitionSpecUpdate {
+    spec_id: i32,
+    schema: Schema,
+    fields: Vec<PartitionField>,
+    last_partition_id: i32,
+}
+
+impl PartitionSpecUpdate {
+    /// Creates a builder for a new spec with the given id, starting from the fields
+    /// of `spec`.
+    ///
+    /// `last_partition_id` is the highest partition field id ever assigned by the
+    /// table, across all of its specs.
+    pub fn new(
+        spec_id: i32,
+        spec: &PartitionSpec,
+        schema: Schema,
+        last_partition_id: i32
+    ) -> Self {
+        Self {
+            spec_id: spec_id,
+            schema: schema,
+            fields: spec.fields().clone(),
+            last_partition_id: last_partition_id,
+        }
+    }
+
+    /// Adds a partition field named `name`, produced by applying `transform` to the
+    /// `source_field` column.
+    ///
+    /// A removed field with the same name is renamed to free its name, by appending
+    /// its field id.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::PartitionError`] is returned if `source_field` does not refer
+    /// to a valid field in the schema, if the transform can't be applied to it, if
+    /// the spec already has the same transform of the field, or if another field
+    /// has the same name.
+    pub fn add_field(
+        mut self,
+        source_field: &str,
+        transform: PartitionTransform,
+        name: &str
+    ) -> IcebergResult<Self> {
+        let source_field = self.schema.get_field_by_name(source_field)
+            .ok_or_else(|| IcebergError::PartitionError {
+                message: format!("source field name {source_field} not found in schema")
+            })?;
+
+        // Fail if this transform can't be applied to the source field.
+        transform.get_result_type_for(source_field)?;
+
+        let redundant = self.fields.iter().any(|field| {
+            field.source_id == source_field.id && field.transform == transform
+        });
+        if redundant {
+            return Err(IcebergError::PartitionError {
+                message: format!(
+                    "redundant partitioning on source field '{}' with transform {}",
+                    source_field.name, transform
+                )
+            });
+        }
+
+        let source_id = source_field.id;
+        if let Some(existing) = self.fields.iter_mut().find(|field| field.name == name) {
+            if existing.transform != PartitionTransform::Void {
+                return Err(IcebergError::PartitionError {
+                    message: format!("partition field {name} already exists")
+                });
+            }
+            existing.name = format!("{}_{}", existing.name, existing.field_id);
+        }
+
+        self.last_partition_id += 1;
+        self.fields.push(PartitionField::new(
+            source_id,
+            self.last_partition_id,
+            name,
+            transform
+        ));
+
+        Ok(self)
+    }
+
+    /// Removes the partition field named `name`, replacing its transform with
+    /// `void`.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::PartitionError`] is returned if the spec has no such field, or
+    /// if it was already removed.
+    pub fn remove_field(mut self, name: &str) -> IcebergResult<Self> {
+        let field = self.fields.iter_mut()
+            .find(|field| {
+                field.name == name && field.transform != PartitionTransform::Void
+            })
+            .ok_or_else(|| IcebergError::PartitionError {
+                message: format!("partition field {name} not found")
+            })?;
+
+        field.transform = PartitionTransform::Void;
+
+        Ok(self)
+    }
+
+    /// Builds the new partition spec.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::PartitionError`] is returned if the fields kept from the
+    /// original spec are not valid for the schema.
+    pub fn build(self) -> IcebergResult<PartitionSpec> {
+        PartitionSpec::try_new(self.spec_id, self.fields, self.schema)
+    }
+}
+
 /// Represents the (transformed) values of a single partition.
 ///
 /// # Examples
@@ -802,7 +1335,8 @@ impl Eq for PartitionValues {}
 mod tests {
     use std::collections::HashMap;
 
-    use chrono::NaiveDate;
+    use uuid::Uuid;
+    use chrono::{NaiveDate, NaiveTime, NaiveDateTime, TimeZone, Utc};
 
     use crate::{IcebergError};
     use crate::schema::{
@@ -810,7 +1344,10 @@ mod tests {
         SchemaType, StructType, PrimitiveType
     };
     use crate::value::Value;
-    use crate::partition::{PartitionSpec, PartitionField, PartitionTransform};
+    use crate::partition::{
+        PartitionSpec, PartitionSpecUpdate, PartitionField, PartitionTransform,
+        PartitionValues, BucketTransform
+    };
 
     fn create_partition_fields() -> Vec<PartitionField> {
         vec![
@@ -971,4 +1508,346 @@ mod tests {
             Err(IcebergError::PartitionError{..})
         ));
     }
+
+    #[test]
+    fn transform_string_round_trip() {
+        let transforms = [
+            ("void", PartitionTransform::Void),
+            ("identity", PartitionTransform::Identity),
+            ("year", PartitionTransform::Year),
+            ("month", PartitionTransform::Month),
+            ("day", PartitionTransform::Day),
+            ("hour", PartitionTransform::Hour),
+            ("bucket[16]", PartitionTransform::Bucket(16)),
+            ("truncate[4]", PartitionTransform::Truncate(4)),
+        ];
+
+        for (s, transform) in transforms {
+            assert_eq!(s.parse::<PartitionTransform>().unwrap(), transform);
+            assert_eq!(transform.to_string(), s);
+
+            let json = serde_json::to_string(&transform).unwrap();
+            assert_eq!(json, format!("\"{s}\""));
+            assert_eq!(
+                serde_json::from_str::<PartitionTransform>(&json).unwrap(),
+                transform
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_transform_string() {
+        for s in ["", "years", "bucket", "bucket[]", "bucket[-1]", "truncate[a]"] {
+            assert!(matches!(
+                s.parse::<PartitionTransform>(),
+                Err(IcebergError::PartitionError{..})
+            ));
+        }
+    }
+
+    #[test]
+    fn identity_and_void_transforms() {
+        let value = Value::String("a".to_string());
+
+        assert_eq!(
+            PartitionTransform::Identity.apply(Some(value.clone())).unwrap(),
+            Some(value.clone())
+        );
+        assert_eq!(PartitionTransform::Void.apply(Some(value)).unwrap(), None);
+        assert_eq!(PartitionTransform::Identity.apply(None).unwrap(), None);
+        assert_eq!(PartitionTransform::Void.apply(None).unwrap(), None);
+
+        let string_type = SchemaType::Primitive(PrimitiveType::String);
+        assert_eq!(
+            PartitionTransform::Identity.get_result_type(string_type.clone()).unwrap(),
+            string_type
+        );
+        assert_eq!(
+            PartitionTransform::Void.get_result_type(string_type.clone()).unwrap(),
+            string_type
+        );
+    }
+
+    #[test]
+    fn partition_path() {
+        let schema = Schema::new(0, vec![
+            Field::new(
+                1,
+                "event_date",
+                true,
+                SchemaType::Primitive(PrimitiveType::Date)
+            ),
+            Field::new(
+                2,
+                "user_id",
+                true,
+                SchemaType::Primitive(PrimitiveType::Long)
+            ),
+            Field::new(
+                3,
+                "name",
+                false,
+                SchemaType::Primitive(PrimitiveType::String)
+            ),
+            Field::new(
+                4,
+                "ts",
+                false,
+                SchemaType::Primitive(PrimitiveType::Timestamp)
+            )
+        ]);
+
+        let spec = PartitionSpec::try_new(
+            0,
+            vec![
+                PartitionField::new(
+                    1, 1000, "event_date", PartitionTransform::Identity
+                ),
+                PartitionField::new(
+                    2, 1001, "bucket", PartitionTransform::Bucket(16)
+                ),
+                PartitionField::new(
+                    3, 1002, "name", PartitionTransform::Identity
+                ),
+                PartitionField::new(
+                    4, 1003, "ts_day", PartitionTransform::Day
+                ),
+            ],
+            schema
+        ).unwrap();
+
+        let values = PartitionValues::from_iter([
+            (
+                "event_date".to_string(),
+                Some(Value::Date(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()))
+            ),
+            ("bucket".to_string(), Some(Value::Int(5))),
+            ("name".to_string(), Some(Value::String("a/b c".to_string()))),
+            ("ts_day".to_string(), Some(Value::Int(19358))),
+        ]);
+
+        assert_eq!(
+            spec.partition_path(&values),
+            "event_date=2023-01-01/bucket=5/name=a%2Fb+c/ts_day=2023-01-01"
+        );
+
+        let values = PartitionValues::from_iter([
+            ("event_date".to_string(), None),
+            ("bucket".to_string(), None),
+            ("name".to_string(), None),
+            ("ts_day".to_string(), None),
+        ]);
+
+        assert_eq!(
+            spec.partition_path(&values),
+            "event_date=null/bucket=null/name=null/ts_day=null"
+        );
+    }
+
+    #[test]
+    fn month_and_hour_transforms() {
+        let ts = NaiveDate::from_ymd_opt(2023, 3, 15).unwrap()
+            .and_hms_opt(10, 30, 0).unwrap();
+        let before_epoch = NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()
+            .and_hms_opt(23, 59, 0).unwrap();
+
+        let month = PartitionTransform::Month;
+        assert_eq!(
+            month.apply(Some(Value::Timestamp(ts))).unwrap(),
+            Some(Value::Int(638))
+        );
+        assert_eq!(
+            month.apply(Some(Value::Date(ts.date()))).unwrap(),
+            Some(Value::Int(638))
+        );
+        assert_eq!(
+            month.apply(Some(Value::Timestamp(before_epoch))).unwrap(),
+            Some(Value::Int(-1))
+        );
+
+        let hour = PartitionTransform::Hour;
+        assert_eq!(
+            hour.apply(Some(Value::Timestamp(ts))).unwrap(),
+            Some(Value::Int(19431 * 24 + 10))
+        );
+        assert_eq!(
+            hour.apply(Some(Value::Timestamptz(Utc.from_utc_datetime(&ts)))).unwrap(),
+            Some(Value::Int(19431 * 24 + 10))
+        );
+        assert_eq!(
+            hour.apply(Some(Value::Timestamp(before_epoch))).unwrap(),
+            Some(Value::Int(-1))
+        );
+
+        // Dates have no hours.
+        let date_type = SchemaType::Primitive(PrimitiveType::Date);
+        assert!(hour.get_result_type(date_type).is_err());
+        assert!(hour.apply(Some(Value::Date(ts.date()))).is_err());
+    }
+
+    #[test]
+    fn truncate_transform() {
+        let truncate = PartitionTransform::Truncate(10);
+        assert_eq!(truncate.apply(Some(Value::Int(1))).unwrap(), Some(Value::Int(0)));
+        assert_eq!(truncate.apply(Some(Value::Int(-1))).unwrap(), Some(Value::Int(-10)));
+        assert_eq!(truncate.apply(Some(Value::Long(25))).unwrap(), Some(Value::Long(20)));
+
+        let truncate = PartitionTransform::Truncate(3);
+        assert_eq!(
+            truncate.apply(Some(Value::String("iceberg".to_string()))).unwrap(),
+            Some(Value::String("ice".to_string()))
+        );
+        // Strings are truncated to code points, not bytes.
+        assert_eq!(
+            truncate.apply(Some(Value::String("ééééé".to_string()))).unwrap(),
+            Some(Value::String("ééé".to_string()))
+        );
+        assert_eq!(
+            truncate.apply(Some(Value::Binary(vec![1, 2, 3, 4]))).unwrap(),
+            Some(Value::Binary(vec![1, 2, 3]))
+        );
+
+        let double_type = SchemaType::Primitive(PrimitiveType::Double);
+        assert!(truncate.get_result_type(double_type).is_err());
+        assert!(PartitionTransform::Truncate(0).apply(Some(Value::Int(1))).is_err());
+    }
+
+    #[test]
+    fn suggest_applicable_transforms() {
+        let schema = Schema::new(0, vec![
+            Field::new_primitive(1, "name", true, PrimitiveType::String),
+            Field::new_primitive(2, "ts", true, PrimitiveType::Timestamp),
+        ]);
+
+        let result = PartitionSpec::try_new(
+            0,
+            vec![PartitionField::new(1, 1000, "name_day", PartitionTransform::Day)],
+            schema.clone()
+        );
+        let Err(IcebergError::PartitionError { message }) = result else {
+            panic!("expected a partition error");
+        };
+        assert_eq!(
+            message,
+            "can't partition by day of source field 'name' of type string, valid \
+            transforms for string are: identity, bucket[N], truncate[W], void"
+        );
+
+        // The builder reports the same error.
+        let result = PartitionSpec::builder(0, schema).add_year_field("name");
+        assert!(matches!(
+            result,
+            Err(IcebergError::PartitionError { message }) if message.ends_with(
+                "identity, bucket[N], truncate[W], void"
+            )
+        ));
+    }
+
+    #[test]
+    fn transform_human_string() {
+        assert_eq!(
+            PartitionTransform::Year.to_human_string(Some(&Value::Int(53))),
+            "2023"
+        );
+        assert_eq!(
+            PartitionTransform::Month.to_human_string(Some(&Value::Int(636))),
+            "2023-01"
+        );
+        assert_eq!(
+            PartitionTransform::Month.to_human_string(Some(&Value::Int(-1))),
+            "1969-12"
+        );
+        assert_eq!(
+            PartitionTransform::Hour.to_human_string(Some(&Value::Int(10))),
+            "1970-01-01-10"
+        );
+        assert_eq!(PartitionTransform::Identity.to_human_string(None), "null");
+    }
+
+    fn timestamp() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2017, 11, 16).unwrap().and_hms_opt(22, 31, 8).unwrap()
+    }
+
+    #[test]
+    fn bucket_hash_spec_values() {
+        // Test vectors from the Iceberg spec.
+        let values = [
+            (Value::Int(34), 2017239379),
+            (Value::Long(34), 2017239379),
+            (Value::Date(NaiveDate::from_ymd_opt(2017, 11, 16).unwrap()), -653330422),
+            (Value::Time(NaiveTime::from_hms_opt(22, 31, 8).unwrap()), -662762989),
+            (Value::Timestamp(timestamp()), -2047944441),
+            (Value::Timestamptz(Utc.from_utc_datetime(&timestamp())), -2047944441),
+            (Value::String("iceberg".to_string()), 1210000089),
+            (
+                Value::Uuid(Uuid::parse_str("f79c3e09-677c-4bbd-a479-3f349cb785e7").unwrap()),
+                1488055340
+            ),
+            (Value::Fixed(vec![0x00, 0x01, 0x02, 0x03]), -188683207),
+            (Value::Binary(vec![0x00, 0x01, 0x02, 0x03]), -188683207),
+        ];
+
+        for (value, hash) in values {
+            assert_eq!(BucketTransform::hash(&value).unwrap(), hash, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn bucket_of() {
+        // Known buckets, matching those assigned by Spark.
+        let buckets = [
+            (16, Value::Int(34), 3),
+            (100, Value::Int(34), 79),
+            (16, Value::Long(-1), 8),
+            (16, Value::Long(100), 0),
+            (16, Value::Long(2147483647), 14),
+            (1000, Value::String("iceberg".to_string()), 89),
+            (16, Value::String("spark".to_string()), 5),
+            (16, Value::String("".to_string()), 0),
+            (16, Value::Date(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()), 12),
+            (16, Value::Date(NaiveDate::from_ymd_opt(2023, 6, 1).unwrap()), 1),
+            (100, Value::Date(NaiveDate::from_ymd_opt(2017, 11, 16).unwrap()), 26),
+            (1000, Value::Timestamp(timestamp()), 207),
+        ];
+
+        for (num_buckets, value, bucket) in buckets {
+            let transform = PartitionTransform::Bucket(num_buckets);
+            assert_eq!(transform.bucket_of(&value), Some(bucket), "{value:?}");
+            assert_eq!(
+                transform.apply(Some(value)).unwrap(),
+                Some(Value::Int(bucket))
+            );
+        }
+
+        assert_eq!(PartitionTransform::Bucket(16).bucket_of(&Value::Double(1.0)), None);
+        assert_eq!(PartitionTransform::Identity.bucket_of(&Value::Int(34)), None);
+        assert_eq!(PartitionTransform::Bucket(16).apply(None).unwrap(), None);
+    }
+
+    #[test]
+    fn update_partition_spec() {
+        let update = || {
+            PartitionSpecUpdate::new(1, &create_partition_spec(), create_schema(), 1001)
+        };
+
+        // Re-adding a removed field's name renames the removed field.
+        let spec = update()
+            .remove_field("ts_day").unwrap()
+            .add_field("ts", PartitionTransform::Hour, "ts_day").unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(spec.spec_id(), 1);
+        assert_eq!(spec.fields(), &vec![
+            PartitionField::new(1, 1000, "user_id", PartitionTransform::Identity),
+            PartitionField::new(2, 1001, "ts_day_1001", PartitionTransform::Void),
+            PartitionField::new(2, 1002, "ts_day", PartitionTransform::Hour),
+        ]);
+
+        assert!(update().add_field("ts", PartitionTransform::Day, "day").is_err());
+        assert!(update().add_field("ts", PartitionTransform::Hour, "user_id").is_err());
+        assert!(update().add_field("id", PartitionTransform::Day, "id_day").is_err());
+        assert!(update().add_field("missing", PartitionTransform::Identity, "m").is_err());
+        assert!(update().remove_field("missing").is_err());
+        assert!(update().remove_field("ts_day").unwrap().remove_field("ts_day").is_err());
+    }
 }
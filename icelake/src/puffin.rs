@@ -0,0 +1,362 @@
+//! Reading and writing of Puffin statistics files.
+//!
+//! [Puffin](https://iceberg.apache.org/puffin-spec/) files store blobs of statistics
+//! about a table's data, such as sketches of the number of distinct values of a
+//! column, which don't fit in manifests. A file consists of the magic bytes, the
+//! blobs one after the other, and a footer describing the blobs:
+//!
+//! ```text
+//! Magic Blob₁ Blob₂ ... Blobₙ Footer
+//! Footer = Magic FooterPayload FooterPayloadSize Flags Magic
+//! ```
+//!
+//! The footer payload is JSON. Compressed blobs and footers are not supported yet.
+//!
+//! Statistics files are referenced from the table metadata by [`StatisticsFile`]s,
+//! one per snapshot.
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{IcebergResult, IcebergError, IcebergTable};
+
+/// Magic bytes at the start and end of Puffin files, and at the start of the footer.
+pub const MAGIC: [u8; 4] = [0x50, 0x46, 0x41, 0x31];
+/// Blob type of an Apache DataSketches theta sketch, estimating the number of
+/// distinct values of a column.
+pub const APACHE_DATASKETCHES_THETA_V1: &str = "apache-datasketches-theta-v1";
+/// Property of theta sketch blobs holding the estimated number of distinct values.
+pub const NDV_PROPERTY: &str = "ndv";
+
+/// Length of the fixed-size part of the footer following the payload: the payload
+/// size, flags and magic.
+const FOOTER_STRUCT_LENGTH: usize = 12;
+/// Flag bit of the footer payload being LZ4-compressed, in the first flags byte.
+const FOOTER_COMPRESSED_FLAG: u8 = 0x01;
+
+/// A blob to be written to a Puffin file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blob {
+    /// Type of the blob, e.g. [`APACHE_DATASKETCHES_THETA_V1`].
+    pub blob_type: String,
+    /// Ids of the fields the blob was computed from.
+    pub fields: Vec<i32>,
+    /// Id of the snapshot the blob was computed from.
+    pub snapshot_id: i64,
+    /// Sequence number of the snapshot the blob was computed from.
+    pub sequence_number: i64,
+    /// The blob's content.
+    pub data: Bytes,
+    /// Additional properties of the blob, e.g. [`NDV_PROPERTY`].
+    pub properties: HashMap<String, String>,
+}
+
+/// Describes a blob of a Puffin file in the file's footer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BlobMetadata {
+    #[serde(rename = "type")]
+    pub blob_type: String,
+    pub fields: Vec<i32>,
+    pub snapshot_id: i64,
+    pub sequence_number: i64,
+    /// Offset of the blob's content from the start of the file.
+    pub offset: i64,
+    /// Length of the blob's content, in bytes.
+    pub length: i64,
+    /// Codec the blob's content is compressed with, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression_codec: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+}
+
+/// The footer payload of a Puffin file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct FileMetadata {
+    blobs: Vec<BlobMetadata>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    properties: HashMap<String, String>,
+}
+
+/// A statistics file of a snapshot, as referenced from the table metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StatisticsFile {
+    /// Id of the snapshot the statistics were computed from.
+    pub snapshot_id: i64,
+    /// Location of the Puffin file.
+    pub statistics_path: String,
+    pub file_size_in_bytes: i64,
+    pub file_footer_size_in_bytes: i64,
+    /// The blobs of the file, without their locations in it.
+    pub blob_metadata: Vec<StatisticsBlobMetadata>,
+}
+
+/// Describes a blob of a statistics file in the table metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StatisticsBlobMetadata {
+    #[serde(rename = "type")]
+    pub blob_type: String,
+    pub snapshot_id: i64,
+    pub sequence_number: i64,
+    pub fields: Vec<i32>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+}
+
+impl From<&BlobMetadata> for StatisticsBlobMetadata {
+    fn from(metadata: &BlobMetadata) -> Self {
+        Self {
+            blob_type: metadata.blob_type.clone(),
+            snapshot_id: metadata.snapshot_id,
+            sequence_number: metadata.sequence_number,
+            fields: metadata.fields.clone(),
+            properties: metadata.properties.clone(),
+        }
+    }
+}
+
+/// Writes blobs to an uncompressed Puffin file.
+pub struct PuffinWriter {
+    blobs: Vec<Blob>,
+    properties: HashMap<String, String>,
+}
+
+impl PuffinWriter {
+    /// Creates a writer of an empty Puffin file.
+    pub fn new() -> Self {
+        Self {
+            blobs: Vec::new(),
+            properties: HashMap::from([
+                ("created-by".to_string(), "icelake".to_string())
+            ]),
+        }
+    }
+
+    /// Adds a blob to the file.
+    pub fn add_blob(&mut self, blob: Blob) {
+        self.blobs.push(blob);
+    }
+
+    /// Encodes the file, returning its content, the metadata of its blobs and the
+    /// size of its footer.
+    pub fn encode(self) -> IcebergResult<(Bytes, Vec<BlobMetadata>, usize)> {
+        let mut data = MAGIC.to_vec();
+
+        let mut blobs = Vec::with_capacity(self.blobs.len());
+        for blob in self.blobs {
+            blobs.push(BlobMetadata {
+                blob_type: blob.blob_type,
+                fields: blob.fields,
+                snapshot_id: blob.snapshot_id,
+                sequence_number: blob.sequence_number,
+                offset: data.len() as i64,
+                length: blob.data.len() as i64,
+                compression_codec: None,
+                properties: blob.properties,
+            });
+            data.extend_from_slice(&blob.data);
+        }
+
+        let payload = serde_json::to_vec(&FileMetadata {
+            blobs: blobs.clone(),
+            properties: self.properties,
+        }).map_err(|source| IcebergError::SerializeJson { source })?;
+        let payload_size = i32::try_from(payload.len()).map_err(|_| {
+            IcebergError::CustomError {
+                message: "Puffin footer too large".to_string()
+            }
+        })?;
+
+        let footer_start = data.len();
+        data.extend_from_slice(&MAGIC);
+        data.extend_from_slice(&payload);
+        data.extend_from_slice(&payload_size.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&MAGIC);
+        let footer_size = data.len() - footer_start;
+
+        Ok((Bytes::from(data), blobs, footer_size))
+    }
+
+    /// Writes the file to the table's metadata directory and returns the
+    /// [`StatisticsFile`] referencing it as the statistics of the given snapshot.
+    /// The statistics file is not added to the table's metadata.
+    pub async fn close(
+        self,
+        table: &IcebergTable,
+        snapshot_id: i64
+    ) -> IcebergResult<StatisticsFile> {
+        let (data, blobs, footer_size) = self.encode()?;
+
+        let file = table.new_metadata_file(
+            &format!("{}-{}.stats", snapshot_id, Uuid::new_v4()),
+            data
+        )?;
+        let statistics_file = StatisticsFile {
+            snapshot_id: snapshot_id,
+            statistics_path: file.url(),
+            file_size_in_bytes: file.len() as i64,
+            file_footer_size_in_bytes: footer_size as i64,
+            blob_metadata: blobs.iter().map(StatisticsBlobMetadata::from).collect(),
+        };
+
+        file.save().await?;
+
+        Ok(statistics_file)
+    }
+}
+
+impl Default for PuffinWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the blobs of a Puffin file.
+pub struct PuffinReader {
+    data: Bytes,
+    metadata: FileMetadata,
+}
+
+impl PuffinReader {
+    /// Parses the footer of the Puffin file with the given content.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::CustomError`] is returned if the file isn't a valid Puffin
+    /// file, and [`IcebergError::Unsupported`] if its footer is compressed.
+    pub fn try_new(data: Bytes) -> IcebergResult<Self> {
+        let invalid = |message: &str| IcebergError::CustomError {
+            message: format!("invalid Puffin file: {message}")
+        };
+
+        let len = data.len();
+        if len < MAGIC.len() * 2 + FOOTER_STRUCT_LENGTH
+            || data[..4] != MAGIC
+            || data[len - 4..] != MAGIC {
+            return Err(invalid("missing magic"));
+        }
+
+        let flags = &data[len - 8..len - 4];
+        if flags[0] & FOOTER_COMPRESSED_FLAG != 0 {
+            return Err(IcebergError::Unsupported(
+                "compressed Puffin footers".to_string()
+            ));
+        }
+
+        // Can't fail, the slice has exactly four bytes.
+        let payload_size = i32::from_le_bytes(
+            data[len - 12..len - 8].try_into().unwrap()
+        );
+        let payload_end = len - FOOTER_STRUCT_LENGTH;
+        let payload_start = usize::try_from(payload_size).ok()
+            .and_then(|size| payload_end.checked_sub(size))
+            .filter(|start| *start >= MAGIC.len() * 2)
+            .ok_or_else(|| invalid("invalid footer size"))?;
+        if data[payload_start - 4..payload_start] != MAGIC {
+            return Err(invalid("missing footer magic"));
+        }
+
+        let metadata: FileMetadata = serde_json::from_slice(
+            &data[payload_start..payload_end]
+        ).map_err(|_| invalid("invalid footer"))?;
+
+        Ok(Self {
+            data: data,
+            metadata: metadata,
+        })
+    }
+
+    /// Metadata of all blobs in the file.
+    pub fn blobs_metadata(&self) -> &[BlobMetadata] {
+        &self.metadata.blobs
+    }
+
+    /// Properties of the file.
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.metadata.properties
+    }
+
+    /// Reads the content of the blob with the given metadata.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::CustomError`] is returned if the blob lies outside the file,
+    /// and [`IcebergError::Unsupported`] if it is compressed.
+    pub fn read_blob(&self, metadata: &BlobMetadata) -> IcebergResult<Bytes> {
+        if let Some(codec) = &metadata.compression_codec {
+            return Err(IcebergError::Unsupported(
+                format!("Puffin blobs compressed with {codec}")
+            ));
+        }
+
+        let start = usize::try_from(metadata.offset).ok();
+        let end = start.zip(usize::try_from(metadata.length).ok())
+            .and_then(|(start, length)| start.checked_add(length));
+        match (start, end) {
+            (Some(start), Some(end)) if end <= self.data.len() => {
+                Ok(self.data.slice(start..end))
+            },
+            _ => Err(IcebergError::CustomError {
+                message: format!(
+                    "invalid Puffin file: blob of type {} out of bounds",
+                    metadata.blob_type
+                )
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+
+    use crate::IcebergError;
+    use super::{PuffinWriter, PuffinReader, Blob, MAGIC, APACHE_DATASKETCHES_THETA_V1};
+
+    #[test]
+    fn encode_and_read() {
+        let mut writer = PuffinWriter::new();
+        writer.add_blob(Blob {
+            blob_type: APACHE_DATASKETCHES_THETA_V1.to_string(),
+            fields: vec![1],
+            snapshot_id: 10,
+            sequence_number: 2,
+            data: Bytes::from_static(b"sketch-1"),
+            properties: HashMap::from([("ndv".to_string(), "42".to_string())]),
+        });
+        writer.add_blob(Blob {
+            blob_type: "custom".to_string(),
+            fields: vec![2, 3],
+            snapshot_id: 10,
+            sequence_number: 2,
+            data: Bytes::from_static(b"blob-2"),
+            properties: HashMap::new(),
+        });
+
+        let (data, blobs, footer_size) = writer.encode().unwrap();
+        assert_eq!(data[..4], MAGIC);
+        assert_eq!(blobs[0].offset, 4);
+        assert_eq!(blobs[1].offset, 12);
+        assert_eq!(data.len(), 4 + 8 + 6 + footer_size);
+
+        let reader = PuffinReader::try_new(data.clone()).unwrap();
+        assert_eq!(reader.blobs_metadata(), blobs);
+        assert_eq!(reader.properties()["created-by"], "icelake");
+        assert_eq!(reader.read_blob(&blobs[0]).unwrap(), Bytes::from_static(b"sketch-1"));
+        assert_eq!(reader.read_blob(&blobs[1]).unwrap(), Bytes::from_static(b"blob-2"));
+
+        // Truncated files are invalid.
+        assert!(matches!(
+            PuffinReader::try_new(data.slice(..data.len() - 1)),
+            Err(IcebergError::CustomError { .. })
+        ));
+    }
+}
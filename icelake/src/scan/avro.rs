@@ -0,0 +1,183 @@
+//! Reading of Avro data files into Arrow record batches.
+use std::sync::Arc;
+
+use apache_avro::{Reader as AvroReader, types::Value as AvroValue};
+use apache_avro::schema::Schema as AvroSchema;
+use arrow_schema::{DataType as ArrowDataType, TimeUnit as ArrowTimeUnit};
+use arrow_array::{
+    RecordBatch, RecordBatchOptions, ArrayRef, BooleanArray, Int32Array, Int64Array,
+    Float32Array, Float64Array, Date32Array, Time64MicrosecondArray,
+    TimestampMicrosecondArray, StringArray, BinaryArray
+};
+
+use crate::{IcebergResult, IcebergError};
+use crate::schema::Schema;
+use crate::schema::avro::avro_to_iceberg_schema;
+use crate::scan::projection::FileProjection;
+
+/// Magic bytes at the start of Avro object container files.
+const AVRO_MAGIC: &[u8] = b"Obj\x01";
+/// Key of the writer's schema in the metadata of Avro files.
+const AVRO_SCHEMA_KEY: &str = "avro.schema";
+/// Maximum number of rows in each batch read from a file.
+const BATCH_SIZE: usize = 8192;
+
+fn invalid_file(message: String) -> IcebergError {
+    IcebergError::ScanError { message: format!("invalid avro data file: {message}") }
+}
+
+/// Returns the Iceberg schema of an Avro data file, converted from the writer's
+/// schema in the file's header, with the field ids of its `field-id` attributes.
+///
+/// The header is parsed directly, since the Avro reader doesn't keep the custom
+/// attributes of the writer's schema.
+pub(crate) fn avro_file_schema(bytes: &[u8]) -> IcebergResult<Schema> {
+    let mut header = bytes.strip_prefix(AVRO_MAGIC)
+        .ok_or_else(|| invalid_file("missing magic".to_string()))?;
+
+    let metadata_schema = AvroSchema::Map(Box::new(AvroSchema::Bytes));
+    let metadata = apache_avro::from_avro_datum(&metadata_schema, &mut header, None)?;
+    let schema = match metadata {
+        AvroValue::Map(mut metadata) => metadata.remove(AVRO_SCHEMA_KEY),
+        _ => None,
+    };
+
+    match schema {
+        Some(AvroValue::Bytes(json)) => {
+            let json = String::from_utf8(json)
+                .map_err(|_| invalid_file("schema isn't valid utf-8".to_string()))?;
+            avro_to_iceberg_schema(0, &json)
+        },
+        _ => Err(invalid_file("missing schema".to_string())),
+    }
+}
+
+/// Converts Avro values of a column to an Arrow array of the given type.
+fn avro_array(
+    values: Vec<AvroValue>,
+    data_type: &ArrowDataType
+) -> IcebergResult<ArrayRef> {
+    let values = values.into_iter().map(|value| match value {
+        AvroValue::Union(_, value) => *value,
+        value => value,
+    });
+    let invalid = |value: AvroValue| {
+        invalid_file(format!("unexpected value {value:?} for type {data_type}"))
+    };
+
+    macro_rules! array {
+        ($array_type:ty, $($pattern:pat => $value:expr),+) => {
+            values
+                .map(|value| match value {
+                    AvroValue::Null => Ok(None),
+                    $($pattern => Ok(Some($value)),)+
+                    value => Err(invalid(value)),
+                })
+                .collect::<IcebergResult<$array_type>>()?
+        };
+    }
+
+    let array: ArrayRef = match data_type {
+        ArrowDataType::Boolean => {
+            Arc::new(array!(BooleanArray, AvroValue::Boolean(b) => b))
+        },
+        ArrowDataType::Int32 => Arc::new(array!(Int32Array, AvroValue::Int(i) => i)),
+        ArrowDataType::Int64 => Arc::new(array!(Int64Array, AvroValue::Long(l) => l)),
+        ArrowDataType::Float32 => {
+            Arc::new(array!(Float32Array, AvroValue::Float(f) => f))
+        },
+        ArrowDataType::Float64 => {
+            Arc::new(array!(Float64Array, AvroValue::Double(d) => d))
+        },
+        ArrowDataType::Date32 => Arc::new(array!(
+            Date32Array,
+            AvroValue::Date(days) => days,
+            AvroValue::Int(days) => days
+        )),
+        ArrowDataType::Time64(ArrowTimeUnit::Microsecond) => Arc::new(array!(
+            Time64MicrosecondArray,
+            AvroValue::TimeMicros(micros) => micros,
+            AvroValue::Long(micros) => micros
+        )),
+        ArrowDataType::Timestamp(ArrowTimeUnit::Microsecond, timezone) => {
+            let array = array!(
+                TimestampMicrosecondArray,
+                AvroValue::TimestampMicros(micros) => micros,
+                AvroValue::Long(micros) => micros
+            );
+            match timezone {
+                Some(timezone) => Arc::new(array.with_timezone(timezone.clone())),
+                None => Arc::new(array),
+            }
+        },
+        ArrowDataType::Utf8 => Arc::new(array!(StringArray, AvroValue::String(s) => s)),
+        ArrowDataType::Binary => Arc::new(array!(BinaryArray, AvroValue::Bytes(b) => b)),
+        data_type => return Err(IcebergError::Unsupported(
+            format!("reading {data_type} values from avro data files")
+        )),
+    };
+
+    Ok(array)
+}
+
+/// Converts the records of an Avro file to a batch of the projection's physical
+/// schema.
+fn records_to_batch(
+    records: Vec<Vec<(String, AvroValue)>>,
+    projection: &FileProjection
+) -> IcebergResult<RecordBatch> {
+    let physical_schema = projection.physical_schema();
+    let num_rows = records.len();
+
+    let mut columns: Vec<Vec<AvroValue>> = projection.root_indices().iter()
+        .map(|_| Vec::with_capacity(records.len()))
+        .collect();
+    for record in records {
+        let mut fields: Vec<Option<AvroValue>> = record.into_iter()
+            .map(|(_, value)| Some(value))
+            .collect();
+        for (column, index) in columns.iter_mut().zip(projection.root_indices()) {
+            let value = fields.get_mut(*index).and_then(Option::take)
+                .ok_or_else(|| invalid_file("record is missing fields".to_string()))?;
+            column.push(value);
+        }
+    }
+
+    let arrays = columns.into_iter()
+        .zip(physical_schema.fields())
+        .map(|(values, field)| avro_array(values, field.data_type()))
+        .collect::<IcebergResult<Vec<_>>>()?;
+
+    // The row count is needed for batches without columns.
+    Ok(RecordBatch::try_new_with_options(
+        physical_schema,
+        arrays,
+        &RecordBatchOptions::new().with_row_count(Some(num_rows))
+    )?)
+}
+
+/// Reads the columns of an Avro data file selected by `projection`, into batches of
+/// its physical schema.
+pub(crate) fn read_avro_file(
+    bytes: &[u8],
+    projection: &FileProjection
+) -> IcebergResult<Vec<RecordBatch>> {
+    let mut batches = Vec::new();
+    let mut records = Vec::with_capacity(BATCH_SIZE);
+    for value in AvroReader::new(bytes)? {
+        match value? {
+            AvroValue::Record(record) => records.push(record),
+            value => return Err(invalid_file(format!("unexpected value {value:?}"))),
+        }
+
+        if records.len() == BATCH_SIZE {
+            batches.push(records_to_batch(std::mem::take(&mut records), projection)?);
+        }
+    }
+
+    if !records.is_empty() {
+        batches.push(records_to_batch(records, projection)?);
+    }
+
+    Ok(batches)
+}
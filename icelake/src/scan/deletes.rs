@@ -0,0 +1,397 @@
+//! Application of position and equality delete files to the rows of a scan.
+use std::collections::{HashMap, HashSet};
+
+use bytes::Bytes;
+use arrow_schema::ArrowError;
+use arrow_array::{Array, ArrayRef, RecordBatch, BooleanArray, StringArray, Int64Array};
+use arrow_row::{RowConverter, SortField};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::{IcebergResult, IcebergError};
+use crate::value::Value;
+use crate::schema::{Schema, PrimitiveType};
+use crate::schema::arrow::iceberg_to_arrow_schema;
+use crate::storage::IcebergStorage;
+use crate::manifest::{DataFile, DataFileContent};
+use crate::writer::DELETE_FILE_PATH_FIELD_ID;
+
+/// A live delete file of a snapshot, with the metadata needed to decide which data
+/// files it applies to.
+struct IndexedDeleteFile {
+    sequence_number: i64,
+    spec_id: i32,
+    /// Whether the delete file was written with an unpartitioned spec, in which case
+    /// equality deletes apply to data files of all partitions.
+    unpartitioned: bool,
+    delete_file: DataFile,
+}
+
+/// Indexes the delete files of a snapshot to find the ones applying to each data file.
+///
+/// Following the spec, a position delete file applies to data files of the same
+/// partition with a sequence number lower than or equal to its own, while an
+/// equality delete file applies to data files with a strictly lower sequence number.
+#[derive(Default)]
+pub(crate) struct DeleteFileIndex {
+    delete_files: Vec<IndexedDeleteFile>,
+}
+
+impl DeleteFileIndex {
+    pub fn add(
+        &mut self,
+        sequence_number: i64,
+        spec_id: i32,
+        unpartitioned: bool,
+        delete_file: DataFile
+    ) {
+        self.delete_files.push(IndexedDeleteFile {
+            sequence_number: sequence_number,
+            spec_id: spec_id,
+            unpartitioned: unpartitioned,
+            delete_file: delete_file,
+        });
+    }
+
+    /// Returns the delete files applying to a data file with the given data sequence
+    /// number, written with the partition spec `spec_id`.
+    ///
+    /// The delete files are ordered by their sequence number, i.e. by the order of
+    /// the commits that added them.
+    pub fn for_data_file(
+        &self,
+        sequence_number: i64,
+        spec_id: i32,
+        data_file: &DataFile
+    ) -> Vec<DataFile> {
+        let mut delete_files: Vec<&IndexedDeleteFile> = self.delete_files.iter()
+            .filter(|indexed| {
+                let same_partition = indexed.spec_id == spec_id
+                    && indexed.delete_file.partition == data_file.partition;
+
+                match indexed.delete_file.content {
+                    DataFileContent::PositionDelete => {
+                        same_partition
+                            && indexed.sequence_number >= sequence_number
+                            && may_reference(&indexed.delete_file, &data_file.file_path)
+                    },
+                    DataFileContent::EqualityDelete => {
+                        (same_partition || indexed.unpartitioned)
+                            && indexed.sequence_number > sequence_number
+                    },
+                    DataFileContent::Data => false,
+                }
+            })
+            .collect();
+        delete_files.sort_by_key(|indexed| indexed.sequence_number);
+
+        delete_files.into_iter()
+            .map(|indexed| indexed.delete_file.clone())
+            .collect()
+    }
+}
+
+/// Checks whether a position delete file may contain deletes for the data file at
+/// `file_path`, based on the bounds of its `file_path` column.
+fn may_reference(delete_file: &DataFile, file_path: &str) -> bool {
+    let bound = |bounds: &Option<HashMap<i32, Vec<u8>>>| {
+        bounds.as_ref()
+            .and_then(|bounds| bounds.get(&DELETE_FILE_PATH_FIELD_ID))
+            .and_then(|bytes| Value::from_bytes(bytes, &PrimitiveType::String).ok())
+    };
+    let file_path = Value::String(file_path.to_string());
+
+    let above_lower = match bound(&delete_file.lower_bounds) {
+        Some(lower) => lower <= file_path,
+        None => true,
+    };
+    let below_upper = match bound(&delete_file.upper_bounds) {
+        Some(upper) => file_path <= upper,
+        None => true,
+    };
+
+    above_lower && below_upper
+}
+
+/// The keys of the rows deleted by an equality delete file.
+struct EqualityDeletes {
+    /// Positions of the equality columns in the batches being filtered.
+    columns: Vec<usize>,
+    converter: RowConverter,
+    keys: HashSet<Box<[u8]>>,
+}
+
+/// Filters out the deleted rows of a single data file.
+///
+/// The deletes of all delete files applying to the data file take effect: a row is
+/// dropped if any position delete file deletes its position, or if its key matches
+/// a row of any equality delete file.
+pub(crate) struct DeleteFilter {
+    positions: HashSet<i64>,
+    equality_deletes: Vec<EqualityDeletes>,
+}
+
+impl DeleteFilter {
+    /// Loads the given delete files of the data file at `file_path`, in order.
+    ///
+    /// `field_ids` are the ids of the columns of the batches to be filtered, which
+    /// must include all equality columns of the equality delete files. Their types
+    /// are taken from `schema`.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ScanError`] is returned if a delete file lacks one of the
+    /// columns it should contain.
+    pub async fn load(
+        storage: &IcebergStorage,
+        file_path: &str,
+        delete_files: &[DataFile],
+        schema: &Schema,
+        field_ids: &[i32]
+    ) -> IcebergResult<Self> {
+        let mut filter = Self {
+            positions: HashSet::new(),
+            equality_deletes: Vec::new(),
+        };
+
+        for delete_file in delete_files {
+            let path = storage.create_path_from_url(&delete_file.file_path)?;
+            let bytes = storage.get(&path).await?;
+
+            match delete_file.content {
+                DataFileContent::PositionDelete => {
+                    filter.load_positions(bytes, file_path, &delete_file.file_path)?;
+                },
+                DataFileContent::EqualityDelete => {
+                    filter.equality_deletes.push(
+                        load_equality_deletes(bytes, delete_file, schema, field_ids)?
+                    );
+                },
+                DataFileContent::Data => {},
+            }
+        }
+
+        Ok(filter)
+    }
+
+    fn load_positions(
+        &mut self,
+        bytes: Bytes,
+        file_path: &str,
+        delete_file_path: &str
+    ) -> IcebergResult<()> {
+        let missing_column = |column: &str| IcebergError::ScanError {
+            message: format!(
+                "position delete file {delete_file_path} lacks column {column}"
+            )
+        };
+
+        for batch in ParquetRecordBatchReaderBuilder::try_new(bytes)?.build()? {
+            let batch = batch?;
+            let file_paths = batch.column_by_name("file_path")
+                .and_then(|column| column.as_any().downcast_ref::<StringArray>())
+                .ok_or_else(|| missing_column("file_path"))?;
+            let positions = batch.column_by_name("pos")
+                .and_then(|column| column.as_any().downcast_ref::<Int64Array>())
+                .ok_or_else(|| missing_column("pos"))?;
+
+            for (path, pos) in file_paths.iter().zip(positions.iter()) {
+                if let (Some(path), Some(pos)) = (path, pos) {
+                    if path == file_path {
+                        self.positions.insert(pos);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns for each row of a batch whether it was not deleted, given the
+    /// position of the batch's first row in the data file.
+    pub fn keep(
+        &mut self,
+        batch: &RecordBatch,
+        offset: i64
+    ) -> Result<BooleanArray, ArrowError> {
+        let mut keep: Vec<bool> = (0..batch.num_rows())
+            .map(|row| !self.positions.contains(&(offset + row as i64)))
+            .collect();
+
+        for equality_deletes in &mut self.equality_deletes {
+            let columns: Vec<ArrayRef> = equality_deletes.columns.iter()
+                .map(|column| batch.column(*column).clone())
+                .collect();
+            let rows = equality_deletes.converter.convert_columns(&columns)?;
+
+            for (row, keep) in rows.iter().zip(keep.iter_mut()) {
+                if *keep && equality_deletes.keys.contains(row.as_ref()) {
+                    *keep = false;
+                }
+            }
+        }
+
+        Ok(BooleanArray::from(keep))
+    }
+}
+
+fn load_equality_deletes(
+    bytes: Bytes,
+    delete_file: &DataFile,
+    schema: &Schema,
+    field_ids: &[i32]
+) -> IcebergResult<EqualityDeletes> {
+    let missing_field = |field_id: &i32| IcebergError::ScanError {
+        message: format!(
+            "equality field {field_id} of delete file {} not found",
+            delete_file.file_path
+        )
+    };
+
+    let equality_ids = delete_file.equality_ids.clone().unwrap_or_default();
+    let equality_fields = equality_ids.iter()
+        .map(|field_id| {
+            schema.fields().iter()
+                .find(|field| field.id == *field_id)
+                .cloned()
+                .ok_or_else(|| missing_field(field_id))
+        })
+        .collect::<IcebergResult<Vec<_>>>()?;
+    let key_schema = iceberg_to_arrow_schema(&Schema::new(schema.id(), equality_fields))?;
+
+    let columns = equality_ids.iter()
+        .map(|field_id| {
+            field_ids.iter()
+                .position(|id| id == field_id)
+                .ok_or_else(|| missing_field(field_id))
+        })
+        .collect::<IcebergResult<Vec<_>>>()?;
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(bytes)?;
+
+    // Position of each equality column in the delete file, by field id or by name.
+    let file_fields = builder.parquet_schema().root_schema().get_fields();
+    let file_columns = equality_ids.iter()
+        .zip(key_schema.fields().iter())
+        .map(|(field_id, key_field)| {
+            file_fields.iter()
+                .position(|field| {
+                    let info = field.get_basic_info();
+                    info.has_id() && info.id() == *field_id
+                })
+                .or_else(|| {
+                    file_fields.iter().position(|field| field.name() == key_field.name())
+                })
+                .ok_or_else(|| missing_field(field_id))
+        })
+        .collect::<IcebergResult<Vec<_>>>()?;
+
+    let mut converter = RowConverter::new(
+        key_schema.fields().iter()
+            .map(|field| SortField::new(field.data_type().clone()))
+            .collect()
+    )?;
+
+    let mut keys = HashSet::new();
+    for batch in builder.build()? {
+        let batch = batch?;
+        let key_columns = file_columns.iter()
+            .zip(key_schema.fields().iter())
+            .map(|(column, key_field)| {
+                let column = batch.column(*column);
+                if column.data_type() == key_field.data_type() {
+                    Ok(column.clone())
+                } else {
+                    arrow_cast::cast(column, key_field.data_type())
+                }
+            })
+            .collect::<Result<Vec<ArrayRef>, ArrowError>>()?;
+
+        let rows = converter.convert_columns(&key_columns)?;
+        keys.extend(rows.iter().map(|row| Box::from(row.as_ref())));
+    }
+
+    Ok(EqualityDeletes {
+        columns: columns,
+        converter: converter,
+        keys: keys,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::value::Value;
+    use crate::manifest::{DataFile, DataFileContent, DataFileFormat};
+    use crate::scan::deletes::DeleteFileIndex;
+    use crate::writer::DELETE_FILE_PATH_FIELD_ID;
+
+    fn file(content: DataFileContent, path: &str) -> DataFile {
+        DataFile::builder(content, path, DataFileFormat::Parquet, 1, 100).build()
+    }
+
+    fn paths(files: Vec<DataFile>) -> Vec<String> {
+        files.into_iter().map(|file| file.file_path).collect()
+    }
+
+    #[test]
+    fn delete_file_sequence_numbers() {
+        let data_file = file(DataFileContent::Data, "data.parquet");
+
+        let mut index = DeleteFileIndex::default();
+        index.add(1, 0, true, file(DataFileContent::PositionDelete, "pos-1"));
+        index.add(2, 0, true, file(DataFileContent::PositionDelete, "pos-2"));
+        index.add(1, 0, true, file(DataFileContent::EqualityDelete, "eq-1"));
+        index.add(2, 0, true, file(DataFileContent::EqualityDelete, "eq-2"));
+
+        // Equality deletes don't apply to data files of their own sequence number.
+        assert_eq!(
+            paths(index.for_data_file(1, 0, &data_file)),
+            ["pos-1", "pos-2", "eq-2"]
+        );
+        assert_eq!(paths(index.for_data_file(2, 0, &data_file)), ["pos-2"]);
+        assert!(index.for_data_file(3, 0, &data_file).is_empty());
+    }
+
+    #[test]
+    fn delete_files_in_sequence_number_order() {
+        let data_file = file(DataFileContent::Data, "data.parquet");
+
+        // Delete files are indexed in the order of their manifests, which needn't be
+        // the order of their commits.
+        let mut index = DeleteFileIndex::default();
+        index.add(4, 0, true, file(DataFileContent::EqualityDelete, "eq-4"));
+        index.add(2, 0, true, file(DataFileContent::PositionDelete, "pos-2"));
+        index.add(3, 0, true, file(DataFileContent::PositionDelete, "pos-3"));
+        index.add(2, 0, true, file(DataFileContent::EqualityDelete, "eq-2"));
+
+        assert_eq!(
+            paths(index.for_data_file(1, 0, &data_file)),
+            ["pos-2", "eq-2", "pos-3", "eq-4"]
+        );
+    }
+
+    #[test]
+    fn position_delete_file_path_bounds() {
+        let bound = |path: &str| -> HashMap<i32, Vec<u8>> {
+            HashMap::from([(
+                DELETE_FILE_PATH_FIELD_ID,
+                Value::String(path.to_string()).try_into().unwrap()
+            )])
+        };
+        let delete_file = DataFile::builder(
+            DataFileContent::PositionDelete, "pos", DataFileFormat::Parquet, 1, 100
+        ).with_lower_bounds(bound("b.parquet"))
+            .with_upper_bounds(bound("c.parquet"))
+            .build();
+
+        let mut index = DeleteFileIndex::default();
+        index.add(1, 0, true, delete_file);
+
+        let cases = [("a.parquet", false), ("b.parquet", true), ("d.parquet", false)];
+        for (path, applies) in cases {
+            let data_file = file(DataFileContent::Data, path);
+            assert_eq!(!index.for_data_file(1, 0, &data_file).is_empty(), applies);
+        }
+    }
+}
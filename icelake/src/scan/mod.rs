@@ -0,0 +1,1279 @@
+//! Interface to Iceberg table scans.
+//!
+//! A [`TableScan`] plans which data files need to be read to scan a table's
+//! snapshot, producing a [`FileScanTask`] for every such file. The data itself can be
+//! read as Arrow record batches with [`TableScan::to_arrow_reader()`], which applies
+//! the position and equality delete files of the snapshot to the rows read.
+mod avro;
+mod deletes;
+mod projection;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use arrow_schema::{
+    ArrowError,
+    SchemaRef as ArrowSchemaRef,
+    Schema as ArrowSchema,
+    Field as ArrowField,
+    FieldRef as ArrowFieldRef,
+    DataType as ArrowDataType,
+};
+use arrow_array::{
+    RecordBatch, RecordBatchReader, ArrayRef, Int64Array, UInt32Array, new_null_array
+};
+use arrow_select::filter::{filter, filter_record_batch};
+use arrow_select::take::take;
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::basic::Type as PhysicalType;
+use parquet::file::properties::ReaderProperties;
+use parquet::file::reader::{FileReader, RowGroupReader, SerializedFileReader};
+use parquet::file::serialized_reader::ReadOptionsBuilder;
+
+use crate::{
+    IcebergTable, IcebergTableMetadata, IcebergResult, IcebergError, NotFoundKind
+};
+use crate::storage::IcebergStorage;
+use crate::schema::{Schema, Field, PrimitiveType};
+use crate::partition::PartitionSpec;
+use crate::schema::arrow::iceberg_to_arrow_schema;
+use crate::schema::mapping::{NameMapping, NAME_MAPPING_PROPERTY};
+use crate::snapshot::{Snapshot, SnapshotOperation};
+use crate::manifest::{
+    DataFile, DataFileContent, DataFileFormat, ManifestCache, ManifestEntry, ManifestFile,
+    ManifestFileType, ManifestReader
+};
+use crate::scan::deletes::{DeleteFileIndex, DeleteFilter};
+use crate::scan::avro::{avro_file_schema, read_avro_file};
+use crate::expr::{
+    Expression, BoundExpression, ManifestEvaluator, InclusiveMetricsEvaluator,
+    ResidualEvaluator, BloomFilterEvaluator, literal_array
+};
+
+pub use crate::scan::projection::FileProjection;
+
+/// Name of the `_row_id` metadata column, holding the row lineage id of each row.
+pub const ROW_ID_COLUMN: &str = "_row_id";
+/// Field id reserved for the `_row_id` metadata column.
+pub const ROW_ID_FIELD_ID: i32 = 2147483540;
+
+/// A task for reading a single data file as part of a table scan.
+///
+/// The task carries the full [`DataFile`] descriptor from the manifest, including
+/// its record count, size and column metrics, so it can be used for metadata-only
+/// analysis without reading the file.
+#[derive(Debug, Clone)]
+pub struct FileScanTask {
+    data_file: DataFile,
+    field_ids: Vec<i32>,
+    residual: BoundExpression,
+    deletes: Vec<DataFile>,
+}
+
+impl FileScanTask {
+    /// The data file to read.
+    pub fn data_file(&self) -> &DataFile {
+        &self.data_file
+    }
+
+    /// Number of records in the data file.
+    pub fn record_count(&self) -> i64 {
+        self.data_file.record_count
+    }
+
+    /// Size of the data file in bytes.
+    pub fn file_size_in_bytes(&self) -> i64 {
+        self.data_file.file_size_in_bytes
+    }
+
+    /// Ids of the fields to read from the data file.
+    pub fn field_ids(&self) -> &[i32] {
+        &self.field_ids
+    }
+
+    /// The part of the scan's filter left to evaluate on the rows of the data file,
+    /// after simplifying away the predicates decided by its partition values.
+    pub fn residual(&self) -> &BoundExpression {
+        &self.residual
+    }
+
+    /// The position and equality delete files applying to the data file, whose
+    /// deleted rows must be skipped when reading it. The files are ordered by their
+    /// sequence numbers.
+    pub fn deletes(&self) -> &[DataFile] {
+        &self.deletes
+    }
+}
+
+/// Plans a scan over a snapshot of an Iceberg table.
+///
+/// By default the current snapshot is scanned, all columns are read and no rows are
+/// filtered.
+pub struct TableScan<'a> {
+    table: &'a IcebergTable,
+    snapshot_id: Option<i64>,
+    selected_columns: Option<Vec<String>>,
+    filter: Expression,
+    partition_columns: bool,
+    row_ids: bool,
+    bloom_filters: bool,
+    from_snapshot_id: Option<i64>,
+    allow_non_append: bool,
+    manifest_cache: Option<Arc<ManifestCache>>,
+    limit: Option<usize>,
+}
+
+impl<'a> TableScan<'a> {
+    /// Initializes a scan over the current snapshot of the table.
+    pub fn new(table: &'a IcebergTable) -> Self {
+        Self {
+            table: table,
+            snapshot_id: None,
+            selected_columns: None,
+            filter: Expression::AlwaysTrue,
+            partition_columns: false,
+            row_ids: false,
+            bloom_filters: false,
+            from_snapshot_id: None,
+            allow_non_append: false,
+            manifest_cache: None,
+            limit: None,
+        }
+    }
+
+    /// Scans the snapshot with the given id instead of the current snapshot.
+    pub fn use_snapshot(mut self, snapshot_id: i64) -> Self {
+        self.snapshot_id = Some(snapshot_id);
+        self
+    }
+
+    /// Scans only the data files appended after the snapshot with the given id, up
+    /// to the current snapshot or the one set with
+    /// [`to_snapshot_inclusive()`](Self::to_snapshot_inclusive).
+    ///
+    /// The snapshot must be an ancestor of the last scanned snapshot. Deletes
+    /// committed up to the last scanned snapshot are applied to the appended rows.
+    pub fn from_snapshot_exclusive(mut self, snapshot_id: i64) -> Self {
+        self.from_snapshot_id = Some(snapshot_id);
+        self
+    }
+
+    /// Scans data files appended up to, and including, the snapshot with the given
+    /// id. Same as [`use_snapshot()`](Self::use_snapshot).
+    pub fn to_snapshot_inclusive(self, snapshot_id: i64) -> Self {
+        self.use_snapshot(snapshot_id)
+    }
+
+    /// Whether an incremental scan set with
+    /// [`from_snapshot_exclusive()`](Self::from_snapshot_exclusive) may span
+    /// overwrite and delete snapshots.
+    ///
+    /// The data files added by such snapshots are never scanned, since they may hold
+    /// rows rewritten from earlier files rather than new rows.
+    pub fn allow_non_append(mut self, allow_non_append: bool) -> Self {
+        self.allow_non_append = allow_non_append;
+        self
+    }
+
+    /// Reads the entries of manifests through the given cache when planning the
+    /// scan, so that manifests cached by earlier scans aren't read again.
+    pub fn with_manifest_cache(mut self, manifest_cache: Arc<ManifestCache>) -> Self {
+        self.manifest_cache = Some(manifest_cache);
+        self
+    }
+
+    /// Reads only the columns with the given names.
+    pub fn select(mut self, columns: &[&str]) -> Self {
+        self.selected_columns = Some(
+            columns.iter().map(|column| column.to_string()).collect()
+        );
+        self
+    }
+
+    /// Scans only rows matching the filter. Manifests and data files that can't
+    /// contain matching rows are skipped according to their partition summaries,
+    /// partition values and column metrics.
+    ///
+    /// Calling this multiple times combines the filters.
+    pub fn filter(mut self, filter: Expression) -> Self {
+        self.filter = self.filter.and(filter);
+        self
+    }
+
+    /// Whether to append the fields of the table's partition spec as columns to the
+    /// batches read by [`to_arrow_reader()`](Self::to_arrow_reader).
+    ///
+    /// The partition columns are named after the partition fields and hold the
+    /// transformed partition values of each data file, e.g. the bucket number of a
+    /// bucket partition, which are the same for all rows of the file.
+    pub fn with_partition_columns(mut self, partition_columns: bool) -> Self {
+        self.partition_columns = partition_columns;
+        self
+    }
+
+    /// Whether to append the [`ROW_ID_COLUMN`] metadata column as the last column of
+    /// the batches read by [`to_arrow_reader()`](Self::to_arrow_reader).
+    ///
+    /// The row id of each row is computed from the `first_row_id` of its data file
+    /// plus the row's position in the file, and is null for files written before
+    /// row lineage was enabled, which have no `first_row_id`.
+    pub fn with_row_ids(mut self, row_ids: bool) -> Self {
+        self.row_ids = row_ids;
+        self
+    }
+
+    /// Whether [`to_arrow_reader()`](Self::to_arrow_reader) should skip the row groups
+    /// of data files whose Parquet bloom filters show they can't contain rows
+    /// matching the filter's equality and `IN` predicates.
+    ///
+    /// Reading bloom filters costs additional reads per row group, so this is only
+    /// worthwhile for selective filters on high-cardinality columns.
+    pub fn with_bloom_filters(mut self, bloom_filters: bool) -> Self {
+        self.bloom_filters = bloom_filters;
+        self
+    }
+
+    /// Reads at most `limit` rows, counted after deletes are applied.
+    ///
+    /// Once `limit` rows were read, the last batch is truncated and no further data
+    /// files are opened.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns the snapshot to be scanned, or `None` if the table has no snapshots.
+    fn snapshot(&self) -> IcebergResult<Option<&'a Snapshot>> {
+        let metadata = self.table.current_metadata()?;
+
+        match self.snapshot_id {
+            Some(snapshot_id) => {
+                metadata.snapshot_by_id(snapshot_id)
+                    .map(Some)
+                    .ok_or_else(|| IcebergError::NotFound {
+                        kind: NotFoundKind::Snapshot,
+                        identifier: snapshot_id.to_string(),
+                    })
+            },
+            None => Ok(metadata.current_snapshot())
+        }
+    }
+
+    /// Returns the append snapshots of an incremental scan, between the snapshot set
+    /// with [`from_snapshot_exclusive()`](Self::from_snapshot_exclusive) and the
+    /// scanned snapshot.
+    fn appended_snapshots(
+        &self,
+        snapshot: &'a Snapshot,
+        from_snapshot_id: i64
+    ) -> IcebergResult<Vec<&'a Snapshot>> {
+        let mut snapshots = Vec::new();
+        let ancestors = self.table.current_metadata()?
+            .ancestors_of(snapshot.snapshot_id);
+        for ancestor in ancestors {
+            if ancestor.snapshot_id == from_snapshot_id {
+                return Ok(snapshots);
+            }
+
+            match ancestor.summary.operation() {
+                Some(SnapshotOperation::Append) => snapshots.push(ancestor),
+                // Replace snapshots only rewrite existing rows.
+                Some(SnapshotOperation::Replace) => {},
+                _ if self.allow_non_append => {},
+                _ => return Err(IcebergError::ScanError {
+                    message: format!(
+                        "incremental scan spans non-append snapshot {}",
+                        ancestor.snapshot_id
+                    )
+                }),
+            }
+        }
+
+        Err(IcebergError::ScanError {
+            message: format!(
+                "snapshot {from_snapshot_id} is not an ancestor of snapshot {}",
+                snapshot.snapshot_id
+            )
+        })
+    }
+
+    /// Returns the schema of the scanned snapshot, which is the schema the table had
+    /// when the snapshot was created.
+    fn schema(&self, snapshot: Option<&Snapshot>) -> IcebergResult<&'a Schema> {
+        let metadata = self.table.current_metadata()?;
+
+        Ok(snapshot
+            .and_then(|snapshot| snapshot.schema_id)
+            .and_then(|schema_id| metadata.schema_by_id(schema_id))
+            .unwrap_or_else(|| metadata.current_schema()))
+    }
+
+    /// Returns the table's default name mapping, if set.
+    fn name_mapping(&self) -> IcebergResult<Option<NameMapping>> {
+        default_name_mapping(self.table.current_metadata()?)
+    }
+
+    /// Returns the fields of the partition columns appended to the scanned rows, if
+    /// enabled with [`with_partition_columns()`](Self::with_partition_columns).
+    fn partition_fields(&self) -> IcebergResult<Vec<Field>> {
+        match self.partition_columns {
+            true => {
+                let spec = self.table.current_metadata()?.current_partition_spec();
+                Ok(spec.as_struct_type().fields().to_vec())
+            },
+            false => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the fields of the metadata columns appended to the scanned rows, if
+    /// enabled with [`with_row_ids()`](Self::with_row_ids).
+    fn metadata_fields(&self) -> Vec<Field> {
+        match self.row_ids {
+            true => vec![Field::new_primitive(
+                ROW_ID_FIELD_ID,
+                ROW_ID_COLUMN,
+                false,
+                PrimitiveType::Long
+            )],
+            false => Vec::new(),
+        }
+    }
+
+    /// Returns the fields to read, in the order they should be read.
+    fn projected_schema(&self, schema: &Schema) -> IcebergResult<Schema> {
+        let field_ids = self.projected_field_ids(schema)?;
+        let fields = field_ids.iter()
+            .filter_map(|field_id| {
+                schema.fields().iter().find(|field| field.id == *field_id).cloned()
+            })
+            .collect();
+
+        Ok(Schema::new(schema.id(), fields))
+    }
+
+    /// Returns the ids of the fields to read.
+    fn projected_field_ids(&self, schema: &Schema) -> IcebergResult<Vec<i32>> {
+        match &self.selected_columns {
+            Some(columns) => {
+                columns.iter()
+                    .map(|column| {
+                        schema.get_field_by_name(column)
+                            .map(|field| field.id)
+                            .ok_or_else(|| IcebergError::ScanError {
+                                message: format!("column '{column}' not found in schema")
+                            })
+                    })
+                    .collect()
+            },
+            None => {
+                Ok(schema.fields().iter().map(|field| field.id).collect())
+            }
+        }
+    }
+
+    /// Plans the scan, returning a task for every live data file of the snapshot,
+    /// along with the delete files applying to it.
+    ///
+    /// Only the manifest list and manifests are read; data and delete files are
+    /// never opened. Delete manifests are skipped if they are older than all
+    /// manifests of the scanned data files, since their deletes can't apply.
+    ///
+    /// For incremental scans, only the data files added by the append snapshots in
+    /// the scanned range are planned, read from the manifests each snapshot added.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::NotFound`] is returned if the snapshot selected with
+    /// [`use_snapshot()`](Self::use_snapshot) does not exist, or if a manifest was
+    /// written with a partition spec missing from the table's metadata.
+    /// [`IcebergError::ScanError`] is returned if one of the selected columns does
+    /// not exist in the snapshot's schema. For incremental
+    /// scans, it is also returned if the starting snapshot isn't an ancestor of the
+    /// scanned snapshot, or if the range includes overwrite or delete snapshots
+    /// without [`allow_non_append()`](Self::allow_non_append).
+    /// [`IcebergError::ExpressionError`] is returned if the filter can't be bound to
+    /// the snapshot's schema.
+    pub async fn plan_files(&self) -> IcebergResult<Vec<FileScanTask>> {
+        let snapshot = self.snapshot()?;
+        let schema = self.schema(snapshot)?;
+        let field_ids = self.projected_field_ids(schema)?;
+        let filter = self.filter.bind(schema)?;
+
+        let snapshot = match (snapshot, self.from_snapshot_id) {
+            (Some(snapshot), _) => snapshot,
+            (None, Some(from_snapshot_id)) => return Err(IcebergError::NotFound {
+                kind: NotFoundKind::Snapshot,
+                identifier: from_snapshot_id.to_string(),
+            }),
+            (None, None) => return Ok(Vec::new())
+        };
+
+        let storage = self.table.storage();
+        let manifest_list = self.table.read_manifest_list(snapshot).await?;
+
+        let mut evaluators = HashMap::new();
+
+        // Incremental scans read the manifests added by each appended snapshot, which
+        // may have been rewritten out of the scanned snapshot's manifest list.
+        let candidate_manifests = match self.from_snapshot_id {
+            Some(from_snapshot_id) => {
+                let mut manifests = Vec::new();
+                for appended in self.appended_snapshots(snapshot, from_snapshot_id)? {
+                    let appended_list = self.table.read_manifest_list(appended).await?;
+                    manifests.extend(appended_list.manifest_files().iter()
+                        .filter(|manifest_file| {
+                            manifest_file.added_snapshot_id == appended.snapshot_id
+                        })
+                        .cloned());
+                }
+                manifests
+            },
+            None => manifest_list.manifest_files().to_vec(),
+        };
+
+        let mut data_manifests = Vec::new();
+        for manifest_file in candidate_manifests {
+            if manifest_file.content == ManifestFileType::Data
+                && self.might_match(&filter, &manifest_file, &mut evaluators)? {
+                data_manifests.push(manifest_file);
+            }
+        }
+
+        // Delete files only apply to data files with a lower or equal sequence number,
+        // and a manifest's sequence number bounds those of its files. Delete manifests
+        // older than all scanned data can't apply to it, and are never read.
+        let min_data_sequence_number = data_manifests.iter()
+            .map(|manifest_file| manifest_file.min_sequence_number)
+            .min();
+
+        // Index the delete files first, to find the ones applying to each data file.
+        let mut delete_index = DeleteFileIndex::default();
+        for manifest_file in manifest_list.manifest_files() {
+            if manifest_file.content != ManifestFileType::Delete {
+                continue;
+            }
+
+            let might_apply = min_data_sequence_number
+                .map_or(false, |min| manifest_file.sequence_number >= min);
+            if !might_apply
+                || !self.might_match(&filter, manifest_file, &mut evaluators)? {
+                continue;
+            }
+
+            let spec_id = manifest_file.partition_spec_id;
+            let unpartitioned = self.table.current_metadata()?
+                .partition_spec(spec_id)
+                .map_or(true, |spec| spec.is_empty());
+
+            let entries = self.read_entries(manifest_file, &storage).await?;
+            for entry in entries.iter() {
+                if entry.deleted() {
+                    continue;
+                }
+
+                delete_index.add(
+                    entry.sequence_number().unwrap_or(0),
+                    spec_id,
+                    unpartitioned,
+                    entry.data_file.clone()
+                );
+            }
+        }
+
+        let metrics_evaluator = InclusiveMetricsEvaluator::new(&filter);
+        let mut tasks = Vec::new();
+        for manifest_file in data_manifests {
+            let residual_evaluator = ResidualEvaluator::new(
+                &self.partition_spec(manifest_file.partition_spec_id)?,
+                &filter
+            );
+
+            let entries = self.read_entries(&manifest_file, &storage).await?;
+            for entry in entries.iter() {
+                if entry.deleted() || !metrics_evaluator.eval(&entry.data_file)? {
+                    continue;
+                }
+
+                // Files whose partition rules out all matching rows are skipped.
+                let residual =
+                    residual_evaluator.residual_for(&entry.data_file.partition)?;
+                if residual == Expression::AlwaysFalse {
+                    continue;
+                }
+
+                // Manifests added by an appended snapshot may also carry over files
+                // of earlier snapshots.
+                let appended = entry.added()
+                    && entry.snapshot_id() == Some(manifest_file.added_snapshot_id);
+                if self.from_snapshot_id.is_some() && !appended {
+                    continue;
+                }
+
+                let deletes = delete_index.for_data_file(
+                    entry.sequence_number().unwrap_or(0),
+                    manifest_file.partition_spec_id,
+                    &entry.data_file
+                );
+
+                tasks.push(FileScanTask {
+                    data_file: entry.data_file.clone(),
+                    field_ids: field_ids.clone(),
+                    residual: residual,
+                    deletes: deletes,
+                });
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    /// Reads the entries of the manifest file, through the manifest cache if set.
+    async fn read_entries(
+        &self,
+        manifest_file: &ManifestFile,
+        storage: &IcebergStorage
+    ) -> IcebergResult<Arc<Vec<ManifestEntry>>> {
+        match &self.manifest_cache {
+            Some(manifest_cache) => {
+                manifest_cache.read_entries(manifest_file, storage).await
+            },
+            None => {
+                let entries = ManifestReader::for_manifest_file(manifest_file)
+                    .read_entries(&manifest_file.manifest_path, storage)
+                    .await?;
+                Ok(Arc::new(entries))
+            },
+        }
+    }
+
+    /// Evaluates the filter against the manifest's partition summaries, creating an
+    /// evaluator for the manifest's partition spec if needed.
+    fn might_match(
+        &self,
+        filter: &BoundExpression,
+        manifest_file: &ManifestFile,
+        evaluators: &mut HashMap<i32, ManifestEvaluator>
+    ) -> IcebergResult<bool> {
+        if *filter == Expression::AlwaysTrue {
+            return Ok(true);
+        }
+
+        let spec_id = manifest_file.partition_spec_id;
+        if !evaluators.contains_key(&spec_id) {
+            let spec = self.partition_spec(spec_id)?;
+            evaluators.insert(spec_id, ManifestEvaluator::new(&spec, filter)?);
+        }
+
+        evaluators[&spec_id].eval(manifest_file)
+    }
+
+    /// Returns the table's partition spec with the given id.
+    fn partition_spec(&self, spec_id: i32) -> IcebergResult<PartitionSpec> {
+        self.table.current_metadata()?
+            .partition_spec(spec_id)
+            .ok_or_else(|| IcebergError::NotFound {
+                kind: NotFoundKind::PartitionSpec,
+                identifier: spec_id.to_string(),
+            })
+    }
+
+    /// Computes how the given data file is read into the projected schema of the
+    /// scan, by reading the file's footer, or the header of Avro files.
+    ///
+    /// The returned [`FileProjection`] holds the file's own Arrow schema of the
+    /// columns to read, and which projected columns are missing from the file.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ScanError`] is returned if one of the selected columns does
+    /// not exist in the snapshot's schema.
+    /// [`IcebergError::ParquetError`] is returned if the file isn't a valid Parquet
+    /// file, and [`IcebergError::Unsupported`] if it is an ORC file.
+    pub async fn file_projection(
+        &self,
+        data_file: &DataFile
+    ) -> IcebergResult<FileProjection> {
+        let schema = self.projected_schema(self.schema(self.snapshot()?)?)?;
+        let name_mapping = self.name_mapping()?;
+
+        check_file_format(data_file)?;
+
+        let storage = self.table.storage();
+        let path = storage.create_path_from_url(&data_file.file_path)?;
+        let bytes = storage.get(&path).await?;
+
+        match data_file.file_format {
+            DataFileFormat::Avro => {
+                FileProjection::for_file_schema(&schema, &avro_file_schema(&bytes)?)
+            },
+            _ => {
+                let builder = ParquetRecordBatchReaderBuilder::try_new(bytes)?;
+                let name_mapping = name_mapping.as_ref();
+                FileProjection::try_new(&schema, builder.metadata(), name_mapping)
+            }
+        }
+    }
+
+    /// Plans the scan and opens every planned data file for reading, returning a
+    /// reader of the scanned rows as Arrow record batches.
+    ///
+    /// Both Parquet and Avro data files are read. Columns are matched to the table's
+    /// schema by their field ids, so renamed columns are read correctly. Columns
+    /// missing from older data files, e.g. because they were added to the schema
+    /// later, are filled with their initial default value, or with nulls if they
+    /// have none.
+    ///
+    /// Parquet columns without field ids, e.g. in files written by tools unaware of
+    /// Iceberg, are matched by their names using the table's default
+    /// [`NameMapping`], set in the `schema.name-mapping.default` table property.
+    ///
+    /// Rows deleted by the position and equality delete files of each data file are
+    /// skipped.
+    ///
+    /// The contents of all data and delete files are fetched into memory before the
+    /// reader is returned, and are held until the reader has read past them, so
+    /// memory use grows with the size of the scan. Large scans should use
+    /// [`to_arrow_stream()`](Self::to_arrow_stream) instead, which fetches the
+    /// files only as they're read.
+    ///
+    /// Like in [`plan_files()`](Self::plan_files), the filter is only used to skip
+    /// whole files, and with [`with_bloom_filters()`](Self::with_bloom_filters),
+    /// whole row groups. Rows of the files read are not filtered. Row groups are never
+    /// skipped in files with position deletes, which refer to rows by position, nor
+    /// when reading row ids, which are computed from the positions of the rows.
+    ///
+    /// If enabled with [`with_partition_columns()`](Self::with_partition_columns),
+    /// the partition values of each data file are appended as columns after the
+    /// selected ones. Partition fields missing from a file's partition tuple, e.g.
+    /// because the file was written with an older partition spec, are null.
+    /// If enabled with [`with_row_ids()`](Self::with_row_ids), the [`ROW_ID_COLUMN`]
+    /// is appended last.
+    ///
+    /// With [`with_limit()`](Self::with_limit), the files are read one at a time,
+    /// and no further files are opened once enough rows were read.
+    ///
+    /// # Errors
+    ///
+    /// Fails in the same cases as [`plan_files()`](Self::plan_files), or if a data
+    /// or delete file can't be fetched or isn't a valid Parquet file.
+    /// [`IcebergError::ScanError`] is returned if a delete file lacks the columns it
+    /// deletes rows by, or if a data file lacks a required column that has no
+    /// initial default.
+    /// [`IcebergError::SchemaError`] is returned if the table's name mapping is
+    /// invalid.
+    /// [`IcebergError::Unsupported`] is returned if a data file is an ORC file.
+    pub async fn to_arrow_reader(&self) -> IcebergResult<impl RecordBatchReader> {
+        let context = self.read_context()?;
+
+        let mut files = Vec::new();
+        let mut batches = Vec::new();
+        let mut remaining = self.limit;
+        for task in self.plan_files().await? {
+            match remaining {
+                None => files.push(context.open(task).await?),
+                Some(0) => break,
+                Some(ref mut remaining) => {
+                    for batch in context.read(task).await? {
+                        if *remaining == 0 {
+                            break;
+                        }
+                        let rows = batch.num_rows().min(*remaining);
+                        *remaining -= rows;
+                        batches.push(batch.slice(0, rows));
+                    }
+                },
+            }
+        }
+
+        Ok(ArrowScanReader {
+            schema: context.arrow_schema,
+            batches: batches.into_iter(),
+            files: files.into_iter(),
+            current: None,
+        })
+    }
+
+    /// Reads the scanned rows like [`to_arrow_reader()`](Self::to_arrow_reader), but
+    /// returns a stream of batches that reads up to `concurrency` files at once.
+    ///
+    /// Each file is fetched and decoded, with its deletes applied, in a separate
+    /// task. Files are read only as the stream is polled, with the batches of at
+    /// most `concurrency` files buffered, and are returned in the order of
+    /// [`plan_files()`](Self::plan_files). A `concurrency` of 0 is treated as 1.
+    /// With [`with_limit()`](Self::with_limit), the stream ends once enough rows
+    /// were read.
+    ///
+    /// # Errors
+    ///
+    /// Fails in the same cases as [`plan_files()`](Self::plan_files). Errors reading
+    /// a file are returned by the stream, in place of the file's batches.
+    pub async fn to_arrow_stream(
+        &self,
+        concurrency: usize
+    ) -> IcebergResult<impl Stream<Item = IcebergResult<RecordBatch>> + Send> {
+        let context = Arc::new(self.read_context()?);
+        let tasks = self.plan_files().await?;
+
+        let stream = futures::stream::iter(tasks)
+            .map(move |task| {
+                let context = context.clone();
+                async move {
+                    tokio::spawn(async move { context.read(task).await })
+                        .await
+                        .map_err(|e| IcebergError::ScanError {
+                            message: format!("failed to read data file: {e}")
+                        })?
+                }
+            })
+            .buffered(concurrency.max(1))
+            .map_ok(|batches| {
+                futures::stream::iter(batches.into_iter().map(Ok::<_, IcebergError>))
+            })
+            .try_flatten()
+            .scan(self.limit.unwrap_or(usize::MAX), |remaining, batch| {
+                if *remaining == 0 {
+                    return futures::future::ready(None);
+                }
+                let batch = match batch {
+                    Ok(batch) => {
+                        let rows = batch.num_rows().min(*remaining);
+                        *remaining -= rows;
+                        Ok(batch.slice(0, rows))
+                    },
+                    Err(e) => Err(e),
+                };
+                futures::future::ready(Some(batch))
+            });
+
+        Ok(stream)
+    }
+
+    /// Returns the state shared by the readers of all data files of the scan.
+    fn read_context(&self) -> IcebergResult<ReadContext> {
+        let snapshot = self.snapshot()?;
+        let table_schema = self.schema(snapshot)?;
+        let schema = self.projected_schema(table_schema)?;
+        let partition_fields = self.partition_fields()?;
+        let metadata_fields = self.metadata_fields();
+        let arrow_schema = Arc::new(iceberg_to_arrow_schema(&Schema::new(
+            schema.id(),
+            schema.fields().iter()
+                .chain(&partition_fields)
+                .chain(&metadata_fields)
+                .cloned()
+                .collect()
+        ))?);
+        let row_id_field = match self.row_ids {
+            true => arrow_schema.fields().last().cloned(),
+            false => None,
+        };
+        let bloom_filter_evaluator = match self.bloom_filters {
+            true => Some(BloomFilterEvaluator::new(&self.filter.bind(table_schema)?)),
+            false => None,
+        };
+
+        Ok(ReadContext {
+            storage: self.table.storage(),
+            table_schema: table_schema.clone(),
+            projected: schema.fields().len(),
+            arrow_schema: arrow_schema,
+            partition_fields: partition_fields,
+            row_id_field: row_id_field,
+            name_mapping: self.name_mapping()?,
+            bloom_filter_evaluator: bloom_filter_evaluator,
+        })
+    }
+}
+
+/// State shared by the readers of all data files of a table scan.
+struct ReadContext {
+    storage: Arc<IcebergStorage>,
+    /// Schema of the scanned snapshot.
+    table_schema: Schema,
+    /// Number of projected fields.
+    projected: usize,
+    /// Arrow schema of the scanned rows, including partition and metadata columns.
+    arrow_schema: ArrowSchemaRef,
+    partition_fields: Vec<Field>,
+    row_id_field: Option<ArrowFieldRef>,
+    name_mapping: Option<NameMapping>,
+    bloom_filter_evaluator: Option<BloomFilterEvaluator>,
+}
+
+impl ReadContext {
+    /// Fetches the data file of the task along with its delete files, and opens it
+    /// for reading.
+    async fn open(&self, task: FileScanTask) -> IcebergResult<DataFileReader> {
+        check_file_format(&task.data_file)?;
+        let table_schema = &self.table_schema;
+
+        // Equality deletes may compare columns that weren't selected, which are
+        // read as well and dropped after applying the deletes.
+        let mut field_ids = task.field_ids.clone();
+        for delete_file in &task.deletes {
+            for field_id in delete_file.equality_ids.iter().flatten() {
+                if !field_ids.contains(field_id) {
+                    field_ids.push(*field_id);
+                }
+            }
+        }
+        let read_schema = Schema::new(
+            table_schema.id(),
+            field_ids.iter()
+                .map(|field_id| {
+                    table_schema.fields().iter()
+                        .find(|field| field.id == *field_id)
+                        .cloned()
+                        .ok_or_else(|| IcebergError::ScanError {
+                            message: format!("field {field_id} not found in schema")
+                        })
+                })
+                .collect::<IcebergResult<Vec<_>>>()?
+        );
+
+        let deletes = match task.deletes.is_empty() {
+            true => None,
+            false => Some(DeleteFilter::load(
+                &self.storage,
+                &task.data_file.file_path,
+                &task.deletes,
+                table_schema,
+                &field_ids
+            ).await?),
+        };
+
+        let path = self.storage.create_path_from_url(&task.data_file.file_path)?;
+        let bytes = self.storage.get(&path).await?;
+
+        let has_position_deletes = task.deletes.iter().any(|delete_file| {
+            delete_file.content == DataFileContent::PositionDelete
+        });
+        let row_groups = match &self.bloom_filter_evaluator {
+            Some(evaluator) if !has_position_deletes && self.row_id_field.is_none()
+                && task.data_file.file_format == DataFileFormat::Parquet => {
+                Some(matching_row_groups(evaluator, bytes.clone())?)
+            },
+            _ => None,
+        };
+
+        let (projection, reader) = open_data_file(
+            &task.data_file,
+            bytes,
+            &read_schema,
+            self.name_mapping.as_ref(),
+            row_groups
+        )?;
+
+        let partition_values = self.partition_fields.iter()
+            .zip(&self.arrow_schema.fields()[self.projected..])
+            .map(|(field, arrow_field)| {
+                partition_value_array(&task.data_file, &field.name, arrow_field)
+            })
+            .collect::<IcebergResult<Vec<_>>>()?;
+
+        let read_arrow_schema = Arc::new(iceberg_to_arrow_schema(&read_schema)?);
+        let defaults = initial_defaults(&read_schema, &read_arrow_schema, &projection)?;
+
+        Ok(DataFileReader {
+            reader: reader,
+            schema: read_arrow_schema,
+            columns: projection.columns().to_vec(),
+            defaults: defaults,
+            projected: task.field_ids.len(),
+            partition_values: partition_values,
+            row_id_field: self.row_id_field.clone(),
+            first_row_id: task.data_file.first_row_id,
+            deletes: deletes,
+            position: 0,
+        })
+    }
+
+    /// Fetches and decodes all batches of the task's data file, with its deletes
+    /// applied.
+    async fn read(&self, task: FileScanTask) -> IcebergResult<Vec<RecordBatch>> {
+        let mut file = self.open(task).await?;
+
+        let mut batches = Vec::new();
+        while let Some(batch) = file.reader.next() {
+            batches.push(file.convert(batch?)?);
+        }
+
+        Ok(batches)
+    }
+}
+
+/// Returns the table's default name mapping, set in the `schema.name-mapping.default`
+/// table property.
+pub(crate) fn default_name_mapping(
+    metadata: &IcebergTableMetadata
+) -> IcebergResult<Option<NameMapping>> {
+    metadata.properties
+        .as_ref()
+        .and_then(|properties| properties.get(NAME_MAPPING_PROPERTY))
+        .map(|json| NameMapping::decode(json.as_bytes()))
+        .transpose()
+}
+
+/// Fails for ORC data files, which can't be read yet.
+fn check_file_format(data_file: &DataFile) -> IcebergResult<()> {
+    match data_file.file_format {
+        DataFileFormat::Parquet | DataFileFormat::Avro => Ok(()),
+        DataFileFormat::ORC => Err(IcebergError::Unsupported(format!(
+            "reading {:?} data file {}",
+            data_file.file_format,
+            data_file.file_path
+        ))),
+    }
+}
+
+/// Reader of the batches of a single data file.
+type BatchReader = Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>> + Send>;
+
+/// Opens the data file with the given content for reading the fields of `schema`,
+/// returning the projection of the schema onto the file and a reader of the file's
+/// batches, in the projection's physical schema.
+///
+/// Only the given row groups are read from Parquet files, if given. Avro files are
+/// decoded up front.
+fn open_data_file(
+    data_file: &DataFile,
+    bytes: Bytes,
+    schema: &Schema,
+    name_mapping: Option<&NameMapping>,
+    row_groups: Option<Vec<usize>>
+) -> IcebergResult<(FileProjection, BatchReader)> {
+    if data_file.file_format == DataFileFormat::Avro {
+        let projection = FileProjection::for_file_schema(
+            schema,
+            &avro_file_schema(&bytes)?
+        )?;
+        let batches = read_avro_file(&bytes, &projection)?;
+        return Ok((projection, Box::new(batches.into_iter().map(Ok))));
+    }
+
+    let mut builder = ParquetRecordBatchReaderBuilder::try_new(bytes)?;
+    if let Some(row_groups) = row_groups {
+        builder = builder.with_row_groups(row_groups);
+    }
+    let projection = FileProjection::try_new(schema, builder.metadata(), name_mapping)?;
+
+    let mask = projection.projection_mask(builder.parquet_schema());
+    let reader = builder.with_projection(mask).build()?;
+    Ok((projection, Box::new(reader)))
+}
+
+/// Reads all rows of a data file into the given schema, without applying any delete
+/// files.
+///
+/// Columns are matched like in [`TableScan::to_arrow_reader()`], and fields missing
+/// from the file are filled with their initial defaults, or with nulls.
+pub(crate) async fn read_data_file(
+    table: &IcebergTable,
+    schema: &Schema,
+    name_mapping: Option<&NameMapping>,
+    data_file: &DataFile
+) -> IcebergResult<Vec<RecordBatch>> {
+    check_file_format(data_file)?;
+
+    let storage = table.storage();
+    let path = storage.create_path_from_url(&data_file.file_path)?;
+    let bytes = storage.get(&path).await?;
+
+    let (projection, reader) = open_data_file(
+        data_file,
+        bytes,
+        schema,
+        name_mapping,
+        None
+    )?;
+    let arrow_schema = iceberg_to_arrow_schema(schema)?;
+    let defaults = initial_defaults(schema, &arrow_schema, &projection)?;
+
+    let batches = reader
+        .map(|batch| {
+            convert_batch(&arrow_schema, projection.columns(), &defaults, batch?)
+        })
+        .collect::<Result<Vec<_>, ArrowError>>()?;
+
+    Ok(batches)
+}
+
+/// Returns for every field of the schema missing from the file a single-row array of
+/// the field's initial default, if it has one.
+///
+/// Fails with [`IcebergError::ScanError`] if a required field is missing from the
+/// file and has no initial default, since it can't be read as nulls.
+fn initial_defaults(
+    schema: &Schema,
+    arrow_schema: &ArrowSchema,
+    projection: &FileProjection
+) -> IcebergResult<Vec<Option<ArrayRef>>> {
+    schema.fields().iter()
+        .zip(arrow_schema.fields())
+        .zip(projection.columns())
+        .map(|((field, arrow_field), column)| {
+            match (column, field.initial_default()) {
+                (None, Some(value)) => {
+                    literal_array(value, arrow_field.data_type(), 1).map(Some)
+                },
+                (None, None) if field.required() => Err(IcebergError::ScanError {
+                    message: format!(
+                        "required field '{}' (id {}) is missing from the data file and \
+                        has no initial default",
+                        field.name,
+                        field.id
+                    )
+                }),
+                _ => Ok(None),
+            }
+        })
+        .collect()
+}
+
+/// Reads the projected columns of a single data file, skipping deleted rows.
+struct DataFileReader {
+    reader: BatchReader,
+    /// Schema of the read fields: the projected fields, followed by the fields only
+    /// needed for applying equality deletes.
+    schema: ArrowSchemaRef,
+    /// For every read field, the index of its column in the batches of `reader`, or
+    /// `None` if the file doesn't contain the field.
+    columns: Vec<Option<usize>>,
+    /// For every read field missing from the file, a single-row array of the field's
+    /// initial default, if it has one.
+    defaults: Vec<Option<ArrayRef>>,
+    /// Number of projected fields.
+    projected: usize,
+    /// Single-row arrays of the file's partition values, appended to every batch as
+    /// columns after the projected fields.
+    partition_values: Vec<(ArrowFieldRef, ArrayRef)>,
+    /// Field of the row id column appended to every batch, if row ids are read.
+    row_id_field: Option<ArrowFieldRef>,
+    /// Row id of the first row in the file, if the file has row lineage.
+    first_row_id: Option<i64>,
+    deletes: Option<DeleteFilter>,
+    /// Position in the file of the next row to be read.
+    position: i64,
+}
+
+impl DataFileReader {
+    /// Converts a batch read from the file to the projected schema of the scan,
+    /// dropping deleted rows.
+    fn convert(&mut self, batch: RecordBatch) -> Result<RecordBatch, ArrowError> {
+        let batch = convert_batch(&self.schema, &self.columns, &self.defaults, batch)?;
+
+        let offset = self.position;
+        self.position += batch.num_rows() as i64;
+
+        let row_ids: Option<ArrayRef> = self.row_id_field.as_ref().map(|_| {
+            let first_row_id = self.first_row_id;
+            Arc::new(Int64Array::from_iter(
+                (offset..self.position).map(|position| {
+                    first_row_id.map(|first_row_id| first_row_id + position)
+                })
+            )) as ArrayRef
+        });
+
+        let (batch, row_ids) = match &mut self.deletes {
+            Some(deletes) => {
+                let keep = deletes.keep(&batch, offset)?;
+                (
+                    filter_record_batch(&batch, &keep)?,
+                    row_ids.map(|row_ids| filter(row_ids.as_ref(), &keep)).transpose()?
+                )
+            },
+            None => (batch, row_ids),
+        };
+
+        let batch = batch.project(&(0..self.projected).collect::<Vec<usize>>())?;
+        if self.partition_values.is_empty() && row_ids.is_none() {
+            return Ok(batch);
+        }
+
+        let mut fields = batch.schema().fields().to_vec();
+        let mut columns = batch.columns().to_vec();
+        let indices = UInt32Array::from(vec![0; batch.num_rows()]);
+        for (field, value) in &self.partition_values {
+            fields.push(field.clone());
+            columns.push(take(value.as_ref(), &indices, None)?);
+        }
+        if let (Some(field), Some(row_ids)) = (&self.row_id_field, row_ids) {
+            fields.push(field.clone());
+            columns.push(row_ids);
+        }
+
+        let metadata = batch.schema().metadata().clone();
+        RecordBatch::try_new(
+            Arc::new(ArrowSchema::new_with_metadata(fields, metadata)),
+            columns
+        )
+    }
+}
+
+/// Returns the indices of the row groups of a Parquet file whose bloom filters don't
+/// rule out rows matching the evaluator's filter.
+///
+/// Columns are matched to the filter's fields by their Parquet field ids.
+fn matching_row_groups(
+    evaluator: &BloomFilterEvaluator,
+    bytes: Bytes
+) -> IcebergResult<Vec<usize>> {
+    let options = ReadOptionsBuilder::new()
+        .with_reader_properties(
+            ReaderProperties::builder().set_read_bloom_filter(true).build()
+        )
+        .build();
+    let reader = SerializedFileReader::new_with_options(bytes, options)?;
+
+    let schema = reader.metadata().file_metadata().schema_descr_ptr();
+    let columns: HashMap<i32, (usize, PhysicalType)> = schema.columns().iter()
+        .enumerate()
+        .filter(|(_, column)| column.self_type().get_basic_info().has_id())
+        .map(|(index, column)| {
+            let field_id = column.self_type().get_basic_info().id();
+            (field_id, (index, column.physical_type()))
+        })
+        .collect();
+
+    let mut row_groups = Vec::new();
+    for index in 0..reader.num_row_groups() {
+        let row_group = reader.get_row_group(index)?;
+        let might_match = evaluator.eval(|field_id| {
+            let (column, physical_type) = columns.get(&field_id)?;
+            row_group.get_column_bloom_filter(*column)
+                .map(|bloom_filter| (bloom_filter, *physical_type))
+        });
+
+        if might_match {
+            row_groups.push(index);
+        }
+    }
+
+    Ok(row_groups)
+}
+
+/// Returns a single-row array of the data file's value for the named partition field,
+/// which is null if the file has no value for the field.
+fn partition_value_array(
+    data_file: &DataFile,
+    name: &str,
+    field: &ArrowFieldRef
+) -> IcebergResult<(ArrowFieldRef, ArrayRef)> {
+    let value = data_file.partition.values().iter()
+        .find(|(partition_name, _)| partition_name == name)
+        .and_then(|(_, value)| value.as_ref());
+
+    let array = match value {
+        Some(value) => literal_array(value, field.data_type(), 1).map_err(|e| {
+            IcebergError::ScanError {
+                message: format!(
+                    "invalid value for partition {name} of {}: {e}",
+                    data_file.file_path
+                )
+            }
+        })?,
+        None => new_null_array(field.data_type(), 1),
+    };
+
+    Ok((field.clone(), array))
+}
+
+/// Reads the data files of a table scan one after the other, converting their
+/// batches to the projected schema of the scan.
+///
+/// The batches already read by a scan with a limit are returned first.
+struct ArrowScanReader {
+    schema: ArrowSchemaRef,
+    batches: std::vec::IntoIter<RecordBatch>,
+    files: std::vec::IntoIter<DataFileReader>,
+    current: Option<DataFileReader>,
+}
+
+/// Converts a batch read from a data file to the projected schema of the scan.
+///
+/// Fields missing from the file are filled with their single-row `defaults` if
+/// given, and with nulls otherwise.
+fn convert_batch(
+    schema: &ArrowSchema,
+    columns: &[Option<usize>],
+    defaults: &[Option<ArrayRef>],
+    batch: RecordBatch
+) -> Result<RecordBatch, ArrowError> {
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut arrays = Vec::with_capacity(columns.len());
+
+    for ((field, column), default) in schema.fields().iter().zip(columns).zip(defaults) {
+        let array: ArrayRef = match column {
+            Some(column) => {
+                let array = batch.column(*column);
+                if array.data_type() == field.data_type() || is_nested(field.data_type()) {
+                    array.clone()
+                } else {
+                    // A promoted type, e.g. int to long.
+                    arrow_cast::cast(array, field.data_type())?
+                }
+            },
+            None => match default {
+                Some(default) => {
+                    let indices = UInt32Array::from(vec![0; batch.num_rows()]);
+                    take(default.as_ref(), &indices, None)?
+                },
+                None => new_null_array(field.data_type(), batch.num_rows()),
+            },
+        };
+
+        // Nested types may differ by the metadata of their children.
+        fields.push(
+            ArrowField::new(field.name(), array.data_type().clone(), field.is_nullable())
+                .with_metadata(field.metadata().clone())
+        );
+        arrays.push(array);
+    }
+
+    RecordBatch::try_new(
+        Arc::new(ArrowSchema::new_with_metadata(fields, schema.metadata().clone())),
+        arrays
+    )
+}
+
+fn is_nested(data_type: &ArrowDataType) -> bool {
+    matches!(
+        data_type,
+        ArrowDataType::Struct(_)
+        | ArrowDataType::List(_)
+        | ArrowDataType::LargeList(_)
+        | ArrowDataType::FixedSizeList(..)
+        | ArrowDataType::Map(..)
+    )
+}
+
+impl Iterator for ArrowScanReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(batch) = self.batches.next() {
+            return Some(Ok(batch));
+        }
+
+        loop {
+            if self.current.is_none() {
+                self.current = Some(self.files.next()?);
+            }
+
+            let current = self.current.as_mut().unwrap();
+            match current.reader.next() {
+                Some(Ok(batch)) => return Some(current.convert(batch)),
+                Some(Err(e)) => return Some(Err(e)),
+                None => self.current = None,
+            }
+        }
+    }
+}
+
+impl RecordBatchReader for ArrowScanReader {
+    fn schema(&self) -> ArrowSchemaRef {
+        self.schema.clone()
+    }
+}
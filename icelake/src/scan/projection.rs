@@ -0,0 +1,170 @@
+//! Projection of a read schema onto the columns of a single data file.
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef as ArrowSchemaRef;
+use parquet::arrow::{ProjectionMask, parquet_to_arrow_schema_by_columns};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::schema::types::SchemaDescriptor;
+
+use crate::IcebergResult;
+use crate::schema::Schema;
+use crate::schema::arrow::iceberg_to_arrow_schema;
+use crate::schema::mapping::NameMapping;
+
+/// Describes how a single data file is read into a schema.
+///
+/// Data files keep the schema they were written with, which may differ from the
+/// schema being read after the table's schema evolved: columns may have been
+/// renamed, promoted to wider types, or added after the file was written. Columns
+/// are matched by field id, using the table's name mapping for files written
+/// without field ids.
+#[derive(Debug, Clone)]
+pub struct FileProjection {
+    /// The file's own schema of the columns read from it, in the file's order.
+    physical_schema: ArrowSchemaRef,
+    /// Positions of the read columns among the file's top-level columns.
+    root_indices: Vec<usize>,
+    /// Ids of the fields of the read schema.
+    field_ids: Vec<i32>,
+    /// For every field of the read schema, the index of its column in the physical
+    /// schema, or `None` if the file doesn't contain the field.
+    columns: Vec<Option<usize>>,
+}
+
+impl FileProjection {
+    /// Projects `schema` onto the columns of the Parquet file with the given
+    /// metadata.
+    ///
+    /// # Errors
+    ///
+    /// [`crate::IcebergError::ParquetError`] is returned if the file's schema can't
+    /// be converted to Arrow.
+    pub fn try_new(
+        schema: &Schema,
+        metadata: &ParquetMetaData,
+        name_mapping: Option<&NameMapping>,
+    ) -> IcebergResult<Self> {
+        let file_metadata = metadata.file_metadata();
+        let schema_descr = file_metadata.schema_descr();
+
+        // Ids of the top-level columns in the file, by their position.
+        let file_field_ids: Vec<Option<i32>> = schema_descr
+            .root_schema()
+            .get_fields()
+            .iter()
+            .map(|field| {
+                let info = field.get_basic_info();
+                if info.has_id() {
+                    Some(info.id())
+                } else {
+                    name_mapping.and_then(|mapping| mapping.field_id(info.name()))
+                }
+            })
+            .collect();
+
+        let (field_ids, root_indices, columns) = project(schema, &file_field_ids);
+
+        let physical_schema = parquet_to_arrow_schema_by_columns(
+            schema_descr,
+            ProjectionMask::roots(schema_descr, root_indices.iter().copied()),
+            file_metadata.key_value_metadata(),
+        )?;
+
+        Ok(Self {
+            physical_schema: Arc::new(physical_schema),
+            root_indices: root_indices,
+            field_ids: field_ids,
+            columns: columns,
+        })
+    }
+
+    /// Projects `schema` onto the top-level fields of a data file whose own schema
+    /// was converted to an Iceberg schema, such as an Avro data file.
+    ///
+    /// # Errors
+    ///
+    /// [`crate::IcebergError::SchemaError`] is returned if the file's schema can't
+    /// be converted to Arrow.
+    pub fn for_file_schema(schema: &Schema, file_schema: &Schema) -> IcebergResult<Self> {
+        let file_field_ids: Vec<Option<i32>> = file_schema.fields().iter()
+            .map(|field| Some(field.id))
+            .collect();
+
+        let (field_ids, root_indices, columns) = project(schema, &file_field_ids);
+
+        let physical_schema = iceberg_to_arrow_schema(&Schema::new(
+            file_schema.id(),
+            root_indices.iter()
+                .map(|index| file_schema.fields()[*index].clone())
+                .collect()
+        ))?;
+
+        Ok(Self {
+            physical_schema: Arc::new(physical_schema),
+            root_indices: root_indices,
+            field_ids: field_ids,
+            columns: columns,
+        })
+    }
+
+    /// Returns the Arrow schema of the columns read from the file, with the names
+    /// and types they were written with.
+    pub fn physical_schema(&self) -> ArrowSchemaRef {
+        self.physical_schema.clone()
+    }
+
+    /// Returns, for every field of the read schema, the index of its column in
+    /// [`physical_schema()`](Self::physical_schema), or `None` if the column is
+    /// missing from the file and must be filled with its initial default or nulls.
+    pub fn columns(&self) -> &[Option<usize>] {
+        &self.columns
+    }
+
+    /// Returns the ids of the fields missing from the file.
+    pub fn missing_field_ids(&self) -> Vec<i32> {
+        self.field_ids.iter()
+            .zip(&self.columns)
+            .filter(|(_, column)| column.is_none())
+            .map(|(field_id, _)| *field_id)
+            .collect()
+    }
+
+    /// Returns the positions of the read columns among the file's top-level columns,
+    /// in the file's order.
+    pub(crate) fn root_indices(&self) -> &[usize] {
+        &self.root_indices
+    }
+
+    /// Returns the mask selecting the read columns from the file.
+    pub fn projection_mask(&self, schema_descr: &SchemaDescriptor) -> ProjectionMask {
+        ProjectionMask::roots(schema_descr, self.root_indices.iter().copied())
+    }
+}
+
+/// Matches the fields of `schema` to the top-level columns of a file with the given
+/// field ids, returning the ids of the schema's fields, the positions of the
+/// matched columns in the file, and for every field the index of its column among
+/// the matched ones.
+fn project(
+    schema: &Schema,
+    file_field_ids: &[Option<i32>]
+) -> (Vec<i32>, Vec<usize>, Vec<Option<usize>>) {
+    let field_ids: Vec<i32> = schema.fields().iter().map(|field| field.id).collect();
+
+    let mut root_indices: Vec<usize> = field_ids.iter()
+        .filter_map(|field_id| {
+            file_field_ids.iter().position(|id| *id == Some(*field_id))
+        })
+        .collect();
+    root_indices.sort_unstable();
+
+    // Batches read from the file contain the read columns in the file's order.
+    let columns = field_ids.iter()
+        .map(|field_id| {
+            root_indices.iter()
+                .position(|index| file_field_ids[*index] == Some(*field_id))
+        })
+        .collect();
+
+    (field_ids, root_indices, columns)
+}
@@ -11,88 +11,176 @@ use arrow_schema::{
 use crate::{IcebergResult, IcebergError};
 use crate::schema::{
     Schema, Field, SchemaType,
-    PrimitiveType, ListType, StructType,
+    PrimitiveType, ListType, StructType, MapType,
 };
 
 const FIELD_ID_KEY: &str = "ICEBERG:field_id";
+const PARQUET_FIELD_ID_KEY: &str = "PARQUET:field_id";
+
+/// Returns the Iceberg field id stored in the metadata of an Arrow field, either by
+/// the conversion from an Iceberg schema or by the Parquet reader, or 0 if the field
+/// has no id.
+fn arrow_field_id(arrow_field: &ArrowField) -> Result<i32, ArrowError> {
+    let metadata = arrow_field.metadata();
+    match metadata.get(FIELD_ID_KEY).or(metadata.get(PARQUET_FIELD_ID_KEY)) {
+        Some(field_id) => field_id.parse().map_err(|_| {
+            ArrowError::SchemaError(format!(
+                "invalid field id {field_id} of field {}", arrow_field.name()
+            ))
+        }),
+        None => Ok(0),
+    }
+}
 
-impl TryFrom<&SchemaType> for ArrowDataType {
-    type Error = ArrowError;
+/// Returns the Iceberg field id of the element of an Arrow list, from its metadata,
+/// or from its name if it follows the `field_<id>` convention of some writers, or 0
+/// if the element has no id.
+fn arrow_list_element_id(element: &ArrowField) -> Result<i32, ArrowError> {
+    match arrow_field_id(element)? {
+        0 => Ok(element.name().strip_prefix("field_")
+            .and_then(|id| id.parse().ok())
+            .unwrap_or(0)),
+        field_id => Ok(field_id),
+    }
+}
 
-    fn try_from(t: &SchemaType) -> Result<Self, Self::Error> {
-        Ok(match t {
-            SchemaType::Primitive(primitive_type) => {
-                match primitive_type {
-                    PrimitiveType::Boolean => ArrowDataType::Boolean,
-                    PrimitiveType::Int => ArrowDataType::Int32,
-                    PrimitiveType::Long => ArrowDataType::Int64,
-                    PrimitiveType::Float => ArrowDataType::Float32,
-                    PrimitiveType::Double => ArrowDataType::Float64,
-                    PrimitiveType::Decimal{precision, scale} => {
-                        ArrowDataType::Decimal128(
-                            *precision,
-                            i8::try_from(*scale).map_err(|_| {
-                                ArrowError::SchemaError(format!(
-                                    "can't convert decimal with scale {scale}"
-                                ))
-                            })?
-                        )
-                    },
-                    PrimitiveType::Date => ArrowDataType::Date32,
-                    PrimitiveType::Time => {
-                        ArrowDataType::Time64(ArrowTimeUnit::Microsecond)
-                    },
-                    PrimitiveType::Timestamp => {
-                        ArrowDataType::Timestamp(ArrowTimeUnit::Microsecond, None)
-                    },
-                    PrimitiveType::Timestamptz => {
-                        ArrowDataType::Timestamp(
-                            ArrowTimeUnit::Microsecond,
-                            Some(Arc::from("UTC"))
-                        )
-                    },
-                    PrimitiveType::String => ArrowDataType::Utf8,
-                    PrimitiveType::Uuid => ArrowDataType::FixedSizeBinary(16),
-                    PrimitiveType::Fixed(size) => {
-                        ArrowDataType::FixedSizeBinary(
-                            i32::try_from(*size).map_err(|_| {
-                                ArrowError::SchemaError(format!(
-                                    "can't convert fixed size binary with size {size}"
-                                ))
-                            })?
-                        )
-                    },
-                    PrimitiveType::Binary => ArrowDataType::Binary,
-                }
-            },
-            SchemaType::Struct(struct_type) => {
-                let converted_fields: Result<Vec<ArrowField>, _> = struct_type.fields
-                    .iter()
-                    .map(|field| field.try_into())
-                    .collect();
+/// Options of the conversion between Iceberg and Arrow schemas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaConversionOptions {
+    /// Timezone of Arrow timestamps converted from `timestamptz` fields, `"UTC"` by
+    /// default. Some Arrow consumers expect an offset such as `"+00:00"` instead.
+    pub timestamptz_tz: String,
+    /// Whether converting Arrow timestamps with a unit other than microseconds
+    /// fails, instead of changing their precision to Iceberg's microseconds.
+    /// Disabled by default.
+    pub strict_timestamp_precision: bool,
+}
 
-                ArrowDataType::Struct(ArrowFields::from(converted_fields?))
-            },
-            SchemaType::List(list_type) => {
-                ArrowDataType::List(Arc::new(
-                    list_type.field().try_into()?
-                ))
-            },
-            SchemaType::Map(map_type) => {
-                let mut kv: Vec<ArrowField> = Vec::new();
-                kv.push(map_type.key().try_into()?);
-                kv.push(map_type.value().try_into()?);
+impl Default for SchemaConversionOptions {
+    fn default() -> Self {
+        Self {
+            timestamptz_tz: "UTC".to_string(),
+            strict_timestamp_precision: false,
+        }
+    }
+}
 
-                ArrowDataType::Map(
-                    Arc::new(ArrowField::new(
-                        "entries",
-                        ArrowDataType::Struct(ArrowFields::from(kv)),
-                        true
-                    )),
-                    false
-                )
+fn to_arrow_data_type(
+    t: &SchemaType,
+    options: &SchemaConversionOptions
+) -> Result<ArrowDataType, ArrowError> {
+    Ok(match t {
+        SchemaType::Primitive(primitive_type) => {
+            match primitive_type {
+                PrimitiveType::Boolean => ArrowDataType::Boolean,
+                PrimitiveType::Int => ArrowDataType::Int32,
+                PrimitiveType::Long => ArrowDataType::Int64,
+                PrimitiveType::Float => ArrowDataType::Float32,
+                PrimitiveType::Double => ArrowDataType::Float64,
+                PrimitiveType::Decimal{precision, scale} => {
+                    ArrowDataType::Decimal128(
+                        *precision,
+                        i8::try_from(*scale).map_err(|_| {
+                            ArrowError::SchemaError(format!(
+                                "can't convert decimal with scale {scale}"
+                            ))
+                        })?
+                    )
+                },
+                PrimitiveType::Date => ArrowDataType::Date32,
+                PrimitiveType::Time => {
+                    ArrowDataType::Time64(ArrowTimeUnit::Microsecond)
+                },
+                PrimitiveType::Timestamp => {
+                    ArrowDataType::Timestamp(ArrowTimeUnit::Microsecond, None)
+                },
+                PrimitiveType::Timestamptz => {
+                    ArrowDataType::Timestamp(
+                        ArrowTimeUnit::Microsecond,
+                        Some(Arc::from(options.timestamptz_tz.as_str()))
+                    )
+                },
+                PrimitiveType::String => ArrowDataType::Utf8,
+                PrimitiveType::Uuid => ArrowDataType::FixedSizeBinary(16),
+                PrimitiveType::Fixed(size) => {
+                    ArrowDataType::FixedSizeBinary(
+                        i32::try_from(*size).map_err(|_| {
+                            ArrowError::SchemaError(format!(
+                                "can't convert fixed size binary with size {size}"
+                            ))
+                        })?
+                    )
+                },
+                PrimitiveType::Binary => ArrowDataType::Binary,
             }
-        })
+        },
+        SchemaType::Struct(struct_type) => {
+            let converted_fields: Result<Vec<ArrowField>, _> = struct_type.fields
+                .iter()
+                .map(|field| to_arrow_field(field, options))
+                .collect();
+
+            ArrowDataType::Struct(ArrowFields::from(converted_fields?))
+        },
+        SchemaType::List(list_type) => {
+            ArrowDataType::List(Arc::new(
+                to_arrow_field(list_type.field(), options)?
+            ))
+        },
+        SchemaType::Map(map_type) => {
+            let mut kv: Vec<ArrowField> = Vec::new();
+            kv.push(to_arrow_field(map_type.key(), options)?);
+            kv.push(to_arrow_field(map_type.value(), options)?);
+
+            ArrowDataType::Map(
+                Arc::new(ArrowField::new(
+                    "entries",
+                    ArrowDataType::Struct(ArrowFields::from(kv)),
+                    true
+                )),
+                false
+            )
+        }
+    })
+}
+
+fn to_arrow_field(
+    field: &Field,
+    options: &SchemaConversionOptions
+) -> Result<ArrowField, ArrowError> {
+    let converted_type = to_arrow_data_type(&field.r#type, options)?;
+
+    let arrow_field = ArrowField::new(
+        field.name.clone(),
+        converted_type,
+        !field.required
+    ).with_metadata(HashMap::from_iter([
+        (
+            FIELD_ID_KEY.to_string(),
+            field.id.to_string()
+        )
+    ]));
+
+    Ok(arrow_field)
+}
+
+fn to_arrow_schema(
+    schema: &Schema,
+    options: &SchemaConversionOptions
+) -> Result<ArrowSchema, ArrowError> {
+    let converted_fields: Result<Vec<ArrowField>, _> = schema.fields()
+        .iter()
+        .map(|field| to_arrow_field(field, options))
+        .collect();
+
+    Ok(ArrowSchema::new(converted_fields?))
+}
+
+impl TryFrom<&SchemaType> for ArrowDataType {
+    type Error = ArrowError;
+
+    fn try_from(t: &SchemaType) -> Result<Self, Self::Error> {
+        to_arrow_data_type(t, &SchemaConversionOptions::default())
     }
 }
 
@@ -100,20 +188,7 @@ impl TryFrom<&Field> for ArrowField {
     type Error = ArrowError;
 
     fn try_from(field: &Field) -> Result<Self, Self::Error> {
-        let converted_type: ArrowDataType = (&field.r#type).try_into()?;
-
-        let arrow_field = ArrowField::new(
-            field.name.clone(),
-            converted_type,
-            !field.required
-        ).with_metadata(HashMap::from_iter([
-            (
-                FIELD_ID_KEY.to_string(),
-                field.id.to_string()
-            )
-        ]));
-
-        Ok(arrow_field)
+        to_arrow_field(field, &SchemaConversionOptions::default())
     }
 }
 
@@ -129,12 +204,7 @@ impl TryFrom<&Schema> for ArrowSchema {
     type Error = ArrowError;
 
     fn try_from(schema: &Schema) -> Result<Self, Self::Error> {
-        let converted_fields: Result<Vec<ArrowField>, _> = schema.fields()
-            .iter()
-            .map(|field| field.try_into())
-            .collect();
-
-        Ok(ArrowSchema::new(converted_fields?))
+        to_arrow_schema(schema, &SchemaConversionOptions::default())
     }
 }
 
@@ -146,108 +216,152 @@ impl TryFrom<Schema> for ArrowSchema {
     }
 }
 
+fn from_arrow_data_type(
+    arrow_type: &ArrowDataType,
+    options: &SchemaConversionOptions
+) -> Result<SchemaType, ArrowError> {
+    match arrow_type {
+        ArrowDataType::Boolean => Ok(SchemaType::Primitive(PrimitiveType::Boolean)),
+        ArrowDataType::Int8 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
+        ArrowDataType::Int16 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
+        ArrowDataType::Int32 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
+        ArrowDataType::Int64 => Ok(SchemaType::Primitive(PrimitiveType::Long)),
+        ArrowDataType::UInt8 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
+        ArrowDataType::UInt16 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
+        ArrowDataType::UInt32 => Ok(SchemaType::Primitive(PrimitiveType::Long)),
+        ArrowDataType::Float16 => Ok(SchemaType::Primitive(PrimitiveType::Float)),
+        ArrowDataType::Float32 => Ok(SchemaType::Primitive(PrimitiveType::Float)),
+        ArrowDataType::Float64 => Ok(SchemaType::Primitive(PrimitiveType::Double)),
+        // Iceberg timestamps have microsecond precision, other units are converted
+        // unless the precision is strict. Timestamps with any timezone are
+        // timestamptz.
+        ArrowDataType::Timestamp(unit, tz) => {
+            if options.strict_timestamp_precision && *unit != ArrowTimeUnit::Microsecond {
+                return Err(ArrowError::SchemaError(format!(
+                    "can't convert timestamp with unit {unit:?} to Iceberg's \
+                    microsecond precision"
+                )));
+            }
+
+            match tz {
+                None => Ok(SchemaType::Primitive(PrimitiveType::Timestamp)),
+                Some(_) => Ok(SchemaType::Primitive(PrimitiveType::Timestamptz)),
+            }
+        },
+        ArrowDataType::Date32 => Ok(SchemaType::Primitive(PrimitiveType::Date)),
+        ArrowDataType::Date64 => Ok(SchemaType::Primitive(PrimitiveType::Date)),
+        // Time of day. Iceberg supports only up to microsecond precision.
+        ArrowDataType::Time32(ArrowTimeUnit::Second)
+        | ArrowDataType::Time32(ArrowTimeUnit::Millisecond)
+        | ArrowDataType::Time32(ArrowTimeUnit::Microsecond) => {
+            Ok(SchemaType::Primitive(PrimitiveType::Time))
+        },
+        ArrowDataType::Time64(ArrowTimeUnit::Second)
+        | ArrowDataType::Time64(ArrowTimeUnit::Millisecond)
+        | ArrowDataType::Time64(ArrowTimeUnit::Microsecond) => {
+            Ok(SchemaType::Primitive(PrimitiveType::Time))
+        },
+        ArrowDataType::Binary => Ok(SchemaType::Primitive(PrimitiveType::Binary)),
+        ArrowDataType::FixedSizeBinary(size) => {
+            // Convert i32 to u64
+            let converted_size = <i32 as TryInto<u64>>::try_into(*size)
+                .map_err(|_| {
+                    ArrowError::SchemaError(format!(
+                        "can't convert Fixed-size binary with negative size {size}"
+                    ))
+                }
+            )?;
+
+            Ok(SchemaType::Primitive(PrimitiveType::Fixed(converted_size)))
+        },
+        ArrowDataType::Utf8 => Ok(SchemaType::Primitive(PrimitiveType::String)),
+        ArrowDataType::List(field)
+        | ArrowDataType::FixedSizeList(field, _)
+        | ArrowDataType::LargeList(field) => {
+            Ok(SchemaType::List(ListType::new(
+                arrow_list_element_id(field)?,
+                !field.is_nullable(),
+                from_arrow_data_type(field.data_type(), options)?
+            )))
+        },
+        ArrowDataType::Struct(fields) => {
+            Ok(SchemaType::Struct(StructType::new(
+                fields.iter().map(|field| from_arrow_field(field, options))
+                    .collect::<Result<Vec<Field>, _>>()?
+            )))
+        },
+        ArrowDataType::Decimal128(p, s) => {
+            let converted_scale = <i8 as TryInto<u8>>::try_into(*s)
+                .map_err(|_| {
+                    ArrowError::SchemaError(format!(
+                        "can't convert decimal with negative scale {s}"
+                    ))
+                }
+            )?;
+
+            Ok(SchemaType::Primitive(PrimitiveType::Decimal {
+                precision: *p,
+                scale: converted_scale,
+            }))
+        },
+
+        ArrowDataType::Map(entries, _) => {
+            let (key, value) = match entries.data_type() {
+                ArrowDataType::Struct(fields) if fields.len() == 2 => {
+                    (&fields[0], &fields[1])
+                },
+                dt => {
+                    return Err(ArrowError::SchemaError(format!(
+                        "expected Arrow map entries of key and value, found {dt}"
+                    )));
+                }
+            };
+
+            Ok(SchemaType::Map(MapType::new(
+                arrow_field_id(key)?,
+                from_arrow_data_type(key.data_type(), options)?,
+                arrow_field_id(value)?,
+                !value.is_nullable(),
+                from_arrow_data_type(value.data_type(), options)?
+            )))
+        },
+
+        // Dictionary encoding is physical, Iceberg only stores the logical type.
+        ArrowDataType::Dictionary(_, value_type) => {
+            from_arrow_data_type(value_type, options)
+        },
+
+        // ArrowDataType::Null
+        // ArrowDataType::Unit64
+        // ArrowDataType::Duration
+        // ArrowDataType::Interval
+        // ArrowDataType::LargeBinary
+        // ArrowDataType::Decimal256
+        dt => {
+            Err(ArrowError::SchemaError(format!(
+                "unsupported Arrow data type for Iceberg: {dt}"
+            )))
+        }
+    }
+}
+
+fn from_arrow_field(
+    arrow_field: &ArrowField,
+    options: &SchemaConversionOptions
+) -> Result<Field, ArrowError> {
+    Ok(Field::new(
+        arrow_field_id(arrow_field)?,
+        arrow_field.name(),
+        !arrow_field.is_nullable(),
+        from_arrow_data_type(arrow_field.data_type(), options)?,
+    ))
+}
+
 impl TryFrom<&ArrowDataType> for SchemaType {
     type Error = ArrowError;
 
     fn try_from(arrow_type: &ArrowDataType) -> Result<Self, Self::Error> {
-        match arrow_type {
-            ArrowDataType::Boolean => Ok(SchemaType::Primitive(PrimitiveType::Boolean)),
-            ArrowDataType::Int8 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
-            ArrowDataType::Int16 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
-            ArrowDataType::Int32 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
-            ArrowDataType::Int64 => Ok(SchemaType::Primitive(PrimitiveType::Long)),
-            ArrowDataType::UInt8 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
-            ArrowDataType::UInt16 => Ok(SchemaType::Primitive(PrimitiveType::Int)),
-            ArrowDataType::UInt32 => Ok(SchemaType::Primitive(PrimitiveType::Long)),
-            ArrowDataType::Float16 => Ok(SchemaType::Primitive(PrimitiveType::Float)),
-            ArrowDataType::Float32 => Ok(SchemaType::Primitive(PrimitiveType::Float)),
-            ArrowDataType::Float64 => Ok(SchemaType::Primitive(PrimitiveType::Double)),
-            // Timestamps without timezone.
-            // Iceberg supports only up to microsecond precision.
-            ArrowDataType::Timestamp(ArrowTimeUnit::Second, None)
-            | ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, None)
-            | ArrowDataType::Timestamp(ArrowTimeUnit::Microsecond, None) => {
-                Ok(SchemaType::Primitive(PrimitiveType::Timestamp))
-            },
-            // Timestamps with timezone.
-            // Iceberg supports only up to microsecond precision.
-            ArrowDataType::Timestamp(ArrowTimeUnit::Second, Some(_tz))
-            | ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, Some(_tz))
-            | ArrowDataType::Timestamp(ArrowTimeUnit::Microsecond, Some(_tz)) => {
-                Ok(SchemaType::Primitive(PrimitiveType::Timestamptz))
-            },
-            ArrowDataType::Date32 => Ok(SchemaType::Primitive(PrimitiveType::Date)),
-            ArrowDataType::Date64 => Ok(SchemaType::Primitive(PrimitiveType::Date)),
-            // Time of day. Iceberg supports only up to microsecond precision.
-            ArrowDataType::Time32(ArrowTimeUnit::Second)
-            | ArrowDataType::Time32(ArrowTimeUnit::Millisecond)
-            | ArrowDataType::Time32(ArrowTimeUnit::Microsecond) => {
-                Ok(SchemaType::Primitive(PrimitiveType::Time))
-            },
-            ArrowDataType::Time64(ArrowTimeUnit::Second)
-            | ArrowDataType::Time64(ArrowTimeUnit::Millisecond)
-            | ArrowDataType::Time64(ArrowTimeUnit::Microsecond) => {
-                Ok(SchemaType::Primitive(PrimitiveType::Time))
-            },
-            ArrowDataType::Binary => Ok(SchemaType::Primitive(PrimitiveType::Binary)),
-            ArrowDataType::FixedSizeBinary(size) => {
-                // Convert i32 to u64
-                let converted_size = <i32 as TryInto<u64>>::try_into(*size)
-                    .map_err(|_| {
-                        ArrowError::SchemaError(format!(
-                            "can't convert Fixed-size binary with negative size {size}"
-                        ))
-                    }
-                )?;
-
-                Ok(SchemaType::Primitive(PrimitiveType::Fixed(converted_size)))
-            },
-            ArrowDataType::Utf8 => Ok(SchemaType::Primitive(PrimitiveType::String)),
-            ArrowDataType::List(field)
-            | ArrowDataType::FixedSizeList(field, _)
-            | ArrowDataType::LargeList(field) => {
-                Ok(SchemaType::List(ListType::new(
-                    // TODO: Handle field IDs
-                    0,
-                    !field.is_nullable(),
-                    field.data_type().try_into()?
-                )))
-            },
-            ArrowDataType::Struct(fields) => {
-                Ok(SchemaType::Struct(StructType::new(
-                    fields.iter().map(|field| field.as_ref().try_into())
-                        .collect::<Result<Vec<Field>, _>>()?
-                )))
-            },
-            ArrowDataType::Decimal128(p, s) => {
-                let converted_scale = <i8 as TryInto<u8>>::try_into(*s)
-                    .map_err(|_| {
-                        ArrowError::SchemaError(format!(
-                            "can't convert decimal with negative scale {s}"
-                        ))
-                    }
-                )?;
-
-                Ok(SchemaType::Primitive(PrimitiveType::Decimal {
-                    precision: *p,
-                    scale: converted_scale,
-                }))
-            },
-
-            // TODO: Handle ArrowDataType::Map, ArrowDataType::Dictionary
-
-            // ArrowDataType::Null
-            // ArrowDataType::Unit64
-            // ArrowDataType::Duration
-            // ArrowDataType::Interval
-            // ArrowDataType::LargeBinary
-            // ArrowDataType::Decimal256
-            dt => {
-                Err(ArrowError::SchemaError(format!(
-                    "unsupported Arrow data type for Iceberg: {dt}"
-                )))
-            }
-        }
+        from_arrow_data_type(arrow_type, &SchemaConversionOptions::default())
     }
 }
 
@@ -255,13 +369,7 @@ impl TryFrom<&ArrowField> for Field {
     type Error = ArrowError;
 
     fn try_from(arrow_field: &ArrowField) -> Result<Self, Self::Error> {
-        Ok(Field::new(
-            // TODO: Handle field IDs
-            0,
-            arrow_field.name(),
-            !arrow_field.is_nullable(),
-            arrow_field.data_type().try_into()?,
-        ))
+        from_arrow_field(arrow_field, &SchemaConversionOptions::default())
     }
 }
 
@@ -270,13 +378,68 @@ impl TryFrom<&ArrowField> for Field {
 /// Iceberg field ids are encoded in the Arrow field metadata with the key
 /// `"ICEBERG:field_id"`.
 pub fn iceberg_to_arrow_schema(schema: &Schema) -> IcebergResult<ArrowSchema> {
-    <ArrowSchema as TryFrom<&Schema>>::try_from(schema).map_err(|e| {
+    iceberg_to_arrow_schema_with_options(schema, &SchemaConversionOptions::default())
+}
+
+/// Converts an Iceberg table schema to an Arrow schema, like
+/// [`iceberg_to_arrow_schema()`], with the given conversion options.
+pub fn iceberg_to_arrow_schema_with_options(
+    schema: &Schema,
+    options: &SchemaConversionOptions
+) -> IcebergResult<ArrowSchema> {
+    to_arrow_schema(schema, options).map_err(|e| {
         IcebergError::SchemaError {
             message: format!("Failed to convert arrow schema: {e}")
         }
     })
 }
 
+/// Converts an Arrow field to an Iceberg field, with the given conversion options.
+///
+/// Field ids are read from the Arrow field metadata, either with the key
+/// `"ICEBERG:field_id"` or `"PARQUET:field_id"`, and are 0 if missing.
+pub fn arrow_to_iceberg_field_with_options(
+    arrow_field: &ArrowField,
+    options: &SchemaConversionOptions
+) -> IcebergResult<Field> {
+    from_arrow_field(arrow_field, options).map_err(|e| {
+        IcebergError::SchemaError {
+            message: format!("Failed to convert arrow field: {e}")
+        }
+    })
+}
+
+impl Schema {
+    /// Converts an Arrow schema to an Iceberg schema with id 0, ignoring any field
+    /// ids of the Arrow fields and assigning new ids instead.
+    ///
+    /// Ids are assigned from 1 in a depth-first pre-order traversal: each field is
+    /// assigned an id before its nested fields, which are the fields of a struct, the
+    /// element of a list, or the key and value of a map, in that order. Converting
+    /// the same Arrow schema therefore always produces the same Iceberg schema.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if a field has a type that can't be
+    /// converted.
+    pub fn from_arrow_assigning_ids(arrow_schema: &ArrowSchema) -> IcebergResult<Schema> {
+        let mut last_id = 0;
+        let mut next_id = || { last_id += 1; last_id };
+
+        let fields = arrow_schema.fields().iter()
+            .map(|arrow_field| {
+                from_arrow_field(arrow_field, &SchemaConversionOptions::default())
+                    .map(|field| field.with_fresh_ids(&mut next_id))
+            })
+            .collect::<Result<Vec<Field>, _>>()
+            .map_err(|e| IcebergError::SchemaError {
+                message: format!("Failed to convert arrow schema: {e}")
+            })?;
+
+        Ok(Schema::new(0, fields))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,6 +495,42 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn struct_round_trip() {
+        // The nullability of struct children is independent of the struct's own.
+        let field = Field::new_struct(1, "user", true, vec![
+            Field::new_primitive(2, "id", true, PrimitiveType::Long),
+            Field::new_primitive(3, "name", false, PrimitiveType::String),
+            Field::new_struct(4, "address", false, vec![
+                Field::new_primitive(5, "city", true, PrimitiveType::String),
+                Field::new_primitive(6, "zip", false, PrimitiveType::String),
+            ]),
+        ]);
+
+        let arrow_field: ArrowField = (&field).try_into().unwrap();
+        assert_eq!(arrow_field, new_arrow_field(
+            "user",
+            ArrowDataType::Struct(ArrowFields::from(vec![
+                new_arrow_field("id", ArrowDataType::Int64, false, 2),
+                new_arrow_field("name", ArrowDataType::Utf8, true, 3),
+                new_arrow_field(
+                    "address",
+                    ArrowDataType::Struct(ArrowFields::from(vec![
+                        new_arrow_field("city", ArrowDataType::Utf8, false, 5),
+                        new_arrow_field("zip", ArrowDataType::Utf8, true, 6),
+                    ])),
+                    true,
+                    4
+                ),
+            ])),
+            false,
+            1
+        ));
+
+        let converted: Field = (&arrow_field).try_into().unwrap();
+        assert_eq!(converted, field);
+    }
+
     #[test]
     fn iceberg_to_arrow_list() {
         let field = Field::new(
@@ -360,6 +559,53 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn list_round_trip() {
+        let schema = Schema::new(0, vec![
+            Field::new_list(
+                1,
+                "tags",
+                false,
+                Field::new_primitive(2, "element", true, PrimitiveType::String)
+            ),
+        ]);
+
+        let arrow_schema = super::iceberg_to_arrow_schema(&schema).unwrap();
+        let converted: Field = arrow_schema.field(0).try_into().unwrap();
+        assert_eq!(&converted, &schema.fields()[0]);
+        match converted.schema_type() {
+            SchemaType::List(list_type) => assert_eq!(list_type.field().id, 2),
+            schema_type => panic!("expected a list, found {schema_type:?}"),
+        }
+
+        // Without metadata, the element id is taken from its name.
+        let arrow_field = ArrowField::new(
+            "tags",
+            ArrowDataType::List(Arc::new(
+                ArrowField::new("field_7", ArrowDataType::Utf8, false)
+            )),
+            true
+        );
+        let converted: Field = (&arrow_field).try_into().unwrap();
+        match converted.schema_type() {
+            SchemaType::List(list_type) => assert_eq!(list_type.field().id, 7),
+            schema_type => panic!("expected a list, found {schema_type:?}"),
+        }
+
+        let arrow_field = ArrowField::new(
+            "tags",
+            ArrowDataType::List(Arc::new(
+                ArrowField::new("item", ArrowDataType::Utf8, false)
+            )),
+            true
+        );
+        let converted: Field = (&arrow_field).try_into().unwrap();
+        match converted.schema_type() {
+            SchemaType::List(list_type) => assert_eq!(list_type.field().id, 0),
+            schema_type => panic!("expected a list, found {schema_type:?}"),
+        }
+    }
+
     #[test]
     fn iceberg_to_arrow_schema() {
         let schema = Schema::new(0, vec![
@@ -384,4 +630,221 @@ mod tests {
             new_arrow_field("name", ArrowDataType::Utf8, false, 2)
         ]));
     }
+
+    #[test]
+    fn timestamptz_timezone() {
+        let schema = Schema::new(0, vec![
+            Field::new_primitive(1, "ts", true, PrimitiveType::Timestamptz),
+        ]);
+
+        let arrow_schema = super::iceberg_to_arrow_schema(&schema).unwrap();
+        assert_eq!(
+            arrow_schema.field(0).data_type(),
+            &ArrowDataType::Timestamp(ArrowTimeUnit::Microsecond, Some(Arc::from("UTC")))
+        );
+
+        let options = SchemaConversionOptions {
+            timestamptz_tz: "+00:00".to_string(),
+            ..Default::default()
+        };
+        let arrow_schema = iceberg_to_arrow_schema_with_options(&schema, &options)
+            .unwrap();
+        assert_eq!(
+            arrow_schema.field(0).data_type(),
+            &ArrowDataType::Timestamp(
+                ArrowTimeUnit::Microsecond,
+                Some(Arc::from("+00:00"))
+            )
+        );
+
+        // Any timezone converts back to a timestamptz.
+        let converted: Field = arrow_schema.field(0).try_into().unwrap();
+        assert_eq!(&converted, &schema.fields()[0]);
+    }
+
+    #[test]
+    fn timestamp_precision() {
+        let nanos = ArrowField::new(
+            "ts",
+            ArrowDataType::Timestamp(ArrowTimeUnit::Nanosecond, None),
+            true
+        );
+        let nanos_tz = ArrowField::new(
+            "ts",
+            ArrowDataType::Timestamp(ArrowTimeUnit::Nanosecond, Some(Arc::from("UTC"))),
+            true
+        );
+        let micros = ArrowField::new(
+            "ts",
+            ArrowDataType::Timestamp(ArrowTimeUnit::Microsecond, None),
+            true
+        );
+
+        let converted: Field = (&nanos).try_into().unwrap();
+        assert_eq!(
+            converted.schema_type(),
+            &SchemaType::Primitive(PrimitiveType::Timestamp)
+        );
+        let converted: Field = (&nanos_tz).try_into().unwrap();
+        assert_eq!(
+            converted.schema_type(),
+            &SchemaType::Primitive(PrimitiveType::Timestamptz)
+        );
+
+        let strict = SchemaConversionOptions {
+            strict_timestamp_precision: true,
+            ..Default::default()
+        };
+        for arrow_field in [&nanos, &nanos_tz] {
+            assert!(matches!(
+                arrow_to_iceberg_field_with_options(arrow_field, &strict),
+                Err(IcebergError::SchemaError { .. })
+            ));
+        }
+        let millis = ArrowField::new(
+            "ts",
+            ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, None),
+            true
+        );
+        assert!(arrow_to_iceberg_field_with_options(&millis, &strict).is_err());
+
+        let converted = arrow_to_iceberg_field_with_options(&micros, &strict).unwrap();
+        assert_eq!(
+            converted.schema_type(),
+            &SchemaType::Primitive(PrimitiveType::Timestamp)
+        );
+    }
+
+    #[test]
+    fn dictionary_to_value_type() {
+        let arrow_type = ArrowDataType::Dictionary(
+            Box::new(ArrowDataType::Int32),
+            Box::new(ArrowDataType::Utf8)
+        );
+        let converted: SchemaType = (&arrow_type).try_into().unwrap();
+        assert_eq!(converted, SchemaType::Primitive(PrimitiveType::String));
+    }
+
+    #[test]
+    fn arrow_schema_with_assigned_ids() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("id", ArrowDataType::Int64, false),
+            ArrowField::new(
+                "tags",
+                ArrowDataType::List(Arc::new(
+                    ArrowField::new("item", ArrowDataType::Utf8, true)
+                )),
+                true
+            ),
+            ArrowField::new(
+                "points",
+                ArrowDataType::Map(
+                    Arc::new(ArrowField::new(
+                        "entries",
+                        ArrowDataType::Struct(ArrowFields::from(vec![
+                            ArrowField::new("key", ArrowDataType::Utf8, false),
+                            ArrowField::new(
+                                "value",
+                                ArrowDataType::Struct(ArrowFields::from(vec![
+                                    ArrowField::new("x", ArrowDataType::Float64, false),
+                                    ArrowField::new("y", ArrowDataType::Float64, false),
+                                ])),
+                                true
+                            ),
+                        ])),
+                        false
+                    )),
+                    false
+                ),
+                true
+            ),
+            // Existing ids are ignored.
+            new_arrow_field("name", ArrowDataType::Utf8, true, 100),
+        ]);
+
+        let schema = Schema::from_arrow_assigning_ids(&arrow_schema).unwrap();
+        assert_eq!(schema, Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new(
+                2,
+                "tags",
+                false,
+                SchemaType::List(ListType::new(
+                    3,
+                    false,
+                    SchemaType::Primitive(PrimitiveType::String)
+                ))
+            ),
+            Field::new(
+                4,
+                "points",
+                false,
+                SchemaType::Map(MapType::new(
+                    5,
+                    SchemaType::Primitive(PrimitiveType::String),
+                    6,
+                    false,
+                    SchemaType::Struct(StructType::new(vec![
+                        Field::new_primitive(7, "x", true, PrimitiveType::Double),
+                        Field::new_primitive(8, "y", true, PrimitiveType::Double),
+                    ]))
+                ))
+            ),
+            Field::new_primitive(9, "name", false, PrimitiveType::String),
+        ]));
+
+        assert_eq!(Schema::from_arrow_assigning_ids(&arrow_schema).unwrap(), schema);
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let field = Field::new(
+            1,
+            "scores",
+            true,
+            SchemaType::Map(MapType::new(
+                2,
+                SchemaType::Primitive(PrimitiveType::String),
+                3,
+                false,
+                SchemaType::Primitive(PrimitiveType::Int)
+            ))
+        );
+
+        let arrow_field: ArrowField = (&field).try_into().unwrap();
+        assert_eq!(arrow_field, new_arrow_field(
+            "scores",
+            ArrowDataType::Map(
+                Arc::new(ArrowField::new(
+                    "entries",
+                    ArrowDataType::Struct(ArrowFields::from(vec![
+                        new_arrow_field("key", ArrowDataType::Utf8, false, 2),
+                        new_arrow_field("value", ArrowDataType::Int32, true, 3),
+                    ])),
+                    true
+                )),
+                false
+            ),
+            false,
+            1
+        ));
+
+        let converted: Field = (&arrow_field).try_into().unwrap();
+        assert_eq!(converted, field);
+        match converted.schema_type() {
+            SchemaType::Map(map_type) => {
+                assert_eq!(map_type.key().id, 2);
+                assert_eq!(map_type.value().id, 3);
+            },
+            schema_type => panic!("expected a map, found {schema_type:?}"),
+        }
+
+        // Ids read from Parquet files are recognized as well.
+        let parquet_field = ArrowField::new("id", ArrowDataType::Int64, false)
+            .with_metadata(HashMap::from([
+                (PARQUET_FIELD_ID_KEY.to_string(), "7".to_string())
+            ]));
+        let converted: Field = (&parquet_field).try_into().unwrap();
+        assert_eq!(converted.id, 7);
+    }
 }
@@ -0,0 +1,252 @@
+//! Conversion between Iceberg table schema and the Avro schema of Avro data files.
+//!
+//! Following the Iceberg spec, every field of the Avro schema carries the id of its
+//! Iceberg field in a `field-id` attribute, optional fields are unions of `null` and
+//! their type, and timestamps are marked with an `adjust-to-utc` attribute.
+//!
+//! Only schemas of primitive fields are supported yet, excluding decimals, UUIDs and
+//! fixed-length binaries.
+use apache_avro::schema::Schema as AvroSchema;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+use crate::{IcebergResult, IcebergError};
+use crate::schema::{Schema, Field, SchemaType, PrimitiveType};
+
+const FIELD_ID_ATTRIBUTE: &str = "field-id";
+const ADJUST_TO_UTC_ATTRIBUTE: &str = "adjust-to-utc";
+/// Name of the top-level Avro record.
+const RECORD_NAME: &str = "table";
+
+fn schema_error(message: String) -> IcebergError {
+    IcebergError::SchemaError { message: message }
+}
+
+fn avro_type(field: &Field) -> IcebergResult<JsonValue> {
+    let primitive = match field.schema_type() {
+        SchemaType::Primitive(primitive) => primitive,
+        _ => return Err(schema_error(format!(
+            "can't convert nested field {} to avro", field.name()
+        ))),
+    };
+
+    let avro_type = match primitive {
+        PrimitiveType::Boolean => json!("boolean"),
+        PrimitiveType::Int => json!("int"),
+        PrimitiveType::Long => json!("long"),
+        PrimitiveType::Float => json!("float"),
+        PrimitiveType::Double => json!("double"),
+        PrimitiveType::Date => json!({"type": "int", "logicalType": "date"}),
+        PrimitiveType::Time => json!({"type": "long", "logicalType": "time-micros"}),
+        PrimitiveType::Timestamp | PrimitiveType::Timestamptz => json!({
+            "type": "long",
+            "logicalType": "timestamp-micros",
+            ADJUST_TO_UTC_ATTRIBUTE: *primitive == PrimitiveType::Timestamptz,
+        }),
+        PrimitiveType::String => json!("string"),
+        PrimitiveType::Binary => json!("bytes"),
+        _ => return Err(schema_error(format!(
+            "can't convert field {} of type {primitive} to avro", field.name()
+        ))),
+    };
+
+    Ok(match field.required() {
+        true => avro_type,
+        false => json!(["null", avro_type]),
+    })
+}
+
+/// Converts an Iceberg schema to the Avro schema of data files, with the field ids
+/// in `field-id` attributes.
+///
+/// # Errors
+///
+/// [`IcebergError::SchemaError`] is returned if the schema has nested fields, or
+/// fields of types not supported yet.
+pub fn iceberg_to_avro_schema(schema: &Schema) -> IcebergResult<AvroSchema> {
+    let fields = schema.fields().iter()
+        .map(|field| {
+            let mut avro_field = json!({
+                "name": field.name(),
+                "type": avro_type(field)?,
+                FIELD_ID_ATTRIBUTE: field.id(),
+            });
+            if !field.required() {
+                avro_field["default"] = JsonValue::Null;
+            }
+            Ok(avro_field)
+        })
+        .collect::<IcebergResult<Vec<_>>>()?;
+
+    let json = json!({
+        "type": "record",
+        "name": RECORD_NAME,
+        "fields": fields,
+    });
+
+    AvroSchema::parse(&json).map_err(|e| {
+        schema_error(format!("Failed to convert avro schema: {e}"))
+    })
+}
+
+fn primitive_type(avro_type: &JsonValue) -> Option<PrimitiveType> {
+    let (name, attributes) = match avro_type {
+        JsonValue::String(name) => (name.as_str(), None),
+        JsonValue::Object(attributes) => (
+            attributes.get("type")?.as_str()?,
+            Some(attributes)
+        ),
+        _ => return None,
+    };
+    let attribute = |key: &str| attributes.and_then(|attributes| attributes.get(key));
+
+    Some(match (name, attribute("logicalType").and_then(JsonValue::as_str)) {
+        ("boolean", None) => PrimitiveType::Boolean,
+        ("int", None) => PrimitiveType::Int,
+        ("long", None) => PrimitiveType::Long,
+        ("float", None) => PrimitiveType::Float,
+        ("double", None) => PrimitiveType::Double,
+        ("int", Some("date")) => PrimitiveType::Date,
+        ("long", Some("time-micros")) => PrimitiveType::Time,
+        ("long", Some("timestamp-micros")) => {
+            match attribute(ADJUST_TO_UTC_ATTRIBUTE).and_then(JsonValue::as_bool) {
+                Some(true) => PrimitiveType::Timestamptz,
+                _ => PrimitiveType::Timestamp,
+            }
+        },
+        ("string", None) => PrimitiveType::String,
+        ("bytes", None) => PrimitiveType::Binary,
+        _ => return None,
+    })
+}
+
+fn iceberg_field(
+    avro_field: &JsonMap<String, JsonValue>
+) -> IcebergResult<Option<Field>> {
+    let name = avro_field.get("name").and_then(JsonValue::as_str)
+        .ok_or_else(|| schema_error("avro field without a name".to_string()))?;
+    let field_id = match avro_field.get(FIELD_ID_ATTRIBUTE).and_then(JsonValue::as_i64) {
+        Some(field_id) => i32::try_from(field_id).map_err(|_| {
+            schema_error(format!("invalid field id {field_id} of avro field {name}"))
+        })?,
+        // Without an id, the field can't be matched to the table's schema.
+        None => return Ok(None),
+    };
+
+    let avro_type = avro_field.get("type")
+        .ok_or_else(|| schema_error(format!("avro field {name} has no type")))?;
+    let (required, avro_type) = match avro_type {
+        JsonValue::Array(union) => match union.as_slice() {
+            [JsonValue::String(null), avro_type] if null == "null" => (false, avro_type),
+            [avro_type, JsonValue::String(null)] if null == "null" => (false, avro_type),
+            _ => return Err(schema_error(format!(
+                "avro field {name} has an unsupported union type"
+            ))),
+        },
+        avro_type => (true, avro_type),
+    };
+
+    let primitive = primitive_type(avro_type).ok_or_else(|| {
+        schema_error(format!("avro field {name} has an unsupported type {avro_type}"))
+    })?;
+
+    Ok(Some(Field::new_primitive(field_id, name, required, primitive)))
+}
+
+/// Converts the Avro schema of a data file, as JSON, to an Iceberg schema with the
+/// given id. Fields without a `field-id` attribute are skipped.
+///
+/// # Errors
+///
+/// [`IcebergError::SchemaError`] is returned if the schema isn't a record of fields
+/// of supported types.
+pub fn avro_to_iceberg_schema(schema_id: i32, json: &str) -> IcebergResult<Schema> {
+    let json: JsonValue = serde_json::from_str(json).map_err(|e| {
+        schema_error(format!("invalid avro schema: {e}"))
+    })?;
+
+    let fields = json.get("fields").and_then(JsonValue::as_array)
+        .filter(|_| json.get("type").and_then(JsonValue::as_str) == Some("record"))
+        .ok_or_else(|| schema_error("avro schema isn't a record".to_string()))?;
+
+    let fields = fields.iter()
+        .map(|field| {
+            field.as_object()
+                .ok_or_else(|| schema_error(format!("invalid avro field {field}")))
+                .and_then(iceberg_field)
+        })
+        .collect::<IcebergResult<Vec<_>>>()?;
+
+    Ok(Schema::new(schema_id, fields.into_iter().flatten().collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avro_schema_round_trip() {
+        let schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "name", false, PrimitiveType::String),
+            Field::new_primitive(3, "ts", false, PrimitiveType::Timestamp),
+            Field::new_primitive(4, "tstz", true, PrimitiveType::Timestamptz),
+            Field::new_primitive(5, "day", false, PrimitiveType::Date),
+            Field::new_primitive(6, "payload", false, PrimitiveType::Binary),
+        ]);
+
+        let avro_schema = iceberg_to_avro_schema(&schema).unwrap();
+        assert!(matches!(
+            avro_schema,
+            AvroSchema::Record { ref fields, .. } if fields.len() == 6
+        ));
+
+        let json = json!({
+            "type": "record",
+            "name": "table",
+            "fields": [
+                {"name": "id", "type": "long", "field-id": 1},
+                {
+                    "name": "name",
+                    "type": ["null", "string"],
+                    "default": null,
+                    "field-id": 2
+                },
+                {
+                    "name": "ts",
+                    "type": ["null", {
+                        "type": "long",
+                        "logicalType": "timestamp-micros",
+                        "adjust-to-utc": false
+                    }],
+                    "field-id": 3
+                },
+                {
+                    "name": "tstz",
+                    "type": {
+                        "type": "long",
+                        "logicalType": "timestamp-micros",
+                        "adjust-to-utc": true
+                    },
+                    "field-id": 4
+                },
+                {
+                    "name": "day",
+                    "type": ["null", {"type": "int", "logicalType": "date"}],
+                    "field-id": 5
+                },
+                {"name": "payload", "type": ["null", "bytes"], "field-id": 6},
+                {"name": "unmapped", "type": "int"}
+            ]
+        });
+        assert_eq!(avro_to_iceberg_schema(0, &json.to_string()).unwrap(), schema);
+
+        // Nested fields aren't supported.
+        let nested = Schema::new(0, vec![
+            Field::new_list(
+                1, "tags", false,
+                Field::new_primitive(2, "element", false, PrimitiveType::String)
+            ),
+        ]);
+        assert!(iceberg_to_avro_schema(&nested).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Checking whether data with one schema can be written to a table with another.
+//!
+//! Fields are matched by their ids. Data may omit optional table fields, and its
+//! primitive types may be promoted to the table's types according to the
+//! [schema evolution spec](https://iceberg.apache.org/spec/#schema-evolution), e.g.
+//! `int` data can be written to a `long` field.
+use std::fmt;
+use std::collections::HashMap;
+
+use crate::schema::{Schema, Field, SchemaType};
+use crate::schema::update::SchemaUpdate;
+
+/// The reason a field of written data is incompatible with a table field.
+#[derive(Debug, PartialEq, Clone)]
+pub enum IncompatibilityKind {
+    /// A required table field is missing from the data.
+    MissingRequiredField,
+    /// An optional field of the data is written to a required table field.
+    OptionalToRequired,
+    /// The table field's type is narrower than the data's, e.g. `long` data
+    /// written to an `int` field.
+    TypeNarrowing { data_type: SchemaType, table_type: SchemaType },
+    /// The types of the data and the table field are unrelated.
+    IncompatibleType { data_type: SchemaType, table_type: SchemaType },
+}
+
+/// A field of written data that is incompatible with the table's schema.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Incompatibility {
+    /// Id of the table field.
+    pub field_id: i32,
+    /// Full name of the table field, e.g. `"person.address.city"`.
+    pub field_path: String,
+    pub kind: IncompatibilityKind,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            IncompatibilityKind::MissingRequiredField => {
+                write!(f, "required field {} is missing", self.field_path)
+            },
+            IncompatibilityKind::OptionalToRequired => {
+                write!(f, "optional data written to required field {}", self.field_path)
+            },
+            IncompatibilityKind::TypeNarrowing { data_type, table_type } => {
+                write!(
+                    f, "field {} narrows type {data_type} to {table_type}",
+                    self.field_path
+                )
+            },
+            IncompatibilityKind::IncompatibleType { data_type, table_type } => {
+                write!(
+                    f, "field {} can't convert type {data_type} to {table_type}",
+                    self.field_path
+                )
+            },
+        }
+    }
+}
+
+impl Schema {
+    /// Checks whether data with the `other` schema can be written to a table with
+    /// this schema, matching fields by their ids.
+    ///
+    /// Fields of `other` that aren't part of this schema are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns all incompatible fields, in the depth-first order of this schema.
+    pub fn is_write_compatible_with(
+        &self,
+        other: &Schema
+    ) -> Result<(), Vec<Incompatibility>> {
+        let data_fields: HashMap<i32, &Field> = other.all_fields_by_id().collect();
+
+        let mut incompatibilities = Vec::new();
+        for field in self.fields() {
+            check_field(field, None, &data_fields, &mut incompatibilities);
+        }
+
+        if incompatibilities.is_empty() {
+            Ok(())
+        } else {
+            Err(incompatibilities)
+        }
+    }
+}
+
+/// Checks a table field and its nested fields against the fields of the data.
+fn check_field(
+    field: &Field,
+    parent: Option<&str>,
+    data_fields: &HashMap<i32, &Field>,
+    incompatibilities: &mut Vec<Incompatibility>
+) {
+    let field_path = parent.map(|parent| format!("{parent}.{}", field.name()))
+        .unwrap_or_else(|| field.name().to_string());
+    let mut incompatible = |kind| incompatibilities.push(Incompatibility {
+        field_id: field.id(),
+        field_path: field_path.clone(),
+        kind: kind,
+    });
+
+    let data_field = match data_fields.get(&field.id()) {
+        Some(data_field) => data_field,
+        None => {
+            if field.required() {
+                incompatible(IncompatibilityKind::MissingRequiredField);
+            }
+            return;
+        },
+    };
+
+    if field.required() && !data_field.required() {
+        incompatible(IncompatibilityKind::OptionalToRequired);
+    }
+
+    let nested: Vec<&Field> = match (field.schema_type(), data_field.schema_type()) {
+        (SchemaType::Primitive(table_type), SchemaType::Primitive(data_type)) => {
+            // Equal types can be promoted as well.
+            if !SchemaUpdate::can_promote_primitive(data_type, table_type) {
+                let narrowing = SchemaUpdate::can_promote_primitive(
+                    table_type,
+                    data_type
+                );
+                let data_type = data_field.schema_type().clone();
+                let table_type = field.schema_type().clone();
+                incompatible(if narrowing {
+                    IncompatibilityKind::TypeNarrowing { data_type, table_type }
+                } else {
+                    IncompatibilityKind::IncompatibleType { data_type, table_type }
+                });
+            }
+            Vec::new()
+        },
+        (SchemaType::Struct(s), SchemaType::Struct(_)) => s.fields().iter().collect(),
+        (SchemaType::List(l), SchemaType::List(_)) => vec![l.field()],
+        (SchemaType::Map(m), SchemaType::Map(_)) => vec![m.key(), m.value()],
+        (table_type, data_type) => {
+            incompatible(IncompatibilityKind::IncompatibleType {
+                data_type: data_type.clone(),
+                table_type: table_type.clone(),
+            });
+            Vec::new()
+        },
+    };
+
+    for nested_field in nested {
+        check_field(nested_field, Some(&field_path), data_fields, incompatibilities);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::PrimitiveType;
+
+    fn table_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_struct(2, "location", false, vec![
+                Field::new_primitive(3, "lat", true, PrimitiveType::Double),
+                Field::new_primitive(4, "long", true, PrimitiveType::Double),
+            ]),
+            Field::new_primitive(5, "comment", false, PrimitiveType::String),
+        ])
+    }
+
+    #[test]
+    fn allowed_promotion() {
+        // int and float data are promoted, and the optional comment may be missing.
+        let data_schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Int),
+            Field::new_struct(2, "location", false, vec![
+                Field::new_primitive(3, "lat", true, PrimitiveType::Float),
+                Field::new_primitive(4, "long", true, PrimitiveType::Double),
+            ]),
+        ]);
+
+        assert_eq!(table_schema().is_write_compatible_with(&data_schema), Ok(()));
+    }
+
+    #[test]
+    fn disallowed_narrowing() {
+        let table_schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Int),
+            Field::new_struct(2, "location", false, vec![
+                Field::new_primitive(3, "lat", true, PrimitiveType::Float),
+                Field::new_primitive(4, "long", true, PrimitiveType::Double),
+            ]),
+        ]);
+        let data_schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_struct(2, "location", false, vec![
+                Field::new_primitive(3, "lat", true, PrimitiveType::Double),
+                Field::new_primitive(4, "long", false, PrimitiveType::String),
+            ]),
+        ]);
+
+        let double = SchemaType::Primitive(PrimitiveType::Double);
+        assert_eq!(table_schema.is_write_compatible_with(&data_schema), Err(vec![
+            Incompatibility {
+                field_id: 1,
+                field_path: "id".to_string(),
+                kind: IncompatibilityKind::TypeNarrowing {
+                    data_type: SchemaType::Primitive(PrimitiveType::Long),
+                    table_type: SchemaType::Primitive(PrimitiveType::Int),
+                },
+            },
+            Incompatibility {
+                field_id: 3,
+                field_path: "location.lat".to_string(),
+                kind: IncompatibilityKind::TypeNarrowing {
+                    data_type: double.clone(),
+                    table_type: SchemaType::Primitive(PrimitiveType::Float),
+                },
+            },
+            Incompatibility {
+                field_id: 4,
+                field_path: "location.long".to_string(),
+                kind: IncompatibilityKind::OptionalToRequired,
+            },
+            Incompatibility {
+                field_id: 4,
+                field_path: "location.long".to_string(),
+                kind: IncompatibilityKind::IncompatibleType {
+                    data_type: SchemaType::Primitive(PrimitiveType::String),
+                    table_type: double,
+                },
+            },
+        ]));
+
+        // A missing required field is incompatible.
+        let data_schema = Schema::new(0, vec![
+            Field::new_primitive(5, "comment", false, PrimitiveType::String),
+        ]);
+        let incompatibilities = table_schema()
+            .is_write_compatible_with(&data_schema)
+            .unwrap_err();
+        assert_eq!(incompatibilities.len(), 1);
+        assert_eq!(incompatibilities[0].field_path, "id");
+        assert_eq!(incompatibilities[0].kind, IncompatibilityKind::MissingRequiredField);
+    }
+}
@@ -0,0 +1,163 @@
+//! Name mapping of columns in data files that lack field ids.
+//!
+//! Data files written by tools unaware of Iceberg field ids, e.g. files imported into
+//! a table, can only be matched to the table's schema by their column names. A
+//! [`NameMapping`] maps such names to field ids. It is stored as JSON in the
+//! `schema.name-mapping.default` table property, according to the
+//! [column projection spec](https://iceberg.apache.org/spec/#column-projection).
+use serde::{Deserialize, Serialize};
+
+use crate::{IcebergResult, IcebergError};
+use crate::schema::{Schema, Field, SchemaType};
+
+/// Table property holding the table's default name mapping as JSON.
+pub const NAME_MAPPING_PROPERTY: &str = "schema.name-mapping.default";
+
+/// Maps a set of names to a field id, along with mappings of nested fields.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct MappedField {
+    /// The field id the names map to. Names without a field id are known to have no
+    /// corresponding field in the table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_id: Option<i32>,
+    /// All names the field may have in data files.
+    pub names: Vec<String>,
+    /// Mappings of the nested fields of a struct, list or map.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<MappedField>,
+}
+
+impl MappedField {
+    pub fn new(field_id: i32, names: &[&str]) -> Self {
+        Self {
+            field_id: Some(field_id),
+            names: names.iter().map(|name| name.to_string()).collect(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds mappings of nested fields.
+    pub fn with_fields(mut self, fields: Vec<MappedField>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    fn from_field(field: &Field) -> Self {
+        let nested: Vec<&Field> = match field.schema_type() {
+            SchemaType::Primitive(_) => Vec::new(),
+            SchemaType::Struct(s) => s.fields().iter().collect(),
+            SchemaType::List(l) => vec![l.field()],
+            SchemaType::Map(m) => vec![m.key(), m.value()],
+        };
+
+        Self::new(field.id, &[&field.name])
+            .with_fields(nested.into_iter().map(Self::from_field).collect())
+    }
+}
+
+/// A mapping from column names in data files to field ids.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Default)]
+#[serde(transparent)]
+pub struct NameMapping {
+    fields: Vec<MappedField>,
+}
+
+impl NameMapping {
+    pub fn new(fields: Vec<MappedField>) -> Self {
+        Self { fields: fields }
+    }
+
+    /// Creates a name mapping of every field in the schema to its current name.
+    pub fn from_schema(schema: &Schema) -> Self {
+        Self::new(schema.fields().iter().map(MappedField::from_field).collect())
+    }
+
+    /// Mappings of the top-level fields.
+    pub fn fields(&self) -> &[MappedField] {
+        &self.fields
+    }
+
+    /// Finds the mapping of a field by its path of names in a data file, e.g.
+    /// `["location", "lat"]` for a field nested within a struct.
+    pub fn find(&self, path: &[&str]) -> Option<&MappedField> {
+        let (first, rest) = path.split_first()?;
+        let field = self.fields.iter()
+            .find(|field| field.names.iter().any(|name| name == first))?;
+
+        rest.iter().try_fold(field, |field, name| {
+            field.fields.iter().find(|nested| nested.names.iter().any(|n| n == name))
+        })
+    }
+
+    /// Returns the field id mapped to a top-level column name, if any.
+    pub fn field_id(&self, name: &str) -> Option<i32> {
+        self.find(&[name]).and_then(|field| field.field_id)
+    }
+
+    pub fn encode(&self) -> IcebergResult<String> {
+        serde_json::to_string(self).map_err(|e| IcebergError::SchemaError {
+            message: format!("error serializing name mapping to json: {e}")
+        })
+    }
+
+    pub fn decode(input: &[u8]) -> IcebergResult<Self> {
+        serde_json::from_slice::<Self>(input).map_err(|e| IcebergError::SchemaError {
+            message: format!("error deserializing name mapping from json: {e}")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{Schema, Field, SchemaType, StructType, PrimitiveType};
+    use crate::schema::mapping::{NameMapping, MappedField};
+
+    #[test]
+    fn decode_name_mapping() {
+        let json = r#"[
+            {"field-id": 1, "names": ["id", "record_id"]},
+            {"field-id": 2, "names": ["data"]},
+            {"names": ["unmapped"]},
+            {"field-id": 3, "names": ["location"], "fields": [
+                {"field-id": 4, "names": ["latitude", "lat"]},
+                {"field-id": 5, "names": ["longitude", "long"]}
+            ]}
+        ]"#;
+
+        let mapping = NameMapping::decode(json.as_bytes()).unwrap();
+
+        assert_eq!(mapping.field_id("id"), Some(1));
+        assert_eq!(mapping.field_id("record_id"), Some(1));
+        assert_eq!(mapping.field_id("unmapped"), None);
+        assert_eq!(mapping.field_id("missing"), None);
+        assert_eq!(mapping.find(&["location", "lat"]).unwrap().field_id, Some(4));
+        assert!(mapping.find(&["location", "alt"]).is_none());
+
+        let decoded = NameMapping::decode(mapping.encode().unwrap().as_bytes()).unwrap();
+        assert_eq!(decoded, mapping);
+    }
+
+    #[test]
+    fn name_mapping_from_schema() {
+        let schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new(2, "location", false, SchemaType::Struct(StructType::new(vec![
+                Field::new_primitive(3, "lat", false, PrimitiveType::Double),
+            ]))),
+        ]);
+
+        assert_eq!(
+            NameMapping::from_schema(&schema),
+            NameMapping::new(vec![
+                MappedField::new(1, &["id"]),
+                MappedField::new(2, &["location"])
+                    .with_fields(vec![MappedField::new(3, &["lat"])]),
+            ])
+        );
+        assert_eq!(
+            NameMapping::from_schema(&schema).encode().unwrap(),
+            r#"[{"field-id":1,"names":["id"]},{"field-id":2,"names":["location"],"fields":[{"field-id":3,"names":["lat"]}]}]"#
+        );
+    }
+}
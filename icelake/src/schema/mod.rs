@@ -68,8 +68,13 @@
 mod schema;
 pub mod update;
 pub mod arrow;
+pub mod avro;
+pub mod mapping;
+pub mod compatibility;
 
 pub use self::schema::{
     Schema, SchemaBuilder, Field,
     SchemaType, PrimitiveType, StructType, ListType, MapType
 };
+pub use self::mapping::{NameMapping, MappedField};
+pub use self::compatibility::{Incompatibility, IncompatibilityKind};
@@ -4,16 +4,16 @@ use std::cell::RefCell;
 use std::borrow::Cow;
 
 use regex::Regex;
+use lazy_static::lazy_static;
 use serde::{
-    de::{self, IntoDeserializer},
+    de,
     Serialize, Serializer, Deserialize, Deserializer
 };
 
 use crate::{IcebergResult, IcebergError};
+use crate::value::Value;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
-#[serde(rename_all = "lowercase")]
-#[serde(remote = "Self")]
+#[derive(Debug, PartialEq, Eq, Clone)]
 /// An enum of possible primitive field types.
 pub enum PrimitiveType {
     /// True or False
@@ -52,9 +52,11 @@ pub enum PrimitiveType {
 }
 
 impl std::fmt::Display for PrimitiveType {
+    /// Formats the type as its Iceberg string representation, e.g. `"long"` or
+    /// `"decimal(10,2)"`.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            PrimitiveType::Boolean => write!(f, "bool"),
+            PrimitiveType::Boolean => write!(f, "boolean"),
             PrimitiveType::Int => write!(f, "int"),
             PrimitiveType::Long => write!(f, "long"),
             PrimitiveType::Float => write!(f, "float"),
@@ -73,105 +75,132 @@ impl std::fmt::Display for PrimitiveType {
     }
 }
 
+impl PrimitiveType {
+    /// Maximum precision of a decimal, the number of digits that fit in 16 bytes.
+    pub const MAX_DECIMAL_PRECISION: u8 = 38;
+
+    /// Creates a decimal type with the given precision and scale.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if the precision is not between 1
+    /// and 38, or if the scale is greater than the precision.
+    pub fn decimal(precision: u8, scale: u8) -> IcebergResult<Self> {
+        if precision == 0 || precision > Self::MAX_DECIMAL_PRECISION {
+            return Err(IcebergError::SchemaError {
+                message: format!(
+                    "invalid decimal precision {}, must be between 1 and {}",
+                    precision, Self::MAX_DECIMAL_PRECISION
+                )
+            });
+        }
+        if scale > precision {
+            return Err(IcebergError::SchemaError {
+                message: format!(
+                    "invalid decimal scale {}, must not exceed precision {}",
+                    scale, precision
+                )
+            });
+        }
+
+        Ok(PrimitiveType::Decimal { precision: precision, scale: scale })
+    }
+
+    /// Creates a fixed length byte array type.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if the length is zero.
+    pub fn fixed(len: u64) -> IcebergResult<Self> {
+        if len == 0 {
+            return Err(IcebergError::SchemaError {
+                message: "invalid fixed length 0, must be positive".to_string()
+            });
+        }
+
+        Ok(PrimitiveType::Fixed(len))
+    }
+}
+
+impl std::str::FromStr for PrimitiveType {
+    type Err = IcebergError;
+
+    /// Parses a type from its Iceberg string representation.
+    ///
+    /// Parameterized types may have whitespace around their parameters, e.g.
+    /// `"decimal(10, 2)"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref DECIMAL_REGEX: Regex =
+                Regex::new(r#"^decimal\(\s*(?P<p>\d+)\s*,\s*(?P<s>\d+)\s*\)$"#).unwrap();
+            static ref FIXED_REGEX: Regex =
+                Regex::new(r#"^fixed\[\s*(?P<l>\d+)\s*\]$"#).unwrap();
+        }
+
+        let invalid = |message: &str| IcebergError::SchemaError {
+            message: format!("invalid primitive type '{s}': {message}")
+        };
+
+        match s {
+            "boolean" => Ok(PrimitiveType::Boolean),
+            "int" => Ok(PrimitiveType::Int),
+            "long" => Ok(PrimitiveType::Long),
+            "float" => Ok(PrimitiveType::Float),
+            "double" => Ok(PrimitiveType::Double),
+            "date" => Ok(PrimitiveType::Date),
+            "time" => Ok(PrimitiveType::Time),
+            "timestamp" => Ok(PrimitiveType::Timestamp),
+            "timestamptz" => Ok(PrimitiveType::Timestamptz),
+            "string" => Ok(PrimitiveType::String),
+            "uuid" => Ok(PrimitiveType::Uuid),
+            "binary" => Ok(PrimitiveType::Binary),
+            _ if s.starts_with("decimal") => {
+                let caps = DECIMAL_REGEX.captures(s)
+                    .ok_or_else(|| invalid("expected decimal(P,S)"))?;
+                let precision: u8 = caps["p"].parse()
+                    .map_err(|_| invalid("precision out of range"))?;
+                let scale: u8 = caps["s"].parse()
+                    .map_err(|_| invalid("scale out of range"))?;
+
+                Ok(PrimitiveType::Decimal { precision: precision, scale: scale })
+            },
+            _ if s.starts_with("fixed") => {
+                let caps = FIXED_REGEX.captures(s)
+                    .ok_or_else(|| invalid("expected fixed[L]"))?;
+                let length: u64 = caps["l"].parse()
+                    .map_err(|_| invalid("length out of range"))?;
+
+                Ok(PrimitiveType::Fixed(length))
+            },
+            _ => Err(invalid("unknown type")),
+        }
+    }
+}
 
-/// Serialize for PrimitiveType with special handling for
-/// Decimal and Fixed types.
 impl Serialize for PrimitiveType {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        use PrimitiveType::*;
-        match self {
-            Decimal {
-                precision: p,
-                scale: s,
-            } => serializer.serialize_str(&format!("decimal({p},{s})")),
-            Fixed(l) => serializer.serialize_str(&format!("fixed[{l}]")),
-            _ => PrimitiveType::serialize(self, serializer),
-        }
+        serializer.serialize_str(&self.to_string())
     }
 }
 
-/// Serialize for PrimitiveType wit special handling for
-/// Decimal and Fixed types.
 impl<'de> Deserialize<'de> for PrimitiveType {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        if s.starts_with("decimal") {
-            deserialize_decimal(s.into_deserializer())
-        } else if s.starts_with("fixed") {
-            deserialize_fixed(s.into_deserializer())
-        } else {
-            PrimitiveType::deserialize(s.into_deserializer())
-        }
+        s.parse().map_err(de::Error::custom)
     }
 }
 
-/// Parsing for the Decimal PrimitiveType
-fn deserialize_decimal<'de, D>(deserializer: D) -> Result<PrimitiveType, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let this = String::deserialize(deserializer)?;
-    let re = Regex::new(r#"^decimal\((?P<p>\d+),(?P<s>\d+)\)$"#).unwrap();
-
-    let err_msg = format!("Invalid decimal format {}", this);
-
-    let caps = re
-        .captures(&this)
-        .ok_or_else(|| de::Error::custom(&err_msg))?;
-    let precision: u8 = caps
-        .name("p")
-        .ok_or_else(|| de::Error::custom(&err_msg))
-        .and_then(|p| {
-            p.as_str()
-                .parse()
-                .map_err(|_| de::Error::custom("precision not u8"))
-        })?;
-    let scale: u8 = caps
-        .name("s")
-        .ok_or_else(|| de::Error::custom(&err_msg))
-        .and_then(|p| {
-            p.as_str()
-                .parse()
-                .map_err(|_| de::Error::custom("scale not u8"))
-        })?;
-    Ok(PrimitiveType::Decimal { precision, scale })
-}
-
-/// Deserialize for the Fixed PrimitiveType
-fn deserialize_fixed<'de, D>(deserializer: D) -> Result<PrimitiveType, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let this = String::deserialize(deserializer)?;
-    let re = Regex::new(r#"^fixed\[(?P<l>\d+)\]$"#).unwrap();
-
-    let err_msg = format!("Invalid fixed format {}", this);
-
-    let caps = re
-        .captures(&this)
-        .ok_or_else(|| de::Error::custom(&err_msg))?;
-    let length: u64 = caps
-        .name("l")
-        .ok_or_else(|| de::Error::custom(&err_msg))
-        .and_then(|p| {
-            p.as_str()
-                .parse()
-                .map_err(|_| de::Error::custom("length not u64"))
-        })?;
-    Ok(PrimitiveType::Fixed(length))
-}
-
 static STRUCT_TAG: &str = "struct";
 static LIST_TAG: &str = "list";
 static MAP_TAG: &str = "map";
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "kebab-case")]
 /// A complex field type that contains a tuple of nested fields.
 ///
@@ -197,7 +226,8 @@ impl StructType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(try_from = "FieldModel", into = "FieldModel")]
 /// An Iceberg schema field.
 pub struct Field {
     /// Unique Id
@@ -209,9 +239,77 @@ pub struct Field {
     /// Field can have any type
     pub r#type: SchemaType,
     /// Fields can have any optional comment or doc string.
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub doc: Option<String>,
-    // TODO: initial-default and write-default
+    /// Value of the field in all records written before the field was added to the
+    /// schema.
+    pub initial_default: Option<Value>,
+    /// Value written into the field when a writer doesn't supply one.
+    pub write_default: Option<Value>,
+}
+
+/// Serialized form of [`Field`]. Default values are kept as raw JSON since they can
+/// only be parsed into a [`Value`] once the field type is known.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct FieldModel {
+    id: i32,
+    name: String,
+    required: bool,
+    r#type: SchemaType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    initial_default: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    write_default: Option<serde_json::Value>,
+}
+
+impl TryFrom<FieldModel> for Field {
+    type Error = IcebergError;
+
+    fn try_from(model: FieldModel) -> Result<Self, Self::Error> {
+        let parse_default = |json: Option<serde_json::Value>| {
+            json.map(|json| match &model.r#type {
+                SchemaType::Primitive(primitive) => Value::from_json(&json, primitive),
+                other => Err(IcebergError::SchemaError {
+                    message: format!(
+                        "default values of type {} are not supported (field '{}')",
+                        other, model.name
+                    )
+                })
+            }).transpose()
+        };
+        let initial_default = parse_default(model.initial_default.clone())?;
+        let write_default = parse_default(model.write_default.clone())?;
+
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            required: model.required,
+            r#type: model.r#type,
+            doc: model.doc,
+            initial_default: initial_default,
+            write_default: write_default,
+        })
+    }
+}
+
+impl From<Field> for FieldModel {
+    fn from(field: Field) -> Self {
+        let to_json = |value: Option<Value>| {
+            value.and_then(|value| serde_json::to_value(value).ok())
+        };
+
+        Self {
+            id: field.id,
+            name: field.name,
+            required: field.required,
+            r#type: field.r#type,
+            doc: field.doc,
+            initial_default: to_json(field.initial_default),
+            write_default: to_json(field.write_default),
+        }
+    }
 }
 
 impl Field {
@@ -227,6 +325,8 @@ impl Field {
             required: required,
             r#type: r#type,
             doc: None,
+            initial_default: None,
+            write_default: None,
         }
     }
 
@@ -322,6 +422,26 @@ impl Field {
         self
     }
 
+    pub fn initial_default(&self) -> Option<&Value> {
+        self.initial_default.as_ref()
+    }
+
+    pub fn write_default(&self) -> Option<&Value> {
+        self.write_default.as_ref()
+    }
+
+    /// Sets the value of this field for records written before it was added.
+    pub fn with_initial_default(mut self, value: Value) -> Self {
+        self.initial_default = Some(value);
+        self
+    }
+
+    /// Sets the value written into this field when a writer doesn't supply one.
+    pub fn with_write_default(mut self, value: Value) -> Self {
+        self.write_default = Some(value);
+        self
+    }
+
     /// Assigns new ids from the `next_id` function to this field and all
     /// recursively nested fields.
     pub fn with_fresh_ids<F>(mut self, next_id: &mut F) -> Self
@@ -333,6 +453,46 @@ impl Field {
         self
     }
 
+    /// Returns a copy of this field with `f` applied to the types of all recursively
+    /// nested primitive fields. See [`Schema::map_types()`].
+    fn map_types<F>(&self, f: &F) -> Self
+    where
+        F: Fn(&PrimitiveType) -> PrimitiveType
+    {
+        let mut field = self.clone();
+        field.r#type = self.r#type.map_types(f);
+        field
+    }
+
+    /// Returns this field pruned to the given field ids, or `None` if neither it nor
+    /// any nested field is selected. See [`Schema::project()`].
+    fn project(&self, field_ids: &[i32]) -> Option<Self> {
+        if field_ids.contains(&self.id) {
+            return Some(self.clone());
+        }
+
+        match &self.r#type {
+            SchemaType::Primitive(_) => None,
+            SchemaType::Struct(s) => {
+                let fields: Vec<Field> = s.fields.iter()
+                    .filter_map(|field| field.project(field_ids))
+                    .collect();
+                if fields.is_empty() {
+                    return None;
+                }
+
+                let mut field = self.clone();
+                field.r#type = SchemaType::Struct(StructType::new(fields));
+                Some(field)
+            },
+            SchemaType::List(_) | SchemaType::Map(_) => {
+                self.all_fields()
+                    .any(|field| field_ids.contains(&field.id))
+                    .then(|| self.clone())
+            },
+        }
+    }
+
     /// Returns an iterator on all recursively nested fields inside this field,
     /// including `self`.
     pub fn all_fields(&self) -> Box<dyn Iterator<Item = &Self> + '_> {
@@ -404,7 +564,7 @@ impl Field {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(from = "ListTypeModel", into = "ListTypeModel")]
 /// A field type that represents a list of identical elements.
 pub struct ListType {
@@ -434,7 +594,7 @@ impl ListType {
 }
 
 /// Serializable `ListType`
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "kebab-case")]
 struct ListTypeModel {
     /// Always set to "list".
@@ -468,7 +628,7 @@ impl From<ListTypeModel> for ListType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(from = "MapTypeModel", into = "MapTypeModel")]
 /// A complex field type that contains key-value pairs.
 ///
@@ -526,7 +686,7 @@ impl MapType {
 }
 
 /// Serializable `MapType`
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "kebab-case")]
 struct MapTypeModel {
     /// Always set to "map".
@@ -562,9 +722,13 @@ impl From<MapTypeModel> for MapType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, PartialEq, Clone)]
 #[serde(untagged)]
 /// Represents the type of a field in an Iceberg table schema.
+///
+/// Primitive types are serialized as strings, e.g. `"decimal(10,2)"`, and nested
+/// types as JSON objects, according to the Iceberg
+/// [spec](https://iceberg.apache.org/spec/#appendix-c-json-serialization).
 pub enum SchemaType {
     /// A primitive field type.
     Primitive(PrimitiveType),
@@ -630,19 +794,112 @@ impl SchemaType {
             },
         }
     }
+
+    /// Applies `f` to this type if it's primitive, or to the types of all
+    /// recursively nested primitive fields otherwise.
+    fn map_types<F>(&self, f: &F) -> Self
+    where
+        F: Fn(&PrimitiveType) -> PrimitiveType
+    {
+        match self {
+            SchemaType::Primitive(p) => {
+                SchemaType::Primitive(f(p))
+            },
+            SchemaType::Struct(s) => {
+                SchemaType::Struct(StructType::new(
+                    s.fields.iter().map(|field| field.map_types(f))
+                ))
+            },
+            SchemaType::List(l) => {
+                SchemaType::List(ListType::of_field(l.field.map_types(f)))
+            },
+            SchemaType::Map(m) => {
+                SchemaType::Map(MapType::of_fields(
+                    m.key.map_types(f),
+                    m.value.map_types(f)
+                ))
+            },
+        }
+    }
 }
 
 impl std::fmt::Display for SchemaType {
+    /// Formats the type as in a JSON `type` field: the string representation of a
+    /// primitive type, or the JSON object of a nested type.
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{self:?}")
+        match self {
+            SchemaType::Primitive(p) => write!(f, "{p}"),
+            _ => {
+                let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+                write!(f, "{json}")
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for SchemaType {
+    type Err = IcebergError;
+
+    /// Parses a primitive type from its string representation, or a nested type
+    /// from its JSON object.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim_start().starts_with('{') {
+            serde_json::from_str(s).map_err(|e| IcebergError::SchemaError {
+                message: format!("invalid nested type '{s}': {e}")
+            })
+        } else {
+            s.parse().map(SchemaType::Primitive)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemaType {
+    /// Deserializes a type by the `type` field of nested types, for clearer errors
+    /// than trying each variant in turn.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+
+        let tag = match &value {
+            serde_json::Value::String(s) => {
+                return s.parse().map(SchemaType::Primitive).map_err(de::Error::custom);
+            },
+            serde_json::Value::Object(object) => {
+                object.get("type").and_then(|tag| tag.as_str())
+            },
+            _ => None,
+        };
+
+        match tag {
+            Some(tag) if tag == STRUCT_TAG => {
+                StructType::deserialize(value).map(SchemaType::Struct)
+            },
+            Some(tag) if tag == LIST_TAG => {
+                ListType::deserialize(value).map(SchemaType::List)
+            },
+            Some(tag) if tag == MAP_TAG => {
+                MapType::deserialize(value).map(SchemaType::Map)
+            },
+            Some(tag) => {
+                return Err(de::Error::custom(format!("unknown nested type '{tag}'")));
+            },
+            None => {
+                return Err(de::Error::custom(
+                    "expected a primitive type string or a nested type object"
+                ));
+            },
+        }.map_err(de::Error::custom)
     }
 }
 
 /// An Iceberg table schema.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct Schema {
-    /// Unique schema identifier.
+    /// Unique schema identifier. Optional in version 1 metadata, where it is 0.
+    #[serde(default)]
     schema_id: i32,
     /// Optionally track the set of primitive fields that identify rows in a table.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -712,6 +969,78 @@ impl Schema {
             .map(|field| (field.id(), field))
     }
 
+    /// Returns a copy of the schema with `f` applied to the type of every primitive
+    /// field, including fields nested in structs, lists and maps.
+    ///
+    /// Field ids, names and the schema id are preserved. This is useful for
+    /// normalizing types across a schema, e.g. mapping all timestamps to
+    /// timestamps with time zone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use icelake::schema::{Schema, Field, PrimitiveType};
+    ///
+    /// let schema = Schema::new(0, vec![
+    ///     Field::new_primitive(1, "ts", false, PrimitiveType::Timestamp)
+    /// ]);
+    /// let schema = schema.map_types(|t| match t {
+    ///     PrimitiveType::Timestamp => PrimitiveType::Timestamptz,
+    ///     other => other.clone(),
+    /// });
+    ///
+    /// assert_eq!(
+    ///     schema.fields()[0],
+    ///     Field::new_primitive(1, "ts", false, PrimitiveType::Timestamptz)
+    /// );
+    /// ```
+    pub fn map_types(&self, f: impl Fn(&PrimitiveType) -> PrimitiveType) -> Schema {
+        let mut schema = self.clone();
+        schema.schema = self.schema.map_types(&f);
+        schema
+    }
+
+    /// Returns a copy of the schema with only the fields of the given ids, and the
+    /// structs containing them.
+    ///
+    /// Field ids, names and required flags are preserved, as is the schema id.
+    /// Selecting a struct selects all of its fields, while selecting any field
+    /// nested in a list or map selects the whole list or map.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use icelake::schema::{Schema, Field, PrimitiveType};
+    ///
+    /// let schema = Schema::new(0, vec![
+    ///     Field::new_primitive(1, "id", true, PrimitiveType::Long),
+    ///     Field::new_primitive(2, "name", false, PrimitiveType::String),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     schema.project(&[2]).fields(),
+    ///     &[Field::new_primitive(2, "name", false, PrimitiveType::String)]
+    /// );
+    /// ```
+    pub fn project(&self, field_ids: &[i32]) -> Schema {
+        let fields: Vec<Field> = self.fields().iter()
+            .filter_map(|field| field.project(field_ids))
+            .collect();
+
+        let mut schema = self.clone();
+        schema.schema = SchemaType::Struct(StructType::new(fields));
+
+        // Identifier fields that were pruned no longer identify rows.
+        let projected_ids: Vec<i32> = schema.all_fields().map(|field| field.id).collect();
+        if let Some(identifier_field_ids) = &mut schema.identifier_field_ids {
+            identifier_field_ids.retain(|id| {
+                i32::try_from(*id).is_ok_and(|id| projected_ids.contains(&id))
+            });
+        }
+
+        schema
+    }
+
     /// Finds a top-level schema field by its name.
     pub fn get_field_by_name(&self, name: &str) -> Option<&Field> {
         self.fields().iter()
@@ -729,6 +1058,25 @@ impl Schema {
             message: format!("error deserializing table schema from json: {e}")
         })
     }
+
+    /// Parses a schema from its JSON representation in table metadata.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if the JSON is malformed or isn't a
+    /// valid schema.
+    pub fn from_json(json: &str) -> IcebergResult<Self> {
+        Self::decode(json.as_bytes())
+    }
+
+    /// Serializes the schema to its JSON representation in table metadata.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if the schema can't be serialized.
+    pub fn to_json(&self) -> IcebergResult<String> {
+        self.encode()
+    }
 }
 
 /// Provides an interface for building Iceberg schemas with automatic field id
@@ -994,6 +1342,94 @@ mod tests {
         assert_eq!(serde_json::to_value(schema).unwrap(), expected);
     }
 
+    #[test]
+    fn json_string_round_trip() {
+        let schema = create_schema(3);
+
+        let json = schema.to_json().unwrap();
+        assert!(json.contains("\"schema-id\":3"));
+        assert_eq!(Schema::from_json(&json).unwrap(), schema);
+
+        assert!(matches!(
+            Schema::from_json("{\"schema-id\": 0, \"type\": \"struct\""),
+            Err(IcebergError::SchemaError { .. })
+        ));
+        assert!(matches!(
+            Schema::from_json(r#"{"schema-id": "0", "type": "struct", "fields": []}"#),
+            Err(IcebergError::SchemaError { .. })
+        ));
+    }
+
+    #[test]
+    fn map_types() {
+        let schema = |ts_type: PrimitiveType| Schema::new(3, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "ts", false, ts_type.clone()),
+            Field::new_list(3, "events", false,
+                Field::new_struct(4, "element", false, vec![
+                    Field::new_primitive(5, "name", true, PrimitiveType::String),
+                    Field::new_primitive(6, "ts", true, ts_type.clone()),
+                ])
+            ),
+            Field::new_map(7, "updates", false,
+                Field::new_primitive(8, "key", true, PrimitiveType::String),
+                Field::new_primitive(9, "value", false, ts_type),
+            ),
+        ]);
+
+        let mapped = schema(PrimitiveType::Timestamp).map_types(|t| match t {
+            PrimitiveType::Timestamp => PrimitiveType::Timestamptz,
+            other => other.clone(),
+        });
+        assert_eq!(mapped, schema(PrimitiveType::Timestamptz));
+        assert_eq!(mapped.id(), 3);
+
+        let ids: Vec<i32> = mapped.all_fields().map(|field| field.id()).collect();
+        assert_eq!(ids, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn field_default_values() {
+        let field = Field::new_primitive(1, "price", true, PrimitiveType::Decimal {
+            precision: 9,
+            scale: 2
+        }).with_initial_default(Value::Decimal {
+            unscaled: 150,
+            precision: 9,
+            scale: 2
+        });
+
+        let json = serde_json::to_value(&field).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "id": 1,
+            "name": "price",
+            "required": true,
+            "type": "decimal(9,2)",
+            "initial-default": "1.50"
+        }));
+        assert_eq!(serde_json::from_value::<Field>(json).unwrap(), field);
+
+        // Defaults are parsed according to the field's type.
+        let field = serde_json::from_value::<Field>(serde_json::json!({
+            "id": 2,
+            "name": "count",
+            "required": true,
+            "type": "long",
+            "initial-default": 7,
+            "write-default": 8
+        })).unwrap();
+        assert_eq!(field.initial_default(), Some(&Value::Long(7)));
+        assert_eq!(field.write_default(), Some(&Value::Long(8)));
+
+        assert!(serde_json::from_value::<Field>(serde_json::json!({
+            "id": 3,
+            "name": "count",
+            "required": true,
+            "type": "int",
+            "initial-default": "seven"
+        })).is_err());
+    }
+
     #[test]
     fn deserialize_from_json() {
         let schema = serde_json::from_value::<Schema>(serde_json::json!({
@@ -1060,6 +1496,40 @@ mod tests {
         assert_eq!(schema, expected);
     }
 
+    #[test]
+    fn project() {
+        let schema = Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_struct(2, "person", true, vec![
+                Field::new_primitive(3, "first", true, PrimitiveType::String),
+                Field::new_primitive(4, "middle", false, PrimitiveType::String),
+                Field::new_primitive(5, "last", false, PrimitiveType::String),
+            ]),
+            Field::new_list(
+                6,
+                "tags",
+                false,
+                Field::new_primitive(7, "element", true, PrimitiveType::String)
+            ),
+        ]);
+
+        let projected = schema.project(&[3, 5]);
+        assert_eq!(projected.id(), 0);
+        assert_eq!(projected.fields(), &[
+            Field::new_struct(2, "person", true, vec![
+                Field::new_primitive(3, "first", true, PrimitiveType::String),
+                Field::new_primitive(5, "last", false, PrimitiveType::String),
+            ]),
+        ]);
+
+        // Selecting a list element keeps the whole list, selecting a struct keeps
+        // all of its fields.
+        let projected = schema.project(&[7, 2]);
+        assert_eq!(projected.fields(), &schema.fields()[1..]);
+
+        assert!(schema.project(&[]).fields().is_empty());
+    }
+
     #[test]
     fn max_field_id() {
         assert_eq!(create_schema(0).max_field_id(), 9)
@@ -1154,4 +1624,127 @@ mod tests {
         assert_eq!(struct_type.fields[0].id, 13);
         assert_eq!(struct_type.fields[1].id, 14);
     }
+
+    #[test]
+    fn primitive_type_strings() {
+        let types = [
+            (PrimitiveType::Boolean, "boolean"),
+            (PrimitiveType::Int, "int"),
+            (PrimitiveType::Long, "long"),
+            (PrimitiveType::Float, "float"),
+            (PrimitiveType::Double, "double"),
+            (PrimitiveType::Decimal { precision: 10, scale: 2 }, "decimal(10,2)"),
+            (PrimitiveType::Date, "date"),
+            (PrimitiveType::Time, "time"),
+            (PrimitiveType::Timestamp, "timestamp"),
+            (PrimitiveType::Timestamptz, "timestamptz"),
+            (PrimitiveType::String, "string"),
+            (PrimitiveType::Uuid, "uuid"),
+            (PrimitiveType::Fixed(16), "fixed[16]"),
+            (PrimitiveType::Binary, "binary"),
+        ];
+
+        for (primitive_type, s) in types {
+            assert_eq!(primitive_type.to_string(), s);
+            assert_eq!(s.parse::<PrimitiveType>().unwrap(), primitive_type);
+            assert_eq!(
+                serde_json::to_string(&primitive_type).unwrap(),
+                format!("\"{s}\"")
+            );
+        }
+
+        assert_eq!(
+            "decimal(10, 2)".parse::<PrimitiveType>().unwrap(),
+            PrimitiveType::Decimal { precision: 10, scale: 2 }
+        );
+
+        let malformed = ["bool", "decimal(10)", "decimal(300,2)", "fixed(16)", "fixed[]"];
+        for malformed in malformed {
+            assert!(matches!(
+                malformed.parse::<PrimitiveType>(),
+                Err(IcebergError::SchemaError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn nested_type_json() {
+        // A list of lists of strings.
+        let json = r#"{"type":"list","element-id":3,"element-required":true,"element":{"type":"list","element-id":4,"element-required":false,"element":"string"}}"#;
+        let list_type: SchemaType = serde_json::from_str(json).unwrap();
+        assert_eq!(list_type, SchemaType::List(ListType::new(
+            3,
+            true,
+            SchemaType::List(ListType::new(
+                4,
+                false,
+                SchemaType::Primitive(PrimitiveType::String)
+            ))
+        )));
+        assert_eq!(serde_json::to_string(&list_type).unwrap(), json);
+        assert_eq!(list_type.to_string(), json);
+        assert_eq!(json.parse::<SchemaType>().unwrap(), list_type);
+
+        // A map with struct values.
+        let json = r#"{"type":"map","key-id":5,"key":"string","value-id":6,"value-required":false,"value":{"type":"struct","fields":[{"id":7,"name":"x","required":true,"type":"double"},{"id":8,"name":"y","required":false,"type":"decimal(9,2)"}]}}"#;
+        let map_type: SchemaType = serde_json::from_str(json).unwrap();
+        assert_eq!(map_type, SchemaType::Map(MapType::new(
+            5,
+            SchemaType::Primitive(PrimitiveType::String),
+            6,
+            false,
+            SchemaType::Struct(StructType::new(vec![
+                Field::new_primitive(7, "x", true, PrimitiveType::Double),
+                Field::new_primitive(8, "y", false, PrimitiveType::Decimal {
+                    precision: 9,
+                    scale: 2
+                }),
+            ]))
+        )));
+        assert_eq!(serde_json::to_string(&map_type).unwrap(), json);
+        assert_eq!(json.parse::<SchemaType>().unwrap(), map_type);
+
+        // Primitives are bare strings.
+        let primitive_type: SchemaType = serde_json::from_str(r#""fixed[4]""#).unwrap();
+        assert_eq!(primitive_type, SchemaType::Primitive(PrimitiveType::Fixed(4)));
+        assert_eq!(serde_json::to_string(&primitive_type).unwrap(), r#""fixed[4]""#);
+        assert_eq!("fixed[4]".parse::<SchemaType>().unwrap(), primitive_type);
+
+        assert!(serde_json::from_str::<SchemaType>(r#"{"type":"set"}"#).is_err());
+        assert!(r#"{"type":"list"}"#.parse::<SchemaType>().is_err());
+    }
+
+    #[test]
+    fn decimal_constructor() {
+        assert_eq!(
+            PrimitiveType::decimal(10, 2).unwrap(),
+            PrimitiveType::Decimal { precision: 10, scale: 2 }
+        );
+        assert_eq!(
+            PrimitiveType::decimal(38, 38).unwrap(),
+            PrimitiveType::Decimal { precision: 38, scale: 38 }
+        );
+
+        assert!(matches!(
+            PrimitiveType::decimal(0, 0),
+            Err(IcebergError::SchemaError { .. })
+        ));
+        assert!(matches!(
+            PrimitiveType::decimal(39, 2),
+            Err(IcebergError::SchemaError { .. })
+        ));
+        assert!(matches!(
+            PrimitiveType::decimal(5, 6),
+            Err(IcebergError::SchemaError { .. })
+        ));
+    }
+
+    #[test]
+    fn fixed_constructor() {
+        assert_eq!(PrimitiveType::fixed(16).unwrap(), PrimitiveType::Fixed(16));
+        assert!(matches!(
+            PrimitiveType::fixed(0),
+            Err(IcebergError::SchemaError { .. })
+        ));
+    }
 }
@@ -28,6 +28,7 @@
 use std::collections::HashMap;
 
 use crate::{IcebergResult, IcebergError};
+use crate::value::Value;
 use crate::schema::{
     Schema, Field,
     SchemaType, PrimitiveType, StructType, ListType, MapType
@@ -51,7 +52,7 @@ impl FieldUpdate {
 }
 
 /// A set of actions that update a schema according to Iceberg schema evolution rules.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SchemaUpdate {
     /// Map of parent field ids to their respective new child fields.
     /// key=-1 indicates top-level field.
@@ -140,12 +141,15 @@ impl SchemaUpdate {
             }
         };
 
-        Field::new(
-            field.id(),
-            update.map(|u| u.name.as_str()).unwrap_or_else(|| field.name()),
-            update.map(|u| u.required).unwrap_or_else(|| field.required()),
-            new_type
-        )
+        Field {
+            id: field.id(),
+            name: update.map(|u| u.name.clone()).unwrap_or_else(|| field.name.clone()),
+            required: update.map(|u| u.required).unwrap_or_else(|| field.required()),
+            r#type: new_type,
+            doc: update.map(|u| u.doc.clone()).unwrap_or_else(|| field.doc.clone()),
+            initial_default: field.initial_default.clone(),
+            write_default: field.write_default.clone(),
+        }
     }
 
     fn apply_fields(
@@ -184,7 +188,12 @@ impl SchemaUpdate {
         Schema::new(new_schema_id, self.apply_fields(-1, schema.fields()))
     }
 
-    fn can_promote_primitive(base: &PrimitiveType, target: &PrimitiveType) -> bool {
+    /// Whether a field of type `base` can be promoted to type `target`, including
+    /// when they are equal.
+    pub(crate) fn can_promote_primitive(
+        base: &PrimitiveType,
+        target: &PrimitiveType
+    ) -> bool {
         match base {
             // int can be promoted to long
             PrimitiveType::Int => {
@@ -501,13 +510,73 @@ impl<'a> SchemaUpdateBuilder<'a> {
             .is_some()
     }
 
+    /// Adds a new field named `name` to the struct field with id `parent`, or to the
+    /// top level of the schema if `parent` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IcebergError::SchemaError`] if the parent isn't an existing struct
+    /// field, or if `required` is set. Existing data files have no values for the
+    /// new field, so required fields must be added with
+    /// [`SchemaUpdateBuilder::add_field_with_default()`].
     pub fn add_field(
-        mut self,
+        self,
         parent: Option<i32>,
         name: &str,
         required: bool,
         schema_type: SchemaType
     ) -> IcebergResult<Self> {
+        if required {
+            return Err(IcebergError::SchemaError {
+                message: format!(
+                    "can't add required field '{}' without an initial default",
+                    name
+                )
+            });
+        }
+
+        self.add_new_field(parent, Field::new(0, name, required, schema_type))
+    }
+
+    /// Adds a new required field named `name` to the struct field with id `parent`,
+    /// or to the top level of the schema if `parent` is `None`.
+    ///
+    /// The field reads as `initial_default` in all records written before it was
+    /// added, and `initial_default` is also used as its write default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IcebergError::SchemaError`] if the parent isn't an existing struct
+    /// field, or if `initial_default` is not of type `schema_type`.
+    pub fn add_field_with_default(
+        self,
+        parent: Option<i32>,
+        name: &str,
+        schema_type: SchemaType,
+        initial_default: Value
+    ) -> IcebergResult<Self> {
+        if initial_default.get_type().ok().as_ref() != Some(&schema_type) {
+            return Err(IcebergError::SchemaError {
+                message: format!(
+                    "can't add field '{}' of type {} with default value {}",
+                    name, schema_type, initial_default
+                )
+            });
+        }
+
+        let field = Field::new(0, name, true, schema_type)
+            .with_initial_default(initial_default.clone())
+            .with_write_default(initial_default);
+
+        self.add_new_field(parent, field)
+    }
+
+    fn add_new_field(
+        mut self,
+        parent: Option<i32>,
+        field: Field
+    ) -> IcebergResult<Self> {
+        let name = field.name().to_string();
         if let Some(parent_id) = parent {
             if self.is_deleted(parent_id) {
                 return Err(IcebergError::SchemaError {
@@ -537,12 +606,7 @@ impl<'a> SchemaUpdateBuilder<'a> {
             }
         }
 
-        let field = Field::new(
-            0,
-            name,
-            required,
-            schema_type
-        ).with_fresh_ids(&mut || self.next_id());
+        let field = field.with_fresh_ids(&mut || self.next_id());
 
         self.inner.adds
             .get_or_insert_with(|| HashMap::new())
@@ -3,6 +3,13 @@ use std::collections::HashMap;
 
 use serde::{Serialize, Deserialize};
 
+use crate::IcebergResult;
+use crate::storage::IcebergStorage;
+use crate::manifest::{
+    DataFile, DataFileContent, ManifestEntryStatus, ManifestFileType, ManifestList,
+    ManifestReader
+};
+
 // Parts of this module were taken from
 // https://github.com/oliverdaff/iceberg-rs/
 
@@ -39,6 +46,16 @@ impl SnapshotSummary {
     pub fn builder() -> SnapshotSummaryBuilder {
         SnapshotSummaryBuilder::new()
     }
+
+    /// The type of operation in the snapshot.
+    pub fn operation(&self) -> Option<&SnapshotOperation> {
+        self.operation.as_ref()
+    }
+
+    /// Returns the value of a summary metric, e.g. `"total-records"`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.stats.get(key).map(|value| value.as_str())
+    }
 }
 
 pub struct SnapshotSummaryBuilder {
@@ -109,6 +126,78 @@ impl SnapshotSummaryBuilder {
         self
     }
 
+    pub fn added_delete_file(
+        &mut self,
+        content: &DataFileContent,
+        records: i64,
+        size: i64
+    ) -> &Self {
+        self.add_to_stat("added-delete-files", 1);
+        self.add_to_stat("total-delete-files", 1);
+
+        match content {
+            DataFileContent::EqualityDelete => {
+                self.add_to_stat("added-equality-deletes", records);
+                self.add_to_stat("total-equality-deletes", records);
+            },
+            _ => {
+                self.add_to_stat("added-position-deletes", records);
+                self.add_to_stat("total-position-deletes", records);
+            }
+        }
+
+        self.add_to_stat("added-files-size", size);
+        self.add_to_stat("total-files-size", size);
+
+        self
+    }
+
+    /// Adds the given data and delete files to the delta counters and totals of the
+    /// summary.
+    ///
+    /// To accumulate the totals over the previous snapshot, call
+    /// [`copy_totals()`](Self::copy_totals) first.
+    pub fn added_files(&mut self, data_files: &[DataFile]) -> &mut Self {
+        for data_file in data_files {
+            match data_file.content {
+                DataFileContent::Data => {
+                    self.added_data_file(
+                        data_file.record_count,
+                        data_file.file_size_in_bytes
+                    );
+                },
+                _ => {
+                    self.added_delete_file(
+                        &data_file.content,
+                        data_file.record_count,
+                        data_file.file_size_in_bytes
+                    );
+                }
+            }
+        }
+        self
+    }
+
+    /// Adds the given data files to the delta counters of the summary and subtracts
+    /// them from the totals copied with [`copy_totals()`](Self::copy_totals).
+    pub fn removed_files(&mut self, data_files: &[DataFile]) -> &mut Self {
+        for data_file in data_files {
+            self.removed_data_file(data_file.record_count, data_file.file_size_in_bytes);
+
+            self.subtract_from_total("total-data-files", 1);
+            self.subtract_from_total("total-records", data_file.record_count);
+            self.subtract_from_total("total-files-size", data_file.file_size_in_bytes);
+        }
+        self
+    }
+
+    fn subtract_from_total(&mut self, stat_name: &str, count: i64) {
+        // Totals unknown to the previous snapshot remain unknown.
+        if let Some(value) = self.stats.get_mut(stat_name) {
+            *value -= count;
+        }
+    }
+
     pub fn build(&self) -> SnapshotSummary {
         SnapshotSummary {
             operation: self.operation.clone(),
@@ -130,7 +219,8 @@ pub struct Snapshot {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_snapshot_id: Option<i64>,
     /// A monotonically increasing long that tracks the order of
-    /// changes to a table.
+    /// changes to a table. Missing from version 1 metadata, where it is 0.
+    #[serde(default)]
     pub sequence_number: i64,
     /// A timestamp when the snapshot was created, used for garbage
     /// collection and table inspection
@@ -144,6 +234,71 @@ pub struct Snapshot {
     pub schema_id: Option<i32>,
 }
 
+impl Snapshot {
+    /// Reads the snapshot's manifest list from storage.
+    pub async fn read_manifest_list(
+        &self,
+        storage: &IcebergStorage
+    ) -> IcebergResult<ManifestList> {
+        let path = storage.create_path_from_url(&self.manifest_list)?;
+        let bytes = storage.get(&path).await?;
+
+        ManifestList::decode(bytes.as_ref())
+    }
+
+    /// Returns the data files added by this snapshot.
+    ///
+    /// Only the data manifests written by this snapshot are read.
+    pub async fn added_data_files(
+        &self,
+        storage: &IcebergStorage
+    ) -> IcebergResult<Vec<DataFile>> {
+        self.changed_data_files(storage, ManifestEntryStatus::Added).await
+    }
+
+    /// Returns the data files removed by this snapshot, e.g. by an overwrite or a
+    /// delete.
+    ///
+    /// Only the data manifests written by this snapshot are read.
+    pub async fn removed_data_files(
+        &self,
+        storage: &IcebergStorage
+    ) -> IcebergResult<Vec<DataFile>> {
+        self.changed_data_files(storage, ManifestEntryStatus::Deleted).await
+    }
+
+    /// Returns the data files of this snapshot's manifest entries with the given
+    /// status.
+    async fn changed_data_files(
+        &self,
+        storage: &IcebergStorage,
+        status: ManifestEntryStatus
+    ) -> IcebergResult<Vec<DataFile>> {
+        let manifest_list = self.read_manifest_list(storage).await?;
+
+        let mut data_files = Vec::new();
+        for manifest_file in manifest_list.manifest_files() {
+            // Manifests carried over from earlier snapshots have no changes of this one.
+            if manifest_file.content != ManifestFileType::Data
+                || manifest_file.added_snapshot_id != self.snapshot_id {
+                continue;
+            }
+
+            let entries = ManifestReader::for_manifest_file(manifest_file)
+                .read_entries(&manifest_file.manifest_path, storage)
+                .await?;
+            data_files.extend(entries.into_iter()
+                .filter(|entry| {
+                    entry.status() == status
+                        && entry.snapshot_id() == Some(self.snapshot_id)
+                })
+                .map(|entry| entry.data_file));
+        }
+
+        Ok(data_files)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 #[serde(rename_all = "kebab-case")]
 /// A log of when each snapshot was made.
@@ -175,6 +330,21 @@ pub struct SnapshotReference {
     max_ref_age_ms: Option<i64>,
 }
 
+impl SnapshotReference {
+    /// Creates a branch pointing to the given snapshot, using the table's default
+    /// retention policy.
+    pub fn branch(snapshot_id: i64) -> Self {
+        Self {
+            snapshot_id: snapshot_id,
+            r#type: SnapshotReferenceType::Branch {
+                min_snapshots_to_keep: None,
+                max_snapshot_age_ms: None,
+            },
+            max_ref_age_ms: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 #[serde(rename_all = "lowercase", tag = "type")]
 /// Retention policy field, which differ based on it it
@@ -195,3 +365,84 @@ pub enum SnapshotReferenceType {
     /// A tag reference.
     Tag,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::{DataFile, DataFileContent, DataFileFormat};
+    use crate::snapshot::{SnapshotSummary, SnapshotOperation};
+
+    fn data_file(path: &str, records: i64, size: i64) -> DataFile {
+        DataFile::builder(
+            DataFileContent::Data,
+            path,
+            DataFileFormat::Parquet,
+            records,
+            size
+        ).build()
+    }
+
+    #[test]
+    fn initial_snapshot_summary() {
+        let summary = SnapshotSummary::builder()
+            .operation(SnapshotOperation::Append)
+            .added_files(&[
+                data_file("/tmp/a.parquet", 100, 1000),
+                data_file("/tmp/b.parquet", 50, 700),
+            ])
+            .build();
+
+        assert_eq!(summary.operation(), Some(&SnapshotOperation::Append));
+        assert_eq!(summary.get("added-data-files"), Some("2"));
+        assert_eq!(summary.get("added-records"), Some("150"));
+        assert_eq!(summary.get("added-files-size"), Some("1700"));
+        assert_eq!(summary.get("total-data-files"), Some("2"));
+        assert_eq!(summary.get("total-records"), Some("150"));
+        assert_eq!(summary.get("total-files-size"), Some("1700"));
+    }
+
+    #[test]
+    fn append_after_append() {
+        let previous = SnapshotSummary::builder()
+            .operation(SnapshotOperation::Append)
+            .added_files(&[data_file("/tmp/a.parquet", 100, 1000)])
+            .build();
+
+        let summary = SnapshotSummary::builder()
+            .copy_totals(&previous)
+            .operation(SnapshotOperation::Append)
+            .added_files(&[data_file("/tmp/b.parquet", 50, 700)])
+            .build();
+
+        // Delta counters only count the new snapshot, totals accumulate.
+        assert_eq!(summary.get("added-data-files"), Some("1"));
+        assert_eq!(summary.get("added-records"), Some("50"));
+        assert_eq!(summary.get("total-data-files"), Some("2"));
+        assert_eq!(summary.get("total-records"), Some("150"));
+        assert_eq!(summary.get("total-files-size"), Some("1700"));
+    }
+
+    #[test]
+    fn remove_files() {
+        let previous = SnapshotSummary::builder()
+            .operation(SnapshotOperation::Append)
+            .added_files(&[
+                data_file("/tmp/a.parquet", 100, 1000),
+                data_file("/tmp/b.parquet", 50, 700),
+            ])
+            .build();
+
+        let summary = SnapshotSummary::builder()
+            .copy_totals(&previous)
+            .operation(SnapshotOperation::Delete)
+            .removed_files(&[data_file("/tmp/a.parquet", 100, 1000)])
+            .build();
+
+        assert_eq!(summary.operation(), Some(&SnapshotOperation::Delete));
+        assert_eq!(summary.get("deleted-data-files"), Some("1"));
+        assert_eq!(summary.get("deleted-records"), Some("100"));
+        assert_eq!(summary.get("added-data-files"), None);
+        assert_eq!(summary.get("total-data-files"), Some("1"));
+        assert_eq!(summary.get("total-records"), Some("50"));
+        assert_eq!(summary.get("total-files-size"), Some("700"));
+    }
+}
@@ -1,6 +1,10 @@
 //! Interface to Iceberg table ordering.
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::{IcebergResult, IcebergError};
+use crate::schema::{Schema, SchemaType};
 use crate::partition::PartitionTransform;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -54,4 +58,270 @@ pub struct SortOrder {
 impl SortOrder {
     /// Create a new, empty sort order.
     pub fn new() -> Self { Self { order_id: 0, fields: Vec::new() } }
+
+    /// Creates a new builder to easily build a `SortOrder` for the given schema.
+    pub fn builder(order_id: i32, schema: Schema) -> SortOrderBuilder {
+        SortOrderBuilder::new(order_id, schema)
+    }
+
+    /// Returns `true` if this sort order has no fields, i.e. the data is unsorted.
+    pub fn is_unsorted(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Checks that this sort order is valid for the given schema.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SortOrderError`] is returned if one of the sort fields
+    /// references a source field that does not exist within the schema as a primitive
+    /// type. [`IcebergError::PartitionError`] is returned if the transform of one of
+    /// the sort fields can't be applied to its source field.
+    pub fn validate(&self, schema: &Schema) -> IcebergResult<()> {
+        let field_by_id: HashMap<i32, _> = schema.all_fields_by_id().collect();
+
+        for field in &self.fields {
+            let source_field = field_by_id.get(&field.source_id).ok_or_else(|| {
+                IcebergError::SortOrderError {
+                    message: format!(
+                        "source field id {} not found in schema",
+                        field.source_id
+                    )
+                }
+            })?;
+
+            if !matches!(source_field.schema_type(), SchemaType::Primitive(_)) {
+                return Err(IcebergError::SortOrderError {
+                    message: format!(
+                        "can't sort by non-primitive field '{}' with id {}",
+                        source_field.name, source_field.id
+                    )
+                });
+            }
+
+            // Will fail if the transform can't be applied to the source field.
+            field.transform.get_result_type(source_field.schema_type().clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder struct to create new `SortOrder`s easily, referencing source fields by
+/// their names in the schema.
+pub struct SortOrderBuilder {
+    order_id: i32,
+    schema: Schema,
+    fields: Vec<SortField>,
+}
+
+impl SortOrderBuilder {
+    pub fn new(order_id: i32, schema: Schema) -> Self {
+        Self {
+            order_id: order_id,
+            schema: schema,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a field to sort by, after the fields already added.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SortOrderError`] is returned if `source_field` does not refer
+    /// to a primitive field in the schema. [`IcebergError::PartitionError`] is
+    /// returned if the transform can't be applied to the source field.
+    pub fn add_field(
+        mut self,
+        source_field: &str,
+        transform: PartitionTransform,
+        direction: SortDirection,
+        null_order: NullOrder
+    ) -> IcebergResult<Self> {
+        let field = self.schema.get_field_by_name(source_field)
+            .ok_or_else(|| {
+                IcebergError::SortOrderError {
+                    message: format!(
+                        "source field name {source_field} not found in schema"
+                    )
+                }
+            })?;
+
+        let sort_field = SortField {
+            source_id: field.id,
+            transform: transform,
+            direction: direction,
+            null_order: null_order,
+        };
+
+        // Fail early if the field can't be sorted by.
+        SortOrder { order_id: self.order_id, fields: vec![sort_field.clone()] }
+            .validate(&self.schema)?;
+
+        self.fields.push(sort_field);
+        Ok(self)
+    }
+
+    /// Adds an untransformed field to sort by in ascending order, with nulls first.
+    ///
+    /// # Errors
+    ///
+    /// See [`SortOrderBuilder::add_field()`].
+    pub fn asc(self, source_field: &str) -> IcebergResult<Self> {
+        self.add_field(
+            source_field,
+            PartitionTransform::Identity,
+            SortDirection::Ascending,
+            NullOrder::First
+        )
+    }
+
+    /// Adds an untransformed field to sort by in descending order, with nulls last.
+    ///
+    /// # Errors
+    ///
+    /// See [`SortOrderBuilder::add_field()`].
+    pub fn desc(self, source_field: &str) -> IcebergResult<Self> {
+        self.add_field(
+            source_field,
+            PartitionTransform::Identity,
+            SortDirection::Descending,
+            NullOrder::Last
+        )
+    }
+
+    pub fn build(self) -> SortOrder {
+        SortOrder {
+            order_id: self.order_id,
+            fields: self.fields,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::IcebergError;
+    use crate::schema::{Schema, Field, PrimitiveType};
+    use crate::partition::PartitionTransform;
+    use crate::sort::{SortOrder, SortField, SortDirection, NullOrder};
+
+    fn create_schema() -> Schema {
+        Schema::new(0, vec![
+            Field::new_primitive(1, "id", true, PrimitiveType::Long),
+            Field::new_primitive(2, "ts", false, PrimitiveType::Timestamp),
+            Field::new_primitive(3, "name", false, PrimitiveType::String),
+        ])
+    }
+
+    fn sort_field(source_id: i32, transform: PartitionTransform) -> SortField {
+        SortField {
+            source_id: source_id,
+            transform: transform,
+            direction: SortDirection::Ascending,
+            null_order: NullOrder::First
+        }
+    }
+
+    #[test]
+    fn valid_sort_order() {
+        let sort_order = SortOrder {
+            order_id: 1,
+            fields: vec![
+                sort_field(2, PartitionTransform::Day),
+                sort_field(1, PartitionTransform::Identity),
+            ]
+        };
+
+        assert!(sort_order.validate(&create_schema()).is_ok());
+        assert!(SortOrder::new().validate(&create_schema()).is_ok());
+    }
+
+    #[test]
+    fn sort_order_with_missing_column() {
+        let sort_order = SortOrder {
+            order_id: 1,
+            fields: vec![sort_field(4, PartitionTransform::Identity)]
+        };
+
+        assert!(matches!(
+            sort_order.validate(&create_schema()),
+            Err(IcebergError::SortOrderError{..})
+        ));
+    }
+
+    #[test]
+    fn sort_order_with_invalid_transform() {
+        // Can't apply a day transform to a string.
+        let sort_order = SortOrder {
+            order_id: 1,
+            fields: vec![sort_field(3, PartitionTransform::Day)]
+        };
+
+        assert!(matches!(
+            sort_order.validate(&create_schema()),
+            Err(IcebergError::PartitionError{..})
+        ));
+    }
+
+    #[test]
+    fn build_sort_order() {
+        let sort_order = SortOrder::builder(1, create_schema())
+            .add_field(
+                "ts",
+                PartitionTransform::Day,
+                SortDirection::Descending,
+                NullOrder::First
+            ).unwrap()
+            .asc("name").unwrap()
+            .build();
+
+        assert_eq!(sort_order, SortOrder {
+            order_id: 1,
+            fields: vec![
+                SortField {
+                    source_id: 2,
+                    transform: PartitionTransform::Day,
+                    direction: SortDirection::Descending,
+                    null_order: NullOrder::First,
+                },
+                sort_field(3, PartitionTransform::Identity),
+            ]
+        });
+
+        let json = serde_json::to_value(&sort_order).unwrap();
+        assert_eq!(json, json!({
+            "order-id": 1,
+            "fields": [
+                {
+                    "source-id": 2,
+                    "transform": "day",
+                    "direction": "desc",
+                    "null-order": "nulls-first"
+                },
+                {
+                    "source-id": 3,
+                    "transform": "identity",
+                    "direction": "asc",
+                    "null-order": "nulls-first"
+                }
+            ]
+        }));
+        assert_eq!(serde_json::from_value::<SortOrder>(json).unwrap(), sort_order);
+
+        assert!(matches!(
+            SortOrder::builder(1, create_schema()).desc("missing"),
+            Err(IcebergError::SortOrderError{..})
+        ));
+        assert!(matches!(
+            SortOrder::builder(1, create_schema()).add_field(
+                "name",
+                PartitionTransform::Day,
+                SortDirection::Ascending,
+                NullOrder::First
+            ),
+            Err(IcebergError::PartitionError{..})
+        ));
+    }
 }
@@ -133,10 +133,73 @@ impl IcebergStorage {
         }
     }
 
+    /// Initializes a new IcebergStorage at the given location, backed by an existing
+    /// object store.
+    ///
+    /// This allows accessing object stores that [`IcebergStorage::from_url()`] does
+    /// not create, like [`object_store::memory::InMemory`] in tests. Objects are
+    /// addressed by their full path under the location's host, as in S3 buckets.
+    pub fn from_object_store(
+        location: &str,
+        object_store: Arc<dyn ObjectStore>
+    ) -> IcebergResult<Self> {
+        let mut location = location.to_string();
+        if !location.ends_with("/") {
+            location.push_str("/");
+        }
+
+        let url = Url::parse(&location).map_err(|e| {
+            IcebergError::InvalidTableLocation(format!(
+                "Invalid table url {}: {}", location, e
+            ))
+        })?;
+
+        Ok(IcebergStorage {
+            location: url,
+            object_store: object_store,
+            storage_type: IcebergStorageType::S3
+        })
+    }
+
     pub fn location(&self) -> &str {
         self.location.as_str().trim_end_matches("/")
     }
 
+    /// Creates a storage for another location on the same object store, e.g. for a
+    /// table elsewhere in the same S3 bucket.
+    ///
+    /// Storages of S3 buckets share the underlying object store. Local storages are
+    /// created anew for the given location.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidTableLocation`] is returned if the location isn't a
+    /// valid url on the same object store, e.g. in another bucket.
+    pub fn with_location(&self, location: &str) -> IcebergResult<Self> {
+        match self.storage_type {
+            IcebergStorageType::Local => {
+                IcebergStorage::from_url(location, HashMap::new())
+            },
+            IcebergStorageType::S3 => {
+                let storage = IcebergStorage::from_object_store(
+                    location,
+                    self.object_store.clone()
+                )?;
+
+                if storage.location.scheme() != self.location.scheme()
+                    || storage.location.host_str() != self.location.host_str() {
+                    return Err(IcebergError::InvalidTableLocation(format!(
+                        "{} is not in the object store of {}",
+                        location,
+                        self.location()
+                    )));
+                }
+
+                Ok(storage)
+            }
+        }
+    }
+
     /// Returns a reference to the underlying object store.
     pub fn object_store(&self) -> Arc<dyn object_store::ObjectStore> {
         self.object_store.clone()
@@ -229,6 +292,62 @@ impl IcebergStorage {
         Ok(())
     }
 
+    /// Returns whether an object exists at the given path.
+    pub async fn exists(&self, path: &IcebergPath) -> IcebergResult<bool> {
+        let result = self.object_store
+            .head(&self.to_object_store_path(Some(path)).unwrap())
+            .await;
+
+        match result {
+            Ok(..) => Ok(true),
+            Err(ObjectStoreError::NotFound { .. }) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Wraps the `rename()` method of the underlying object store, replacing the
+    /// destination object if it exists.
+    pub async fn rename(&self, from: &IcebergPath, to: &IcebergPath) -> IcebergResult<()> {
+        self.object_store.rename(
+            &self.to_object_store_path(Some(from)).unwrap(),
+            &self.to_object_store_path(Some(to)).unwrap()
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Wraps the `rename_if_not_exists()` method of the underlying object store.
+    ///
+    /// Fails with [`object_store::Error::AlreadyExists`] if the destination object
+    /// exists. Not all object stores support this operation.
+    pub async fn rename_if_not_exists(
+        &self,
+        from: &IcebergPath,
+        to: &IcebergPath
+    ) -> IcebergResult<()> {
+        self.object_store.rename_if_not_exists(
+            &self.to_object_store_path(Some(from)).unwrap(),
+            &self.to_object_store_path(Some(to)).unwrap()
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Lists the directories directly under the given path, or under the storage's
+    /// location if no path is given.
+    pub async fn list_directories(
+        &self,
+        path: Option<&IcebergPath>
+    ) -> IcebergResult<Vec<IcebergPath>> {
+        let result = self.object_store
+            .list_with_delimiter(self.to_object_store_path(path).as_ref())
+            .await?;
+
+        result.common_prefixes.into_iter()
+            .map(|prefix| self.to_iceberg_path(prefix))
+            .collect()
+    }
+
     pub async fn list(
         &self,
         path: Option<&IcebergPath>
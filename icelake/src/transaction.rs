@@ -1,25 +1,32 @@
 //! Interface to Iceberg table transactions.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use rand::Rng;
 use uuid::Uuid;
 use bytes::Bytes;
 use async_trait;
 use futures::future::try_join_all;
+use arrow_arith::boolean::not;
+use arrow_select::filter::filter_record_batch;
 
-use crate::{IcebergResult, IcebergTable, IcebergTableMetadata, IcebergFile};
+use crate::{IcebergResult, IcebergError, IcebergTable, IcebergTableMetadata, IcebergFile};
 use crate::schema::Schema;
 use crate::schema::update::SchemaUpdate;
 use crate::utils;
 use crate::manifest::{
-    ManifestList, ManifestFileType,
+    ManifestList, ManifestFile, ManifestFileType,
     Manifest, ManifestContentType,
     ManifestEntry, ManifestEntryStatus,
     ManifestReader, ManifestWriter,
-    DataFile
+    DataFile, DataFileContent
 };
 use crate::partition::PartitionSpec;
+use crate::sort::SortOrder;
+use crate::expr::{Expression, InclusiveMetricsEvaluator, RowEvaluator};
+use crate::scan::{default_name_mapping, read_data_file};
+use crate::writer::RollingDataWriter;
+use crate::catalog::{Catalog, TableIdentifier, TableCommit};
 use crate::snapshot::{
     Snapshot, SnapshotSummary, SnapshotSummaryBuilder,
     SnapshotOperation, SnapshotLog
@@ -33,6 +40,13 @@ pub trait TableOperation {
         table: &IcebergTable,
         metadata: &IcebergTableMetadata
     ) -> IcebergResult<TransactionState>;
+
+    /// Whether this operation changes the table's schema. Such operations are
+    /// applied before all other operations of a transaction, so that data written
+    /// by the transaction uses the new schema.
+    fn updates_schema(&self) -> bool {
+        false
+    }
 }
 
 /// An empty operation that does not affect tht table.
@@ -55,6 +69,60 @@ impl TableOperation for DoNothingOperation {
         Ok(TransactionState {
             snapshot: None,
             schema: None,
+            sort_order: None,
+            partition_spec: None,
+            properties: None,
+            files: Vec::new()
+        })
+    }
+}
+
+/// An operation for setting and removing table properties.
+pub struct UpdatePropertiesOperation {
+    updates: HashMap<String, String>,
+    removals: Vec<String>,
+}
+
+impl UpdatePropertiesOperation {
+    pub fn new() -> Self {
+        Self {
+            updates: HashMap::new(),
+            removals: Vec::new()
+        }
+    }
+
+    /// Sets a property to the given value, replacing any existing value.
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.removals.retain(|removed| removed != key);
+        self.updates.insert(key.to_string(), value.to_string());
+    }
+
+    /// Removes a property from the table, if it exists.
+    pub fn remove(&mut self, key: &str) {
+        self.updates.remove(key);
+        self.removals.push(key.to_string());
+    }
+}
+
+#[async_trait::async_trait]
+impl TableOperation for UpdatePropertiesOperation {
+    async fn apply(
+        &self,
+        _table: &IcebergTable,
+        metadata: &IcebergTableMetadata
+    ) -> IcebergResult<TransactionState> {
+        let mut properties = metadata.properties.clone().unwrap_or_default();
+        for key in &self.removals {
+            properties.remove(key);
+        }
+        properties.extend(self.updates.clone());
+
+        Ok(TransactionState {
+            snapshot: None,
+            schema: None,
+            sort_order: None,
+            partition_spec: None,
+            properties: Some(properties),
             files: Vec::new()
         })
     }
@@ -82,6 +150,143 @@ impl UpdateSchemaOperation {
     }
 }
 
+/// An operation for replacing the default sort order of the table.
+pub struct ReplaceSortOrderOperation {
+    sort_order: Option<SortOrder>
+}
+
+impl ReplaceSortOrderOperation {
+    pub fn new() -> Self {
+        Self {
+            sort_order: None
+        }
+    }
+
+    /// Sets the sort order to be set as the default sort order.
+    ///
+    /// Note: The order id associated with the input sort order is ignored. An unsorted
+    /// order always has id 0. Otherwise, an existing id is reused if the table
+    /// already has an identical sort order, or a new id is assigned automatically.
+    pub fn set_sort_order(&mut self, sort_order: SortOrder) {
+        self.sort_order = Some(sort_order);
+    }
+}
+
+#[async_trait::async_trait]
+impl TableOperation for ReplaceSortOrderOperation {
+    async fn apply(
+        &self,
+        _table: &IcebergTable,
+        metadata: &IcebergTableMetadata
+    ) -> IcebergResult<TransactionState> {
+        let new_sort_order = match &self.sort_order {
+            Some(sort_order) => {
+                sort_order.validate(metadata.current_schema())?;
+
+                let mut new_sort_order = sort_order.clone();
+                // Order id 0 is reserved for the unsorted order, even if the table
+                // doesn't have it yet. Otherwise reuse the id of an identical sort
+                // order, if any.
+                new_sort_order.order_id = if sort_order.is_unsorted() {
+                    0
+                } else {
+                    metadata.sort_orders
+                        .iter()
+                        .find(|existing| existing.fields == sort_order.fields)
+                        .map(|existing| existing.order_id)
+                        .unwrap_or_else(|| {
+                            metadata.sort_orders
+                                .iter()
+                                .map(|existing| existing.order_id)
+                                .max()
+                                .unwrap_or(0) + 1
+                        })
+                };
+
+                Some(new_sort_order)
+            },
+            None => None
+        };
+
+        Ok(TransactionState {
+            snapshot: None,
+            schema: None,
+            sort_order: new_sort_order,
+            partition_spec: None,
+            properties: None,
+            files: Vec::new()
+        })
+    }
+}
+
+/// An operation for replacing the default partition spec of the table.
+///
+/// The spec is usually built from the table's current spec with
+/// [`IcebergTable::update_spec()`]. Data files written before the change keep
+/// their original specs.
+pub struct UpdatePartitionSpecOperation {
+    partition_spec: Option<PartitionSpec>
+}
+
+impl UpdatePartitionSpecOperation {
+    pub fn new() -> Self {
+        Self {
+            partition_spec: None
+        }
+    }
+
+    /// Sets the partition spec to be set as the default partition spec.
+    ///
+    /// Note: The spec id associated with the input spec is ignored. An existing id
+    /// is reused if the table already has an identical spec, otherwise a new id is
+    /// assigned automatically.
+    pub fn set_partition_spec(&mut self, partition_spec: PartitionSpec) {
+        self.partition_spec = Some(partition_spec);
+    }
+}
+
+#[async_trait::async_trait]
+impl TableOperation for UpdatePartitionSpecOperation {
+    async fn apply(
+        &self,
+        _table: &IcebergTable,
+        metadata: &IcebergTableMetadata
+    ) -> IcebergResult<TransactionState> {
+        let new_partition_spec = match &self.partition_spec {
+            Some(partition_spec) => {
+                let specs = metadata.partition_specs();
+                let spec_id = specs.iter()
+                    .find(|existing| existing.fields == *partition_spec.fields())
+                    .map(|existing| existing.spec_id)
+                    .unwrap_or_else(|| {
+                        specs.iter().map(|existing| existing.spec_id).max().unwrap_or(0) + 1
+                    });
+
+                Some(PartitionSpec::try_new(
+                    spec_id,
+                    partition_spec.fields().clone(),
+                    metadata.current_schema().clone()
+                )?)
+            },
+            None => None
+        };
+
+        Ok(TransactionState {
+            snapshot: None,
+            schema: None,
+            sort_order: None,
+            partition_spec: new_partition_spec,
+            properties: None,
+            files: Vec::new()
+        })
+    }
+}
+
+/// Converts the failure of a spawned task, e.g. a panic, into an error.
+fn task_error(err: tokio::task::JoinError) -> IcebergError {
+    IcebergError::CustomError { message: format!("background task failed: {err}") }
+}
+
 fn generate_new_snapshot(
     snapshot_id: i64,
     metadata: &IcebergTableMetadata,
@@ -136,12 +341,26 @@ impl TableOperation for UpdateSchemaOperation {
         Ok(TransactionState {
             snapshot: None,
             schema: new_schema,
+            sort_order: None,
+            partition_spec: None,
+            properties: None,
             files: Vec::new()
         })
     }
+
+    fn updates_schema(&self) -> bool {
+        self.schema.is_some()
+    }
 }
 
 /// An operation to append data files to the table.
+///
+/// The appended files are written to a single new manifest, which is added first to
+/// the manifest list of the current snapshot, so the newest data is listed first.
+/// Existing manifests are reused as they are and never rewritten or merged, i.e. a
+/// fast append: a commit only writes one manifest and one manifest list regardless
+/// of the table's size, but every commit adds a manifest, so scans planned after
+/// many small appends read many small manifests.
 pub struct AppendFilesOperation {
     appended_files: Vec<DataFile>,
 }
@@ -217,8 +436,8 @@ impl TableOperation for AppendFilesOperation {
         manifest_file.set_bytes(manifest_content);
 
         // Update the manifest list with a ManifestFile pointing to the new
-        // manifest file.
-        manifest_list.push(manifest_file_entry);
+        // manifest file, listed before the existing manifests.
+        manifest_list.insert(0, manifest_file_entry);
 
         // Write a new manifest list to storage
         let manifest_list_file = table.new_metadata_file(
@@ -235,13 +454,9 @@ impl TableOperation for AppendFilesOperation {
         if let Some(snapshot) = current_snapshot {
             summary_builder.copy_totals(&snapshot.summary);
         }
-        summary_builder.operation(SnapshotOperation::Append);
-        for data_file in &self.appended_files {
-            summary_builder.added_data_file(
-                data_file.record_count,
-                data_file.file_size_in_bytes
-            );
-        }
+        summary_builder
+            .operation(SnapshotOperation::Append)
+            .added_files(&self.appended_files);
 
         Ok(TransactionState {
             snapshot: Some(generate_new_snapshot(
@@ -251,6 +466,9 @@ impl TableOperation for AppendFilesOperation {
                 summary_builder.build()
             )),
             schema: None,
+            sort_order: None,
+            partition_spec: None,
+            properties: None,
             files: vec![manifest_list_file, manifest_file]
         })
     }
@@ -259,16 +477,23 @@ impl TableOperation for AppendFilesOperation {
 /// A logical overwrite operation to append and remove data files at the same time.
 pub struct OverwriteFilesOperation {
     appended_files: Vec<DataFile>,
+    // Files appended with the partition spec of the files they replace, whose
+    // partition tuples may not match the default spec.
+    rewritten_files: Vec<(PartitionSpec, DataFile)>,
     deleted_files: Vec<String>,
     delete_all: bool,
+    // Operation recorded in the summary of the new snapshot.
+    operation: SnapshotOperation,
 }
 
 impl OverwriteFilesOperation {
     pub fn new() -> Self {
         Self {
             appended_files: Vec::new(),
+            rewritten_files: Vec::new(),
             deleted_files: Vec::new(),
-            delete_all: false
+            delete_all: false,
+            operation: SnapshotOperation::Overwrite,
         }
     }
 
@@ -304,19 +529,21 @@ impl OverwriteFilesOperation {
             )
     }
 
+    async fn current_manifest_list(
+        table: &IcebergTable,
+        metadata: &IcebergTableMetadata,
+    ) -> IcebergResult<ManifestList> {
+        match metadata.current_snapshot() {
+            Some(current_snapshot) => table.read_manifest_list(current_snapshot).await,
+            None => Ok(ManifestList::new())
+        }
+    }
+
     async fn current_manifests(
         &self,
         table: &IcebergTable,
-        metadata: &IcebergTableMetadata,
+        manifest_list: &ManifestList,
     ) -> IcebergResult<Vec<Manifest>> {
-        let current_snapshot = metadata.current_snapshot();
-        let manifest_list = match current_snapshot {
-            Some(current_snapshot) => {
-                table.read_manifest_list(current_snapshot).await?
-            },
-            None => ManifestList::new()
-        };
-
         // Obtain all current manifests tracking data files in parallel.
         let futures = manifest_list.manifest_files().iter()
             .filter(|manifest_file| manifest_file.content == ManifestFileType::Data)
@@ -331,7 +558,8 @@ impl OverwriteFilesOperation {
                 })
             });
 
-        try_join_all(futures).await.unwrap()
+        try_join_all(futures).await
+            .map_err(task_error)?
             .into_iter()
             .collect()
     }
@@ -340,25 +568,28 @@ impl OverwriteFilesOperation {
         &self,
         table: &IcebergTable,
         metadata: &IcebergTableMetadata,
+        manifest_list: &ManifestList,
         snapshot_id: i64,
     ) -> IcebergResult<(Vec<Manifest>, SnapshotSummaryBuilder)> {
         let mut summary_builder = SnapshotSummary::builder();
-        summary_builder.operation(SnapshotOperation::Overwrite);
+        summary_builder.operation(self.operation.clone());
 
-        let manifests = self.current_manifests(table, metadata).await?;
+        let manifests = self.current_manifests(table, manifest_list).await?;
 
         // For each ManifestEntry change its status to Existing or Deleted.
         let mut groups: HashMap<i32, Vec<ManifestEntry>> = HashMap::new();
         let mut specs: HashMap<i32, PartitionSpec> = HashMap::new();
+        let mut live_files = HashSet::new();
         for manifest in manifests.into_iter() {
             let spec_id = manifest.partition_spec().spec_id();
             // Save the partition spec for reconstructing the manifest later.
             specs.entry(spec_id)
                 .or_insert(manifest.partition_spec().clone());
 
-            for entry in manifest.into_entries() {
+            for mut entry in manifest.into_entries() {
                 // Do not keep entries deleted in a previous manifest
                 if entry.status != ManifestEntryStatus::Deleted {
+                    live_files.insert(entry.data_file().file_path.clone());
                     let is_deleted = self.is_deleted(&entry.data_file().file_path);
 
                     if is_deleted {
@@ -366,6 +597,8 @@ impl OverwriteFilesOperation {
                             entry.data_file().record_count,
                             entry.data_file().file_size_in_bytes
                         );
+                        // Deleted entries belong to the snapshot deleting them.
+                        entry.snapshot_id = Some(snapshot_id);
                     } else {
                         summary_builder.existing_data_file(
                             entry.data_file().record_count,
@@ -386,11 +619,24 @@ impl OverwriteFilesOperation {
             }
         }
 
+        // Files already deleted, e.g. by an earlier operation of the same
+        // transaction, can't be deleted again.
+        if let Some(missing) = self.deleted_files.iter()
+            .find(|deleted_file| !live_files.contains(*deleted_file)) {
+            return Err(IcebergError::Validation {
+                message: format!("cannot delete {missing}, not a live data file")
+            });
+        }
+
         // Add entries for new files
-        specs.entry(metadata.default_spec_id)
-            .or_insert_with(|| metadata.current_partition_spec());
-        for data_file in &self.appended_files {
-            groups.entry(metadata.default_spec_id)
+        let default_spec = metadata.current_partition_spec();
+        let appended_files = self.appended_files.iter()
+            .map(|data_file| (&default_spec, data_file))
+            .chain(self.rewritten_files.iter().map(|(spec, file)| (spec, file)));
+        for (spec, data_file) in appended_files {
+            specs.entry(spec.spec_id())
+                .or_insert_with(|| spec.clone());
+            groups.entry(spec.spec_id())
                 .or_insert_with(Vec::new)
                 .push(ManifestEntry::new(
                     ManifestEntryStatus::Added,
@@ -430,8 +676,10 @@ impl TableOperation for OverwriteFilesOperation {
     ) -> IcebergResult<TransactionState> {
         let new_snapshot_id = rand::thread_rng().gen_range(0..i64::MAX);
 
-        let (manifests, summary_builder) =
-            self.create_manifests(table, metadata, new_snapshot_id).await?;
+        let current_manifest_list = Self::current_manifest_list(table, metadata).await?;
+        let (manifests, summary_builder) = self.create_manifests(
+            table, metadata, &current_manifest_list, new_snapshot_id
+        ).await?;
 
         let mut manifest_list = ManifestList::new();
         let mut files: Vec<IcebergFile> = Vec::new();
@@ -459,6 +707,14 @@ impl TableOperation for OverwriteFilesOperation {
             manifest_list.push(manifest_file_entry);
         }
 
+        // Delete manifests are kept as they are, so rows deleted from the remaining
+        // data files stay deleted.
+        for manifest_file in current_manifest_list.manifest_files() {
+            if manifest_file.content == ManifestFileType::Delete {
+                manifest_list.push(manifest_file.clone());
+            }
+        }
+
         let manifest_list_file = table.new_metadata_file(
             &format!(
                 "snap-{}-1-{}.avro",
@@ -480,6 +736,318 @@ impl TableOperation for OverwriteFilesOperation {
         Ok(TransactionState {
             snapshot: Some(snapshot),
             schema: None,
+            sort_order: None,
+            partition_spec: None,
+            properties: None,
+            files: files
+        })
+    }
+}
+
+/// An operation to delete the rows matching a filter from the table by rewriting the
+/// data files containing them, i.e. copy-on-write.
+///
+/// Data files whose column metrics rule out matching rows are kept as they are. Every
+/// other data file is read and, if any of its rows match the filter, replaced with a
+/// new file in the same partition holding the rows that don't match. Files whose
+/// rows all match are removed without a replacement.
+///
+/// The operation of the new snapshot is `delete` if files were only removed, and
+/// `overwrite` if some were rewritten.
+pub struct DeleteRowsOperation {
+    filter: Expression,
+}
+
+impl DeleteRowsOperation {
+    /// Creates an operation deleting the rows matching the filter.
+    pub fn new(filter: Expression) -> Self {
+        Self {
+            filter: filter,
+        }
+    }
+
+    /// Finds the data files containing rows matching the filter, writing new files
+    /// with their remaining rows, and returns an overwrite operation replacing them.
+    async fn rewrite_files(
+        &self,
+        table: &IcebergTable,
+        metadata: &IcebergTableMetadata
+    ) -> IcebergResult<OverwriteFilesOperation> {
+        let schema = metadata.current_schema();
+        let filter = self.filter.bind(schema)?;
+        let metrics_evaluator = InclusiveMetricsEvaluator::new(&filter);
+        let row_evaluator = RowEvaluator::new(&filter);
+        let name_mapping = default_name_mapping(metadata)?;
+
+        // Rows deleted by delete files would reappear in the rewritten files.
+        let manifest_list =
+            OverwriteFilesOperation::current_manifest_list(table, metadata).await?;
+        if manifest_list.manifest_files().iter()
+            .any(|manifest_file| manifest_file.content == ManifestFileType::Delete) {
+            return Err(IcebergError::Unsupported(
+                "deleting rows from a table with delete files".to_string()
+            ));
+        }
+
+        let mut overwrite = OverwriteFilesOperation::new();
+        let manifests = overwrite.current_manifests(table, &manifest_list).await?;
+        for manifest in manifests {
+            // Rewritten files keep the partition tuples of the files they replace.
+            let spec = manifest.partition_spec().clone();
+            for entry in manifest.into_entries() {
+                let data_file = entry.data_file();
+                if entry.deleted() || !metrics_evaluator.eval(data_file)? {
+                    continue;
+                }
+
+                let batches = read_data_file(
+                    table,
+                    schema,
+                    name_mapping.as_ref(),
+                    data_file
+                ).await?;
+
+                let mut matched = 0;
+                let mut remaining = Vec::with_capacity(batches.len());
+                for batch in batches {
+                    let matches = row_evaluator.eval(&batch)?;
+                    matched += matches.true_count();
+                    remaining.push(filter_record_batch(&batch, &not(&matches)?)?);
+                }
+
+                if matched == 0 {
+                    continue;
+                }
+
+                overwrite.delete_file(&data_file.file_path);
+                if remaining.iter().all(|batch| batch.num_rows() == 0) {
+                    continue;
+                }
+
+                let mut writer = RollingDataWriter::try_new(
+                    schema.clone(),
+                    data_file.partition.clone()
+                )?.with_compression(
+                    metadata.parquet_compression_codec()?,
+                    metadata.parquet_compression_level()?
+                )?.with_metrics_config(metadata.metrics_config()?)
+                    .with_bloom_filter_config(metadata.bloom_filter_config()?);
+                for batch in remaining.iter().filter(|batch| batch.num_rows() > 0) {
+                    writer.write(batch)?;
+                }
+                for rewritten_file in writer.close(table).await? {
+                    overwrite.rewritten_files.push((spec.clone(), rewritten_file));
+                }
+            }
+        }
+
+        if overwrite.rewritten_files.is_empty() {
+            overwrite.operation = SnapshotOperation::Delete;
+        }
+
+        Ok(overwrite)
+    }
+}
+
+#[async_trait::async_trait]
+impl TableOperation for DeleteRowsOperation {
+    async fn apply(
+        &self,
+        table: &IcebergTable,
+        metadata: &IcebergTableMetadata
+    ) -> IcebergResult<TransactionState> {
+        self.rewrite_files(table, metadata).await?
+            .apply(table, metadata)
+            .await
+    }
+}
+
+/// An operation to merge small data manifests into fewer, larger manifests, e.g.
+/// after many fast appends.
+///
+/// Data manifests of the current snapshot smaller than the target size are packed,
+/// in order, into groups of the same partition spec whose total size doesn't exceed
+/// the target, and the manifests of each group are replaced with a single manifest.
+/// Within a merged manifest, entries of the same partition are kept together. Every
+/// live entry keeps its status, snapshot id and sequence numbers, while entries of
+/// deleted files are dropped. Larger manifests and delete manifests are kept as they
+/// are.
+///
+/// The new snapshot's operation is `replace`. No snapshot is created if there is
+/// nothing to merge.
+pub struct RewriteManifestsOperation {
+    target_size_bytes: u64,
+}
+
+impl RewriteManifestsOperation {
+    /// Creates an operation merging manifests smaller than `target_size_bytes`.
+    pub fn new(target_size_bytes: u64) -> Self {
+        Self {
+            target_size_bytes: target_size_bytes,
+        }
+    }
+
+    /// Groups the small data manifests of the manifest list to be merged together,
+    /// returning the groups and the manifests to keep as they are.
+    fn plan_groups(
+        &self,
+        manifest_list: &ManifestList
+    ) -> (Vec<Vec<ManifestFile>>, Vec<ManifestFile>) {
+        let mut kept = Vec::new();
+        // Group currently being filled for each partition spec, and its size.
+        let mut open: HashMap<i32, (Vec<ManifestFile>, u64)> = HashMap::new();
+        let mut groups = Vec::new();
+
+        for manifest_file in manifest_list.manifest_files() {
+            let size = u64::try_from(manifest_file.manifest_length).unwrap_or(0);
+            if manifest_file.content != ManifestFileType::Data
+                || size >= self.target_size_bytes {
+                kept.push(manifest_file.clone());
+                continue;
+            }
+
+            let (group, group_size) = open.entry(manifest_file.partition_spec_id)
+                .or_insert_with(|| (Vec::new(), 0));
+            if !group.is_empty() && *group_size + size > self.target_size_bytes {
+                groups.push(std::mem::take(group));
+                *group_size = 0;
+            }
+            group.push(manifest_file.clone());
+            *group_size += size;
+        }
+        groups.extend(open.into_values().map(|(group, _)| group));
+
+        // A manifest alone in its group has nothing to be merged with.
+        for group in std::mem::take(&mut groups) {
+            match group.len() {
+                0 => {},
+                1 => kept.extend(group),
+                _ => groups.push(group),
+            }
+        }
+
+        (groups, kept)
+    }
+
+    /// Reads the manifests of the group and merges their live entries into a
+    /// single manifest.
+    async fn merge(
+        &self,
+        table: &IcebergTable,
+        group: &[ManifestFile]
+    ) -> IcebergResult<Manifest> {
+        let storage = table.storage();
+
+        let mut merged: Option<Manifest> = None;
+        // Entries by partition, in order of first appearance.
+        let mut partitions: Vec<(String, Vec<ManifestEntry>)> = Vec::new();
+        for manifest_file in group {
+            let path = storage.create_path_from_url(&manifest_file.manifest_path)?;
+            let bytes = storage.get(&path).await?;
+            let manifest = ManifestReader::for_manifest_file(manifest_file).read(&bytes)?;
+
+            merged.get_or_insert_with(|| Manifest::new(
+                manifest.schema().clone(),
+                manifest.partition_spec().clone(),
+                ManifestContentType::Data
+            ));
+
+            for entry in manifest.into_entries().filter(|entry| !entry.deleted()) {
+                let partition = entry.data_file().partition.to_string();
+                match partitions.iter_mut().find(|(key, _)| *key == partition) {
+                    Some((_, entries)) => entries.push(entry),
+                    None => partitions.push((partition, vec![entry])),
+                }
+            }
+        }
+
+        // Can't fail, groups to merge are never empty.
+        let mut merged = merged.unwrap();
+        for (_, entries) in partitions {
+            merged.add_manifest_entries(entries);
+        }
+
+        Ok(merged)
+    }
+}
+
+#[async_trait::async_trait]
+impl TableOperation for RewriteManifestsOperation {
+    async fn apply(
+        &self,
+        table: &IcebergTable,
+        metadata: &IcebergTableMetadata
+    ) -> IcebergResult<TransactionState> {
+        let current_snapshot = match metadata.current_snapshot() {
+            Some(snapshot) => snapshot,
+            None => return DoNothingOperation::new().apply(table, metadata).await,
+        };
+
+        let (groups, kept) = self.plan_groups(
+            &table.read_manifest_list(current_snapshot).await?
+        );
+        if groups.is_empty() {
+            return DoNothingOperation::new().apply(table, metadata).await;
+        }
+
+        let new_snapshot_id = rand::thread_rng().gen_range(0..i64::MAX);
+
+        let mut manifest_list = ManifestList::new();
+        let mut files: Vec<IcebergFile> = Vec::new();
+        for (i, group) in groups.iter().enumerate() {
+            let manifest = self.merge(table, group).await?;
+
+            let mut manifest_file = table.new_metadata_file(
+                &format!("{}-m{}.avro", Uuid::new_v4().to_string(), i),
+                Bytes::new()
+            )?;
+
+            let writer = ManifestWriter::new(
+                metadata.last_sequence_number,
+                new_snapshot_id
+            );
+            let (manifest_content, manifest_file_entry) = writer.write(
+                &manifest_file.url(), &manifest
+            )?;
+
+            manifest_file.set_bytes(manifest_content);
+            files.push(manifest_file);
+            manifest_list.push(manifest_file_entry);
+        }
+        for manifest_file in kept {
+            manifest_list.push(manifest_file);
+        }
+
+        let manifest_list_file = table.new_metadata_file(
+            &format!(
+                "snap-{}-1-{}.avro",
+                new_snapshot_id,
+                Uuid::new_v4().to_string()
+            ),
+            Bytes::from(manifest_list.encode()?)
+        )?;
+
+        // The table's data is unchanged.
+        let mut summary_builder = SnapshotSummary::builder();
+        summary_builder
+            .copy_totals(&current_snapshot.summary)
+            .operation(SnapshotOperation::Replace);
+
+        let snapshot = generate_new_snapshot(
+            new_snapshot_id,
+            metadata,
+            manifest_list_file.url(),
+            summary_builder.build()
+        );
+
+        files.push(manifest_list_file);
+
+        Ok(TransactionState {
+            snapshot: Some(snapshot),
+            schema: None,
+            sort_order: None,
+            partition_spec: None,
+            properties: None,
             files: files
         })
     }
@@ -492,10 +1060,163 @@ pub struct TransactionState {
     snapshot: Option<Snapshot>,
     /// Updated schema
     schema: Option<Schema>,
+    /// Updated default sort order
+    sort_order: Option<SortOrder>,
+    /// Updated default partition spec
+    partition_spec: Option<PartitionSpec>,
+    /// Updated table properties
+    properties: Option<HashMap<String, String>>,
     /// List of files pending to be written to the table's storage.
     files: Vec<IcebergFile>
 }
 
+/// An operation to add data files and delete files to the table at the same time,
+/// e.g. to update rows in place by deleting them and writing their new versions.
+///
+/// Equality delete files only apply to data files with a strictly lower sequence
+/// number, so rows added by the same operation are not affected by its equality
+/// deletes. Position delete files apply to data files with a lower or equal
+/// sequence number, and can delete rows of data files added by the same operation.
+pub struct RowDeltaOperation {
+    added_rows: Vec<DataFile>,
+    added_deletes: Vec<DataFile>,
+}
+
+impl RowDeltaOperation {
+    pub fn new() -> Self {
+        Self {
+            added_rows: Vec::new(),
+            added_deletes: Vec::new()
+        }
+    }
+
+    /// Adds a data file to be appended.
+    pub fn add_rows(&mut self, file: DataFile) {
+        self.added_rows.push(file);
+    }
+
+    /// Adds a position or equality delete file to be appended.
+    pub fn add_deletes(&mut self, file: DataFile) {
+        self.added_deletes.push(file);
+    }
+
+    fn create_manifest(
+        metadata: &IcebergTableMetadata,
+        snapshot_id: i64,
+        content: ManifestContentType,
+        files: &[DataFile]
+    ) -> Manifest {
+        let mut manifest = Manifest::new(
+            metadata.current_schema().clone(),
+            metadata.current_partition_spec().clone(),
+            content
+        );
+        for file in files {
+            manifest.add_manifest_entry(ManifestEntry::new(
+                ManifestEntryStatus::Added,
+                snapshot_id,
+                file.clone()
+            ));
+        }
+        manifest
+    }
+}
+
+#[async_trait::async_trait]
+impl TableOperation for RowDeltaOperation {
+    async fn apply(
+        &self,
+        table: &IcebergTable,
+        metadata: &IcebergTableMetadata
+    ) -> IcebergResult<TransactionState> {
+        if let Some(file) = self.added_deletes.iter()
+            .find(|file| file.content == DataFileContent::Data) {
+            return Err(IcebergError::CustomError {
+                message: format!("{} is not a delete file", file.file_path)
+            });
+        }
+
+        let current_snapshot = metadata.current_snapshot();
+        let mut manifest_list = match current_snapshot {
+            Some(current_snapshot) => {
+                table.read_manifest_list(current_snapshot).await?
+            },
+            None => ManifestList::new()
+        };
+
+        let new_snapshot_id = rand::thread_rng().gen_range(0..i64::MAX);
+
+        let mut files = Vec::new();
+        let manifests = [
+            (ManifestContentType::Data, &self.added_rows),
+            (ManifestContentType::Deletes, &self.added_deletes),
+        ];
+        for (i, (content, added_files)) in manifests.into_iter().enumerate() {
+            if added_files.is_empty() {
+                continue;
+            }
+
+            let manifest = Self::create_manifest(
+                metadata, new_snapshot_id, content, added_files
+            );
+
+            let mut manifest_file = table.new_metadata_file(
+                &format!("{}-m{}.avro", Uuid::new_v4().to_string(), i),
+                Bytes::new()
+            )?;
+
+            // Encode the on-disk manifest file.
+            let writer = ManifestWriter::new(
+                metadata.last_sequence_number,
+                new_snapshot_id
+            );
+            let (manifest_content, manifest_file_entry) = writer.write(
+                &manifest_file.url(), &manifest
+            )?;
+
+            manifest_file.set_bytes(manifest_content);
+            files.push(manifest_file);
+
+            manifest_list.push(manifest_file_entry);
+        }
+
+        let manifest_list_file = table.new_metadata_file(
+            &format!(
+                "snap-{}-1-{}.avro",
+                new_snapshot_id,
+                Uuid::new_v4().to_string()
+            ),
+            Bytes::from(manifest_list.encode()?)
+        )?;
+
+        let mut summary_builder = SnapshotSummary::builder();
+        if let Some(snapshot) = current_snapshot {
+            summary_builder.copy_totals(&snapshot.summary);
+        }
+        summary_builder
+            .operation(SnapshotOperation::Overwrite)
+            .added_files(&self.added_rows)
+            .added_files(&self.added_deletes);
+
+        let snapshot = generate_new_snapshot(
+            new_snapshot_id,
+            metadata,
+            manifest_list_file.url(),
+            summary_builder.build()
+        );
+        files.insert(0, manifest_list_file);
+
+        Ok(TransactionState {
+            snapshot: Some(snapshot),
+            schema: None,
+            sort_order: None,
+            partition_spec: None,
+            properties: None,
+            files: files
+        })
+    }
+}
+
 /// A transaction for performing multiple operations on a table.
 pub struct Transaction<'a> {
     table: &'a mut IcebergTable,
@@ -515,20 +1236,147 @@ impl<'a> Transaction<'a> {
         self.operations.push(operation);
     }
 
+    /// Appends data files to the table as part of this transaction.
+    ///
+    /// On commit, the files are written to a new manifest, listed before the
+    /// manifests of the current snapshot in a new snapshot whose summary totals
+    /// include the appended files. Shorthand for adding an [`AppendFilesOperation`].
+    ///
+    /// Manifests are never merged on commit, so this is the same as
+    /// [`Transaction::fast_append()`].
+    pub fn append(&mut self, data_files: Vec<DataFile>) {
+        self.fast_append(data_files);
+    }
+
+    /// Appends data files to the table as part of this transaction, without
+    /// rewriting any of the table's existing manifests.
+    ///
+    /// On commit, only a manifest of the appended files and a new manifest list
+    /// starting with it, followed by the current snapshot's manifests, are written.
+    /// Every commit adds a manifest, which
+    /// [`IcebergTable::rewrite_manifests()`](crate::IcebergTable::rewrite_manifests)
+    /// can later merge.
+    pub fn fast_append(&mut self, data_files: Vec<DataFile>) {
+        let mut operation = AppendFilesOperation::new();
+        operation.append_files(data_files);
+        self.add_operation(Box::new(operation));
+    }
+
+    /// Deletes the rows matching the filter from the table as part of this
+    /// transaction, rewriting the data files containing them.
+    ///
+    /// Data files are skipped according to their column metrics, like in a table
+    /// scan. Shorthand for adding a [`DeleteRowsOperation`].
+    pub fn delete(&mut self, filter: Expression) {
+        self.add_operation(Box::new(DeleteRowsOperation::new(filter)));
+    }
+
     /// Attempts to commit this transaction to the table, applying all operations
-    /// one after the other and generating new table metadata.
-    pub async fn commit(self) -> IcebergResult<()> {
-        for operation in self.operations {
-            let current_metadata = self.table.current_metadata()?;
-            let mut new_metadata = (*current_metadata).clone();
+    /// one after the other and generating a single new version of the table metadata.
+    ///
+    /// Operations that update the schema are applied first, followed by the rest of
+    /// the operations in the order they were added. Each operation sees the changes
+    /// of the operations applied before it, and the table is only updated once all
+    /// of the operations were applied successfully.
+    pub async fn commit(mut self) -> IcebergResult<()> {
+        let new_metadata = self.apply().await?;
+
+        // TODO: If a commit fails we need to revert changes.
+        self.table.commit(new_metadata).await?;
+
+        Ok(())
+    }
+
+    /// Attempts to commit this transaction to the table through a catalog, which
+    /// atomically replaces the table's metadata only if no other writer changed it
+    /// since the table was loaded.
+    ///
+    /// The operations are applied like in [`Transaction::commit()`], and the
+    /// resulting changes are committed as a [`TableCommit`] requiring the table's
+    /// current snapshot and schema to be unchanged. On success, the table is updated
+    /// to the committed version.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::CommitConflict`] is returned if the table was changed by
+    /// another writer. The table is left unchanged, and the caller may reload it
+    /// from the catalog and retry the transaction.
+    pub async fn commit_to_catalog(
+        mut self,
+        catalog: &dyn Catalog,
+        identifier: &TableIdentifier
+    ) -> IcebergResult<()> {
+        let base = self.table.current_metadata()?.clone();
+        let new_metadata = self.apply().await?;
+
+        // TODO: Files written by the operations are left orphaned if the commit
+        // fails.
+        let commit = TableCommit::from_changes(identifier.clone(), &base, &new_metadata);
+        *self.table = catalog.commit_transaction(commit).await?;
+
+        Ok(())
+    }
+
+    /// Applies all operations to the table's current metadata and writes the files
+    /// they create to storage, returning the new metadata.
+    async fn apply(&mut self) -> IcebergResult<IcebergTableMetadata> {
+        let mut new_metadata = self.table.current_metadata()?.clone();
+        let mut operations = std::mem::take(&mut self.operations);
+
+        // Stable sort, preserving the order of the rest of the operations.
+        operations.sort_by_key(|operation| !operation.updates_schema());
+
+        // Metadata replaced by an earlier operation of this transaction. A later
+        // operation replacing it again would silently discard the earlier change.
+        let mut schema_replaced = false;
+        let mut sort_order_replaced = false;
+        let mut partition_spec_replaced = false;
+        let conflict = |replaced: &str| IcebergError::Validation {
+            message: format!(
+                "more than one operation in the transaction replaces the {replaced}"
+            )
+        };
+
+        for operation in operations {
             new_metadata.last_updated_ms = utils::current_time_ms()?;
+            // Operations use the next sequence number for the snapshot they create.
             new_metadata.last_sequence_number += 1;
 
             // Apply the operation, potentially producing a new snapshot and a new
             // schema.
             let state = operation.apply(self.table, &new_metadata).await?;
 
+            if state.snapshot.is_none() {
+                new_metadata.last_sequence_number -= 1;
+            }
+
+            if state.schema.is_some() && std::mem::replace(&mut schema_replaced, true) {
+                return Err(conflict("schema"));
+            }
+            if state.sort_order.is_some()
+                && std::mem::replace(&mut sort_order_replaced, true) {
+                return Err(conflict("sort order"));
+            }
+            if state.partition_spec.is_some()
+                && std::mem::replace(&mut partition_spec_replaced, true) {
+                return Err(conflict("partition spec"));
+            }
+
             if let Some(snapshot) = state.snapshot {
+                // Every snapshot must build on the one created by the previous
+                // operation, otherwise its changes would be lost.
+                if snapshot.parent_snapshot_id != new_metadata.current_snapshot_id {
+                    return Err(IcebergError::Validation {
+                        message: format!(
+                            "snapshot {} is based on snapshot {:?} instead of the \
+                            transaction's current snapshot {:?}",
+                            snapshot.snapshot_id,
+                            snapshot.parent_snapshot_id,
+                            new_metadata.current_snapshot_id
+                        )
+                    });
+                }
+
                 // Set the new snapshot set as current.
                 new_metadata.current_snapshot_id = Some(snapshot.snapshot_id);
                 new_metadata.snapshot_log
@@ -540,11 +1388,32 @@ impl<'a> Transaction<'a> {
             }
 
             if let Some(schema) = state.schema {
+                new_metadata.last_column_id =
+                    new_metadata.last_column_id.max(schema.max_field_id());
                 new_metadata.current_schema_id = schema.id();
                 new_metadata.schemas.push(schema);
             }
 
-            // Write all files created by the operation to storage.
+            if let Some(sort_order) = state.sort_order {
+                new_metadata.default_sort_order_id = sort_order.order_id;
+                if !new_metadata.sort_orders
+                    .iter()
+                    .any(|existing| existing.order_id == sort_order.order_id) {
+                    new_metadata.sort_orders.push(sort_order);
+                }
+            }
+
+            if let Some(partition_spec) = state.partition_spec {
+                new_metadata.default_spec_id = partition_spec.spec_id();
+                new_metadata.add_partition_spec(partition_spec.model());
+            }
+
+            if let Some(properties) = state.properties {
+                new_metadata.properties = Some(properties);
+            }
+
+            // Write all files created by the operation to storage. This must be done
+            // before applying the next operation, which may read them.
             let futures = state.files.into_iter()
                 .map(|file| {
                     tokio::spawn(async move {
@@ -552,14 +1421,12 @@ impl<'a> Transaction<'a> {
                     })
                 });
 
-            try_join_all(futures).await.unwrap()
+            try_join_all(futures).await
+                .map_err(task_error)?
                 .into_iter()
                 .collect::<IcebergResult<Vec<_>>>()?;
-
-            // TODO: If a commit fails we need to revert changes.
-            self.table.commit(new_metadata).await?
         }
 
-        Ok(())
+        Ok(new_metadata)
     }
 }
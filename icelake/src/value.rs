@@ -18,7 +18,7 @@ use crate::schema::{
 /// The value can be serialized and deserialized from JSON using the `serde_json`
 /// module. The JSON form adheres to the
 /// [Iceberg single-value serialization spec](https://iceberg.apache.org/spec/#json-single-value-serialization).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Value {
     Boolean(bool),
@@ -26,7 +26,17 @@ pub enum Value {
     Long(i64),
     Float(f32),
     Double(f64),
-    // TODO: Decimal
+    /// A fixed point decimal, whose value is `unscaled * 10^-scale`.
+    ///
+    /// Decimals are serialized to JSON as strings, e.g. `"-1.50"`. They can't be
+    /// told apart from other strings without knowing the field type, so they are
+    /// never deserialized from JSON.
+    #[serde(serialize_with = "decimal_serde::serialize", skip_deserializing)]
+    Decimal {
+        unscaled: i128,
+        precision: u8,
+        scale: u8,
+    },
     Date(NaiveDate),
     #[serde(with = "time_serde")]
     Time(NaiveTime),
@@ -86,6 +96,12 @@ impl Value {
             Value::Double(_) => {
                 Ok(SchemaType::Primitive(PrimitiveType::Double))
             },
+            Value::Decimal { precision, scale, .. } => {
+                Ok(SchemaType::Primitive(PrimitiveType::Decimal {
+                    precision: *precision,
+                    scale: *scale
+                }))
+            },
             Value::Date(_) => {
                 Ok(SchemaType::Primitive(PrimitiveType::Date))
             },
@@ -150,60 +166,262 @@ impl Value {
     }
 }
 
-impl std::fmt::Display for Value {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Value::Boolean(x) => write!(f, "{}", x),
-            Value::Int(x) => write!(f, "{}", x),
-            Value::Long(x) => write!(f, "{}", x),
-            Value::Float(x) => write!(f, "{}", x),
-            Value::Double(x) => write!(f, "{}", x),
-            Value::Date(d) => write!(f, "{}", d.format("%Y-%m-%d")),
-            Value::Time(t) => write!(f, "{}", t.format("%H:%M:%S%.6f")),
-            Value::Timestamp(ts) => {
-                write!(f, "{}", ts.format("%Y-%m-%dT%H:%M:%S%.6f"))
+impl Value {
+    /// Decodes a value of the given type from its binary representation, according
+    /// to the
+    /// [single-value binary serialization spec](https://iceberg.apache.org/spec/#binary-single-value-serialization).
+    ///
+    /// This is the inverse of converting a `Value` into `Vec<u8>`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use icelake::value::Value;
+    /// use icelake::schema::PrimitiveType;
+    ///
+    /// assert_eq!(
+    ///     Value::from_bytes(&[0x44, 0x33, 0x22, 0x11], &PrimitiveType::Int).unwrap(),
+    ///     Value::Int(0x11223344)
+    /// )
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`] is returned if `bytes` is not a valid binary
+    /// representation of a value of type `field_type`.
+    pub fn from_bytes(bytes: &[u8], field_type: &PrimitiveType) -> IcebergResult<Self> {
+        fn to_array<const N: usize>(
+            bytes: &[u8],
+            field_type: &PrimitiveType
+        ) -> IcebergResult<[u8; N]> {
+            bytes.try_into().map_err(|_| {
+                IcebergError::ValueError(format!(
+                    "invalid binary value for type {field_type}: expected {N} bytes, \
+                    found {}",
+                    bytes.len()
+                ))
+            })
+        }
+
+        let out_of_range = || {
+            IcebergError::ValueError(
+                format!("binary value of type {field_type} is out of range")
+            )
+        };
+
+        match field_type {
+            PrimitiveType::Boolean => {
+                Ok(Value::Boolean(to_array::<1>(bytes, field_type)?[0] != 0x00))
             },
-            Value::String(s) => write!(f, "{}", s),
-            Value::Uuid(u) => write!(f, "{}", u.to_string()),
-            Value::Binary(b) | Value::Fixed(b) => {
-                write!(
-                    f,
-                    "{}",
-                    b.iter().map(|x| format!("{:02x}", x)).collect::<String>()
-                )
+            PrimitiveType::Int => {
+                Ok(Value::Int(i32::from_le_bytes(to_array(bytes, field_type)?)))
             },
-            _ => {
-                todo!("non-primitive types can't be displayed");
+            PrimitiveType::Long => {
+                Ok(Value::Long(i64::from_le_bytes(to_array(bytes, field_type)?)))
+            },
+            PrimitiveType::Float => {
+                Ok(Value::Float(f32::from_le_bytes(to_array(bytes, field_type)?)))
+            },
+            PrimitiveType::Double => {
+                Ok(Value::Double(f64::from_le_bytes(to_array(bytes, field_type)?)))
+            },
+            PrimitiveType::Date => {
+                // Days since 1970-01-01
+                let days = i32::from_le_bytes(to_array(bytes, field_type)?);
+                NaiveDate::default()
+                    .checked_add_signed(chrono::Duration::days(i64::from(days)))
+                    .map(Value::Date)
+                    .ok_or_else(out_of_range)
+            },
+            PrimitiveType::Time => {
+                // Microseconds since midnight
+                let micros = i64::from_le_bytes(to_array(bytes, field_type)?);
+                NaiveTime::from_num_seconds_from_midnight_opt(
+                    u32::try_from(micros.div_euclid(1_000_000))
+                        .map_err(|_| out_of_range())?,
+                    u32::try_from(micros.rem_euclid(1_000_000) * 1000)
+                        .map_err(|_| out_of_range())?
+                ).map(Value::Time).ok_or_else(out_of_range)
+            },
+            PrimitiveType::Timestamp => {
+                // Microseconds since 1970-01-01 00:00:00
+                let micros = i64::from_le_bytes(to_array(bytes, field_type)?);
+                NaiveDateTime::default()
+                    .checked_add_signed(chrono::Duration::microseconds(micros))
+                    .map(Value::Timestamp)
+                    .ok_or_else(out_of_range)
+            },
+            PrimitiveType::Timestamptz => {
+                let micros = i64::from_le_bytes(to_array(bytes, field_type)?);
+                DateTime::<Utc>::default()
+                    .checked_add_signed(chrono::Duration::microseconds(micros))
+                    .map(Value::Timestamptz)
+                    .ok_or_else(out_of_range)
+            },
+            PrimitiveType::String => {
+                Ok(Value::String(
+                    String::from_utf8(bytes.to_vec()).map_err(|_| {
+                        IcebergError::ValueError(
+                            "invalid binary value for type string: not UTF-8"
+                                .to_string()
+                        )
+                    })?
+                ))
+            },
+            PrimitiveType::Uuid => {
+                Ok(Value::Uuid(Uuid::from_bytes(to_array(bytes, field_type)?)))
+            },
+            PrimitiveType::Fixed(_) => Ok(Value::Fixed(bytes.to_vec())),
+            PrimitiveType::Binary => Ok(Value::Binary(bytes.to_vec())),
+            PrimitiveType::Decimal { precision, scale } => {
+                // Two's-complement big-endian unscaled value, sign-extended to 128 bits.
+                if bytes.is_empty() || bytes.len() > 16 {
+                    return Err(IcebergError::ValueError(format!(
+                        "invalid binary value for type {field_type}: expected 1 to 16 \
+                        bytes, found {}",
+                        bytes.len()
+                    )));
+                }
+                let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0x00 };
+                let mut buf = [fill; 16];
+                buf[16 - bytes.len()..].copy_from_slice(bytes);
+
+                Ok(Value::Decimal {
+                    unscaled: i128::from_be_bytes(buf),
+                    precision: *precision,
+                    scale: *scale
+                })
             }
         }
     }
-}
 
-impl TryFrom<Value> for Vec<u8> {
-    type Error = IcebergError;
+    /// Decodes a value of type `field_type` from its
+    /// [single-value binary serialization](https://iceberg.apache.org/spec/#binary-single-value-serialization),
+    /// as used for column bounds and partition values.
+    ///
+    /// Same as [`Value::from_bytes()`].
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`] is returned if `bytes` is not a valid binary
+    /// representation of a value of type `field_type`.
+    pub fn from_iceberg_bytes(
+        field_type: &PrimitiveType,
+        bytes: &[u8]
+    ) -> IcebergResult<Self> {
+        Self::from_bytes(bytes, field_type)
+    }
 
-    /// Converts `Value` to its binary representation according to the
-    /// [single-value binary serialization spec](https://iceberg.apache.org/spec/#binary-single-value-serialization).
+    /// Parses a value of type `field_type` from its
+    /// [single-value JSON serialization](https://iceberg.apache.org/spec/#json-single-value-serialization),
+    /// as used for field default values.
+    ///
+    /// Unlike deserializing a `Value` directly, this uses the field type to tell
+    /// apart values with the same JSON form, such as longs and ints or decimals and
+    /// strings.
     ///
     /// # Examples
     /// ```rust
     /// use icelake::value::Value;
+    /// use icelake::schema::PrimitiveType;
     ///
     /// assert_eq!(
-    ///     <Vec<u8> as TryFrom<Value>>::try_from(
-    ///         Value::Int(0x11223344)
-    ///     ).unwrap(),
-    ///     vec![0x44, 0x33, 0x22, 0x11]
+    ///     Value::from_json(&serde_json::json!(34), &PrimitiveType::Long).unwrap(),
+    ///     Value::Long(34)
     /// )
     /// ```
-    fn try_from(value: Value) -> Result<Self, Self::Error> {
-        match value {
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`] is returned if `json` is not a valid JSON
+    /// representation of a value of type `field_type`.
+    pub fn from_json(
+        json: &serde_json::Value,
+        field_type: &PrimitiveType
+    ) -> IcebergResult<Self> {
+        let invalid = || {
+            IcebergError::ValueError(
+                format!("invalid JSON value for type {field_type}: {json}")
+            )
+        };
+
+        match field_type {
+            PrimitiveType::Boolean => {
+                bool::deserialize(json).map(Value::Boolean).map_err(|_| invalid())
+            },
+            PrimitiveType::Int => {
+                i32::deserialize(json).map(Value::Int).map_err(|_| invalid())
+            },
+            PrimitiveType::Long => {
+                i64::deserialize(json).map(Value::Long).map_err(|_| invalid())
+            },
+            PrimitiveType::Float => {
+                f32::deserialize(json).map(Value::Float).map_err(|_| invalid())
+            },
+            PrimitiveType::Double => {
+                f64::deserialize(json).map(Value::Double).map_err(|_| invalid())
+            },
+            PrimitiveType::Decimal { precision, scale } => {
+                json.as_str()
+                    .and_then(|s| decimal_serde::from_str(s, *scale))
+                    .map(|unscaled| Value::Decimal {
+                        unscaled: unscaled,
+                        precision: *precision,
+                        scale: *scale
+                    })
+                    .ok_or_else(invalid)
+            },
+            PrimitiveType::Date => {
+                NaiveDate::deserialize(json).map(Value::Date).map_err(|_| invalid())
+            },
+            PrimitiveType::Time => {
+                time_serde::deserialize(json).map(Value::Time).map_err(|_| invalid())
+            },
+            PrimitiveType::Timestamp => {
+                timestamp_serde::deserialize(json)
+                    .map(Value::Timestamp)
+                    .map_err(|_| invalid())
+            },
+            PrimitiveType::Timestamptz => {
+                timestamptz_serde::deserialize(json)
+                    .map(Value::Timestamptz)
+                    .map_err(|_| invalid())
+            },
+            PrimitiveType::String => {
+                String::deserialize(json).map(Value::String).map_err(|_| invalid())
+            },
+            PrimitiveType::Uuid => {
+                Uuid::deserialize(json).map(Value::Uuid).map_err(|_| invalid())
+            },
+            PrimitiveType::Fixed(_) => {
+                binary_serde::deserialize(json).map(Value::Fixed).map_err(|_| invalid())
+            },
+            PrimitiveType::Binary => {
+                binary_serde::deserialize(json).map(Value::Binary).map_err(|_| invalid())
+            },
+        }
+    }
+
+    /// Encodes the value in its
+    /// [single-value binary serialization](https://iceberg.apache.org/spec/#binary-single-value-serialization).
+    ///
+    /// Integers and floating point numbers are little-endian, dates are days and
+    /// times and timestamps microseconds since the epoch, decimals are the minimal
+    /// two's-complement big-endian bytes of their unscaled value, and strings are
+    /// UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`] is returned for structs and lists, which have no
+    /// single-value serialization, and for dates and timestamps too far from the
+    /// epoch.
+    pub fn to_iceberg_bytes(&self) -> IcebergResult<Vec<u8>> {
+        match self {
             Value::Boolean(b) => {
                 Ok(vec![
-                    if b {
-                        0x00
-                    } else {
+                    if *b {
                         0x01
+                    } else {
+                        0x00
                     }
                 ])
             },
@@ -219,9 +437,21 @@ impl TryFrom<Value> for Vec<u8> {
             Value::Double(d) => {
                 Ok(Vec::from(d.to_le_bytes()))
             },
+            Value::Decimal { unscaled, .. } => {
+                // Drop leading bytes that only repeat the sign of the next byte.
+                let bytes = unscaled.to_be_bytes();
+                let redundant = bytes.windows(2)
+                    .take_while(|pair| {
+                        (pair[0] == 0x00 && pair[1] & 0x80 == 0)
+                            || (pair[0] == 0xff && pair[1] & 0x80 != 0)
+                    })
+                    .count();
+
+                Ok(bytes[redundant..].to_vec())
+            },
             Value::Date(date) => {
                 // Days since 1970-01-01
-                let duration = date - NaiveDate::default();
+                let duration = *date - NaiveDate::default();
                 let days = i32::try_from(duration.num_days()).map_err(|_| {
                     IcebergError::ValueError(
                         format!("date {date} is too far from 1970-01-01")
@@ -231,13 +461,13 @@ impl TryFrom<Value> for Vec<u8> {
                 Ok(Vec::from(days.to_le_bytes()))
             },
             Value::Time(time) => {
-                // Microseconds since 1970-01-01
-                let duration = time - NaiveTime::default();
+                // Microseconds since midnight
+                let duration = *time - NaiveTime::default();
                 let micros: i64 = duration.num_microseconds().unwrap();
                 Ok(Vec::from(micros.to_le_bytes()))
             },
             Value::Timestamp(timestamp) => {
-                let duration = timestamp - NaiveDateTime::default();
+                let duration = *timestamp - NaiveDateTime::default();
                 let micros: i64 = duration.num_microseconds().ok_or_else(|| {
                     IcebergError::ValueError(
                         format!("timestamp {timestamp} is too far from 1970-01-01")
@@ -246,16 +476,146 @@ impl TryFrom<Value> for Vec<u8> {
 
                 Ok(Vec::from(micros.to_le_bytes()))
             },
-            // TODO: Implement the rest
-            _ => {
+            Value::Timestamptz(timestamptz) => {
+                let duration = *timestamptz - DateTime::<Utc>::default();
+                let micros: i64 = duration.num_microseconds().ok_or_else(|| {
+                    IcebergError::ValueError(
+                        format!("timestamp {timestamptz} is too far from 1970-01-01")
+                    )
+                })?;
+
+                Ok(Vec::from(micros.to_le_bytes()))
+            },
+            Value::String(s) => {
+                // UTF-8 bytes, without length
+                Ok(s.as_bytes().to_vec())
+            },
+            Value::Uuid(uuid) => {
+                // 16-byte big-endian value
+                Ok(uuid.as_bytes().to_vec())
+            },
+            Value::Fixed(bytes) | Value::Binary(bytes) => {
+                Ok(bytes.clone())
+            },
+            Value::Struct(_) | Value::List(_) => {
                 Err(IcebergError::ValueError(
-                    format!("value {value:?} can't be converted to binary form")
+                    format!("value {self:?} can't be converted to binary form")
                 ))
             }
         }
     }
 }
 
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Boolean(x) => write!(f, "{}", x),
+            Value::Int(x) => write!(f, "{}", x),
+            Value::Long(x) => write!(f, "{}", x),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Double(x) => write!(f, "{}", x),
+            Value::Decimal { unscaled, scale, .. } => {
+                write!(f, "{}", decimal_serde::to_string(*unscaled, *scale))
+            },
+            Value::Date(d) => write!(f, "{}", d.format("%Y-%m-%d")),
+            Value::Time(t) => write!(f, "{}", t.format("%H:%M:%S%.6f")),
+            Value::Timestamp(ts) => {
+                write!(f, "{}", ts.format("%Y-%m-%dT%H:%M:%S%.6f"))
+            },
+            Value::String(s) => write!(f, "{}", s),
+            Value::Uuid(u) => write!(f, "{}", u.to_string()),
+            Value::Binary(b) | Value::Fixed(b) => {
+                write!(
+                    f,
+                    "{}",
+                    b.iter().map(|x| format!("{:02x}", x)).collect::<String>()
+                )
+            },
+            _ => {
+                todo!("non-primitive types can't be displayed");
+            }
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    /// Compares two primitive values of the same type.
+    ///
+    /// Returns `None` if the values are of different types, or if they are structs
+    /// or lists.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Value::Boolean(a), Value::Boolean(b)) => a.partial_cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
+            (Value::Long(a), Value::Long(b)) => a.partial_cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+            (Value::Double(a), Value::Double(b)) => a.partial_cmp(b),
+            (
+                Value::Decimal { unscaled: a, scale: scale_a, .. },
+                Value::Decimal { unscaled: b, scale: scale_b, .. }
+            ) if scale_a == scale_b => a.partial_cmp(b),
+            (Value::Date(a), Value::Date(b)) => a.partial_cmp(b),
+            (Value::Time(a), Value::Time(b)) => a.partial_cmp(b),
+            (Value::Timestamp(a), Value::Timestamp(b)) => a.partial_cmp(b),
+            (Value::Timestamptz(a), Value::Timestamptz(b)) => a.partial_cmp(b),
+            (Value::String(a), Value::String(b)) => a.partial_cmp(b),
+            (Value::Uuid(a), Value::Uuid(b)) => a.partial_cmp(b),
+            (Value::Fixed(a), Value::Fixed(b)) => a.partial_cmp(b),
+            (Value::Binary(a), Value::Binary(b)) => a.partial_cmp(b),
+            _ => None
+        }
+    }
+}
+
+macro_rules! impl_from_for_value {
+    ($($source:ty => $variant:ident),*) => {
+        $(
+            impl From<$source> for Value {
+                fn from(value: $source) -> Self {
+                    Value::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_value!(
+    bool => Boolean,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    NaiveDate => Date,
+    NaiveTime => Time,
+    NaiveDateTime => Timestamp,
+    DateTime<Utc> => Timestamptz,
+    &str => String,
+    String => String,
+    Uuid => Uuid
+);
+
+impl TryFrom<Value> for Vec<u8> {
+    type Error = IcebergError;
+
+    /// Converts `Value` to its binary representation according to the
+    /// [single-value binary serialization spec](https://iceberg.apache.org/spec/#binary-single-value-serialization).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use icelake::value::Value;
+    ///
+    /// assert_eq!(
+    ///     <Vec<u8> as TryFrom<Value>>::try_from(
+    ///         Value::Int(0x11223344)
+    ///     ).unwrap(),
+    ///     vec![0x44, 0x33, 0x22, 0x11]
+    /// )
+    /// ```
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value.to_iceberg_bytes()
+    }
+}
+
 /// Custom serializer and deserializer for `NaiveTime` which uses
 /// microsecond precision instead of the default nanosecond.
 mod time_serde {
@@ -349,6 +709,61 @@ mod timestamptz_serde {
     }
 }
 
+/// Custom serializer for decimals, which are serialized as strings of their exact
+/// value, e.g. `"14.20"`.
+mod decimal_serde {
+    use serde::Serializer;
+
+    pub fn serialize<S>(
+        unscaled: &i128,
+        _precision: &u8,
+        scale: &u8,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&to_string(*unscaled, *scale))
+    }
+
+    /// Formats the decimal `unscaled * 10^-scale` with exactly `scale` digits after
+    /// the decimal point.
+    pub fn to_string(unscaled: i128, scale: u8) -> String {
+        let digits = unscaled.unsigned_abs().to_string();
+        let scale = usize::from(scale);
+        let sign = if unscaled < 0 { "-" } else { "" };
+        if scale == 0 {
+            return format!("{sign}{digits}");
+        }
+
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        format!("{sign}{integer}.{fraction}")
+    }
+
+    /// Parses a decimal string such as `"-1.5"` into its unscaled value with the
+    /// given `scale`. Returns `None` if the string isn't a decimal number, has more
+    /// than `scale` digits after the decimal point, or overflows.
+    pub fn from_str(s: &str, scale: u8) -> Option<i128> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let scale = usize::from(scale);
+        if integer.is_empty()
+            || fraction.len() > scale
+            || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let digits = format!("{integer}{fraction:0<scale$}");
+        let unscaled = digits.parse::<i128>().ok()?;
+        Some(if negative { -unscaled } else { unscaled })
+    }
+}
+
 /// Custom serializer and deserializer for `Value::Fixed` and `Value::Binary`,
 /// encoding them as hex strings.
 mod binary_serde {
@@ -457,4 +872,218 @@ mod tests {
         );
         assert_eq!(serde_json::to_string(&value).unwrap(), "[1,2,3]");
     }
+
+    #[test]
+    fn binary_serialization() {
+        let to_bytes = |value: Value| -> Vec<u8> { value.try_into().unwrap() };
+
+        assert_eq!(to_bytes(Value::Boolean(true)), vec![0x01]);
+        assert_eq!(to_bytes(Value::Boolean(false)), vec![0x00]);
+        assert_eq!(to_bytes(Value::Long(1)), vec![1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            to_bytes(Value::Date(NaiveDate::from_ymd_opt(1970, 1, 2).unwrap())),
+            vec![1, 0, 0, 0]
+        );
+        assert_eq!(to_bytes(Value::String("iceberg".to_string())), b"iceberg");
+        assert_eq!(
+            to_bytes(Value::Uuid(
+                Uuid::parse_str("f79c3e09-677c-4bbd-a479-3f349cb785e7").unwrap()
+            )),
+            vec![
+                0xf7, 0x9c, 0x3e, 0x09, 0x67, 0x7c, 0x4b, 0xbd,
+                0xa4, 0x79, 0x3f, 0x34, 0x9c, 0xb7, 0x85, 0xe7
+            ]
+        );
+        assert_eq!(
+            to_bytes(Value::Timestamptz(
+                DateTime::<Utc>::default() + chrono::Duration::microseconds(2)
+            )),
+            vec![2, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn compare_values() {
+        assert!(Value::Int(1) < Value::Int(2));
+        assert!(Value::String("a".to_string()) < Value::String("b".to_string()));
+        assert_eq!(Value::Int(1).partial_cmp(&Value::Long(2)), None);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        use crate::schema::PrimitiveType;
+
+        let values = [
+            (Value::Boolean(true), PrimitiveType::Boolean),
+            (Value::Int(-42), PrimitiveType::Int),
+            (Value::Long(1 << 40), PrimitiveType::Long),
+            (Value::Double(1.5), PrimitiveType::Double),
+            (
+                Value::Date(NaiveDate::from_ymd_opt(2017, 11, 16).unwrap()),
+                PrimitiveType::Date
+            ),
+            (
+                Value::Time("22:31:08.123456".parse::<NaiveTime>().unwrap()),
+                PrimitiveType::Time
+            ),
+            (
+                Value::Timestamp(
+                    "2017-11-16T22:31:08.123456".parse::<NaiveDateTime>().unwrap()
+                ),
+                PrimitiveType::Timestamp
+            ),
+            (Value::String("iceberg".to_string()), PrimitiveType::String),
+            (
+                Value::Uuid(
+                    Uuid::parse_str("f79c3e09-677c-4bbd-a479-3f349cb785e7").unwrap()
+                ),
+                PrimitiveType::Uuid
+            ),
+            (Value::Binary(vec![0x00, 0x01, 0xff]), PrimitiveType::Binary),
+        ];
+
+        for (value, field_type) in values {
+            let bytes: Vec<u8> = value.clone().try_into().unwrap();
+            assert_eq!(Value::from_bytes(&bytes, &field_type).unwrap(), value);
+        }
+
+        assert!(Value::from_bytes(&[0x01, 0x02], &PrimitiveType::Int).is_err());
+    }
+
+    #[test]
+    fn iceberg_bytes_round_trip() {
+        use crate::schema::PrimitiveType;
+
+        let decimal = |unscaled: i128| Value::Decimal {
+            unscaled: unscaled,
+            precision: 38,
+            scale: 2
+        };
+        let decimal_type = PrimitiveType::Decimal { precision: 38, scale: 2 };
+
+        let values = [
+            (Value::Boolean(false), PrimitiveType::Boolean, vec![0x00]),
+            (Value::Int(-2), PrimitiveType::Int, vec![0xfe, 0xff, 0xff, 0xff]),
+            (Value::Long(258), PrimitiveType::Long, vec![0x02, 0x01, 0, 0, 0, 0, 0, 0]),
+            (Value::Float(1.0), PrimitiveType::Float, vec![0x00, 0x00, 0x80, 0x3f]),
+            (
+                Value::Double(-2.0),
+                PrimitiveType::Double,
+                vec![0, 0, 0, 0, 0, 0, 0x00, 0xc0]
+            ),
+            // Decimals use as few bytes as possible, keeping the sign bit.
+            (decimal(0), decimal_type.clone(), vec![0x00]),
+            (decimal(127), decimal_type.clone(), vec![0x7f]),
+            (decimal(128), decimal_type.clone(), vec![0x00, 0x80]),
+            (decimal(-1), decimal_type.clone(), vec![0xff]),
+            (decimal(-128), decimal_type.clone(), vec![0x80]),
+            (decimal(-129), decimal_type.clone(), vec![0xff, 0x7f]),
+            (decimal(-1420), decimal_type.clone(), vec![0xfa, 0x74]),
+            (
+                decimal(i128::MIN),
+                decimal_type.clone(),
+                [vec![0x80], vec![0x00; 15]].concat()
+            ),
+            // Dates before the epoch are negative days.
+            (
+                Value::Date(NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()),
+                PrimitiveType::Date,
+                vec![0xff, 0xff, 0xff, 0xff]
+            ),
+            (
+                Value::Time("00:00:01".parse::<NaiveTime>().unwrap()),
+                PrimitiveType::Time,
+                vec![0x40, 0x42, 0x0f, 0, 0, 0, 0, 0]
+            ),
+            (
+                Value::Timestamp(
+                    "1969-12-31T23:59:59.999999".parse::<NaiveDateTime>().unwrap()
+                ),
+                PrimitiveType::Timestamp,
+                vec![0xff; 8]
+            ),
+            (
+                Value::Timestamptz(
+                    DateTime::<Utc>::default() + chrono::Duration::microseconds(1)
+                ),
+                PrimitiveType::Timestamptz,
+                vec![0x01, 0, 0, 0, 0, 0, 0, 0]
+            ),
+            (Value::String("ü".to_string()), PrimitiveType::String, vec![0xc3, 0xbc]),
+            (
+                Value::Uuid(
+                    Uuid::parse_str("00000000-0000-0000-0000-0000000000ff").unwrap()
+                ),
+                PrimitiveType::Uuid,
+                [vec![0x00; 15], vec![0xff]].concat()
+            ),
+            (Value::Fixed(vec![0x01, 0x02]), PrimitiveType::Fixed(2), vec![0x01, 0x02]),
+            (Value::Binary(vec![0xff]), PrimitiveType::Binary, vec![0xff]),
+        ];
+
+        for (value, field_type, bytes) in values {
+            assert_eq!(value.to_iceberg_bytes().unwrap(), bytes, "{value:?}");
+            assert_eq!(
+                Value::from_iceberg_bytes(&field_type, &bytes).unwrap(),
+                value
+            );
+        }
+
+        // Decimals are sign-extended, and can't exceed 16 bytes.
+        assert_eq!(
+            Value::from_iceberg_bytes(&decimal_type, &[0xff, 0xff, 0xfa, 0x74]).unwrap(),
+            decimal(-1420)
+        );
+        assert!(Value::from_iceberg_bytes(&decimal_type, &[]).is_err());
+        assert!(Value::from_iceberg_bytes(&decimal_type, &[0x00; 17]).is_err());
+
+        assert!(Value::List(Vec::new()).to_iceberg_bytes().is_err());
+    }
+
+    #[test]
+    fn decimal_to_string() {
+        let decimal = |unscaled: i128, scale: u8| Value::Decimal {
+            unscaled: unscaled,
+            precision: 38,
+            scale: scale
+        };
+
+        assert_eq!(decimal(1420, 2).to_string(), "14.20");
+        assert_eq!(decimal(-5, 3).to_string(), "-0.005");
+        assert_eq!(decimal(-42, 0).to_string(), "-42");
+        assert_eq!(serde_json::to_string(&decimal(-150, 2)).unwrap(), "\"-1.50\"");
+        assert!(decimal(-150, 2) < decimal(1, 2));
+    }
+
+    #[test]
+    fn from_json() {
+        use crate::schema::PrimitiveType;
+
+        assert_eq!(
+            Value::from_json(&serde_json::json!(1), &PrimitiveType::Long).unwrap(),
+            Value::Long(1)
+        );
+        assert_eq!(
+            Value::from_json(
+                &serde_json::json!("-1.5"),
+                &PrimitiveType::Decimal { precision: 9, scale: 2 }
+            ).unwrap(),
+            Value::Decimal { unscaled: -150, precision: 9, scale: 2 }
+        );
+        assert_eq!(
+            Value::from_json(&serde_json::json!("0a1B"), &PrimitiveType::Binary).unwrap(),
+            Value::Binary(vec![0x0a, 0x1b])
+        );
+        assert_eq!(
+            Value::from_json(&serde_json::json!("2017-11-16"), &PrimitiveType::Date)
+                .unwrap(),
+            Value::Date(NaiveDate::from_ymd_opt(2017, 11, 16).unwrap())
+        );
+
+        assert!(Value::from_json(&serde_json::json!(1.5), &PrimitiveType::Int).is_err());
+        assert!(Value::from_json(
+            &serde_json::json!("1.505"),
+            &PrimitiveType::Decimal { precision: 9, scale: 2 }
+        ).is_err());
+    }
 }
@@ -3,8 +3,9 @@
 //! Inspired by the [delta-rs](https://docs.rs/deltalake/0.12.0/deltalake/) crate.
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 
-use chrono;
+use chrono::{self, NaiveDate, NaiveTime, NaiveDateTime, DateTime, Utc};
 use rand::Rng;
 use uuid::Uuid;
 use bytes::Bytes;
@@ -16,16 +17,27 @@ use arrow_schema::{
     Fields as ArrowFields,
     DataType as ArrowDataType,
 };
-use arrow_array::RecordBatch;
+use arrow_array::{
+    RecordBatch, Array, ArrayRef, Decimal128Array, StringArray, Int64Array, BooleanArray,
+    Int32Array, Float32Array, Float64Array, Date32Array, Time64MicrosecondArray,
+    TimestampMicrosecondArray, BinaryArray
+};
+use apache_avro::{Writer as AvroWriter, Schema as AvroSchema, types::Value as AvroValue};
 use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel, GzipLevel, BrotliLevel};
 use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
+use parquet::file::metadata::RowGroupMetaDataPtr;
+use parquet::file::statistics::Statistics;
 
 use crate::{IcebergResult, IcebergError, IcebergTable, IcebergFile};
 use crate::value::Value;
+use crate::schema::{Schema, Field, SchemaType, PrimitiveType};
 use crate::transaction::AppendFilesOperation;
 use crate::partition::{PartitionSpec, PartitionValues};
 use crate::manifest::{DataFile, DataFileContent, DataFileFormat};
 use crate::schema::arrow::iceberg_to_arrow_schema;
+use crate::schema::avro::iceberg_to_avro_schema;
 
 fn arrow_field_add_parquet_id(arrow_field: &ArrowFieldRef) -> ArrowFieldRef {
     let mut metadata = arrow_field.metadata().clone();
@@ -87,95 +99,849 @@ fn arrow_schema_add_parquet_ids(arrow_schema: ArrowSchema) -> ArrowSchema {
     )
 }
 
-/// Writes Apache Arrow `RecordBatch`es to an Iceberg table in Parquet format.
-pub struct RecordBatchWriter {
+/// Rescales decimal values to `scale`, checking that they fit `precision`.
+///
+/// # Errors
+///
+/// [`IcebergError::ValueError`] is returned if a value would lose digits by being
+/// rescaled to a smaller scale, or if it doesn't fit the target precision.
+fn rescale_decimals(
+    array: &Decimal128Array,
+    precision: u8,
+    scale: i8
+) -> IcebergResult<Decimal128Array> {
+    let source_scale = array.scale();
+    let factor = 10i128.checked_pow(u32::from(scale.abs_diff(source_scale)))
+        .ok_or_else(|| IcebergError::ValueError(
+            format!("can't rescale decimals from scale {source_scale} to {scale}")
+        ))?;
+    let max = 10i128.checked_pow(u32::from(precision)).unwrap_or(i128::MAX);
+
+    let rescale = |value: i128| -> IcebergResult<i128> {
+        let rescaled = if scale >= source_scale {
+            value.checked_mul(factor)
+        } else if value % factor == 0 {
+            Some(value / factor)
+        } else {
+            return Err(IcebergError::ValueError(format!(
+                "decimal {value} with scale {source_scale} can't be rescaled to \
+                scale {scale} without losing digits"
+            )));
+        };
+
+        rescaled.filter(|rescaled| rescaled.abs() < max)
+            .ok_or_else(|| IcebergError::ValueError(format!(
+                "decimal {value} with scale {source_scale} overflows precision \
+                {precision} when rescaled to scale {scale}"
+            )))
+    };
+
+    let rescaled = array.iter()
+        .map(|value| value.map(rescale).transpose())
+        .collect::<IcebergResult<Decimal128Array>>()?;
+
+    Ok(rescaled.with_precision_and_scale(precision, scale)?)
+}
+
+/// Converts a batch to the table's Arrow schema.
+///
+/// The batch must have the same columns as the schema, except that decimal columns
+/// may have a different precision and scale, in which case they are rescaled.
+///
+/// # Errors
+///
+/// [`IcebergError::SchemaError`] is returned if the batch doesn't match the schema,
+/// and [`IcebergError::ValueError`] if a decimal column can't be rescaled.
+fn conform_batch(
+    batch: &RecordBatch,
+    arrow_schema: &ArrowSchemaRef
+) -> IcebergResult<RecordBatch> {
+    if batch.schema() == *arrow_schema {
+        return Ok(batch.clone());
+    }
+
+    let schema_mismatch = || IcebergError::SchemaError {
+        message: "schema mismatch".to_string()
+    };
+
+    let batch_schema = batch.schema();
+    if batch_schema.fields().len() != arrow_schema.fields().len() {
+        return Err(schema_mismatch());
+    }
+
+    let columns = batch_schema.fields().iter()
+        .zip(arrow_schema.fields().iter())
+        .zip(batch.columns())
+        .map(|((batch_field, field), column)| -> IcebergResult<ArrayRef> {
+            if batch_field.name() != field.name()
+                || batch_field.is_nullable() != field.is_nullable() {
+                return Err(schema_mismatch());
+            }
+
+            match (batch_field.data_type(), field.data_type()) {
+                (source, target) if source == target => Ok(column.clone()),
+                (ArrowDataType::Decimal128(..), ArrowDataType::Decimal128(p, s)) => {
+                    // Can't fail, the data type was checked above.
+                    let decimals = column.as_any()
+                        .downcast_ref::<Decimal128Array>()
+                        .unwrap();
+                    Ok(Arc::new(rescale_decimals(decimals, *p, *s)?))
+                },
+                _ => Err(schema_mismatch()),
+            }
+        })
+        .collect::<IcebergResult<Vec<ArrayRef>>>()?;
+
+    Ok(RecordBatch::try_new(arrow_schema.clone(), columns)?)
+}
+
+/// Maximum length of string and binary bounds in Iceberg's default `truncate(16)`
+/// metrics mode.
+const BOUNDS_TRUNCATE_LENGTH: usize = 16;
+/// Table property setting the [`MetricsMode`] of columns without a mode of their
+/// own.
+pub const METRICS_DEFAULT_PROPERTY: &str = "write.metadata.metrics.default";
+/// Prefix of the table properties setting the [`MetricsMode`] of a single column,
+/// followed by the column's full name, e.g. `write.metadata.metrics.column.a.b`.
+pub const METRICS_COLUMN_PROPERTY_PREFIX: &str = "write.metadata.metrics.column.";
+
+/// Which metrics of a column are recorded in the manifest entries of written data
+/// files, parsed from `none`, `counts`, `truncate(N)` or `full`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsMode {
+    /// No metrics are recorded.
+    None,
+    /// Only value and null counts are recorded.
+    Counts,
+    /// Counts and bounds are recorded, with string and binary bounds truncated to
+    /// the given number of characters or bytes.
+    Truncate(usize),
+    /// Counts and full bounds are recorded.
+    Full,
+}
+
+impl Default for MetricsMode {
+    fn default() -> Self {
+        MetricsMode::Truncate(BOUNDS_TRUNCATE_LENGTH)
+    }
+}
+
+impl std::str::FromStr for MetricsMode {
+    type Err = IcebergError;
+
+    /// Parses a metrics mode, ignoring case.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`] is returned for unknown modes, and for
+    /// truncation to a length that isn't a positive integer.
+    fn from_str(s: &str) -> IcebergResult<Self> {
+        let invalid = || IcebergError::ValueError(format!("invalid metrics mode '{s}'"));
+
+        let mode = s.trim().to_lowercase();
+        match mode.as_str() {
+            "none" => Ok(MetricsMode::None),
+            "counts" => Ok(MetricsMode::Counts),
+            "full" => Ok(MetricsMode::Full),
+            _ => {
+                let length = mode.strip_prefix("truncate(")
+                    .and_then(|mode| mode.strip_suffix(')'))
+                    .and_then(|length| length.parse::<usize>().ok())
+                    .filter(|length| *length > 0)
+                    .ok_or_else(invalid)?;
+                Ok(MetricsMode::Truncate(length))
+            }
+        }
+    }
+}
+
+/// The [`MetricsMode`] of every column of a table, set by the
+/// `write.metadata.metrics.default` and `write.metadata.metrics.column.<name>`
+/// table properties.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsConfig {
+    default: MetricsMode,
+    columns: HashMap<String, MetricsMode>,
+}
+
+impl MetricsConfig {
+    /// Parses the metrics modes from table properties. Columns are identified by
+    /// their full names, with nested fields separated by dots.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned if one of the modes is invalid.
+    pub fn from_properties(properties: &HashMap<String, String>) -> IcebergResult<Self> {
+        let parse = |key: &str, value: &str| {
+            value.parse::<MetricsMode>().map_err(|_| IcebergError::InvalidProperty {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+        };
+
+        let mut config = Self::default();
+        for (key, value) in properties {
+            if key == METRICS_DEFAULT_PROPERTY {
+                config.default = parse(key, value)?;
+                continue;
+            }
+
+            if let Some(column) = key.strip_prefix(METRICS_COLUMN_PROPERTY_PREFIX) {
+                config.columns.insert(column.to_string(), parse(key, value)?);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Sets the mode of columns without a mode of their own.
+    pub fn with_default(mut self, mode: MetricsMode) -> Self {
+        self.default = mode;
+        self
+    }
+
+    /// Sets the mode of the column with the given full name.
+    pub fn with_column(mut self, column: &str, mode: MetricsMode) -> Self {
+        self.columns.insert(column.to_string(), mode);
+        self
+    }
+
+    /// Returns the mode of the column with the given full name.
+    pub fn column_mode(&self, column: &str) -> MetricsMode {
+        self.columns.get(column).copied().unwrap_or(self.default)
+    }
+}
+
+/// Value counts, null counts and bounds of the columns of a written file, keyed by
+/// field id.
+#[derive(Default)]
+struct ColumnMetrics {
+    value_counts: HashMap<i32, i64>,
+    null_value_counts: HashMap<i32, i64>,
+    lower_bounds: HashMap<i32, Vec<u8>>,
+    upper_bounds: HashMap<i32, Vec<u8>>,
+}
+
+/// Converts parquet column chunk statistics to the lower and upper bounds of an
+/// Iceberg field of type `field_type`.
+///
+/// Returns `None` if the statistics don't contain bounds (e.g. the column contains
+/// only nulls), or if they can't be represented as bounds of `field_type`.
+fn statistics_to_bounds(
+    statistics: &Statistics,
+    field_type: &PrimitiveType
+) -> Option<(Value, Value)> {
+    if !statistics.has_min_max_set() {
+        return None;
+    }
+
+    let date_from_days = |days: i32| {
+        NaiveDate::default()
+            .checked_add_signed(chrono::Duration::days(i64::from(days)))
+    };
+    let time_from_micros = |micros: i64| {
+        NaiveTime::from_num_seconds_from_midnight_opt(
+            u32::try_from(micros.div_euclid(1_000_000)).ok()?,
+            u32::try_from(micros.rem_euclid(1_000_000) * 1000).ok()?
+        )
+    };
+    let timestamp_from_micros = |micros: i64| {
+        NaiveDateTime::default()
+            .checked_add_signed(chrono::Duration::microseconds(micros))
+    };
+    let timestamptz_from_micros = |micros: i64| {
+        DateTime::<Utc>::default()
+            .checked_add_signed(chrono::Duration::microseconds(micros))
+    };
+
+    match (field_type, statistics) {
+        (PrimitiveType::Boolean, Statistics::Boolean(s)) => {
+            Some((Value::Boolean(*s.min()), Value::Boolean(*s.max())))
+        },
+        (PrimitiveType::Int, Statistics::Int32(s)) => {
+            Some((Value::Int(*s.min()), Value::Int(*s.max())))
+        },
+        (PrimitiveType::Long, Statistics::Int64(s)) => {
+            Some((Value::Long(*s.min()), Value::Long(*s.max())))
+        },
+        (PrimitiveType::Float, Statistics::Float(s)) => {
+            // Bounds must not contain NaN values.
+            if s.min().is_nan() || s.max().is_nan() {
+                None
+            } else {
+                Some((Value::Float(*s.min()), Value::Float(*s.max())))
+            }
+        },
+        (PrimitiveType::Double, Statistics::Double(s)) => {
+            if s.min().is_nan() || s.max().is_nan() {
+                None
+            } else {
+                Some((Value::Double(*s.min()), Value::Double(*s.max())))
+            }
+        },
+        (PrimitiveType::Date, Statistics::Int32(s)) => {
+            Some((
+                Value::Date(date_from_days(*s.min())?),
+                Value::Date(date_from_days(*s.max())?)
+            ))
+        },
+        (PrimitiveType::Time, Statistics::Int64(s)) => {
+            Some((
+                Value::Time(time_from_micros(*s.min())?),
+                Value::Time(time_from_micros(*s.max())?)
+            ))
+        },
+        (PrimitiveType::Timestamp, Statistics::Int64(s)) => {
+            Some((
+                Value::Timestamp(timestamp_from_micros(*s.min())?),
+                Value::Timestamp(timestamp_from_micros(*s.max())?)
+            ))
+        },
+        (PrimitiveType::Timestamptz, Statistics::Int64(s)) => {
+            Some((
+                Value::Timestamptz(timestamptz_from_micros(*s.min())?),
+                Value::Timestamptz(timestamptz_from_micros(*s.max())?)
+            ))
+        },
+        (PrimitiveType::String, Statistics::ByteArray(s)) => {
+            Some((
+                Value::String(std::str::from_utf8(s.min().data()).ok()?.to_string()),
+                Value::String(std::str::from_utf8(s.max().data()).ok()?.to_string())
+            ))
+        },
+        (PrimitiveType::Binary, Statistics::ByteArray(s)) => {
+            Some((
+                Value::Binary(s.min().data().to_vec()),
+                Value::Binary(s.max().data().to_vec())
+            ))
+        },
+        (PrimitiveType::Uuid, Statistics::FixedLenByteArray(s)) => {
+            Some((
+                Value::Uuid(Uuid::from_slice(s.min().data()).ok()?),
+                Value::Uuid(Uuid::from_slice(s.max().data()).ok()?)
+            ))
+        },
+        (PrimitiveType::Fixed(_), Statistics::FixedLenByteArray(s)) => {
+            Some((
+                Value::Fixed(s.min().data().to_vec()),
+                Value::Fixed(s.max().data().to_vec())
+            ))
+        },
+        // TODO: Decimal
+        _ => None
+    }
+}
+
+/// Truncates a lower bound to `length` characters or bytes. Only strings and binary
+/// values are truncated; a truncated prefix is always a valid lower bound.
+fn truncate_lower_bound(value: Value, length: usize) -> Value {
+    match value {
+        Value::String(s) => Value::String(s.chars().take(length).collect()),
+        Value::Binary(b) => Value::Binary(b.into_iter().take(length).collect()),
+        value => value
+    }
+}
+
+/// Truncates an upper bound to `length` characters or bytes.
+///
+/// A truncated prefix is smaller than the original value, so the last character
+/// (or byte) of the prefix is incremented to keep it a valid upper bound. Returns
+/// `None` if no such value exists, e.g. when all bytes of the prefix are `0xff`.
+fn truncate_upper_bound(value: Value, length: usize) -> Option<Value> {
+    match value {
+        Value::String(s) if s.chars().count() > length => {
+            let mut chars: Vec<char> = s.chars().take(length).collect();
+            while let Some(c) = chars.pop() {
+                // Skip over the surrogate range, which contains no valid chars.
+                let next = match u32::from(c) + 1 {
+                    0xD800 => char::from_u32(0xE000),
+                    next => char::from_u32(next)
+                };
+
+                if let Some(next) = next {
+                    chars.push(next);
+                    return Some(Value::String(chars.into_iter().collect()));
+                }
+            }
+            None
+        },
+        Value::Binary(b) if b.len() > length => {
+            let mut bytes = b[..length].to_vec();
+            while let Some(byte) = bytes.pop() {
+                if byte < u8::MAX {
+                    bytes.push(byte + 1);
+                    return Some(Value::Binary(bytes));
+                }
+            }
+            None
+        },
+        value => Some(value)
+    }
+}
+
+/// Computes the value counts, null counts and the lower and upper bounds of all
+/// primitive columns from the statistics of the given parquet row groups, keyed by
+/// field id. Bounds are serialized using the Iceberg single-value binary
+/// serialization.
+///
+/// The metrics recorded for each column are chosen by its mode in `metrics_config`.
+/// Columns nested within lists or maps have no metrics, and columns containing only
+/// nulls have no bounds.
+fn collect_metrics(
+    row_groups: &[RowGroupMetaDataPtr],
+    schema: &Schema,
+    metrics_config: &MetricsConfig
+) -> IcebergResult<ColumnMetrics> {
+    let field_types: HashMap<i32, (&PrimitiveType, MetricsMode)> = schema
+        .all_fields_by_name()
+        .filter_map(|(name, field)| match field.schema_type() {
+            SchemaType::Primitive(primitive) => Some(
+                (field.id(), (primitive, metrics_config.column_mode(&name)))
+            ),
+            _ => None
+        })
+        .collect();
+
+    // Merge the counts and bounds of each column over all row groups. Null counts
+    // are only recorded if every row group has statistics for the column.
+    let mut metrics = ColumnMetrics::default();
+    let mut missing_null_counts = Vec::new();
+    let mut bounds: HashMap<i32, (Value, Value)> = HashMap::new();
+    for row_group in row_groups {
+        for column in row_group.columns() {
+            let descr = column.column_descr();
+            let info = descr.self_type().get_basic_info();
+            if !info.has_id() || descr.max_rep_level() > 0 {
+                continue;
+            }
+
+            let (field_type, mode) = match field_types.get(&info.id()) {
+                Some((_, MetricsMode::None)) | None => continue,
+                Some(entry) => entry,
+            };
+
+            *metrics.value_counts.entry(info.id()).or_insert(0) += column.num_values();
+            match column.statistics() {
+                Some(statistics) => {
+                    let null_count = i64::try_from(statistics.null_count())
+                        .unwrap_or(i64::MAX);
+                    *metrics.null_value_counts.entry(info.id())
+                        .or_insert(0) += null_count;
+                },
+                None => missing_null_counts.push(info.id()),
+            }
+
+            if *mode == MetricsMode::Counts {
+                continue;
+            }
+
+            let column_bounds = column.statistics()
+                .and_then(|statistics| statistics_to_bounds(statistics, field_type));
+
+            if let Some((lower, upper)) = column_bounds {
+                bounds.entry(info.id())
+                    .and_modify(|(min, max)| {
+                        if lower < *min {
+                            *min = lower.clone();
+                        }
+                        if upper > *max {
+                            *max = upper.clone();
+                        }
+                    })
+                    .or_insert((lower, upper));
+            }
+        }
+    }
+
+    for field_id in missing_null_counts {
+        metrics.null_value_counts.remove(&field_id);
+    }
+
+    for (field_id, (lower, upper)) in bounds {
+        let (lower, upper) = match field_types.get(&field_id) {
+            Some((_, MetricsMode::Truncate(length))) => (
+                truncate_lower_bound(lower, *length),
+                truncate_upper_bound(upper, *length)
+            ),
+            _ => (lower, Some(upper)),
+        };
+
+        metrics.lower_bounds.insert(field_id, lower.try_into()?);
+        if let Some(upper) = upper {
+            metrics.upper_bounds.insert(field_id, upper.try_into()?);
+        }
+    }
+
+    Ok(metrics)
+}
+
+/// Table property selecting the file format of written data files, `parquet` or
+/// `avro`.
+pub const DEFAULT_FILE_FORMAT_PROPERTY: &str = "write.format.default";
+/// Table property controlling the target size of written data files.
+pub const TARGET_FILE_SIZE_BYTES_PROPERTY: &str = "write.target-file-size-bytes";
+/// Default target size of written data files: 512 MiB.
+pub const DEFAULT_TARGET_FILE_SIZE_BYTES: usize = 512 * 1024 * 1024;
+/// Table property selecting the [`CompressionCodec`] of written Parquet data files.
+pub const PARQUET_COMPRESSION_CODEC_PROPERTY: &str = "write.parquet.compression-codec";
+/// Table property setting the compression level of written Parquet data files, for
+/// codecs supporting levels.
+pub const PARQUET_COMPRESSION_LEVEL_PROPERTY: &str = "write.parquet.compression-level";
+/// Compression level used for ZSTD if none is set.
+const DEFAULT_ZSTD_LEVEL: i32 = 1;
+
+/// Compression codec of written Parquet data files, parsed from the names used by
+/// the `write.parquet.compression-codec` table property, e.g. `"zstd"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionCodec {
+    Uncompressed,
+    Snappy,
+    Gzip,
+    Lz4,
+    #[default]
+    Zstd,
+    Brotli,
+}
+
+impl CompressionCodec {
+    /// Returns the Parquet compression of this codec at the given level, or at the
+    /// codec's default level if none is given. ZSTD defaults to level 1.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned if the level is out of the
+    /// codec's range. Levels are ignored by codecs without levels.
+    pub fn parquet_compression(&self, level: Option<i32>) -> IcebergResult<Compression> {
+        let invalid_level = |level: i32| IcebergError::InvalidProperty {
+            key: PARQUET_COMPRESSION_LEVEL_PROPERTY.to_string(),
+            value: level.to_string(),
+        };
+        let unsigned_level = |level: i32| {
+            u32::try_from(level).map_err(|_| invalid_level(level))
+        };
+
+        let compression = match self {
+            CompressionCodec::Uncompressed => Compression::UNCOMPRESSED,
+            CompressionCodec::Snappy => Compression::SNAPPY,
+            CompressionCodec::Lz4 => Compression::LZ4_RAW,
+            CompressionCodec::Zstd => {
+                let level = level.unwrap_or(DEFAULT_ZSTD_LEVEL);
+                Compression::ZSTD(
+                    ZstdLevel::try_new(level).map_err(|_| invalid_level(level))?
+                )
+            },
+            CompressionCodec::Gzip => Compression::GZIP(match level {
+                Some(level) => GzipLevel::try_new(unsigned_level(level)?)
+                    .map_err(|_| invalid_level(level))?,
+                None => GzipLevel::default(),
+            }),
+            CompressionCodec::Brotli => Compression::BROTLI(match level {
+                Some(level) => BrotliLevel::try_new(unsigned_level(level)?)
+                    .map_err(|_| invalid_level(level))?,
+                None => BrotliLevel::default(),
+            }),
+        };
+
+        Ok(compression)
+    }
+}
+
+impl std::str::FromStr for CompressionCodec {
+    type Err = IcebergError;
+
+    /// Parses a codec name, ignoring case.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned for unknown codecs.
+    fn from_str(s: &str) -> IcebergResult<Self> {
+        match s.to_lowercase().as_str() {
+            "uncompressed" | "none" => Ok(CompressionCodec::Uncompressed),
+            "snappy" => Ok(CompressionCodec::Snappy),
+            "gzip" => Ok(CompressionCodec::Gzip),
+            "lz4" => Ok(CompressionCodec::Lz4),
+            "zstd" => Ok(CompressionCodec::Zstd),
+            "brotli" => Ok(CompressionCodec::Brotli),
+            _ => Err(IcebergError::InvalidProperty {
+                key: PARQUET_COMPRESSION_CODEC_PROPERTY.to_string(),
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Prefix of the table properties enabling a Parquet bloom filter for a single
+/// column, followed by the column's full name, e.g.
+/// `write.parquet.bloom-filter-enabled.column.id`.
+pub const PARQUET_BLOOM_FILTER_ENABLED_PROPERTY_PREFIX: &str =
+    "write.parquet.bloom-filter-enabled.column.";
+/// Prefix of the table properties setting the false positive probability of a
+/// column's Parquet bloom filter, followed by the column's full name.
+pub const PARQUET_BLOOM_FILTER_FPP_PROPERTY_PREFIX: &str =
+    "write.parquet.bloom-filter-fpp.column.";
+/// False positive probability of bloom filters without one of their own.
+pub const DEFAULT_BLOOM_FILTER_FPP: f64 = 0.01;
+
+/// The columns of written Parquet data files that have bloom filters, and the
+/// false positive probability of each filter, set by the
+/// `write.parquet.bloom-filter-enabled.column.<name>` and
+/// `write.parquet.bloom-filter-fpp.column.<name>` table properties.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BloomFilterConfig {
+    columns: HashMap<String, f64>,
+}
+
+impl BloomFilterConfig {
+    /// Parses the bloom filter columns from table properties. Columns are
+    /// identified by their full names, with nested fields separated by dots.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned if one of the enabled flags
+    /// isn't a boolean, or one of the probabilities isn't between 0 and 1,
+    /// exclusive.
+    pub fn from_properties(properties: &HashMap<String, String>) -> IcebergResult<Self> {
+        let invalid = |key: &str, value: &str| IcebergError::InvalidProperty {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        let mut config = Self::default();
+        for (key, value) in properties {
+            let prefix = PARQUET_BLOOM_FILTER_ENABLED_PROPERTY_PREFIX;
+            let column = match key.strip_prefix(prefix) {
+                Some(column) => column,
+                None => continue,
+            };
+            let enabled = value.to_lowercase().parse::<bool>()
+                .map_err(|_| invalid(key, value))?;
+            if !enabled {
+                continue;
+            }
+
+            let fpp_key = format!("{PARQUET_BLOOM_FILTER_FPP_PROPERTY_PREFIX}{column}");
+            let fpp = match properties.get(&fpp_key) {
+                Some(fpp) => fpp.parse::<f64>().ok()
+                    .filter(|fpp| *fpp > 0.0 && *fpp < 1.0)
+                    .ok_or_else(|| invalid(&fpp_key, fpp))?,
+                None => DEFAULT_BLOOM_FILTER_FPP,
+            };
+            config.columns.insert(column.to_string(), fpp);
+        }
+
+        Ok(config)
+    }
+
+    /// Enables a bloom filter for the column with the given full name, with the
+    /// given false positive probability.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::ValueError`] is returned if the probability isn't between 0
+    /// and 1, exclusive.
+    pub fn with_column(mut self, column: &str, fpp: f64) -> IcebergResult<Self> {
+        if !(fpp > 0.0 && fpp < 1.0) {
+            return Err(IcebergError::ValueError(
+                format!("invalid bloom filter false positive probability {fpp}")
+            ));
+        }
+
+        self.columns.insert(column.to_string(), fpp);
+        Ok(self)
+    }
+
+    /// Returns the false positive probability of the bloom filter of the column with
+    /// the given full name, or `None` if the column has no bloom filter.
+    pub fn column_fpp(&self, column: &str) -> Option<f64> {
+        self.columns.get(column).copied()
+    }
+}
+
+/// Properties of the Parquet writers of data files.
+fn data_writer_props(
+    compression: Compression,
+    bloom_filter_config: &BloomFilterConfig
+) -> WriterProperties {
+    let mut builder = WriterProperties::builder()
+        .set_compression(compression)
+        .set_dictionary_enabled(false)
+        .set_encoding(parquet::basic::Encoding::PLAIN);
+
+    for (column, fpp) in &bloom_filter_config.columns {
+        let path = ColumnPath::new(column.split('.').map(str::to_string).collect());
+        builder = builder
+            .set_column_bloom_filter_enabled(path.clone(), true)
+            .set_column_bloom_filter_fpp(path, *fpp);
+    }
+
+    builder.build()
+}
+
+fn new_operation_id() -> String {
+    rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(8)
+        .map(char::from)
+        .collect()
+}
+
+/// Writes Apache Arrow `RecordBatch`es of a single partition to Parquet files,
+/// rolling over to a new file once the current file exceeds a target size.
+///
+/// The size of each file is estimated from the in-memory size of the batches written
+/// to it. Files are rolled only at batch boundaries, so a single `RecordBatch` is
+/// never split between two files, and files may exceed the target size by up to
+/// the size of one batch.
+pub struct RollingDataWriter {
+    schema: Schema,
     arrow_schema: ArrowSchemaRef,
-    partition_spec: PartitionSpec,
-    // Per-partition parquet writer.
-    writers: HashMap<PartitionValues, ArrowWriter<Vec<u8>>>,
-    // Common properties for all ArrowWriters
-    writer_props: WriterProperties,
-    // All files flushed to storage and ready to be commited.
-    flushed_files: Vec<(IcebergFile, DataFile)>,
+    compression: Compression,
+    bloom_filter_config: BloomFilterConfig,
+    partition_values: PartitionValues,
+    target_file_size_bytes: usize,
+    metrics_config: MetricsConfig,
+    // Parquet writer of the file currently being written.
+    current_writer: Option<ArrowWriter<Vec<u8>>>,
+    // Estimated size of the data written to the current file.
+    current_size: usize,
+    // Writers of files which reached the target size.
+    closed_writers: Vec<ArrowWriter<Vec<u8>>>,
     // Unique operation id to be used in file names.
-    // Helps identify files created by the same write operation.
     operation_id: String,
 }
 
-impl RecordBatchWriter {
-    /// Creates a new `RecordBatchWriter` for the given table, deriving the schema and
-    /// partition fields from it.
-    pub fn for_table(table: &IcebergTable) -> IcebergResult<Self> {
-        let arrow_schema = iceberg_to_arrow_schema(table.current_schema()?)?;
+impl RollingDataWriter {
+    /// Creates a new `RollingDataWriter` for the given partition of the table,
+    /// deriving the schema, the compression, the metrics modes and the bloom filters
+    /// from it.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned if the table's compression,
+    /// metrics or bloom filter properties are invalid.
+    pub fn for_table(
+        table: &IcebergTable,
+        partition_values: PartitionValues
+    ) -> IcebergResult<Self> {
+        let metadata = table.current_metadata()?;
+        Ok(Self::try_new(metadata.current_schema().clone(), partition_values)?
+            .with_compression(
+                metadata.parquet_compression_codec()?,
+                metadata.parquet_compression_level()?
+            )?
+            .with_metrics_config(metadata.metrics_config()?)
+            .with_bloom_filter_config(metadata.bloom_filter_config()?))
+    }
+
+    /// Creates a new `RollingDataWriter` writing data of the given schema to the
+    /// partition with the given values, compressed with ZSTD at level 1 and without
+    /// bloom filters.
+    pub fn try_new(
+        schema: Schema,
+        partition_values: PartitionValues
+    ) -> IcebergResult<Self> {
+        let arrow_schema = iceberg_to_arrow_schema(&schema)?;
         let arrow_schema = arrow_schema_add_parquet_ids(arrow_schema);
 
         Ok(Self {
+            schema: schema,
             arrow_schema: Arc::new(arrow_schema),
-            partition_spec: table.current_partition_spec()?,
-            writers: HashMap::new(),
-            writer_props: WriterProperties::builder()
-                .set_compression(parquet::basic::Compression::UNCOMPRESSED)
-                .set_dictionary_enabled(false)
-                .set_encoding(parquet::basic::Encoding::PLAIN)
-                .build(),
-            flushed_files: Vec::new(),
-            operation_id: Self::new_operation_id()
+            compression: CompressionCodec::default().parquet_compression(None)?,
+            bloom_filter_config: BloomFilterConfig::default(),
+            partition_values: partition_values,
+            target_file_size_bytes: DEFAULT_TARGET_FILE_SIZE_BYTES,
+            metrics_config: MetricsConfig::default(),
+            current_writer: None,
+            current_size: 0,
+            closed_writers: Vec::new(),
+            operation_id: new_operation_id(),
         })
     }
 
-    fn new_operation_id() -> String {
-        rand::thread_rng()
-            .sample_iter(&rand::distributions::Alphanumeric)
-            .take(8)
-            .map(char::from)
-            .collect()
+    /// Sets the target size of each written file, in bytes.
+    pub fn with_target_file_size(mut self, target_file_size_bytes: usize) -> Self {
+        self.target_file_size_bytes = target_file_size_bytes;
+        self
+    }
+
+    /// Sets the compression codec of written files, at the given level or at the
+    /// codec's default level. Applies to files not yet started.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned if the level is out of the
+    /// codec's range.
+    pub fn with_compression(
+        mut self,
+        codec: CompressionCodec,
+        level: Option<i32>
+    ) -> IcebergResult<Self> {
+        self.compression = codec.parquet_compression(level)?;
+        Ok(self)
+    }
+
+    /// Sets the modes choosing the metrics recorded for each column of written
+    /// files. By default, counts and bounds truncated to 16 characters or bytes are
+    /// recorded for all columns.
+    pub fn with_metrics_config(mut self, metrics_config: MetricsConfig) -> Self {
+        self.metrics_config = metrics_config;
+        self
+    }
+
+    /// Sets the columns of written files that have bloom filters. Applies to files
+    /// not yet started.
+    pub fn with_bloom_filter_config(
+        mut self,
+        bloom_filter_config: BloomFilterConfig
+    ) -> Self {
+        self.bloom_filter_config = bloom_filter_config;
+        self
+    }
+
+    pub(crate) fn with_operation_id(mut self, operation_id: &str) -> Self {
+        self.operation_id = operation_id.to_string();
+        self
     }
 
     pub fn arrow_schema(&self) -> ArrowSchemaRef {
         self.arrow_schema.clone()
     }
 
-    fn get_or_insert_writer<'a>(
-        &mut self,
-        partition_values: PartitionValues
-    ) -> IcebergResult<&mut ArrowWriter<Vec<u8>>> {
-        Ok(match self.writers.contains_key(&partition_values) {
-            true => self.writers.get_mut(&partition_values).unwrap(),
-            false => {
-                let writer = ArrowWriter::try_new(
-                    Vec::new(),
-                    self.arrow_schema.clone(),
-                    Some(self.writer_props.clone())
-                )?;
-
-                self.writers.entry(partition_values)
-                    .or_insert(writer)
-            }
-        })
-    }
+    /// Writes a `RecordBatch` to the current file, rolling over to a new file
+    /// afterwards if the current file reached the target size.
+    ///
+    /// Decimal columns with a scale different from the table's are rescaled.
+    pub fn write(&mut self, batch: &RecordBatch) -> IcebergResult<()> {
+        let batch = &conform_batch(batch, &self.arrow_schema)?;
 
-    pub fn write_partition(
-        &mut self,
-        source_values: HashMap<i32, Option<Value>>,
-        batch: &RecordBatch
-    ) -> IcebergResult<()> {
-        if batch.schema() != self.arrow_schema {
-            return Err(IcebergError::SchemaError {
-                message: "schema mismatch".to_string()
-            })
+        if self.current_writer.is_none() {
+            self.current_size = 0;
+            self.current_writer = Some(ArrowWriter::try_new(
+                Vec::new(),
+                self.arrow_schema.clone(),
+                Some(data_writer_props(self.compression, &self.bloom_filter_config))
+            )?);
         }
 
-        let partition_values = self.partition_spec.partition_values(source_values)?;
-        let writer = self.get_or_insert_writer(partition_values)?;
+        // Can't fail, the writer was created above.
+        let writer = self.current_writer.as_mut().unwrap();
         writer.write(batch)?;
+        self.current_size += batch.get_array_memory_size();
+
+        if self.current_size >= self.target_file_size_bytes {
+            // Close the file at the row group boundary.
+            if let Some(writer) = self.current_writer.take() {
+                self.closed_writers.push(writer);
+            }
+        }
 
         Ok(())
     }
 
-    async fn flush_writer(
-        &mut self,
-        table: &mut IcebergTable,
-        partition_values: PartitionValues,
+    /// Flushes a single parquet writer to a new data file of the table.
+    fn flush_writer(
+        &self,
+        table: &IcebergTable,
         mut writer: ArrowWriter<Vec<u8>>,
         filename: &str
     ) -> IcebergResult<(IcebergFile, DataFile)> {
@@ -187,11 +953,17 @@ impl RecordBatchWriter {
             .map(|row_group| row_group.num_rows())
             .sum();
 
+        let metrics = collect_metrics(
+            writer.flushed_row_groups(),
+            &self.schema,
+            &self.metrics_config
+        )?;
+
         // Consume the writer and obtain the written data.
         let data = writer.into_inner()?;
 
         let file = table.new_data_file(
-            &partition_values,
+            &self.partition_values,
             filename,
             Bytes::from(data)
         )?;
@@ -206,42 +978,409 @@ impl RecordBatchWriter {
                     message: "Failed to create data file: too large".to_string()
                 }
             })?)
-            .with_partition_values(partition_values)
+            .with_partition_values(self.partition_values.clone())
+            .with_value_counts(metrics.value_counts)
+            .with_null_value_counts(metrics.null_value_counts)
+            .with_lower_bounds(metrics.lower_bounds)
+            .with_upper_bounds(metrics.upper_bounds)
             .build();
 
         Ok((file, data_file))
     }
 
-    /// Flushes all enqueued `RecordBatch`es to the table as Parquet files, but does
-    /// not commit them.
-    ///
-    /// Call [`RecordBatchWriter::commit()`] to commit all flushed files.
+    /// Closes all files, saves them to the table's storage and returns the
+    /// [`DataFile`]s pointing to them. The files are not committed.
     ///
     /// In case of an error, orphan files may be left in the object store.
-    pub async fn flush(&mut self, table: &mut IcebergTable) -> IcebergResult<()> {
-        let writers = std::mem::take(&mut self.writers);
-        let file_count = writers.len();
+    pub async fn close(mut self, table: &IcebergTable) -> IcebergResult<Vec<DataFile>> {
+        let mut writers = std::mem::take(&mut self.closed_writers);
+        writers.extend(self.current_writer.take());
 
         let now = chrono::Utc::now().format("%Y%m%d_%H%M%S");
 
-        for (partition_values, writer) in writers.into_iter() {
+        let mut data_files = Vec::with_capacity(writers.len());
+        for (file_index, writer) in writers.into_iter().enumerate() {
             // Unique file UUID.
             let file_uuid = Uuid::new_v4().to_string();
             let filename = format!(
                 "{}_{:05x}_{}-{}.parquet",
-                now, file_count, self.operation_id, file_uuid,
+                now, file_index, self.operation_id, file_uuid,
             );
 
-            let (file, data_file) = self.flush_writer(
-                table,
-                partition_values,
-                writer,
-                &filename
-            ).await?;
+            let (file, data_file) = self.flush_writer(table, writer, &filename)?;
 
             file.save().await?;
 
-            self.flushed_files.push((file, data_file));
+            data_files.push(data_file);
+        }
+
+        Ok(data_files)
+    }
+}
+
+/// Converts the value at `row` of an Arrow array to the Avro value of a field.
+///
+/// Values of optional fields are wrapped in the `null` union of their Avro type.
+fn avro_value(array: &dyn Array, row: usize, required: bool) -> IcebergResult<AvroValue> {
+    macro_rules! value {
+        ($array_type:ty) => {
+            // Can't fail, the batch was conformed to the table's schema.
+            array.as_any().downcast_ref::<$array_type>().unwrap().value(row)
+        };
+    }
+
+    if array.is_null(row) {
+        return match required {
+            true => Err(IcebergError::ValueError(
+                "null value in required column".to_string()
+            )),
+            false => Ok(AvroValue::Union(0, Box::new(AvroValue::Null))),
+        };
+    }
+
+    let value = match array.data_type() {
+        ArrowDataType::Boolean => AvroValue::Boolean(value!(BooleanArray)),
+        ArrowDataType::Int32 => AvroValue::Int(value!(Int32Array)),
+        ArrowDataType::Int64 => AvroValue::Long(value!(Int64Array)),
+        ArrowDataType::Float32 => AvroValue::Float(value!(Float32Array)),
+        ArrowDataType::Float64 => AvroValue::Double(value!(Float64Array)),
+        ArrowDataType::Date32 => AvroValue::Date(value!(Date32Array)),
+        ArrowDataType::Time64(_) => AvroValue::TimeMicros(value!(Time64MicrosecondArray)),
+        ArrowDataType::Timestamp(..) => {
+            AvroValue::TimestampMicros(value!(TimestampMicrosecondArray))
+        },
+        ArrowDataType::Utf8 => AvroValue::String(value!(StringArray).to_string()),
+        ArrowDataType::Binary => AvroValue::Bytes(value!(BinaryArray).to_vec()),
+        data_type => return Err(IcebergError::Unsupported(
+            format!("writing {data_type} values to avro data files")
+        )),
+    };
+
+    Ok(match required {
+        true => value,
+        false => AvroValue::Union(1, Box::new(value)),
+    })
+}
+
+/// Writes Apache Arrow `RecordBatch`es of a single partition to an Avro data file.
+///
+/// Batches are buffered in memory and encoded when the writer is closed. Only the
+/// value and null counts of each column are recorded in the written [`DataFile`],
+/// without bounds. Tables with nested fields, or decimal, UUID or fixed-length binary
+/// fields, can't be written to Avro yet.
+pub struct AvroDataWriter {
+    schema: Schema,
+    arrow_schema: ArrowSchemaRef,
+    avro_schema: AvroSchema,
+    partition_values: PartitionValues,
+    metrics_config: MetricsConfig,
+    batches: Vec<RecordBatch>,
+    // Unique operation id to be used in file names.
+    operation_id: String,
+}
+
+impl AvroDataWriter {
+    /// Creates a new `AvroDataWriter` for the given partition of the table, deriving
+    /// the schema and the metrics modes from it.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if the table's schema can't be
+    /// written to Avro, and [`IcebergError::InvalidProperty`] if its metrics
+    /// properties are invalid.
+    pub fn for_table(
+        table: &IcebergTable,
+        partition_values: PartitionValues
+    ) -> IcebergResult<Self> {
+        let metadata = table.current_metadata()?;
+        Ok(Self::try_new(metadata.current_schema().clone(), partition_values)?
+            .with_metrics_config(metadata.metrics_config()?))
+    }
+
+    /// Creates a new `AvroDataWriter` writing data of the given schema to the
+    /// partition with the given values.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if the schema can't be written to
+    /// Avro.
+    pub fn try_new(
+        schema: Schema,
+        partition_values: PartitionValues
+    ) -> IcebergResult<Self> {
+        let avro_schema = iceberg_to_avro_schema(&schema)?;
+        let arrow_schema = iceberg_to_arrow_schema(&schema)?;
+        let arrow_schema = arrow_schema_add_parquet_ids(arrow_schema);
+
+        Ok(Self {
+            schema: schema,
+            arrow_schema: Arc::new(arrow_schema),
+            avro_schema: avro_schema,
+            partition_values: partition_values,
+            metrics_config: MetricsConfig::default(),
+            batches: Vec::new(),
+            operation_id: new_operation_id(),
+        })
+    }
+
+    /// Sets the modes choosing the metrics recorded for each column. Value and null
+    /// counts are recorded for columns in any mode other than `none`.
+    pub fn with_metrics_config(mut self, metrics_config: MetricsConfig) -> Self {
+        self.metrics_config = metrics_config;
+        self
+    }
+
+    pub(crate) fn with_operation_id(mut self, operation_id: &str) -> Self {
+        self.operation_id = operation_id.to_string();
+        self
+    }
+
+    pub fn arrow_schema(&self) -> ArrowSchemaRef {
+        self.arrow_schema.clone()
+    }
+
+    /// Buffers a `RecordBatch` to be written to the file.
+    ///
+    /// Decimal columns with a scale different from the table's are rescaled.
+    pub fn write(&mut self, batch: &RecordBatch) -> IcebergResult<()> {
+        let batch = conform_batch(batch, &self.arrow_schema)?;
+        if batch.num_rows() > 0 {
+            self.batches.push(batch);
+        }
+
+        Ok(())
+    }
+
+    /// Encodes the buffered batches, returning the file's content.
+    fn encode(&self) -> IcebergResult<Vec<u8>> {
+        let mut writer = AvroWriter::new(&self.avro_schema, Vec::new());
+        for batch in &self.batches {
+            for row in 0..batch.num_rows() {
+                let record = self.schema.fields().iter()
+                    .zip(batch.columns())
+                    .map(|(field, column)| {
+                        avro_value(column.as_ref(), row, field.required())
+                            .map(|value| (field.name().to_string(), value))
+                    })
+                    .collect::<IcebergResult<Vec<_>>>()?;
+                writer.append(AvroValue::Record(record))?;
+            }
+        }
+
+        Ok(writer.into_inner()?)
+    }
+
+    /// Writes the buffered batches to a single file, saves it to the table's storage
+    /// and returns the [`DataFile`] pointing to it. No file is written if no rows
+    /// were buffered. The file is not committed.
+    pub async fn close(self, table: &IcebergTable) -> IcebergResult<Vec<DataFile>> {
+        if self.batches.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let data = self.encode()?;
+
+        let mut value_counts: HashMap<i32, i64> = HashMap::new();
+        let mut null_value_counts: HashMap<i32, i64> = HashMap::new();
+        for (index, field) in self.schema.fields().iter().enumerate() {
+            if self.metrics_config.column_mode(field.name()) == MetricsMode::None {
+                continue;
+            }
+
+            for batch in &self.batches {
+                let column = batch.column(index);
+                *value_counts.entry(field.id).or_default() += column.len() as i64;
+                *null_value_counts.entry(field.id).or_default() +=
+                    column.null_count() as i64;
+            }
+        }
+        let record_count = self.batches.iter()
+            .map(|batch| batch.num_rows() as i64)
+            .sum();
+
+        let filename = format!(
+            "{}_{}-{}.avro",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+            self.operation_id,
+            Uuid::new_v4()
+        );
+        let file = table.new_data_file(
+            &self.partition_values,
+            &filename,
+            Bytes::from(data)
+        )?;
+
+        let data_file = DataFile::builder(
+            DataFileContent::Data,
+            &file.url(),
+            DataFileFormat::Avro,
+            record_count,
+            i64::try_from(file.len()).map_err(|_| {
+                IcebergError::CustomError {
+                    message: "Failed to create data file: too large".to_string()
+                }
+            })?)
+            .with_partition_values(self.partition_values.clone())
+            .with_value_counts(value_counts)
+            .with_null_value_counts(null_value_counts)
+            .build();
+
+        file.save().await?;
+
+        Ok(vec![data_file])
+    }
+}
+
+/// Writer of the data files of a single partition, in the table's file format.
+enum PartitionWriter {
+    Parquet(RollingDataWriter),
+    Avro(AvroDataWriter),
+}
+
+impl PartitionWriter {
+    fn write(&mut self, batch: &RecordBatch) -> IcebergResult<()> {
+        match self {
+            PartitionWriter::Parquet(writer) => writer.write(batch),
+            PartitionWriter::Avro(writer) => writer.write(batch),
+        }
+    }
+
+    async fn close(self, table: &IcebergTable) -> IcebergResult<Vec<DataFile>> {
+        match self {
+            PartitionWriter::Parquet(writer) => writer.close(table).await,
+            PartitionWriter::Avro(writer) => writer.close(table).await,
+        }
+    }
+}
+
+/// Writes Apache Arrow `RecordBatch`es to an Iceberg table.
+///
+/// Data files are written in the format selected by the `write.format.default`
+/// table property: Parquet by default, or Avro. Each partition is written using a
+/// [`RollingDataWriter`] or an [`AvroDataWriter`], with the target size of Parquet
+/// files taken from the `write.target-file-size-bytes` table property.
+pub struct RecordBatchWriter {
+    schema: Schema,
+    arrow_schema: ArrowSchemaRef,
+    partition_spec: PartitionSpec,
+    target_file_size_bytes: usize,
+    compression_codec: CompressionCodec,
+    compression_level: Option<i32>,
+    metrics_config: MetricsConfig,
+    bloom_filter_config: BloomFilterConfig,
+    file_format: DataFileFormat,
+    // Per-partition writer.
+    writers: HashMap<PartitionValues, PartitionWriter>,
+    // All files flushed to storage and ready to be commited.
+    flushed_files: Vec<DataFile>,
+    // Unique operation id to be used in file names.
+    // Helps identify files created by the same write operation.
+    operation_id: String,
+}
+
+impl RecordBatchWriter {
+    /// Creates a new `RecordBatchWriter` for the given table, deriving the schema,
+    /// partition fields, file format, compression, metrics modes and bloom filters
+    /// from it.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::InvalidProperty`] is returned if one of the table's write
+    /// properties is invalid, and [`IcebergError::Unsupported`] if the file format
+    /// is ORC. [`IcebergError::SchemaError`] is returned if the table's schema
+    /// can't be written to Avro files.
+    pub fn for_table(table: &IcebergTable) -> IcebergResult<Self> {
+        let schema = table.current_schema()?.clone();
+        let arrow_schema = iceberg_to_arrow_schema(&schema)?;
+        let arrow_schema = arrow_schema_add_parquet_ids(arrow_schema);
+
+        let metadata = table.current_metadata()?;
+        let target_file_size_bytes = usize::try_from(
+            metadata.write_target_file_size_bytes()?
+        ).unwrap_or(usize::MAX);
+
+        // Fail early on invalid levels, rather than on the first write.
+        let compression_codec = metadata.parquet_compression_codec()?;
+        let compression_level = metadata.parquet_compression_level()?;
+        compression_codec.parquet_compression(compression_level)?;
+
+        let file_format = metadata.default_file_format()?;
+        match file_format {
+            DataFileFormat::Parquet => {},
+            DataFileFormat::Avro => { iceberg_to_avro_schema(&schema)?; },
+            DataFileFormat::ORC => return Err(IcebergError::Unsupported(
+                "writing ORC data files".to_string()
+            )),
+        }
+
+        Ok(Self {
+            schema: schema,
+            arrow_schema: Arc::new(arrow_schema),
+            partition_spec: table.current_partition_spec()?,
+            target_file_size_bytes: target_file_size_bytes,
+            compression_codec: compression_codec,
+            compression_level: compression_level,
+            metrics_config: metadata.metrics_config()?,
+            bloom_filter_config: metadata.bloom_filter_config()?,
+            file_format: file_format,
+            writers: HashMap::new(),
+            flushed_files: Vec::new(),
+            operation_id: new_operation_id()
+        })
+    }
+
+    pub fn arrow_schema(&self) -> ArrowSchemaRef {
+        self.arrow_schema.clone()
+    }
+
+    pub fn write_partition(
+        &mut self,
+        source_values: HashMap<i32, Option<Value>>,
+        batch: &RecordBatch
+    ) -> IcebergResult<()> {
+        let batch = &conform_batch(batch, &self.arrow_schema)?;
+
+        let partition_values = self.partition_spec.partition_values(source_values)?;
+        let writer = match self.writers.entry(partition_values.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let schema = self.schema.clone();
+                entry.insert(match self.file_format {
+                    DataFileFormat::Avro => PartitionWriter::Avro(
+                        AvroDataWriter::try_new(schema, partition_values)?
+                            .with_metrics_config(self.metrics_config.clone())
+                            .with_operation_id(&self.operation_id)
+                    ),
+                    _ => PartitionWriter::Parquet(
+                        RollingDataWriter::try_new(schema, partition_values)?
+                            .with_target_file_size(self.target_file_size_bytes)
+                            .with_compression(
+                                self.compression_codec,
+                                self.compression_level
+                            )?
+                            .with_metrics_config(self.metrics_config.clone())
+                            .with_bloom_filter_config(self.bloom_filter_config.clone())
+                            .with_operation_id(&self.operation_id)
+                    ),
+                })
+            }
+        };
+
+        writer.write(batch)
+    }
+
+    /// Flushes all enqueued `RecordBatch`es to the table as Parquet files, but does
+    /// not commit them.
+    ///
+    /// Call [`RecordBatchWriter::commit()`] to commit all flushed files.
+    ///
+    /// In case of an error, orphan files may be left in the object store.
+    pub async fn flush(&mut self, table: &mut IcebergTable) -> IcebergResult<()> {
+        let writers = std::mem::take(&mut self.writers);
+
+        for writer in writers.into_values() {
+            let data_files = writer.close(table).await?;
+            self.flushed_files.extend(data_files);
         }
 
         Ok(())
@@ -253,10 +1392,9 @@ impl RecordBatchWriter {
     pub async fn commit(&mut self, table: &mut IcebergTable) -> IcebergResult<()> {
         self.flush(table).await?;
 
-        self.operation_id = Self::new_operation_id();
+        self.operation_id = new_operation_id();
 
-        let data_files = std::mem::take(&mut self.flushed_files)
-            .into_iter().map(|tup| tup.1);
+        let data_files = std::mem::take(&mut self.flushed_files);
 
         let mut transaction = table.new_transaction();
         let mut operation = AppendFilesOperation::new();
@@ -266,3 +1404,431 @@ impl RecordBatchWriter {
         transaction.commit().await
     }
 }
+
+/// Field id of the `file_path` column of position delete files, reserved by the spec.
+pub const DELETE_FILE_PATH_FIELD_ID: i32 = 2147483546;
+/// Field id of the `pos` column of position delete files, reserved by the spec.
+pub const DELETE_POS_FIELD_ID: i32 = 2147483545;
+
+/// Writes position delete files, marking rows of data files as deleted by their
+/// file path and ordinal position within the file.
+///
+/// Deletes are buffered in memory and written to a single Parquet file, sorted by
+/// file path and position as required by the spec, when the writer is closed.
+pub struct PositionDeleteWriter {
+    schema: Schema,
+    partition_values: PartitionValues,
+    deletes: Vec<(String, i64)>,
+}
+
+impl PositionDeleteWriter {
+    /// Creates a new `PositionDeleteWriter` for deletes of rows in data files of the
+    /// partition with the given values.
+    pub fn new(partition_values: PartitionValues) -> Self {
+        Self {
+            schema: Schema::new(0, vec![
+                Field::new_primitive(
+                    DELETE_FILE_PATH_FIELD_ID, "file_path", true, PrimitiveType::String
+                ),
+                Field::new_primitive(DELETE_POS_FIELD_ID, "pos", true, PrimitiveType::Long),
+            ]),
+            partition_values: partition_values,
+            deletes: Vec::new(),
+        }
+    }
+
+    /// Marks the row at position `pos` of the data file at `file_path` as deleted.
+    pub fn delete(&mut self, file_path: &str, pos: i64) {
+        self.deletes.push((file_path.to_string(), pos));
+    }
+
+    /// Writes all deletes to a position delete file, saves it to the table's storage
+    /// and returns the [`DataFile`] pointing to it. The file is not committed.
+    pub async fn close(mut self, table: &IcebergTable) -> IcebergResult<DataFile> {
+        self.deletes.sort();
+        self.deletes.dedup();
+
+        let arrow_schema = Arc::new(arrow_schema_add_parquet_ids(
+            iceberg_to_arrow_schema(&self.schema)?
+        ));
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(StringArray::from_iter_values(
+                    self.deletes.iter().map(|(file_path, _)| file_path)
+                )),
+                Arc::new(Int64Array::from_iter_values(
+                    self.deletes.iter().map(|(_, pos)| *pos)
+                )),
+            ]
+        )?;
+
+        let mut writer = new_delete_file_writer(arrow_schema)?;
+        writer.write(&batch)?;
+
+        save_delete_file(
+            table,
+            writer,
+            &self.schema,
+            self.partition_values,
+            DataFileContent::PositionDelete,
+            None
+        ).await
+    }
+}
+
+/// Writes `RecordBatch`es of rows to delete by their values in some of the table's
+/// columns, to an equality delete file.
+///
+/// Every row of the table whose values in the equality columns match those of a
+/// row in the delete file is deleted.
+pub struct EqualityDeleteWriter {
+    schema: Schema,
+    arrow_schema: ArrowSchemaRef,
+    equality_ids: Vec<i32>,
+    partition_values: PartitionValues,
+    writer: ArrowWriter<Vec<u8>>,
+}
+
+impl EqualityDeleteWriter {
+    /// Creates a new `EqualityDeleteWriter` for the given partition of the table,
+    /// deriving the schema from it.
+    pub fn for_table(
+        table: &IcebergTable,
+        equality_ids: Vec<i32>,
+        partition_values: PartitionValues
+    ) -> IcebergResult<Self> {
+        Self::try_new(table.current_schema()?, equality_ids, partition_values)
+    }
+
+    /// Creates a new `EqualityDeleteWriter` deleting rows by the values of the
+    /// top-level columns of `schema` with the given field ids.
+    ///
+    /// # Errors
+    ///
+    /// [`IcebergError::SchemaError`] is returned if no equality columns are given, or
+    /// if one of them doesn't exist in the schema, isn't a primitive or isn't
+    /// required.
+    pub fn try_new(
+        schema: &Schema,
+        equality_ids: Vec<i32>,
+        partition_values: PartitionValues
+    ) -> IcebergResult<Self> {
+        if equality_ids.is_empty() {
+            return Err(IcebergError::SchemaError {
+                message: "equality deletes require at least one column".to_string()
+            });
+        }
+
+        let mut fields = Vec::with_capacity(equality_ids.len());
+        for field_id in &equality_ids {
+            let field = schema.fields().iter()
+                .find(|field| field.id == *field_id)
+                .ok_or_else(|| IcebergError::SchemaError {
+                    message: format!("equality field {field_id} not found in schema")
+                })?;
+
+            if !matches!(field.schema_type(), SchemaType::Primitive(_)) {
+                return Err(IcebergError::SchemaError {
+                    message: format!("equality field {} must be a primitive", field.name)
+                });
+            }
+            if !field.required {
+                return Err(IcebergError::SchemaError {
+                    message: format!("equality field {} must be required", field.name)
+                });
+            }
+
+            fields.push(field.clone());
+        }
+
+        let schema = Schema::new(schema.id(), fields);
+        let arrow_schema = Arc::new(arrow_schema_add_parquet_ids(
+            iceberg_to_arrow_schema(&schema)?
+        ));
+
+        Ok(Self {
+            schema: schema,
+            arrow_schema: arrow_schema.clone(),
+            equality_ids: equality_ids,
+            partition_values: partition_values,
+            writer: new_delete_file_writer(arrow_schema)?,
+        })
+    }
+
+    /// The Arrow schema of the written batches, containing only the equality columns.
+    pub fn arrow_schema(&self) -> ArrowSchemaRef {
+        self.arrow_schema.clone()
+    }
+
+    /// Writes the values of rows to delete.
+    pub fn write(&mut self, batch: &RecordBatch) -> IcebergResult<()> {
+        let batch = conform_batch(batch, &self.arrow_schema)?;
+        self.writer.write(&batch)?;
+        Ok(())
+    }
+
+    /// Writes the equality delete file to the table's storage and returns the
+    /// [`DataFile`] pointing to it. The file is not committed.
+    pub async fn close(self, table: &IcebergTable) -> IcebergResult<DataFile> {
+        save_delete_file(
+            table,
+            self.writer,
+            &self.schema,
+            self.partition_values,
+            DataFileContent::EqualityDelete,
+            Some(self.equality_ids)
+        ).await
+    }
+}
+
+fn new_delete_file_writer(
+    arrow_schema: ArrowSchemaRef
+) -> IcebergResult<ArrowWriter<Vec<u8>>> {
+    Ok(ArrowWriter::try_new(
+        Vec::new(),
+        arrow_schema,
+        Some(WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::UNCOMPRESSED)
+            .build())
+    )?)
+}
+
+/// Flushes a delete file to the table's storage and creates the [`DataFile`]
+/// pointing to it.
+async fn save_delete_file(
+    table: &IcebergTable,
+    mut writer: ArrowWriter<Vec<u8>>,
+    schema: &Schema,
+    partition_values: PartitionValues,
+    content: DataFileContent,
+    equality_ids: Option<Vec<i32>>
+) -> IcebergResult<DataFile> {
+    writer.flush()?;
+
+    let record_count = writer.flushed_row_groups()
+        .iter()
+        .map(|row_group| row_group.num_rows())
+        .sum();
+    let metrics = collect_metrics(
+        writer.flushed_row_groups(),
+        schema,
+        &MetricsConfig::default()
+    )?;
+    let data = writer.into_inner()?;
+
+    let filename = format!(
+        "{}_{}-{}-deletes.parquet",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+        new_operation_id(),
+        Uuid::new_v4(),
+    );
+    let file = table.new_data_file(&partition_values, &filename, Bytes::from(data))?;
+
+    let mut builder = DataFile::builder(
+        content,
+        &file.url(),
+        DataFileFormat::Parquet,
+        record_count,
+        i64::try_from(file.len()).map_err(|_| {
+            IcebergError::CustomError {
+                message: "Failed to create delete file: too large".to_string()
+            }
+        })?)
+        .with_partition_values(partition_values)
+        .with_value_counts(metrics.value_counts)
+        .with_null_value_counts(metrics.null_value_counts)
+        .with_lower_bounds(metrics.lower_bounds)
+        .with_upper_bounds(metrics.upper_bounds);
+    if let Some(equality_ids) = equality_ids {
+        builder = builder.with_equality_ids(equality_ids);
+    }
+    let data_file = builder.build();
+
+    file.save().await?;
+
+    Ok(data_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_schema::{Schema as ArrowSchema, Field as ArrowField, DataType as ArrowDataType};
+    use arrow_array::{RecordBatch, Array, Decimal128Array};
+
+    use crate::IcebergError;
+    use crate::value::Value;
+    use crate::writer::{
+        truncate_lower_bound, truncate_upper_bound, conform_batch,
+        MetricsMode, MetricsConfig, BloomFilterConfig, DEFAULT_BLOOM_FILTER_FPP
+    };
+
+    #[test]
+    fn truncate_string_bounds() {
+        // Short values are not truncated.
+        let value = Value::String("iceberg".to_string());
+        assert_eq!(truncate_lower_bound(value.clone(), 16), value);
+        assert_eq!(truncate_upper_bound(value.clone(), 16), Some(value));
+
+        let value = Value::String("abcdefghijklmnopqrstuvwxyz".to_string());
+        assert_eq!(
+            truncate_lower_bound(value.clone(), 16),
+            Value::String("abcdefghijklmnop".to_string())
+        );
+        assert_eq!(
+            truncate_upper_bound(value, 16),
+            Some(Value::String("abcdefghijklmnoq".to_string()))
+        );
+
+        // The maximal char can't be incremented, so the previous one is.
+        let value = Value::String(
+            format!("abcdefghijklmno{}xyz", char::MAX)
+        );
+        assert_eq!(
+            truncate_upper_bound(value, 16),
+            Some(Value::String("abcdefghijklmnp".to_string()))
+        );
+    }
+
+    #[test]
+    fn truncate_binary_bounds() {
+        let value = Value::Binary(vec![0x01; 20]);
+        assert_eq!(
+            truncate_lower_bound(value.clone(), 16),
+            Value::Binary(vec![0x01; 16])
+        );
+
+        let mut expected = vec![0x01; 16];
+        expected[15] = 0x02;
+        assert_eq!(truncate_upper_bound(value, 16), Some(Value::Binary(expected)));
+
+        // No valid upper bound exists.
+        let value = Value::Binary(vec![0xff; 20]);
+        assert_eq!(truncate_upper_bound(value, 16), None);
+    }
+
+    #[test]
+    fn metrics_config() {
+        assert_eq!("none".parse::<MetricsMode>().unwrap(), MetricsMode::None);
+        assert_eq!("Counts".parse::<MetricsMode>().unwrap(), MetricsMode::Counts);
+        assert_eq!("full".parse::<MetricsMode>().unwrap(), MetricsMode::Full);
+        assert_eq!(
+            "truncate(8)".parse::<MetricsMode>().unwrap(),
+            MetricsMode::Truncate(8)
+        );
+        for invalid in ["truncate()", "truncate(-1)", "truncate(0)", "partial"] {
+            assert!(invalid.parse::<MetricsMode>().is_err(), "{invalid}");
+        }
+
+        let config = MetricsConfig::from_properties(&[
+            ("write.metadata.metrics.default", "counts"),
+            ("write.metadata.metrics.column.location.lat", "full"),
+            ("write.target-file-size-bytes", "1024"),
+        ].into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()).unwrap();
+        assert_eq!(config.column_mode("location.lat"), MetricsMode::Full);
+        assert_eq!(config.column_mode("location.long"), MetricsMode::Counts);
+
+        // Iceberg's default mode.
+        assert_eq!(
+            MetricsConfig::default().column_mode("id"),
+            MetricsMode::Truncate(16)
+        );
+
+        let invalid = MetricsConfig::from_properties(&[
+            ("write.metadata.metrics.column.id".to_string(), "bounds".to_string())
+        ].into_iter().collect());
+        assert!(matches!(invalid, Err(IcebergError::InvalidProperty { .. })));
+    }
+
+    #[test]
+    fn bloom_filter_config() {
+        let config = BloomFilterConfig::from_properties(&[
+            ("write.parquet.bloom-filter-enabled.column.id", "true"),
+            ("write.parquet.bloom-filter-enabled.column.location.lat", "TRUE"),
+            ("write.parquet.bloom-filter-fpp.column.location.lat", "0.2"),
+            ("write.parquet.bloom-filter-enabled.column.name", "false"),
+            ("write.parquet.bloom-filter-fpp.column.name", "0.1"),
+        ].into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()).unwrap();
+        assert_eq!(config.column_fpp("id"), Some(DEFAULT_BLOOM_FILTER_FPP));
+        assert_eq!(config.column_fpp("location.lat"), Some(0.2));
+        assert_eq!(config.column_fpp("name"), None);
+
+        for (key, value) in [
+            ("write.parquet.bloom-filter-enabled.column.id", "yes"),
+            ("write.parquet.bloom-filter-fpp.column.id", "0"),
+        ] {
+            let invalid = BloomFilterConfig::from_properties(&[
+                ("write.parquet.bloom-filter-enabled.column.id", "true"),
+                (key, value),
+            ].into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+            assert!(matches!(invalid, Err(IcebergError::InvalidProperty { .. })));
+        }
+
+        assert!(BloomFilterConfig::default().with_column("id", 1.0).is_err());
+    }
+
+    fn decimal_batch(values: Vec<Option<i128>>, precision: u8, scale: i8) -> RecordBatch {
+        let array = Decimal128Array::from(values)
+            .with_precision_and_scale(precision, scale)
+            .unwrap();
+        RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![
+                ArrowField::new("price", ArrowDataType::Decimal128(precision, scale), true)
+            ])),
+            vec![Arc::new(array)]
+        ).unwrap()
+    }
+
+    #[test]
+    fn rescale_decimal_column() {
+        let target = decimal_batch(Vec::new(), 10, 4).schema();
+
+        // 12.34, null, -0.05
+        let batch = decimal_batch(vec![Some(1234), None, Some(-5)], 10, 2);
+        let conformed = conform_batch(&batch, &target).unwrap();
+
+        assert_eq!(conformed.schema(), target);
+        let prices = conformed.column(0).as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(prices.iter().collect::<Vec<_>>(), [Some(123400), None, Some(-500)]);
+
+        // Scaling down is fine as long as no digits are lost.
+        let target = decimal_batch(Vec::new(), 10, 1).schema();
+        let conformed = conform_batch(&decimal_batch(vec![Some(1230)], 10, 2), &target);
+        let prices = conformed.unwrap();
+        let prices = prices.column(0).as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(prices.value(0), 123);
+        assert!(matches!(
+            conform_batch(&decimal_batch(vec![Some(1234)], 10, 2), &target),
+            Err(IcebergError::ValueError(_))
+        ));
+    }
+
+    #[test]
+    fn rescale_decimal_overflow() {
+        // 99999.99 needs 9 digits at scale 4.
+        let target = decimal_batch(Vec::new(), 8, 4).schema();
+        let batch = decimal_batch(vec![Some(1), Some(9999999)], 7, 2);
+
+        assert!(matches!(
+            conform_batch(&batch, &target),
+            Err(IcebergError::ValueError(_))
+        ));
+    }
+
+    #[test]
+    fn conform_mismatched_batch() {
+        let target = decimal_batch(Vec::new(), 10, 4).schema();
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![
+                ArrowField::new("price", ArrowDataType::Int64, true)
+            ])),
+            vec![Arc::new(arrow_array::Int64Array::from(vec![1]))]
+        ).unwrap();
+
+        assert!(matches!(
+            conform_batch(&batch, &target),
+            Err(IcebergError::SchemaError{..})
+        ));
+    }
+}
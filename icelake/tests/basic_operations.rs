@@ -1,9 +1,24 @@
 //! Tests for basic transaction operations.
+use std::collections::HashMap;
+
+use bytes::Bytes;
 use uuid::Uuid;
 
-use icelake::{IcebergTable, IcebergTableLoader};
+use icelake::{IcebergTable, IcebergTableLoader, IcebergTableVersion, IcebergError};
+use icelake::iceberg::METADATA_JSON_PRETTY_PROPERTY;
 use icelake::schema::{Schema, Field, PrimitiveType};
-use icelake::transaction::{AppendFilesOperation, OverwriteFilesOperation};
+use icelake::value::Value;
+use icelake::partition::{
+    PartitionSpec, PartitionField, PartitionTransform, PartitionValues
+};
+use icelake::sort::{SortOrder, SortField, SortDirection, NullOrder};
+use icelake::transaction::{
+    AppendFilesOperation, OverwriteFilesOperation, ReplaceSortOrderOperation,
+    UpdateSchemaOperation, UpdatePropertiesOperation, UpdatePartitionSpecOperation,
+    RowDeltaOperation
+};
+use icelake::snapshot::SnapshotOperation;
+use icelake::puffin::{PuffinWriter, PuffinReader, Blob, APACHE_DATASKETCHES_THETA_V1};
 use icelake::manifest::{
     Manifest, ManifestFile, ManifestReader,
     DataFile, DataFileContent, DataFileFormat
@@ -113,17 +128,151 @@ async fn append_operation() {
 
     assert_eq!(manifest_files.len(), 2);
 
-    assert_eq!(manifest_files[0].sequence_number, 1);
+    // The newest manifest is listed first.
+    assert_eq!(manifest_files[0].sequence_number, 2);
     assert_eq!(manifest_files[0].added_data_files_count, 1);
-    assert_eq!(manifest_files[0].added_rows_count, 1111);
+    assert_eq!(manifest_files[0].added_rows_count, 2222);
     assert_eq!(manifest_files[0].existing_data_files_count, 0);
 
-    assert_eq!(manifest_files[1].sequence_number, 2);
+    assert_eq!(manifest_files[1].sequence_number, 1);
     assert_eq!(manifest_files[1].added_data_files_count, 1);
-    assert_eq!(manifest_files[1].added_rows_count, 2222);
+    assert_eq!(manifest_files[1].added_rows_count, 1111);
     assert_eq!(manifest_files[1].existing_data_files_count, 0);
 }
 
+#[tokio::test]
+async fn append_transaction() {
+    let mut table = TestTable::new().await;
+
+    let first_files = vec![table.new_datafile(100, 1000), table.new_datafile(50, 500)];
+    let second_files = vec![table.new_datafile(25, 250)];
+
+    let mut transaction = table.new_transaction();
+    transaction.append(first_files);
+    transaction.commit().await.unwrap();
+    let first_snapshot_id = table.current_snapshot().unwrap().unwrap().snapshot_id;
+
+    let mut transaction = table.new_transaction();
+    transaction.append(second_files);
+    transaction.commit().await.unwrap();
+
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    assert_eq!(snapshot.parent_snapshot_id, Some(first_snapshot_id));
+    assert_eq!(snapshot.sequence_number, 2);
+    assert_eq!(snapshot.summary.get("total-records"), Some("175"));
+    assert_eq!(snapshot.summary.get("total-data-files"), Some("3"));
+    assert_eq!(snapshot.summary.get("added-records"), Some("25"));
+
+    // The new manifest list references the manifest of the first append as well.
+    let manifest_list = table.read_manifest_list(&snapshot).await.unwrap();
+    assert_eq!(manifest_list.manifest_files().len(), 2);
+
+    let metadata = table.current_metadata().unwrap();
+    let history: Vec<i64> = metadata.snapshot_log.as_ref().unwrap().iter()
+        .map(|entry| entry.snapshot_id)
+        .collect();
+    assert_eq!(history, [first_snapshot_id, snapshot.snapshot_id]);
+    assert_eq!(metadata.snapshots.as_ref().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn fast_append_transactions() {
+    let mut table = TestTable::new().await;
+
+    let mut data_files = Vec::new();
+    for i in 1..=3 {
+        let data_file = table.new_datafile(i * 10, i * 100);
+        data_files.push(data_file.clone());
+
+        let mut transaction = table.new_transaction();
+        transaction.fast_append(vec![data_file]);
+        transaction.commit().await.unwrap();
+
+        // Each commit adds a single manifest, reusing the previous ones.
+        let snapshot = table.current_snapshot().unwrap().unwrap();
+        let manifest_list = table.read_manifest_list(&snapshot).await.unwrap();
+        assert_eq!(manifest_list.manifest_files().len(), i as usize);
+        assert_eq!(snapshot.summary.get("total-data-files"), Some(&*i.to_string()));
+    }
+
+    // All appended files are scanned.
+    let mut scanned: Vec<String> = table.scan().plan_files().await.unwrap()
+        .iter()
+        .map(|task| task.data_file().file_path.clone())
+        .collect();
+    scanned.sort();
+    let mut expected: Vec<String> = data_files.iter()
+        .map(|data_file| data_file.file_path.clone())
+        .collect();
+    expected.sort();
+    assert_eq!(scanned, expected);
+}
+
+#[tokio::test]
+async fn rewrite_manifests() {
+    let mut table = TestTable::new().await;
+
+    for i in 1..=5 {
+        let data_file = table.new_datafile(i * 10, i * 100);
+        let mut transaction = table.new_transaction();
+        transaction.fast_append(vec![data_file]);
+        transaction.commit().await.unwrap();
+    }
+
+    let snapshot = table.current_snapshot().unwrap().unwrap().clone();
+    let manifest_list = table.read_manifest_list(&snapshot).await.unwrap();
+    assert_eq!(manifest_list.manifest_files().len(), 5);
+
+    let mut entries_before = Vec::new();
+    for manifest_file in manifest_list.manifest_files() {
+        let manifest = table.read_manifest(manifest_file).await;
+        entries_before.extend(manifest.into_entries().map(|entry| (
+            entry.data_file().file_path.clone(),
+            entry.status(),
+            entry.sequence_number(),
+        )));
+    }
+    entries_before.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let scanned = |tasks: Vec<icelake::scan::FileScanTask>| {
+        let mut paths: Vec<String> = tasks.iter()
+            .map(|task| task.data_file().file_path.clone())
+            .collect();
+        paths.sort();
+        paths
+    };
+    let scanned_before = scanned(table.scan().plan_files().await.unwrap());
+
+    table.rewrite_manifests().await.unwrap();
+
+    let new_snapshot = table.current_snapshot().unwrap().unwrap().clone();
+    assert_eq!(new_snapshot.parent_snapshot_id, Some(snapshot.snapshot_id));
+    assert_eq!(new_snapshot.summary.operation(), Some(&SnapshotOperation::Replace));
+    assert_eq!(new_snapshot.summary.get("total-data-files"), Some("5"));
+
+    let manifest_list = table.read_manifest_list(&new_snapshot).await.unwrap();
+    assert_eq!(manifest_list.manifest_files().len(), 1);
+
+    // Every entry keeps its status and sequence number.
+    let manifest = table.read_manifest(&manifest_list.manifest_files()[0]).await;
+    let mut entries_after: Vec<_> = manifest.into_entries()
+        .map(|entry| (
+            entry.data_file().file_path.clone(),
+            entry.status(),
+            entry.sequence_number(),
+        ))
+        .collect();
+    entries_after.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(entries_after, entries_before);
+
+    assert_eq!(scanned(table.scan().plan_files().await.unwrap()), scanned_before);
+
+    // A single manifest has nothing to be merged with.
+    table.rewrite_manifests().await.unwrap();
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    assert_eq!(snapshot.snapshot_id, new_snapshot.snapshot_id);
+}
+
 #[tokio::test]
 async fn overwrite_operation() {
     let mut table = TestTable::new().await;
@@ -182,3 +331,652 @@ async fn overwrite_operation() {
     assert_eq!(entry.data_file(), &datafile3);
     assert_eq!(entry.sequence_number().unwrap(), 3);
 }
+
+#[tokio::test]
+async fn replace_sort_order_operation() {
+    let mut table = TestTable::new().await;
+
+    let sort_order = SortOrder {
+        order_id: 0,
+        fields: vec![SortField {
+            source_id: 2,
+            transform: PartitionTransform::Day,
+            direction: SortDirection::Descending,
+            null_order: NullOrder::Last
+        }]
+    };
+
+    let mut transaction = table.new_transaction();
+    let mut op = ReplaceSortOrderOperation::new();
+    op.set_sort_order(sort_order.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.default_sort_order_id, 1);
+    assert_eq!(metadata.sort_orders.len(), 2);
+    assert_eq!(metadata.sort_orders[1].fields, sort_order.fields);
+
+    // A sort order referencing a missing column is rejected.
+    let mut transaction = table.new_transaction();
+    let mut op = ReplaceSortOrderOperation::new();
+    op.set_sort_order(SortOrder {
+        order_id: 0,
+        fields: vec![SortField {
+            source_id: 42,
+            transform: PartitionTransform::Identity,
+            direction: SortDirection::Ascending,
+            null_order: NullOrder::First
+        }]
+    });
+    transaction.add_operation(Box::new(op));
+    assert!(transaction.commit().await.is_err());
+
+    assert_eq!(table.current_metadata().unwrap().default_sort_order_id, 1);
+
+    // The unsorted order keeps its reserved id 0, even after it was dropped from the
+    // table's sort orders.
+    let mut metadata = table.current_metadata().unwrap().clone();
+    metadata.sort_orders.retain(|sort_order| sort_order.order_id != 0);
+    table.commit(metadata).await.unwrap();
+
+    let mut transaction = table.new_transaction();
+    let mut op = ReplaceSortOrderOperation::new();
+    op.set_sort_order(SortOrder::new());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.default_sort_order_id, 0);
+    assert_eq!(metadata.sort_orders.len(), 2);
+    assert!(metadata.sort_orders.contains(&SortOrder::new()));
+}
+
+#[tokio::test]
+async fn read_older_snapshot() {
+    let mut table = TestTable::new().await;
+
+    let datafile1 = table.new_datafile(1111, 10111);
+    let datafile2 = table.new_datafile(2222, 20222);
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile1.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let first_snapshot_id = table.current_snapshot().unwrap().unwrap().snapshot_id;
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile2.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    assert!(table.snapshot_by_id(-42).unwrap().is_none());
+
+    // The first snapshot only tracks the first data file.
+    let snapshot = table.snapshot_by_id(first_snapshot_id).unwrap().unwrap();
+    let manifest_list = table.read_manifest_list(&snapshot).await.unwrap();
+    let manifest_files = manifest_list.manifest_files();
+
+    assert_eq!(manifest_files.len(), 1);
+    let manifest = table.read_manifest(&manifest_files[0]).await;
+    assert_eq!(manifest.entries().len(), 1);
+    assert_eq!(manifest.entries()[0].data_file(), &datafile1);
+}
+
+#[tokio::test]
+async fn multiple_operations_in_one_transaction() {
+    let mut table = TestTable::new().await;
+
+    let metadata_log_len = table.current_metadata().unwrap()
+        .metadata_log.as_ref().map_or(0, |log| log.len());
+    let last_sequence_number = table.current_metadata().unwrap().last_sequence_number;
+
+    let new_schema = Schema::new(0, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        Field::new_primitive(2, "ts", false, PrimitiveType::Timestamp),
+        Field::new_primitive(3, "name", false, PrimitiveType::String),
+        Field::new_primitive(4, "comment", false, PrimitiveType::String),
+    ]);
+    let datafile = table.new_datafile(1111, 10111);
+
+    let mut transaction = table.new_transaction();
+
+    // The append is added first, but is applied after the schema update.
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile.clone());
+    transaction.add_operation(Box::new(op));
+
+    let mut op = UpdateSchemaOperation::new();
+    op.set_schema(new_schema);
+    transaction.add_operation(Box::new(op));
+
+    let mut op = UpdatePropertiesOperation::new();
+    op.set("owner", "icelake");
+    transaction.add_operation(Box::new(op));
+
+    transaction.commit().await.unwrap();
+
+    let metadata = table.current_metadata().unwrap();
+
+    // All operations were committed in a single new metadata version.
+    assert_eq!(
+        metadata.metadata_log.as_ref().map_or(0, |log| log.len()),
+        metadata_log_len + 1
+    );
+
+    // Only the append created a snapshot and used a sequence number.
+    assert_eq!(metadata.last_sequence_number, last_sequence_number + 1);
+    assert_eq!(
+        table.current_snapshot().unwrap().unwrap().sequence_number,
+        last_sequence_number + 1
+    );
+
+    assert_eq!(metadata.current_schema_id, 1);
+    assert_eq!(metadata.last_column_id, 4);
+    assert!(metadata.current_schema().get_field_by_name("comment").is_some());
+    assert_eq!(
+        metadata.properties.as_ref().and_then(|properties| properties.get("owner")),
+        Some(&"icelake".to_string())
+    );
+
+    // The appended files were written with the new schema.
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    assert_eq!(snapshot.schema_id, Some(1));
+
+    let manifest_list = table.read_manifest_list(&snapshot).await.unwrap();
+    let manifest_files = manifest_list.manifest_files();
+    assert_eq!(manifest_files.len(), 1);
+
+    let manifest = table.read_manifest(&manifest_files[0]).await;
+    assert_eq!(manifest.schema_id(), 1);
+    assert_eq!(manifest.entries()[0].data_file(), &datafile);
+}
+
+#[tokio::test]
+async fn conflicting_operations_in_one_transaction() {
+    let mut table = TestTable::new().await;
+
+    let datafile = table.new_datafile(1111, 10111);
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let metadata = table.current_metadata().unwrap().clone();
+    let schema = metadata.current_schema().clone();
+
+    // Two schema replacements in the same transaction.
+    let mut transaction = table.new_transaction();
+    for (id, name) in [(1, "first"), (2, "second")] {
+        let mut fields = schema.fields().to_vec();
+        fields.push(Field::new_primitive(
+            schema.max_field_id() + id, name, false, PrimitiveType::String
+        ));
+        let mut op = UpdateSchemaOperation::new();
+        op.set_schema(Schema::new(0, fields));
+        transaction.add_operation(Box::new(op));
+    }
+    let result = transaction.commit().await;
+    assert!(matches!(result, Err(IcebergError::Validation { .. })));
+
+    // Two overwrites deleting the same file.
+    let mut transaction = table.new_transaction();
+    for _ in 0..2 {
+        let mut op = OverwriteFilesOperation::new();
+        op.delete_file(&datafile.file_path);
+        transaction.add_operation(Box::new(op));
+    }
+    let result = transaction.commit().await;
+    assert!(matches!(result, Err(IcebergError::Validation { .. })));
+
+    // Neither transaction was committed.
+    assert_eq!(table.current_metadata().unwrap(), &metadata);
+}
+
+#[tokio::test]
+async fn expire_snapshots() {
+    let mut table = TestTable::new().await;
+
+    let datafile1 = table.new_datafile(1111, 10111);
+    let datafile2 = table.new_datafile(2222, 20222);
+    let datafile3 = table.new_datafile(3333, 30333);
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile1.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let first_snapshot = table.current_snapshot().unwrap().unwrap().clone();
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile2.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let second_snapshot = table.current_snapshot().unwrap().unwrap().clone();
+    let second_manifest_list = table.read_manifest_list(&second_snapshot).await.unwrap();
+
+    // Roll back to the first snapshot and append on top of it, leaving the second
+    // snapshot outside of the current snapshot's lineage.
+    let mut metadata = table.current_metadata().unwrap().clone();
+    metadata.current_snapshot_id = Some(first_snapshot.snapshot_id);
+    table.commit(metadata).await.unwrap();
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile3.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    // Retaining the last two snapshots protects the second snapshot.
+    let removed_files = table.expire_snapshots(i64::MAX, 2).await.unwrap();
+    assert!(removed_files.is_empty());
+    assert_eq!(table.current_metadata().unwrap().snapshots.as_ref().unwrap().len(), 3);
+
+    // The first snapshot is an ancestor of the current snapshot, so only the second
+    // snapshot is expired.
+    let removed_files = table.expire_snapshots(i64::MAX, 0).await.unwrap();
+
+    let snapshots = table.current_metadata().unwrap().snapshots.as_ref().unwrap();
+    assert_eq!(snapshots.len(), 2);
+    assert!(table.snapshot_by_id(first_snapshot.snapshot_id).unwrap().is_some());
+    assert!(table.snapshot_by_id(second_snapshot.snapshot_id).unwrap().is_none());
+
+    // Only the files added by the second snapshot are no longer referenced. Its
+    // last manifest is shared with the first snapshot.
+    let second_manifest = &second_manifest_list.manifest_files()[0];
+    let mut expected_files = vec![
+        second_snapshot.manifest_list.clone(),
+        second_manifest.manifest_path.clone(),
+        datafile2.file_path.clone(),
+    ];
+    expected_files.sort();
+
+    let mut removed_files: Vec<String> = removed_files.into_iter().collect();
+    removed_files.sort();
+
+    assert_eq!(removed_files, expected_files);
+}
+
+#[tokio::test]
+async fn metadata_json_formatting() {
+    let mut table = TestTable::new().await;
+
+    let read_metadata_file = |table: &TestTable| {
+        let uri = table.current_metadata_uri().unwrap();
+        std::fs::read_to_string(uri.strip_prefix("file://").unwrap()).unwrap()
+    };
+
+    // Metadata is written as compact JSON by default.
+    assert!(!read_metadata_file(&table).contains('\n'));
+
+    let mut transaction = table.new_transaction();
+    let mut op = UpdatePropertiesOperation::new();
+    op.set(METADATA_JSON_PRETTY_PROPERTY, "true");
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    assert!(read_metadata_file(&table).contains('\n'));
+
+    // The pretty-printed metadata is read back like the compact one.
+    let loaded = IcebergTableLoader::from_url(table.location()).load().await.unwrap();
+    assert_eq!(loaded.current_metadata().unwrap(), table.current_metadata().unwrap());
+    assert_eq!(loaded.current_metadata().unwrap().metadata_log.as_ref().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn update_partition_spec_operation() {
+    let mut table = TestTable::new().await;
+
+    let commit_spec = |spec: PartitionSpec| {
+        let mut op = UpdatePartitionSpecOperation::new();
+        op.set_partition_spec(spec);
+        op
+    };
+
+    let spec = table.update_spec().unwrap()
+        .add_field("ts", PartitionTransform::Day, "ts_day").unwrap()
+        .build()
+        .unwrap();
+    let mut transaction = table.new_transaction();
+    transaction.add_operation(Box::new(commit_spec(spec)));
+    transaction.commit().await.unwrap();
+
+    let day_spec = table.current_partition_spec().unwrap();
+    assert_eq!(day_spec.spec_id(), 1);
+    assert_eq!(
+        day_spec.fields(),
+        &vec![PartitionField::new(2, 1000, "ts_day", PartitionTransform::Day)]
+    );
+
+    let datafile = DataFile {
+        partition: PartitionValues::from_iter([(
+            "ts_day".to_string(),
+            // 2023-01-01, in days since the epoch.
+            Some(Value::Int(19358))
+        )]),
+        ..table.new_datafile(100, 1000)
+    };
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    // Evolve from daily to monthly partitions.
+    let spec = table.update_spec().unwrap()
+        .remove_field("ts_day").unwrap()
+        .add_field("ts", PartitionTransform::Month, "ts_month").unwrap()
+        .build()
+        .unwrap();
+    let mut transaction = table.new_transaction();
+    transaction.add_operation(Box::new(commit_spec(spec)));
+    transaction.commit().await.unwrap();
+
+    // The removed field keeps its id as a void field, and the new field gets the
+    // next id.
+    let month_spec = table.current_partition_spec().unwrap();
+    assert_eq!(month_spec.spec_id(), 2);
+    assert_eq!(month_spec.fields(), &vec![
+        PartitionField::new(2, 1000, "ts_day", PartitionTransform::Void),
+        PartitionField::new(2, 1001, "ts_month", PartitionTransform::Month),
+    ]);
+
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.default_spec_id, 2);
+    assert_eq!(metadata.last_partition_id, 1001);
+    assert_eq!(metadata.partition_specs().len(), 3);
+    assert_eq!(metadata.partition_spec(1).unwrap().fields(), day_spec.fields());
+
+    // The file written with the daily spec is still planned with its partition.
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].data_file().partition, datafile.partition);
+
+    // Removed fields can't be removed again, and the spec survives a reload.
+    assert!(table.update_spec().unwrap().remove_field("ts_day").is_err());
+    let loaded = IcebergTableLoader::from_url(table.location()).load().await.unwrap();
+    assert_eq!(loaded.current_partition_spec().unwrap().fields(), month_spec.fields());
+}
+
+#[tokio::test]
+async fn puffin_statistics() {
+    let mut table = TestTable::new().await;
+
+    let mut transaction = table.new_transaction();
+    transaction.fast_append(vec![table.new_datafile(100, 1000)]);
+    transaction.commit().await.unwrap();
+    let snapshot = table.current_snapshot().unwrap().unwrap().clone();
+    assert!(table.statistics_for(snapshot.snapshot_id).unwrap().is_none());
+
+    // Write a single theta sketch of the id column.
+    let sketch = Bytes::from_static(&[0x02, 0x03, 0x03, 0x00, 0x00, 0x1a, 0xcc, 0x93]);
+    let mut writer = PuffinWriter::new();
+    writer.add_blob(Blob {
+        blob_type: APACHE_DATASKETCHES_THETA_V1.to_string(),
+        fields: vec![1],
+        snapshot_id: snapshot.snapshot_id,
+        sequence_number: snapshot.sequence_number,
+        data: sketch.clone(),
+        properties: HashMap::from([("ndv".to_string(), "100".to_string())]),
+    });
+    let statistics_file = writer.close(&table, snapshot.snapshot_id).await.unwrap();
+    assert_eq!(statistics_file.blob_metadata.len(), 1);
+    assert_eq!(statistics_file.blob_metadata[0].blob_type, APACHE_DATASKETCHES_THETA_V1);
+    assert_eq!(statistics_file.blob_metadata[0].fields, vec![1]);
+
+    // Read the blob back.
+    let storage = table.storage();
+    let path = storage.create_path_from_url(&statistics_file.statistics_path).unwrap();
+    let bytes = storage.get(&path).await.unwrap();
+    assert_eq!(bytes.len() as i64, statistics_file.file_size_in_bytes);
+    let reader = PuffinReader::try_new(bytes).unwrap();
+    let blobs = reader.blobs_metadata();
+    assert_eq!(blobs.len(), 1);
+    assert_eq!(blobs[0].blob_type, APACHE_DATASKETCHES_THETA_V1);
+    assert_eq!(blobs[0].snapshot_id, snapshot.snapshot_id);
+    assert_eq!(blobs[0].properties.get("ndv"), Some(&"100".to_string()));
+    assert_eq!(reader.read_blob(&blobs[0]).unwrap(), sketch);
+
+    // Reference the statistics file from the table metadata.
+    let mut metadata = table.current_metadata().unwrap().clone();
+    metadata.set_statistics(statistics_file.clone());
+    table.commit(metadata).await.unwrap();
+    assert_eq!(
+        table.statistics_for(snapshot.snapshot_id).unwrap(),
+        Some(&statistics_file)
+    );
+
+    let loaded = IcebergTableLoader::from_url(table.location()).load().await.unwrap();
+    assert_eq!(
+        loaded.statistics_for(snapshot.snapshot_id).unwrap(),
+        Some(&statistics_file)
+    );
+}
+
+#[tokio::test]
+async fn upgrade_format_version() {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("tbl-{}", Uuid::new_v4().to_string()));
+    let location = format!("file://{}", path.to_str().unwrap());
+
+    // Version 1 metadata with a single schema and partition spec, as written by
+    // older writers.
+    let metadata_json = format!(r#"{{
+        "format-version": 1,
+        "table-uuid": "d20125c8-7284-442c-9aea-15fee620737c",
+        "location": "{location}",
+        "last-updated-ms": 1602638573874,
+        "last-column-id": 3,
+        "schema": {{
+            "type": "struct",
+            "fields": [
+                {{"id": 1, "name": "id", "required": true, "type": "long"}},
+                {{"id": 2, "name": "ts", "required": false, "type": "timestamp"}},
+                {{"id": 3, "name": "name", "required": false, "type": "string"}}
+            ]
+        }},
+        "partition-spec": [
+            {{"name": "name", "transform": "identity", "source-id": 3}}
+        ],
+        "properties": {{}},
+        "current-snapshot-id": -1,
+        "snapshots": []
+    }}"#);
+    std::fs::create_dir_all(path.join("metadata")).unwrap();
+    std::fs::write(
+        path.join(format!("metadata/00000-{}.metadata.json", Uuid::new_v4())),
+        metadata_json
+    ).unwrap();
+
+    let mut table = IcebergTableLoader::from_url(&location).load().await.unwrap();
+    assert_eq!(
+        table.current_metadata().unwrap().format_version,
+        IcebergTableVersion::V1
+    );
+
+    assert!(table.upgrade_format_version(IcebergTableVersion::V2).await.unwrap());
+    // Already upgraded.
+    assert!(!table.upgrade_format_version(IcebergTableVersion::V2).await.unwrap());
+    assert!(matches!(
+        table.upgrade_format_version(IcebergTableVersion::V1).await,
+        Err(IcebergError::ValueError(..))
+    ));
+
+    // The new metadata file is in version 2 form.
+    let metadata_uri = table.current_metadata_uri().unwrap();
+    let json: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(metadata_uri.strip_prefix("file://").unwrap()).unwrap()
+    ).unwrap();
+    assert_eq!(json["format-version"], 2);
+    assert!(json.get("schema").is_none());
+    assert!(json.get("partition-spec").is_none());
+    assert_eq!(json["current-schema-id"], 0);
+    assert_eq!(json["partition-specs"][0]["fields"][0]["field-id"], 1000);
+    assert_eq!(json["last-sequence-number"], 1);
+
+    let table = IcebergTableLoader::from_url(&location).load().await.unwrap();
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.format_version, IcebergTableVersion::V2);
+    assert!(metadata.validate().is_ok());
+    assert_eq!(metadata.current_schema(), &TestTable::schema());
+    assert_eq!(
+        metadata.current_partition_spec().fields(),
+        [PartitionField::new(3, 1000, "name", PartitionTransform::Identity)]
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn snapshot_changed_data_files() {
+    let mut table = TestTable::new().await;
+    let storage = table.storage();
+
+    let datafile1 = table.new_datafile(1111, 10111);
+    let datafile2 = table.new_datafile(2222, 20222);
+    let datafile3 = table.new_datafile(3333, 30333);
+
+    fn paths(data_files: &[DataFile]) -> Vec<&str> {
+        let mut paths: Vec<&str> = data_files.iter()
+            .map(|data_file| data_file.file_path.as_str())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_files([datafile1.clone(), datafile2.clone()]);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+    let append_snapshot = table.current_snapshot().unwrap().unwrap().clone();
+
+    let mut transaction = table.new_transaction();
+    let mut op = OverwriteFilesOperation::new();
+    op.delete_file(&datafile1.file_path);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+    let delete_snapshot = table.current_snapshot().unwrap().unwrap().clone();
+
+    // A later append doesn't change what the earlier snapshots report.
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(datafile3.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+    let last_snapshot = table.current_snapshot().unwrap().unwrap().clone();
+
+    let mut expected = vec![datafile1.file_path.as_str(), datafile2.file_path.as_str()];
+    expected.sort();
+    let added = append_snapshot.added_data_files(&storage).await.unwrap();
+    assert_eq!(paths(&added), expected);
+    assert!(append_snapshot.removed_data_files(&storage).await.unwrap().is_empty());
+
+    assert!(delete_snapshot.added_data_files(&storage).await.unwrap().is_empty());
+    let removed = delete_snapshot.removed_data_files(&storage).await.unwrap();
+    assert_eq!(removed, vec![datafile1.clone()]);
+
+    let added = last_snapshot.added_data_files(&storage).await.unwrap();
+    assert_eq!(added, vec![datafile3.clone()]);
+    assert!(last_snapshot.removed_data_files(&storage).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn list_data_files() {
+    let mut table = TestTable::new().await;
+    assert!(table.data_files(true).await.unwrap().is_empty());
+
+    let datafiles: Vec<DataFile> = (1..=3)
+        .map(|i| table.new_datafile(i * 100, i * 1000))
+        .collect();
+
+    for datafile in &datafiles {
+        let mut transaction = table.new_transaction();
+        let mut op = AppendFilesOperation::new();
+        op.append_file(datafile.clone());
+        transaction.add_operation(Box::new(op));
+        transaction.commit().await.unwrap();
+    }
+
+    let mut transaction = table.new_transaction();
+    let mut op = OverwriteFilesOperation::new();
+    op.delete_file(&datafiles[0].file_path);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let delete_file = DataFile::builder(
+        DataFileContent::PositionDelete,
+        table.path.join(format!("metadata/{}.parquet", Uuid::new_v4())).to_str().unwrap(),
+        DataFileFormat::Parquet,
+        1,
+        10
+    ).build();
+    let mut transaction = table.new_transaction();
+    let mut op = RowDeltaOperation::new();
+    op.add_deletes(delete_file.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    // Three files were appended and one deleted.
+    let data_files = table.data_files(false).await.unwrap();
+    assert_eq!(data_files.len(), 2);
+    assert!(data_files.iter().all(|data_file| {
+        data_file.file_path != datafiles[0].file_path
+    }));
+
+    let files = table.data_files(true).await.unwrap();
+    assert_eq!(files.len(), 3);
+    assert!(files.contains(&delete_file));
+}
+
+#[tokio::test]
+async fn list_orphan_files() {
+    let mut table = TestTable::new().await;
+
+    let data_dir = table.path.join("data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    let live_path = data_dir.join(format!("{}.parquet", Uuid::new_v4()));
+    std::fs::write(&live_path, b"live").unwrap();
+    let orphan_path = data_dir.join(format!("{}.parquet", Uuid::new_v4()));
+    std::fs::write(&orphan_path, b"orphan").unwrap();
+
+    let datafile = DataFile::builder(
+        DataFileContent::Data,
+        &format!("file://{}", live_path.to_str().unwrap()),
+        DataFileFormat::Parquet,
+        1,
+        4
+    ).build();
+
+    for _ in 0..2 {
+        let mut transaction = table.new_transaction();
+        let mut op = AppendFilesOperation::new();
+        op.append_file(datafile.clone());
+        transaction.add_operation(Box::new(op));
+        transaction.commit().await.unwrap();
+    }
+
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+
+    // Metadata files, manifest lists, manifests and the appended file are all
+    // referenced by the table.
+    let orphans = table.list_orphan_files(now_ms + 60_000).await.unwrap();
+    assert_eq!(orphans, vec![format!("file://{}", orphan_path.to_str().unwrap())]);
+
+    // Files modified after the cutoff are never listed.
+    assert!(table.list_orphan_files(0).await.unwrap().is_empty());
+
+    std::fs::remove_dir_all(&table.path).unwrap();
+}
@@ -0,0 +1,209 @@
+//! Tests for the Glue catalog using an in-memory Glue client.
+use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use async_trait::async_trait;
+
+use icelake::{IcebergError, IcebergResult, NotFoundKind};
+use icelake::schema::{Schema, Field, PrimitiveType};
+use icelake::catalog::{
+    Catalog, GlueCatalog, Namespace, TableIdentifier, TableCommit, TableUpdate
+};
+use icelake::catalog::glue::{
+    GlueClient, GlueTable, METADATA_LOCATION_PARAM, PREVIOUS_METADATA_LOCATION_PARAM
+};
+
+/// Keeps tables in memory, versioning them like Glue does.
+#[derive(Default)]
+struct MockGlueClient {
+    tables: Mutex<HashMap<(String, String), GlueTable>>,
+    /// Whether to change the table's version right before the next update,
+    /// simulating a concurrent writer.
+    modify_before_update: Mutex<bool>,
+}
+
+#[async_trait]
+impl GlueClient for MockGlueClient {
+    async fn get_databases(&self) -> IcebergResult<Vec<String>> {
+        let tables = self.tables.lock().unwrap();
+        let mut databases: Vec<String> = tables.keys()
+            .map(|(database, _)| database.clone())
+            .collect();
+        databases.sort();
+        databases.dedup();
+
+        Ok(databases)
+    }
+
+    async fn get_table(
+        &self,
+        database: &str,
+        name: &str
+    ) -> IcebergResult<Option<GlueTable>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables.get(&(database.to_string(), name.to_string())).cloned())
+    }
+
+    async fn create_table(&self, database: &str, mut table: GlueTable) -> IcebergResult<()> {
+        let mut tables = self.tables.lock().unwrap();
+        let key = (database.to_string(), table.name.clone());
+        if tables.contains_key(&key) {
+            return Err(IcebergError::TableAlreadyExists(table.name));
+        }
+
+        table.version_id = Some("1".to_string());
+        tables.insert(key, table);
+
+        Ok(())
+    }
+
+    async fn update_table(&self, database: &str, mut table: GlueTable) -> IcebergResult<()> {
+        let mut tables = self.tables.lock().unwrap();
+        let key = (database.to_string(), table.name.clone());
+        let current = tables.get_mut(&key).unwrap();
+
+        let mut modify = self.modify_before_update.lock().unwrap();
+        if *modify {
+            *modify = false;
+            current.version_id = Some("concurrent".to_string());
+        }
+
+        if current.version_id != table.version_id {
+            return Err(IcebergError::CommitConflict(table.name));
+        }
+
+        let version: i32 = current.version_id.as_ref().unwrap().parse().unwrap();
+        table.version_id = Some((version + 1).to_string());
+        *current = table;
+
+        Ok(())
+    }
+
+    async fn delete_table(&self, database: &str, name: &str) -> IcebergResult<()> {
+        let mut tables = self.tables.lock().unwrap();
+        tables.remove(&(database.to_string(), name.to_string()));
+
+        Ok(())
+    }
+}
+
+fn schema() -> Schema {
+    Schema::new(0, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        Field::new_primitive(2, "name", false, PrimitiveType::String),
+    ])
+}
+
+fn create_catalog() -> (std::path::PathBuf, Arc<MockGlueClient>, GlueCatalog) {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("warehouse-{}", Uuid::new_v4().to_string()));
+
+    let client = Arc::new(MockGlueClient::default());
+    let catalog = GlueCatalog::new(
+        client.clone(),
+        &format!("file://{}", path.to_str().unwrap()),
+        HashMap::new()
+    );
+
+    (path, client, catalog)
+}
+
+fn identifier() -> TableIdentifier {
+    TableIdentifier::new(Namespace::new(&["db"]), "users")
+}
+
+fn set_owner(owner: &str) -> TableCommit {
+    TableCommit::new(identifier())
+        .with_update(TableUpdate::SetProperties {
+            updates: HashMap::from([("owner".to_string(), owner.to_string())])
+        })
+}
+
+fn metadata_location(client: &MockGlueClient) -> String {
+    let tables = client.tables.lock().unwrap();
+    let table = &tables[&("db".to_string(), "users".to_string())];
+    table.parameters[METADATA_LOCATION_PARAM].clone()
+}
+
+#[tokio::test]
+async fn load_table() {
+    let (path, client, catalog) = create_catalog();
+
+    assert!(matches!(
+        catalog.load_table(&identifier()).await,
+        Err(IcebergError::NotFound { kind: NotFoundKind::Table, .. })
+    ));
+
+    let created = catalog.create_table(&identifier(), schema(), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(created.current_metadata_uri().unwrap(), metadata_location(&client));
+    assert_eq!(
+        created.location(),
+        format!("file://{}/db.db/users", path.to_str().unwrap())
+    );
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    assert_eq!(table.current_metadata().unwrap(), created.current_metadata().unwrap());
+    assert_eq!(table.current_schema().unwrap(), &schema());
+
+    assert_eq!(
+        catalog.list_namespaces(None).await.unwrap(),
+        vec![Namespace::new(&["db"])]
+    );
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn commit_transaction() {
+    let (path, client, catalog) = create_catalog();
+
+    catalog.create_table(&identifier(), schema(), None, None, None).await
+        .unwrap();
+    let base_location = metadata_location(&client);
+
+    let table = catalog.commit_transaction(set_owner("icelake")).await.unwrap();
+    let new_location = metadata_location(&client);
+
+    assert_eq!(table.current_metadata_uri().unwrap(), new_location);
+    assert!(new_location.contains("/metadata/00001-"));
+    {
+        let tables = client.tables.lock().unwrap();
+        let glue_table = &tables[&("db".to_string(), "users".to_string())];
+        assert_eq!(glue_table.parameters[PREVIOUS_METADATA_LOCATION_PARAM], base_location);
+    }
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.properties.as_ref().unwrap()["owner"], "icelake");
+    assert_eq!(metadata.metadata_log.as_ref().unwrap()[0].metadata_file, base_location);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn conflicting_commit() {
+    let (path, client, catalog) = create_catalog();
+
+    catalog.create_table(&identifier(), schema(), None, None, None).await
+        .unwrap();
+    let base_location = metadata_location(&client);
+
+    // Another writer updates the Glue table after the commit read it.
+    *client.modify_before_update.lock().unwrap() = true;
+    let result = catalog.commit_transaction(set_owner("icelake")).await;
+    assert!(matches!(result, Err(IcebergError::CommitConflict(..))));
+
+    // The table still points to the original metadata, and the metadata file written
+    // by the failed commit was removed.
+    assert_eq!(metadata_location(&client), base_location);
+    let metadata_files = std::fs::read_dir(path.join("db.db/users/metadata"))
+        .unwrap()
+        .count();
+    assert_eq!(metadata_files, 1);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
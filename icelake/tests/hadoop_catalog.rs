@@ -0,0 +1,415 @@
+//! Tests for the Hadoop catalog on the local file system.
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use bytes::Bytes;
+
+use icelake::{
+    IcebergTable, IcebergTableVersion, IcebergError, IcebergResult, NotFoundKind
+};
+use icelake::iceberg::{
+    METADATA_DELETE_AFTER_COMMIT_PROPERTY, METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY
+};
+use icelake::schema::{Schema, Field, PrimitiveType};
+use icelake::partition::PartitionSpec;
+use icelake::sort::SortOrder;
+use icelake::manifest::{
+    DataFile, DataFileContent, DataFileFormat, Manifest, ManifestContentType,
+    ManifestEntry, ManifestEntryStatus, ManifestList, ManifestWriter
+};
+use icelake::snapshot::{Snapshot, SnapshotSummary, SnapshotOperation, SnapshotReference};
+use icelake::transaction::{AppendFilesOperation, UpdateSchemaOperation};
+use icelake::catalog::{
+    Catalog, HadoopCatalog, Namespace, TableIdentifier, TableCommit,
+    TableRequirement, TableUpdate, MAIN_BRANCH
+};
+
+fn schema() -> Schema {
+    Schema::new(0, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        Field::new_primitive(2, "name", false, PrimitiveType::String),
+    ])
+}
+
+fn create_catalog() -> (std::path::PathBuf, HadoopCatalog) {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("warehouse-{}", Uuid::new_v4().to_string()));
+
+    let catalog = HadoopCatalog::new(
+        &format!("file://{}", path.to_str().unwrap()),
+        HashMap::new()
+    ).unwrap();
+
+    (path, catalog)
+}
+
+fn identifier() -> TableIdentifier {
+    TableIdentifier::new(Namespace::new(&["db"]), "users")
+}
+
+/// Builds a commit appending a data file to the table, writing its manifest and
+/// manifest list.
+async fn append_commit(table: &IcebergTable, file_name: &str) -> TableCommit {
+    let metadata = table.current_metadata().unwrap();
+    let current_snapshot = metadata.current_snapshot();
+    let snapshot_id = metadata.snapshots.as_ref()
+        .map_or(0, |snapshots| snapshots.len() as i64) + 1;
+    let sequence_number = metadata.last_sequence_number + 1;
+
+    let data_file = DataFile::builder(
+        DataFileContent::Data,
+        &format!("{}/data/{}", table.location(), file_name),
+        DataFileFormat::Parquet,
+        10,
+        100
+    ).build();
+
+    let mut manifest_list = match current_snapshot {
+        Some(snapshot) => table.read_manifest_list(snapshot).await.unwrap(),
+        None => ManifestList::new(),
+    };
+
+    let mut manifest = Manifest::new(
+        metadata.current_schema().clone(),
+        metadata.current_partition_spec(),
+        ManifestContentType::Data
+    );
+    manifest.add_manifest_entry(ManifestEntry::new(
+        ManifestEntryStatus::Added,
+        snapshot_id,
+        data_file.clone()
+    ));
+
+    let mut manifest_file = table.new_metadata_file(
+        &format!("{}-m0.avro", Uuid::new_v4()),
+        Bytes::new()
+    ).unwrap();
+    let (bytes, manifest_entry) = ManifestWriter::new(sequence_number, snapshot_id)
+        .write(&manifest_file.url(), &manifest)
+        .unwrap();
+    manifest_file.set_bytes(bytes);
+    manifest_file.save().await.unwrap();
+    manifest_list.push(manifest_entry);
+
+    let manifest_list_file = table.new_metadata_file(
+        &format!("snap-{}-1-{}.avro", snapshot_id, Uuid::new_v4()),
+        Bytes::from(manifest_list.encode().unwrap())
+    ).unwrap();
+    manifest_list_file.save().await.unwrap();
+
+    let mut summary = SnapshotSummary::builder();
+    if let Some(snapshot) = current_snapshot {
+        summary.copy_totals(&snapshot.summary);
+    }
+    summary.operation(SnapshotOperation::Append).added_files(&[data_file]);
+
+    let snapshot = Snapshot {
+        snapshot_id: snapshot_id,
+        parent_snapshot_id: current_snapshot.map(|snapshot| snapshot.snapshot_id),
+        sequence_number: sequence_number,
+        timestamp_ms: metadata.last_updated_ms + 1,
+        manifest_list: manifest_list_file.url(),
+        summary: summary.build(),
+        schema_id: Some(metadata.current_schema_id),
+    };
+
+    TableCommit::new(identifier())
+        .with_requirement(TableRequirement::AssertRefSnapshotId {
+            r#ref: MAIN_BRANCH.to_string(),
+            snapshot_id: current_snapshot.map(|snapshot| snapshot.snapshot_id),
+        })
+        .with_update(TableUpdate::AddSnapshot { snapshot: snapshot })
+        .with_update(TableUpdate::SetSnapshotRef {
+            ref_name: MAIN_BRANCH.to_string(),
+            reference: SnapshotReference::branch(snapshot_id),
+        })
+}
+
+#[tokio::test]
+async fn create_table_and_append() {
+    let (path, catalog) = create_catalog();
+
+    let result = catalog.load_table(&identifier()).await;
+    assert!(matches!(
+        result,
+        Err(IcebergError::NotFound { kind: NotFoundKind::Table, identifier })
+            if identifier == "db.users"
+    ));
+
+    let table = catalog.create_table(&identifier(), schema(), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(table.location(), catalog.table_location(&identifier()));
+    assert!(path.join("db/users/metadata/v1.metadata.json").exists());
+    assert_eq!(
+        std::fs::read_to_string(path.join("db/users/metadata/version-hint.text")).unwrap(),
+        "1"
+    );
+
+    let result = catalog.create_table(&identifier(), schema(), None, None, None).await;
+    assert!(matches!(result, Err(IcebergError::TableAlreadyExists(..))));
+
+    let commit = append_commit(&table, "1.parquet").await;
+    let table = catalog.commit_transaction(commit).await.unwrap();
+    let commit = append_commit(&table, "2.parquet").await;
+    let table = catalog.commit_transaction(commit).await.unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(path.join("db/users/metadata/version-hint.text")).unwrap(),
+        "3"
+    );
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.snapshots.as_ref().unwrap().len(), 2);
+    assert_eq!(metadata.current_snapshot_id, Some(2));
+    assert_eq!(metadata.last_sequence_number, 2);
+    assert_eq!(metadata.metadata_log.as_ref().unwrap().len(), 2);
+    assert_eq!(
+        table.current_metadata_uri().unwrap(),
+        format!("{}/metadata/v3.metadata.json", table.location())
+    );
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    let mut files: Vec<&str> = tasks.iter()
+        .map(|task| task.data_file().file_path.rsplit('/').next().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["1.parquet", "2.parquet"]);
+
+    assert_eq!(
+        catalog.list_namespaces(None).await.unwrap(),
+        vec![Namespace::new(&["db"])]
+    );
+    let namespaces = catalog.list_namespaces(Some(&Namespace::new(&["db"]))).await;
+    assert!(namespaces.unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn create_table_with_partition_spec_and_sort_order() {
+    let (path, catalog) = create_catalog();
+
+    let partition_spec = PartitionSpec::builder(1, schema())
+        .add_identity_field("name").unwrap()
+        .build();
+    let sort_order = SortOrder::builder(1, schema())
+        .desc("id").unwrap()
+        .build();
+
+    catalog.create_table(
+        &identifier(),
+        schema(),
+        Some(partition_spec.clone()),
+        Some(sort_order.clone()),
+        None
+    ).await.unwrap();
+    assert!(path.join("db/users/metadata/v1.metadata.json").exists());
+
+    let result = catalog.create_table(&identifier(), schema(), None, None, None).await;
+    assert!(matches!(result, Err(IcebergError::TableAlreadyExists(..))));
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.format_version, IcebergTableVersion::V2);
+    assert!(Uuid::parse_str(&metadata.table_uuid).is_ok());
+    assert_eq!(metadata.last_column_id, 2);
+    assert_eq!(metadata.current_schema(), &schema());
+    let loaded_spec = metadata.current_partition_spec();
+    assert_eq!(loaded_spec.spec_id(), 1);
+    assert_eq!(loaded_spec.fields(), partition_spec.fields());
+    assert_eq!(metadata.default_sort_order_id, 1);
+    assert_eq!(metadata.sort_orders, vec![sort_order]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn conflicting_commit() {
+    let (path, catalog) = create_catalog();
+
+    let table = catalog.create_table(&identifier(), schema(), None, None, None)
+        .await
+        .unwrap();
+
+    // Both commits are based on the same version of the table.
+    let first = append_commit(&table, "1.parquet").await;
+    let second = append_commit(&table, "2.parquet").await;
+
+    catalog.commit_transaction(first).await.unwrap();
+    let result = catalog.commit_transaction(second).await;
+    assert!(matches!(result, Err(IcebergError::CommitConflict(..))));
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    assert_eq!(table.current_metadata().unwrap().snapshots.as_ref().unwrap().len(), 1);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+/// Appends a data file to the table through a transaction committed to the catalog.
+async fn append_transaction(
+    catalog: &HadoopCatalog,
+    table: &mut IcebergTable,
+    file_name: &str
+) -> IcebergResult<()> {
+    let data_file = DataFile::builder(
+        DataFileContent::Data,
+        &format!("{}/data/{}", table.location(), file_name),
+        DataFileFormat::Parquet,
+        10,
+        100
+    ).build();
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(data_file);
+    transaction.add_operation(Box::new(op));
+    transaction.commit_to_catalog(catalog, &identifier()).await
+}
+
+#[tokio::test]
+async fn concurrent_transactions() {
+    let (path, catalog) = create_catalog();
+
+    catalog.create_table(&identifier(), schema(), None, None, None).await
+        .unwrap();
+
+    // Both writers load the same version of the table.
+    let mut first = catalog.load_table(&identifier()).await.unwrap();
+    let mut second = catalog.load_table(&identifier()).await.unwrap();
+
+    append_transaction(&catalog, &mut first, "1.parquet").await.unwrap();
+    assert_eq!(
+        first.current_metadata_uri().unwrap(),
+        format!("{}/metadata/v2.metadata.json", first.location())
+    );
+
+    let result = append_transaction(&catalog, &mut second, "2.parquet").await;
+    assert!(matches!(result, Err(IcebergError::CommitConflict(..))));
+
+    // The second writer retries on top of the first writer's commit.
+    let mut second = catalog.load_table(&identifier()).await.unwrap();
+    append_transaction(&catalog, &mut second, "2.parquet").await.unwrap();
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata, second.current_metadata().unwrap());
+    assert_eq!(metadata.snapshots.as_ref().unwrap().len(), 2);
+    assert_eq!(metadata.snapshot_log.as_ref().unwrap().len(), 2);
+    assert_eq!(metadata.current_ancestors().len(), 2);
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    let mut files: Vec<&str> = tasks.iter()
+        .map(|task| task.data_file().file_path.rsplit('/').next().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["1.parquet", "2.parquet"]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn schema_evolution_transaction() {
+    let (path, catalog) = create_catalog();
+
+    let mut table = catalog.create_table(&identifier(), schema(), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(table.current_metadata().unwrap().last_column_id, 2);
+
+    let mut fields = schema().fields().to_vec();
+    fields.push(Field::new_primitive(3, "comment", false, PrimitiveType::String));
+    let mut transaction = table.new_transaction();
+    let mut op = UpdateSchemaOperation::new();
+    op.set_schema(Schema::new(0, fields));
+    transaction.add_operation(Box::new(op));
+    transaction.commit_to_catalog(&catalog, &identifier()).await.unwrap();
+
+    // The new column's id was assigned in the committed metadata.
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    let metadata = table.current_metadata().unwrap();
+    assert_eq!(metadata.current_schema_id, 1);
+    assert_eq!(metadata.last_column_id, 3);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn delete_metadata_after_commit() {
+    let (path, catalog) = create_catalog();
+
+    let properties = HashMap::from([
+        (METADATA_DELETE_AFTER_COMMIT_PROPERTY.to_string(), "true".to_string()),
+        (METADATA_PREVIOUS_VERSIONS_MAX_PROPERTY.to_string(), "2".to_string()),
+    ]);
+    let mut table = catalog
+        .create_table(&identifier(), schema(), None, None, Some(properties))
+        .await
+        .unwrap();
+
+    for i in 1..=4 {
+        let commit = append_commit(&table, &format!("{i}.parquet")).await;
+        table = catalog.commit_transaction(commit).await.unwrap();
+    }
+
+    // Only the current version and the two versions before it are kept.
+    let metadata_dir = path.join("db/users/metadata");
+    for version in 1..=2 {
+        assert!(!metadata_dir.join(format!("v{version}.metadata.json")).exists());
+    }
+    for version in 3..=5 {
+        assert!(metadata_dir.join(format!("v{version}.metadata.json")).exists());
+    }
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+    let metadata_log: Vec<String> = table.current_metadata().unwrap()
+        .metadata_log
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|entry| entry.metadata_file.clone())
+        .collect();
+    assert_eq!(metadata_log, [
+        format!("{}/metadata/v3.metadata.json", table.location()),
+        format!("{}/metadata/v4.metadata.json", table.location()),
+    ]);
+    assert_eq!(table.current_metadata().unwrap().snapshots.as_ref().unwrap().len(), 4);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn refresh_table() {
+    let (path, catalog) = create_catalog();
+
+    catalog.create_table(&identifier(), schema(), None, None, None).await
+        .unwrap();
+
+    let mut reader = catalog.load_table(&identifier()).await.unwrap();
+    let mut writer = catalog.load_table(&identifier()).await.unwrap();
+
+    // Nothing changed yet.
+    assert!(!reader.refresh(&catalog, &identifier()).await.unwrap());
+
+    append_transaction(&catalog, &mut writer, "1.parquet").await.unwrap();
+    assert!(reader.scan().plan_files().await.unwrap().is_empty());
+
+    assert!(reader.refresh(&catalog, &identifier()).await.unwrap());
+    assert_eq!(
+        reader.current_metadata_uri().unwrap(),
+        writer.current_metadata_uri().unwrap()
+    );
+    assert_eq!(reader.current_metadata().unwrap(), writer.current_metadata().unwrap());
+
+    let tasks = reader.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert!(tasks[0].data_file().file_path.ends_with("1.parquet"));
+
+    // The refreshed table can be committed to.
+    append_transaction(&catalog, &mut reader, "2.parquet").await.unwrap();
+    assert_eq!(reader.scan().plan_files().await.unwrap().len(), 2);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
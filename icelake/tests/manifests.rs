@@ -1,14 +1,19 @@
 //! Tests for manifest files serialization and deserialization.
+use std::collections::HashMap;
+
+use uuid::Uuid;
 use chrono::NaiveDate;
 
 use icelake::IcebergTableVersion;
+use icelake::storage::{IcebergStorage, IcebergPath};
 use icelake::value::Value;
 use icelake::schema::{Schema, Field, PrimitiveType};
 use icelake::partition::{
     PartitionSpec, PartitionField, PartitionTransform, PartitionValues
 };
 use icelake::manifest::{
-    Manifest, ManifestContentType,
+    Manifest, ManifestContentType, ManifestList, ManifestFile, ManifestFileType,
+    PartitionFieldSummary,
     ManifestReader, ManifestWriter,
     ManifestEntry, ManifestEntryStatus,
     DataFile, DataFileContent, DataFileFormat
@@ -94,6 +99,285 @@ fn serialize_manifest() {
         .read(&bytes).unwrap();
 }
 
+#[tokio::test]
+async fn read_manifest_entries() {
+    let schema = lineitem_schema();
+
+    let partition_spec = PartitionSpec::try_new(
+        0,
+        vec![
+            PartitionField::new(
+                11, 1000, "shipdate", PartitionTransform::Identity,
+            )
+        ],
+        schema.clone()
+    ).unwrap();
+
+    let mut manifest = Manifest::new(
+        schema,
+        partition_spec,
+        ManifestContentType::Data
+    );
+
+    let snapshot_id = 3988626671889928484;
+    let data_files: Vec<DataFile> = (1..=3)
+        .map(|day| {
+            DataFile::builder(
+                DataFileContent::Data,
+                &format!("/tmp/data_file_{day}"),
+                DataFileFormat::Parquet,
+                1000 * i64::from(day),
+                10000
+            ).with_partition_values(PartitionValues::from_iter([(
+                "shipdate".to_string(),
+                Some(Value::Date(NaiveDate::from_ymd_opt(2023, 1, day).unwrap()))
+            )]))
+            .with_lower_bounds(HashMap::from([(1, vec![0x01, 0, 0, 0, 0, 0, 0, 0])]))
+            .with_upper_bounds(HashMap::from([(1, vec![0xff, 0, 0, 0, 0, 0, 0, 0])]))
+            .build()
+        })
+        .collect();
+
+    manifest.add_manifest_entries(data_files.iter().map(|data_file| {
+        ManifestEntry::new(ManifestEntryStatus::Added, snapshot_id, data_file.clone())
+    }));
+
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("manifests-{}", Uuid::new_v4()));
+    let storage = IcebergStorage::from_url(
+        &format!("file://{}", path.to_str().unwrap()),
+        HashMap::new()
+    ).unwrap();
+
+    let manifest_path = IcebergPath::from("metadata/manifest-m0.avro");
+    let (bytes, manifest_file) = ManifestWriter::new(1, snapshot_id)
+        .write(&storage.to_uri(&manifest_path), &manifest)
+        .unwrap();
+    storage.put(&manifest_path, bytes).await.unwrap();
+
+    let entries = ManifestReader::for_manifest_file(&manifest_file)
+        .read_entries(&manifest_file.manifest_path, &storage)
+        .await
+        .unwrap();
+
+    assert_eq!(entries.len(), 3);
+    for (entry, data_file) in entries.iter().zip(data_files.iter()) {
+        assert_eq!(entry.status(), ManifestEntryStatus::Added);
+        assert_eq!(entry.snapshot_id(), Some(snapshot_id));
+        assert_eq!(entry.sequence_number(), Some(1));
+        assert_eq!(entry.data_file(), data_file);
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn first_row_id_round_trip() {
+    let mut manifest = Manifest::new(
+        lineitem_schema(),
+        PartitionSpec::unpartitioned(),
+        ManifestContentType::Data
+    );
+
+    let snapshot_id = 3988626671889928484;
+    let data_file = |path: &str| {
+        DataFile::builder(DataFileContent::Data, path, DataFileFormat::Parquet, 1000, 10000)
+    };
+    let data_files = vec![
+        data_file("/tmp/data_file_1").with_first_row_id(0).build(),
+        data_file("/tmp/data_file_2").with_first_row_id(1000).build(),
+        data_file("/tmp/data_file_3").build(),
+    ];
+
+    manifest.add_manifest_entries(data_files.iter().map(|data_file| {
+        ManifestEntry::new(ManifestEntryStatus::Added, snapshot_id, data_file.clone())
+    }));
+
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("manifests-{}", Uuid::new_v4()));
+    let storage = IcebergStorage::from_url(
+        &format!("file://{}", path.to_str().unwrap()),
+        HashMap::new()
+    ).unwrap();
+
+    let manifest_path = IcebergPath::from("metadata/manifest-m0.avro");
+    let (bytes, manifest_file) = ManifestWriter::new(1, snapshot_id)
+        .write(&storage.to_uri(&manifest_path), &manifest)
+        .unwrap();
+    storage.put(&manifest_path, bytes).await.unwrap();
+
+    let entries = ManifestReader::for_manifest_file(&manifest_file)
+        .read_entries(&manifest_file.manifest_path, &storage)
+        .await
+        .unwrap();
+
+    let first_row_ids: Vec<Option<i64>> = entries.iter()
+        .map(|entry| entry.data_file().first_row_id)
+        .collect();
+    assert_eq!(first_row_ids, [Some(0), Some(1000), None]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[test]
+fn write_manifest_partition_summaries() {
+    let schema = lineitem_schema();
+
+    let partition_spec = PartitionSpec::try_new(
+        0,
+        vec![
+            PartitionField::new(
+                3, 1000, "suppkey", PartitionTransform::Identity,
+            ),
+            PartitionField::new(
+                11, 1001, "shipdate", PartitionTransform::Identity,
+            )
+        ],
+        schema.clone()
+    ).unwrap();
+
+    let mut manifest = Manifest::new(
+        schema,
+        partition_spec,
+        ManifestContentType::Data
+    );
+
+    let snapshot_id = 3988626671889928484;
+    let partitions = [
+        (Some(20), Some(NaiveDate::from_ymd_opt(2023, 3, 1).unwrap())),
+        (Some(-5), None),
+        (Some(7), Some(NaiveDate::from_ymd_opt(2023, 1, 15).unwrap())),
+    ];
+    let data_files: Vec<DataFile> = partitions.iter()
+        .enumerate()
+        .map(|(i, (suppkey, shipdate))| {
+            DataFile::builder(
+                DataFileContent::Data,
+                &format!("/tmp/data_file_{i}"),
+                DataFileFormat::Parquet,
+                100,
+                1000
+            ).with_partition_values(PartitionValues::from_iter([
+                ("suppkey".to_string(), suppkey.map(Value::Long)),
+                ("shipdate".to_string(), shipdate.map(Value::Date)),
+            ])).build()
+        })
+        .collect();
+
+    manifest.add_manifest_entries(data_files.iter().map(|data_file| {
+        ManifestEntry::new(ManifestEntryStatus::Added, snapshot_id, data_file.clone())
+    }));
+
+    let (bytes, manifest_file) = ManifestWriter::new(1, snapshot_id)
+        .write("/tmp/manifest", &manifest)
+        .unwrap();
+
+    assert_eq!(manifest_file.added_data_files_count, 3);
+    assert_eq!(manifest_file.existing_data_files_count, 0);
+    assert_eq!(manifest_file.deleted_data_files_count, 0);
+    assert_eq!(manifest_file.added_rows_count, 300);
+
+    // Summaries survive a round trip through the manifest list.
+    let mut manifest_list = ManifestList::new();
+    manifest_list.push(manifest_file);
+    let manifest_list = ManifestList::decode(&manifest_list.encode().unwrap()).unwrap();
+    let manifest_file = &manifest_list.manifest_files()[0];
+
+    let summaries = manifest_file.partitions.as_ref().unwrap();
+    assert_eq!(summaries.len(), 2);
+
+    assert!(!summaries[0].contains_null());
+    assert_eq!(summaries[0].contains_nan(), Some(false));
+    assert_eq!(summaries[0].lower_bound(), Some(&(-5i64).to_le_bytes()[..]));
+    assert_eq!(summaries[0].upper_bound(), Some(&20i64.to_le_bytes()[..]));
+
+    // Dates are stored as days since 1970-01-01.
+    assert!(summaries[1].contains_null());
+    assert_eq!(summaries[1].lower_bound(), Some(&19372i32.to_le_bytes()[..]));
+    assert_eq!(summaries[1].upper_bound(), Some(&19417i32.to_le_bytes()[..]));
+
+    // The entries read back match the ones written.
+    let entries: Vec<ManifestEntry> = ManifestReader::for_manifest_file(manifest_file)
+        .read(&bytes)
+        .unwrap()
+        .into_entries()
+        .collect();
+
+    assert_eq!(entries.len(), data_files.len());
+    for (entry, data_file) in entries.iter().zip(data_files.iter()) {
+        assert_eq!(entry.status(), ManifestEntryStatus::Added);
+        assert_eq!(entry.snapshot_id(), Some(snapshot_id));
+        assert_eq!(entry.data_file(), data_file);
+    }
+}
+
+fn manifest_file(content: ManifestFileType, sequence_number: i64) -> ManifestFile {
+    ManifestFile {
+        manifest_path: format!("/tmp/manifest-{sequence_number}.avro"),
+        manifest_length: 4096,
+        partition_spec_id: 0,
+        content: content,
+        sequence_number: sequence_number,
+        min_sequence_number: sequence_number,
+        added_snapshot_id: 3988626671889928484,
+        added_data_files_count: 3,
+        existing_data_files_count: 2,
+        deleted_data_files_count: 1,
+        added_rows_count: 300,
+        existing_rows_count: 200,
+        deleted_rows_count: 100,
+        partitions: Some(vec![
+            PartitionFieldSummary::new(
+                true,
+                Some(false),
+                Some(1i64.to_le_bytes().to_vec()),
+                Some(99i64.to_le_bytes().to_vec())
+            )
+        ]),
+    }
+}
+
+#[test]
+fn manifest_list_v2_round_trip() {
+    let manifest_files = vec![
+        manifest_file(ManifestFileType::Data, 1),
+        manifest_file(ManifestFileType::Delete, 2),
+    ];
+
+    let mut manifest_list = ManifestList::new();
+    for manifest_file in manifest_files.iter() {
+        manifest_list.push(manifest_file.clone());
+    }
+
+    let encoded = manifest_list.encode_version(IcebergTableVersion::V2).unwrap();
+    let decoded = ManifestList::decode(&encoded).unwrap();
+
+    assert_eq!(decoded.manifest_files(), &manifest_files);
+}
+
+#[test]
+fn manifest_list_v1_round_trip() {
+    let mut manifest_list = ManifestList::new();
+    manifest_list.push(manifest_file(ManifestFileType::Data, 5));
+
+    let encoded = manifest_list.encode_version(IcebergTableVersion::V1).unwrap();
+    let decoded = ManifestList::decode(&encoded).unwrap();
+
+    // v1 manifest lists have no sequence numbers, which are read back as 0.
+    let mut expected = manifest_file(ManifestFileType::Data, 5);
+    expected.sequence_number = 0;
+    expected.min_sequence_number = 0;
+
+    assert_eq!(decoded.manifest_files(), &vec![expected]);
+
+    // Delete manifests can't be written to a v1 manifest list.
+    let mut manifest_list = ManifestList::new();
+    manifest_list.push(manifest_file(ManifestFileType::Delete, 5));
+    assert!(manifest_list.encode_version(IcebergTableVersion::V1).is_err());
+}
+
 /// Deserialize a manifest file created by a different engine (Athena)
 #[test]
 fn deserialize_manifest() {
@@ -169,3 +453,10 @@ fn deserialize_manifest() {
     };
     assert_eq!(date, NaiveDate::from_ymd_opt(1992, 3, 3).unwrap());
 }
+
+#[test]
+fn empty_manifest_list() {
+    let encoded = ManifestList::new().encode().unwrap();
+    assert!(ManifestList::decode(&encoded).unwrap().is_empty());
+    assert!(ManifestList::decode(&[]).unwrap().is_empty());
+}
@@ -0,0 +1,153 @@
+//! Tests for tables on object stores not created from URLs, using an in-memory
+//! object store in place of S3.
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use object_store::memory::InMemory;
+
+use icelake::{IcebergTable, IcebergTableMetadata, IcebergError, NotFoundKind};
+use icelake::schema::{Schema, Field, PrimitiveType};
+use icelake::storage::{IcebergStorage, IcebergPath};
+use icelake::transaction::{AppendFilesOperation, UpdatePropertiesOperation};
+use icelake::manifest::{DataFile, DataFileContent, DataFileFormat};
+
+const LOCATION: &str = "s3://bucket/warehouse/tbl";
+
+fn schema() -> Schema {
+    Schema::new(0, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        Field::new_primitive(2, "name", false, PrimitiveType::String),
+    ])
+}
+
+#[tokio::test]
+async fn storage_operations() {
+    let object_store = Arc::new(InMemory::new());
+    let storage = IcebergStorage::from_object_store(LOCATION, object_store.clone()).unwrap();
+    assert_eq!(storage.location(), LOCATION);
+
+    let path = IcebergPath::from("metadata/file.json");
+    assert!(!storage.exists(&path).await.unwrap());
+
+    storage.put(&path, Bytes::from("{}")).await.unwrap();
+    assert!(storage.exists(&path).await.unwrap());
+    assert_eq!(storage.get(&path).await.unwrap(), Bytes::from("{}"));
+    assert_eq!(storage.to_uri(&path), format!("{LOCATION}/metadata/file.json"));
+
+    // Objects are stored by their full path in the bucket.
+    let object_path = object_store::path::Path::from("warehouse/tbl/metadata/file.json");
+    assert!(object_store::ObjectStore::head(object_store.as_ref(), &object_path).await.is_ok());
+
+    let objects = storage.list(None).await.unwrap();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[0].location.to_string(), "metadata/file.json");
+
+    storage.delete(&path).await.unwrap();
+    assert!(!storage.exists(&path).await.unwrap());
+}
+
+#[tokio::test]
+async fn create_and_load_table() {
+    let storage = Arc::new(
+        IcebergStorage::from_object_store(LOCATION, Arc::new(InMemory::new())).unwrap()
+    );
+
+    let metadata = IcebergTableMetadata::try_new(
+        LOCATION.to_string(),
+        schema(),
+        None,
+        None
+    ).unwrap();
+
+    let mut table = IcebergTable::new(storage.clone());
+    table.create(metadata).await.unwrap();
+
+    let data_file = DataFile::builder(
+        DataFileContent::Data,
+        &format!("{LOCATION}/data/1.parquet"),
+        DataFileFormat::Parquet,
+        10,
+        100
+    ).build();
+
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(data_file.clone());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    // A new table on the same storage finds the committed metadata.
+    let mut loaded = IcebergTable::new(storage);
+    loaded.load().await.unwrap();
+    assert_eq!(loaded.current_metadata().unwrap(), table.current_metadata().unwrap());
+
+    let tasks = loaded.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].data_file(), &data_file);
+}
+
+#[tokio::test]
+async fn load_from_location() {
+    let object_store = Arc::new(InMemory::new());
+    let warehouse = Arc::new(
+        IcebergStorage::from_object_store("s3://bucket/warehouse", object_store).unwrap()
+    );
+    let storage = warehouse.with_location(LOCATION).unwrap();
+
+    let write_version = |version: i32| {
+        let mut metadata = IcebergTableMetadata::try_new(
+            LOCATION.to_string(),
+            schema(),
+            None,
+            Some(HashMap::from([("version".to_string(), version.to_string())]))
+        ).unwrap();
+        metadata.last_updated_ms += i64::from(version);
+        let path = IcebergPath::from(format!("metadata/v{version}.metadata.json"));
+        (path, Bytes::from(metadata.to_json().unwrap()))
+    };
+    let version = |table: &IcebergTable| {
+        table.current_metadata().unwrap().properties.as_ref().unwrap()["version"].clone()
+    };
+
+    for v in [1, 2] {
+        let (path, bytes) = write_version(v);
+        storage.put(&path, bytes).await.unwrap();
+    }
+    let hint_path = IcebergPath::from("metadata/version-hint.text");
+    storage.put(&hint_path, Bytes::from("2\n")).await.unwrap();
+
+    let mut table = IcebergTable::from_location(warehouse.clone(), LOCATION)
+        .await
+        .unwrap();
+    assert_eq!(table.location(), LOCATION);
+    assert_eq!(version(&table), "2");
+    assert_eq!(
+        table.current_metadata_uri().unwrap(),
+        format!("{LOCATION}/metadata/v2.metadata.json")
+    );
+    assert!(table.scan().plan_files().await.unwrap().is_empty());
+
+    // Without a version hint, the highest version is loaded.
+    storage.delete(&hint_path).await.unwrap();
+    let (path, bytes) = write_version(10);
+    storage.put(&path, bytes).await.unwrap();
+    let loaded = IcebergTable::from_location(warehouse.clone(), LOCATION).await.unwrap();
+    assert_eq!(version(&loaded), "10");
+
+    // Commits require a catalog.
+    let mut transaction = table.new_transaction();
+    let mut op = UpdatePropertiesOperation::new();
+    op.set("owner", "icelake");
+    transaction.add_operation(Box::new(op));
+    assert!(matches!(transaction.commit().await, Err(IcebergError::Unsupported(..))));
+
+    assert!(matches!(
+        IcebergTable::from_location(warehouse.clone(), "s3://bucket/missing").await,
+        Err(IcebergError::NotFound { kind: NotFoundKind::Table, .. })
+    ));
+    assert!(matches!(
+        IcebergTable::from_location(warehouse, "s3://other-bucket/tbl").await,
+        Err(IcebergError::InvalidTableLocation(..))
+    ));
+}
@@ -0,0 +1,234 @@
+//! Tests for the REST catalog client against a mocked server.
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use serde_json::json;
+use mockito::{Server, Matcher};
+
+use icelake::{IcebergTableMetadata, IcebergError, NotFoundKind};
+use icelake::schema::{Schema, Field, PrimitiveType};
+use icelake::catalog::{
+    Catalog, RestCatalog, RestCatalogBuilder, Namespace, TableIdentifier,
+    TableCommit, TableRequirement, TableUpdate
+};
+use icelake::transaction::UpdatePropertiesOperation;
+
+fn schema() -> Schema {
+    Schema::new(0, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        Field::new_primitive(2, "name", false, PrimitiveType::String),
+    ])
+}
+
+fn metadata() -> (IcebergTableMetadata, String) {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("tbl-{}", Uuid::new_v4().to_string()));
+    let location = format!("file://{}", path.to_str().unwrap());
+
+    let metadata = IcebergTableMetadata::try_new(
+        location.clone(),
+        schema(),
+        None,
+        None
+    ).unwrap();
+    let metadata_location = format!(
+        "{}/metadata/00000-{}.metadata.json",
+        location,
+        Uuid::new_v4().to_string()
+    );
+
+    (metadata, metadata_location)
+}
+
+async fn catalog(server: &mut Server) -> RestCatalog {
+    server.mock("GET", "/v1/config")
+        .match_query(Matcher::UrlEncoded("warehouse".into(), "wh".into()))
+        .match_header("authorization", "Bearer secret")
+        .with_status(200)
+        .with_body(json!({
+            "defaults": {"prefix": "default-prefix", "clients": "1"},
+            "overrides": {"prefix": "ws"}
+        }).to_string())
+        .create_async()
+        .await;
+
+    RestCatalogBuilder::new(&server.url())
+        .with_token("secret")
+        .with_warehouse("wh")
+        .with_properties(HashMap::from([("clients".to_string(), "4".to_string())]))
+        .build()
+        .await
+        .unwrap()
+}
+
+fn identifier() -> TableIdentifier {
+    TableIdentifier::new(Namespace::new(&["db", "schema"]), "users")
+}
+
+#[tokio::test]
+async fn load_table() {
+    let mut server = Server::new_async().await;
+    let catalog = catalog(&mut server).await;
+
+    assert_eq!(catalog.properties().get("prefix").unwrap(), "ws");
+    assert_eq!(catalog.properties().get("clients").unwrap(), "4");
+    assert_eq!(catalog.properties().get("warehouse").unwrap(), "wh");
+
+    let (metadata, metadata_location) = metadata();
+    server.mock("GET", "/v1/ws/namespaces/db%1Fschema/tables/users")
+        .match_header("authorization", "Bearer secret")
+        .with_status(200)
+        .with_body(json!({
+            "metadata-location": metadata_location,
+            "metadata": metadata,
+        }).to_string())
+        .create_async()
+        .await;
+
+    let table = catalog.load_table(&identifier()).await.unwrap();
+
+    assert_eq!(table.current_metadata().unwrap(), &metadata);
+    assert_eq!(table.current_metadata_uri().unwrap(), metadata_location);
+    assert_eq!(table.current_schema().unwrap(), &schema());
+}
+
+#[tokio::test]
+async fn load_missing_table() {
+    let mut server = Server::new_async().await;
+    let catalog = catalog(&mut server).await;
+
+    server.mock("GET", "/v1/ws/namespaces/db%1Fschema/tables/users")
+        .with_status(404)
+        .with_body(json!({
+            "error": {
+                "message": "Table does not exist: db.schema.users",
+                "type": "NoSuchTableException",
+                "code": 404
+            }
+        }).to_string())
+        .create_async()
+        .await;
+
+    let result = catalog.load_table(&identifier()).await;
+
+    assert!(matches!(
+        result,
+        Err(IcebergError::NotFound { kind: NotFoundKind::Table, identifier })
+            if identifier == "db.schema.users"
+    ));
+}
+
+#[tokio::test]
+async fn create_existing_table() {
+    let mut server = Server::new_async().await;
+    let catalog = catalog(&mut server).await;
+
+    server.mock("POST", "/v1/ws/namespaces/db%1Fschema/tables")
+        .with_status(409)
+        .with_body(json!({
+            "error": {
+                "message": "Table already exists: db.schema.users",
+                "type": "AlreadyExistsException",
+                "code": 409
+            }
+        }).to_string())
+        .create_async()
+        .await;
+
+    let result = catalog.create_table(&identifier(), schema(), None, None, None).await;
+
+    assert!(matches!(
+        result,
+        Err(IcebergError::TableAlreadyExists(identifier))
+            if identifier == "db.schema.users"
+    ));
+}
+
+#[tokio::test]
+async fn commit_transaction() {
+    let mut server = Server::new_async().await;
+    let catalog = catalog(&mut server).await;
+
+    let (mut metadata, metadata_location) = metadata();
+    metadata.properties = Some(HashMap::from([
+        ("owner".to_string(), "icelake".to_string())
+    ]));
+
+    let commit = server.mock("POST", "/v1/ws/namespaces/db%1Fschema/tables/users")
+        .match_header("authorization", "Bearer secret")
+        .match_body(Matcher::PartialJson(json!({
+            "identifier": {"namespace": ["db", "schema"], "name": "users"},
+            "requirements": [
+                {"type": "assert-table-uuid", "uuid": metadata.table_uuid}
+            ],
+            "updates": [
+                {"action": "set-properties", "updates": {"owner": "icelake"}}
+            ]
+        })))
+        .with_status(200)
+        .with_body(json!({
+            "metadata-location": metadata_location,
+            "metadata": metadata,
+        }).to_string())
+        .create_async()
+        .await;
+
+    let table = catalog.commit_transaction(
+        TableCommit::new(identifier())
+            .with_requirement(TableRequirement::AssertTableUuid {
+                uuid: metadata.table_uuid.clone()
+            })
+            .with_update(TableUpdate::SetProperties {
+                updates: HashMap::from([("owner".to_string(), "icelake".to_string())])
+            })
+    ).await.unwrap();
+
+    commit.assert_async().await;
+    assert_eq!(
+        table.current_metadata().unwrap().properties,
+        metadata.properties
+    );
+}
+
+#[tokio::test]
+async fn commit_conflict() {
+    let mut server = Server::new_async().await;
+    let catalog = catalog(&mut server).await;
+
+    let (metadata, metadata_location) = metadata();
+    server.mock("GET", "/v1/ws/namespaces/db%1Fschema/tables/users")
+        .with_status(200)
+        .with_body(json!({
+            "metadata-location": metadata_location,
+            "metadata": metadata,
+        }).to_string())
+        .create_async()
+        .await;
+
+    // Another writer committed since the table was loaded.
+    let commit = server.mock("POST", "/v1/ws/namespaces/db%1Fschema/tables/users")
+        .with_status(409)
+        .with_body(json!({
+            "error": {
+                "message": "Requirement failed: current snapshot changed",
+                "type": "CommitFailedException",
+                "code": 409
+            }
+        }).to_string())
+        .create_async()
+        .await;
+
+    let mut table = catalog.load_table(&identifier()).await.unwrap();
+    let mut transaction = table.new_transaction();
+    let mut op = UpdatePropertiesOperation::new();
+    op.set("owner", "icelake");
+    transaction.add_operation(Box::new(op));
+    let result = transaction.commit_to_catalog(&catalog, &identifier()).await;
+
+    commit.assert_async().await;
+    assert!(matches!(result, Err(IcebergError::CommitConflict(..))));
+
+    // The table is left unchanged, so the transaction can be retried.
+    assert_eq!(table.current_metadata().unwrap(), &metadata);
+}
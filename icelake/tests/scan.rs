@@ -0,0 +1,1552 @@
+//! Tests for planning table scans.
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use chrono::NaiveDate;
+use futures::StreamExt;
+use arrow_schema::{Schema as ArrowSchema, Field as ArrowField, DataType as ArrowDataType};
+use arrow_array::{
+    Array, RecordBatch, RecordBatchReader, Int32Array, Int64Array, Float64Array,
+    StringArray, TimestampMicrosecondArray
+};
+
+use icelake::{
+    IcebergTable, IcebergTableLoader, IcebergError, IcebergResult, NotFoundKind
+};
+use icelake::schema::{
+    Schema, SchemaType, Field, PrimitiveType, NameMapping, MappedField
+};
+use icelake::schema::update::SchemaUpdate;
+use icelake::schema::mapping::NAME_MAPPING_PROPERTY;
+use icelake::partition::{PartitionSpec, PartitionField, PartitionTransform, PartitionValues};
+use icelake::transaction::{
+    AppendFilesOperation, OverwriteFilesOperation, RowDeltaOperation,
+    UpdateSchemaOperation, UpdatePropertiesOperation, UpdatePartitionSpecOperation
+};
+use icelake::manifest::{
+    DataFile, DataFileContent, DataFileFormat, ManifestFileType, ManifestCache
+};
+use icelake::value::Value;
+use icelake::snapshot::SnapshotOperation;
+use icelake::expr::{col, Expression};
+use icelake::scan::ROW_ID_COLUMN;
+use icelake::writer::{
+    RecordBatchWriter, PositionDeleteWriter, EqualityDeleteWriter,
+    DEFAULT_FILE_FORMAT_PROPERTY
+};
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+
+fn schema() -> Schema {
+    Schema::new(0, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        Field::new_primitive(2, "ts", false, PrimitiveType::Timestamp),
+        Field::new_primitive(3, "name", false, PrimitiveType::String),
+    ])
+}
+
+async fn create_table() -> (std::path::PathBuf, IcebergTable) {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("tbl-{}", Uuid::new_v4().to_string()));
+
+    let table = IcebergTableLoader::from_url(
+        &format!("file://{}", path.to_str().unwrap())
+    ).with_schema(schema())
+        .create()
+        .await
+        .unwrap();
+
+    (path, table)
+}
+
+fn data_file(path: &std::path::Path, record_count: i64) -> DataFile {
+    DataFile::builder(
+        DataFileContent::Data,
+        path.join(format!("data/{}.parquet", Uuid::new_v4())).to_str().unwrap(),
+        DataFileFormat::Parquet,
+        record_count,
+        record_count * 10
+    ).build()
+}
+
+async fn append(table: &mut IcebergTable, data_file: DataFile) {
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_file(data_file);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+}
+
+fn file_paths(tasks: &[icelake::scan::FileScanTask]) -> Vec<String> {
+    let mut paths: Vec<String> = tasks.iter()
+        .map(|task| task.data_file().file_path.clone())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn file_paths_of(data_files: &[&DataFile]) -> Vec<String> {
+    let mut paths: Vec<String> = data_files.iter()
+        .map(|data_file| data_file.file_path.clone())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[tokio::test]
+async fn plan_files() {
+    let (path, mut table) = create_table().await;
+
+    // A table without snapshots has nothing to scan.
+    assert!(table.scan().plan_files().await.unwrap().is_empty());
+
+    let data_file1 = data_file(&path, 100);
+    let data_file2 = data_file(&path, 200);
+
+    append(&mut table, data_file1.clone()).await;
+    let first_snapshot_id = table.current_snapshot().unwrap().unwrap().snapshot_id;
+    append(&mut table, data_file2.clone()).await;
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(
+        file_paths(&tasks),
+        file_paths_of(&[&data_file1, &data_file2])
+    );
+    assert!(tasks.iter().all(|task| task.field_ids() == [1, 2, 3]));
+
+    // Projection.
+    let tasks = table.scan().select(&["name", "id"]).plan_files().await.unwrap();
+    assert!(tasks.iter().all(|task| task.field_ids() == [3, 1]));
+
+    // An older snapshot only has the first file.
+    let tasks = table.scan().use_snapshot(first_snapshot_id).plan_files().await.unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&data_file1]));
+
+    assert!(matches!(
+        table.scan().use_snapshot(-1).plan_files().await,
+        Err(IcebergError::NotFound { kind: NotFoundKind::Snapshot, identifier })
+            if identifier == "-1"
+    ));
+    assert!(matches!(
+        table.scan().select(&["missing"]).plan_files().await,
+        Err(IcebergError::ScanError{..})
+    ));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn plan_files_with_partition_filter() {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("tbl-{}", Uuid::new_v4().to_string()));
+
+    let partition_spec = PartitionSpec::try_new(
+        0,
+        vec![PartitionField::new(2, 1000, "ts_day", PartitionTransform::Day)],
+        schema()
+    ).unwrap();
+
+    let mut table = IcebergTableLoader::from_url(
+        &format!("file://{}", path.to_str().unwrap())
+    ).with_schema(schema())
+        .with_partition_spec(partition_spec)
+        .create()
+        .await
+        .unwrap();
+
+    let timestamp = |month: u32, day: u32| {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap().and_hms_opt(0, 0, 0).unwrap()
+    };
+
+    // Every append writes a manifest holding the partitions of a single day.
+    let partitioned_file = |month: u32, day: u32| {
+        let day = PartitionTransform::Day
+            .apply(Some(Value::Timestamp(timestamp(month, day))))
+            .unwrap();
+        let mut data_file = data_file(&path, 100);
+        data_file.partition = PartitionValues::from_iter([("ts_day".to_string(), day)]);
+        data_file
+    };
+
+    let march_file = partitioned_file(3, 15);
+    let april_file = partitioned_file(4, 15);
+    append(&mut table, march_file.clone()).await;
+    append(&mut table, april_file.clone()).await;
+
+    let tasks = table.scan()
+        .filter(col("ts").greater_than_or_equal_to(timestamp(4, 1)))
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&april_file]));
+
+    let tasks = table.scan()
+        .filter(col("ts").less_than(timestamp(4, 1)))
+        .filter(col("name").not_null())
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&march_file]));
+
+    let tasks = table.scan()
+        .filter(col("ts").greater_than(timestamp(5, 1)))
+        .plan_files()
+        .await
+        .unwrap();
+    assert!(tasks.is_empty());
+
+    // Files of different days in the same manifest are pruned by their partition
+    // values, and the predicates decided by the partition are left out of the
+    // residual.
+    let may_file = partitioned_file(5, 15);
+    let june_file = partitioned_file(6, 15);
+    let mut transaction = table.new_transaction();
+    let mut op = AppendFilesOperation::new();
+    op.append_files([may_file.clone(), june_file.clone()]);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let tasks = table.scan()
+        .filter(col("ts").greater_than_or_equal_to(timestamp(6, 1)))
+        .filter(col("name").equal_to("a"))
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&june_file]));
+    assert_eq!(
+        tasks[0].residual(),
+        &col("name").equal_to("a").bind(table.current_schema().unwrap()).unwrap()
+    );
+
+    // Predicates the partition can't decide are kept.
+    let filter = col("ts").greater_than(timestamp(5, 15));
+    let tasks = table.scan().filter(filter.clone()).plan_files().await.unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&may_file, &june_file]));
+    for task in &tasks {
+        let expected = match task.data_file().file_path == may_file.file_path {
+            true => filter.bind(table.current_schema().unwrap()).unwrap(),
+            false => Expression::AlwaysTrue,
+        };
+        assert_eq!(task.residual(), &expected);
+    }
+
+    assert!(matches!(
+        table.scan().filter(col("missing").is_null()).plan_files().await,
+        Err(IcebergError::ExpressionError{..})
+    ));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn plan_files_skips_older_delete_manifests() {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("tbl-{}", Uuid::new_v4().to_string()));
+
+    let partition_spec = PartitionSpec::try_new(
+        0,
+        vec![PartitionField::new(3, 1000, "name", PartitionTransform::Identity)],
+        schema()
+    ).unwrap();
+
+    let mut table = IcebergTableLoader::from_url(
+        &format!("file://{}", path.to_str().unwrap())
+    ).with_schema(schema())
+        .with_partition_spec(partition_spec)
+        .create()
+        .await
+        .unwrap();
+
+    let partition = |name: &str| {
+        PartitionValues::from_iter([("name".to_string(), Some(Value::from(name)))])
+    };
+    let partitioned_file = |content: DataFileContent, name: &str| {
+        DataFile {
+            content: content,
+            partition: partition(name),
+            ..data_file(&path, 100)
+        }
+    };
+
+    // Deletes of partitions "a" and "c" are committed before "b" gets any data.
+    let a_file = partitioned_file(DataFileContent::Data, "a");
+    append(&mut table, a_file.clone()).await;
+
+    let mut transaction = table.new_transaction();
+    let mut op = RowDeltaOperation::new();
+    op.add_deletes(partitioned_file(DataFileContent::PositionDelete, "a"));
+    op.add_deletes(partitioned_file(DataFileContent::PositionDelete, "c"));
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let b_file = partitioned_file(DataFileContent::Data, "b");
+    append(&mut table, b_file.clone()).await;
+
+    // Remove the delete manifest, so planning fails if it is read.
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    let manifest_list = table.read_manifest_list(snapshot).await.unwrap();
+    let delete_manifest = manifest_list.manifest_files().iter()
+        .find(|manifest_file| manifest_file.content == ManifestFileType::Delete)
+        .unwrap();
+    std::fs::remove_file(delete_manifest.manifest_path.trim_start_matches("file://"))
+        .unwrap();
+
+    // The delete manifest's partitions span "b", but its deletes predate the data.
+    let tasks = table.scan()
+        .filter(col("name").equal_to("b"))
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&b_file]));
+    assert!(tasks[0].deletes().is_empty());
+
+    // Partition "a" has data older than the deletes, which must be read.
+    assert!(table.scan().plan_files().await.is_err());
+    assert!(table.scan().filter(col("name").equal_to("a")).plan_files().await.is_err());
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn plan_files_with_metrics_filter() {
+    let (path, mut table) = create_table().await;
+
+    let id_bounds = |lower: i64, upper: i64| {
+        let data_file = data_file(&path, 100);
+        DataFile {
+            lower_bounds: Some(HashMap::from([(1, lower.to_le_bytes().to_vec())])),
+            upper_bounds: Some(HashMap::from([(1, upper.to_le_bytes().to_vec())])),
+            null_value_counts: Some(HashMap::from([(1, 0), (3, 100)])),
+            value_counts: Some(HashMap::from([(1, 100), (3, 100)])),
+            ..data_file
+        }
+    };
+
+    let low_ids = id_bounds(0, 99);
+    let high_ids = id_bounds(100, 199);
+    append(&mut table, low_ids.clone()).await;
+    append(&mut table, high_ids.clone()).await;
+
+    let tasks = table.scan()
+        .filter(col("id").greater_than_or_equal_to(150i64))
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&high_ids]));
+
+    let tasks = table.scan()
+        .filter(col("id").is_in([5i64, 105i64]))
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&low_ids, &high_ids]));
+
+    // Both files contain only nulls in the name column.
+    let tasks = table.scan()
+        .filter(col("name").not_null().or(col("id").is_null()))
+        .plan_files()
+        .await
+        .unwrap();
+    assert!(tasks.is_empty());
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+async fn write_batch(table: &mut IcebergTable, columns: Vec<arrow_array::ArrayRef>) {
+    let mut writer = RecordBatchWriter::for_table(table).unwrap();
+    let batch = RecordBatch::try_new(writer.arrow_schema(), columns).unwrap();
+    writer.write_partition(HashMap::new(), &batch).unwrap();
+    writer.commit(table).await.unwrap();
+}
+
+#[tokio::test]
+async fn read_after_schema_evolution() {
+    let (path, mut table) = create_table().await;
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![1, 2])),
+        Arc::new(TimestampMicrosecondArray::from(vec![0, 1_000_000])),
+        Arc::new(StringArray::from(vec!["a", "b"])),
+    ]).await;
+
+    // Add a column, which is missing from the first data file.
+    let mut fields = schema().fields().to_vec();
+    fields.push(Field::new_primitive(4, "score", false, PrimitiveType::Double));
+    let mut transaction = table.new_transaction();
+    let mut op = UpdateSchemaOperation::new();
+    op.set_schema(Schema::new(1, fields));
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![3])),
+        Arc::new(TimestampMicrosecondArray::from(vec![2_000_000])),
+        Arc::new(StringArray::from(vec!["c"])),
+        Arc::new(Float64Array::from(vec![0.5])),
+    ]).await;
+
+    let reader = table.scan()
+        .select(&["name", "score", "id"])
+        .to_arrow_reader()
+        .await
+        .unwrap();
+
+    let field_names: Vec<&str> = reader.schema().fields().iter()
+        .map(|field| field.name().as_str())
+        .collect();
+    assert_eq!(field_names, ["name", "score", "id"]);
+
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let names = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        let scores = batch.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        let ids = batch.column(2).as_any().downcast_ref::<Int64Array>().unwrap();
+
+        for i in 0..batch.num_rows() {
+            rows.push((
+                ids.value(i),
+                names.value(i).to_string(),
+                (!scores.is_null(i)).then(|| scores.value(i))
+            ));
+        }
+    }
+    rows.sort_by_key(|(id, _, _)| *id);
+
+    assert_eq!(rows, vec![
+        (1, "a".to_string(), None),
+        (2, "b".to_string(), None),
+        (3, "c".to_string(), Some(0.5)),
+    ]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_required_column_with_initial_default() {
+    let (path, mut table) = create_table().await;
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![1, 2])),
+        Arc::new(TimestampMicrosecondArray::from(vec![0, 1_000_000])),
+        Arc::new(StringArray::from(vec!["a", "b"])),
+    ]).await;
+
+    // Required columns can't be added without an initial default.
+    let current_schema = table.current_schema().unwrap().clone();
+    assert!(SchemaUpdate::for_schema(&current_schema)
+        .add_field(None, "count", true, SchemaType::Primitive(PrimitiveType::Int))
+        .is_err());
+
+    let new_schema = SchemaUpdate::for_schema(&current_schema)
+        .add_field_with_default(
+            None,
+            "count",
+            SchemaType::Primitive(PrimitiveType::Int),
+            Value::Int(7)
+        ).unwrap()
+        .apply(1);
+    let mut transaction = table.new_transaction();
+    let mut op = UpdateSchemaOperation::new();
+    op.set_schema(new_schema);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let field = table.current_schema().unwrap().fields()[3].clone();
+    assert!(field.required());
+    assert_eq!(field.initial_default(), Some(&Value::Int(7)));
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![3])),
+        Arc::new(TimestampMicrosecondArray::from(vec![2_000_000])),
+        Arc::new(StringArray::from(vec!["c"])),
+        Arc::new(Int32Array::from(vec![1])),
+    ]).await;
+
+    let reader = table.scan()
+        .select(&["id", "count"])
+        .to_arrow_reader()
+        .await
+        .unwrap();
+
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        let counts = batch.column(1).as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(counts.null_count(), 0);
+
+        for i in 0..batch.num_rows() {
+            rows.push((ids.value(i), counts.value(i)));
+        }
+    }
+    rows.sort();
+
+    // Rows of the file written before the column was added read as the default.
+    assert_eq!(rows, vec![(1, 7), (2, 7), (3, 1)]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_required_column_without_initial_default() {
+    let (path, mut table) = create_table().await;
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![1, 2])),
+        Arc::new(TimestampMicrosecondArray::from(vec![0, 1_000_000])),
+        Arc::new(StringArray::from(vec!["a", "b"])),
+    ]).await;
+
+    // Schema updates reject required columns without an initial default, but other
+    // writers may still add them.
+    let mut metadata = table.current_metadata().unwrap().clone();
+    let mut fields = metadata.current_schema().fields().to_vec();
+    fields.push(Field::new_primitive(4, "count", true, PrimitiveType::Int));
+    metadata.schemas.push(Schema::new(1, fields));
+    metadata.current_schema_id = 1;
+    metadata.last_column_id = 4;
+    table.commit(metadata).await.unwrap();
+
+    // The column can't be read as nulls from the file written before it was added.
+    let result = table.scan().select(&["id", "count"]).to_arrow_reader().await;
+    match result {
+        Err(IcebergError::ScanError { message }) => assert!(message.contains("count")),
+        _ => panic!("expected a scan error"),
+    }
+
+    // Reading other columns isn't affected.
+    assert!(table.scan().select(&["id"]).to_arrow_reader().await.is_ok());
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn file_projection_after_schema_evolution() {
+    let (path, mut table) = create_table().await;
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![1, 2])),
+        Arc::new(TimestampMicrosecondArray::from(vec![0, 1_000_000])),
+        Arc::new(StringArray::from(vec!["a", "b"])),
+    ]).await;
+
+    // Rename a column and add a column missing from the data file.
+    let mut transaction = table.new_transaction();
+    let mut op = UpdateSchemaOperation::new();
+    op.set_schema(Schema::new(1, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Long),
+        Field::new_primitive(2, "ts", false, PrimitiveType::Timestamp),
+        Field::new_primitive(3, "full_name", false, PrimitiveType::String),
+        Field::new_primitive(4, "score", false, PrimitiveType::Double),
+    ]));
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let scan = table.scan().select(&["score", "full_name", "id"]);
+    let tasks = scan.plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+
+    let projection = scan.file_projection(tasks[0].data_file()).await.unwrap();
+
+    // The physical schema has the file's names, in the file's column order.
+    let physical_schema = projection.physical_schema();
+    let fields: Vec<(&str, &ArrowDataType)> = physical_schema.fields().iter()
+        .map(|field| (field.name().as_str(), field.data_type()))
+        .collect();
+    assert_eq!(fields, [("id", &ArrowDataType::Int64), ("name", &ArrowDataType::Utf8)]);
+
+    assert_eq!(projection.columns(), [None, Some(1), Some(0)]);
+    assert_eq!(projection.missing_field_ids(), [4]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_with_name_mapping() {
+    let (path, mut table) = create_table().await;
+
+    // Write a Parquet file without field ids, with a differently named id column.
+    let arrow_schema = Arc::new(ArrowSchema::new(vec![
+        ArrowField::new("record_id", ArrowDataType::Int64, false),
+        ArrowField::new("name", ArrowDataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(arrow_schema.clone(), vec![
+        Arc::new(Int64Array::from(vec![1, 2])),
+        Arc::new(StringArray::from(vec!["a", "b"])),
+    ]).unwrap();
+
+    std::fs::create_dir_all(path.join("data")).unwrap();
+    let file_path = path.join("data/external.parquet");
+    let file = std::fs::File::create(&file_path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, arrow_schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let file_size = std::fs::metadata(&file_path).unwrap().len();
+    let data_file = DataFile::builder(
+        DataFileContent::Data,
+        &format!("file://{}", file_path.to_str().unwrap()),
+        DataFileFormat::Parquet,
+        2,
+        file_size as i64
+    ).build();
+    append(&mut table, data_file).await;
+
+    let name_mapping = NameMapping::new(vec![
+        MappedField::new(1, &["id", "record_id"]),
+        MappedField::new(2, &["ts"]),
+        MappedField::new(3, &["name"]),
+    ]);
+    let mut transaction = table.new_transaction();
+    let mut op = UpdatePropertiesOperation::new();
+    op.set(NAME_MAPPING_PROPERTY, &name_mapping.encode().unwrap());
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let reader = table.scan()
+        .select(&["id", "ts", "name"])
+        .to_arrow_reader()
+        .await
+        .unwrap();
+
+    let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+    assert_eq!(batches.len(), 1);
+
+    let batch = &batches[0];
+    let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+    let names = batch.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!((ids.value(0), ids.value(1)), (1, 2));
+    // The file has no timestamp column.
+    assert_eq!(batch.column(1).null_count(), 2);
+    assert_eq!(names.value(0), "a");
+    assert_eq!(names.value(1), "b");
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn plan_files_metadata_only() {
+    let (path, mut table) = create_table().await;
+
+    let data_file = DataFile {
+        lower_bounds: Some(HashMap::from([(1, 10i64.to_le_bytes().to_vec())])),
+        upper_bounds: Some(HashMap::from([(1, 20i64.to_le_bytes().to_vec())])),
+        ..data_file(&path, 300)
+    };
+    append(&mut table, data_file.clone()).await;
+
+    // The data file doesn't exist, which doesn't matter since it is never read.
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+
+    let task = &tasks[0];
+    assert_eq!(task.record_count(), 300);
+    assert_eq!(task.file_size_in_bytes(), 3000);
+    assert_eq!(task.data_file().file_path, data_file.file_path);
+    assert_eq!(task.data_file().lower_bounds, data_file.lower_bounds);
+    assert_eq!(task.data_file().upper_bounds, data_file.upper_bounds);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn scan_empty_manifest_list() {
+    let (path, mut table) = create_table().await;
+
+    // Overwriting an empty table creates a snapshot without any manifests.
+    let mut transaction = table.new_transaction();
+    let mut op = OverwriteFilesOperation::new();
+    op.delete_all();
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    assert!(table.read_manifest_list(snapshot).await.unwrap().is_empty());
+
+    assert!(table.scan().plan_files().await.unwrap().is_empty());
+
+    let reader = table.scan().to_arrow_reader().await.unwrap();
+    assert_eq!(reader.schema().fields().len(), 3);
+    let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+    assert_eq!(rows, 0);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_unknown_snapshot() {
+    let (path, mut table) = create_table().await;
+
+    let data_file = data_file(&path, 100);
+    append(&mut table, data_file).await;
+
+    let snapshot_id = table.current_snapshot().unwrap().unwrap().snapshot_id;
+    let result = table.scan().use_snapshot(snapshot_id + 1).to_arrow_reader().await;
+    assert!(matches!(
+        result,
+        Err(IcebergError::NotFound { kind: NotFoundKind::Snapshot, identifier })
+            if identifier == (snapshot_id + 1).to_string()
+    ));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_with_deletes() {
+    let (path, mut table) = create_table().await;
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from_iter_values(0..10)),
+        Arc::new(TimestampMicrosecondArray::from(vec![0; 10])),
+        Arc::new(StringArray::from_iter_values((0..10).map(|i| format!("row-{i}")))),
+    ]).await;
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    let data_file_path = tasks[0].data_file().file_path.clone();
+    assert!(tasks[0].deletes().is_empty());
+
+    // Delete the rows at positions 2 and 5, and the row with id 7.
+    let mut position_deletes = PositionDeleteWriter::new(PartitionValues::default());
+    position_deletes.delete(&data_file_path, 5);
+    position_deletes.delete(&data_file_path, 2);
+    let position_delete_file = position_deletes.close(&table).await.unwrap();
+
+    let mut equality_deletes = EqualityDeleteWriter::for_table(
+        &table, vec![1], PartitionValues::default()
+    ).unwrap();
+    let keys = RecordBatch::try_new(
+        equality_deletes.arrow_schema(),
+        vec![Arc::new(Int64Array::from(vec![7]))]
+    ).unwrap();
+    equality_deletes.write(&keys).unwrap();
+    let equality_delete_file = equality_deletes.close(&table).await.unwrap();
+
+    let mut transaction = table.new_transaction();
+    let mut op = RowDeltaOperation::new();
+    op.add_deletes(position_delete_file);
+    op.add_deletes(equality_delete_file);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].deletes().len(), 2);
+
+    // The equality column is read to apply the deletes, but isn't returned.
+    let reader = table.scan().select(&["name"]).to_arrow_reader().await.unwrap();
+    let mut names = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        assert_eq!(batch.num_columns(), 1);
+        let column = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        names.extend(column.iter().flatten().map(|name| name.to_string()));
+    }
+
+    assert_eq!(names.len(), 7);
+    assert_eq!(
+        names,
+        ["row-0", "row-1", "row-3", "row-4", "row-6", "row-8", "row-9"]
+    );
+
+    // Rows written after the equality delete aren't deleted by it.
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![7])),
+        Arc::new(TimestampMicrosecondArray::from(vec![0])),
+        Arc::new(StringArray::from(vec!["row-7"])),
+    ]).await;
+
+    let reader = table.scan().to_arrow_reader().await.unwrap();
+    let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+    assert_eq!(rows, 8);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn delete_rows_by_filter() {
+    let (path, mut table) = create_table().await;
+
+    for ids in [vec![1, 2, 3], vec![4, 5], vec![10, 11]] {
+        let len = ids.len();
+        write_batch(&mut table, vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(TimestampMicrosecondArray::from(vec![0; len])),
+            Arc::new(StringArray::from(vec!["a"; len])),
+        ]).await;
+    }
+
+    let file_paths_before = file_paths(&table.scan().plan_files().await.unwrap());
+
+    async fn read_ids(table: &IcebergTable) -> Vec<i64> {
+        let reader = table.scan().select(&["id"]).to_arrow_reader().await.unwrap();
+        let mut ids = Vec::new();
+        for batch in reader {
+            let batch = batch.unwrap();
+            let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+            ids.extend(column.iter().flatten());
+        }
+        ids.sort();
+        ids
+    }
+
+    // Deletes rows from the first two files, which are rewritten. The third file is
+    // skipped by its metrics.
+    let mut transaction = table.new_transaction();
+    transaction.delete(col("id").greater_than(2i64).and(col("id").less_than(5i64)));
+    transaction.commit().await.unwrap();
+
+    assert_eq!(read_ids(&table).await, [1, 2, 5, 10, 11]);
+
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    assert_eq!(snapshot.summary.operation(), Some(&SnapshotOperation::Overwrite));
+    assert_eq!(snapshot.summary.get("deleted-data-files"), Some("2"));
+    assert_eq!(snapshot.summary.get("added-data-files"), Some("2"));
+    assert_eq!(snapshot.summary.get("total-records"), Some("5"));
+
+    let file_paths_after = file_paths(&table.scan().plan_files().await.unwrap());
+    assert_eq!(file_paths_after.len(), 3);
+    let kept: Vec<&String> = file_paths_after.iter()
+        .filter(|path| file_paths_before.contains(*path))
+        .collect();
+    assert_eq!(kept.len(), 1);
+
+    // Deleting all rows of a file removes it without writing a new one.
+    let mut transaction = table.new_transaction();
+    transaction.delete(col("id").greater_than_or_equal_to(10i64));
+    transaction.commit().await.unwrap();
+
+    assert_eq!(read_ids(&table).await, [1, 2, 5]);
+
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    assert_eq!(snapshot.summary.operation(), Some(&SnapshotOperation::Delete));
+    assert_eq!(snapshot.summary.get("deleted-data-files"), Some("1"));
+    assert_eq!(snapshot.summary.get("total-data-files"), Some("2"));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn delete_rows_after_partition_spec_change() {
+    let (path, mut table) = create_table().await;
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![1, 2, 3])),
+        Arc::new(TimestampMicrosecondArray::from(vec![0; 3])),
+        Arc::new(StringArray::from(vec!["a"; 3])),
+    ]).await;
+
+    let spec = table.update_spec().unwrap()
+        .add_field("ts", PartitionTransform::Day, "ts_day").unwrap()
+        .build()
+        .unwrap();
+    let mut transaction = table.new_transaction();
+    let mut op = UpdatePartitionSpecOperation::new();
+    op.set_partition_spec(spec);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+    assert_eq!(table.current_partition_spec().unwrap().spec_id(), 1);
+
+    let mut transaction = table.new_transaction();
+    transaction.delete(col("id").equal_to(2i64));
+    transaction.commit().await.unwrap();
+
+    // The rewritten file is unpartitioned like the file it replaces, so it's tracked
+    // by a manifest of the old partition spec.
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    assert_eq!(snapshot.summary.get("added-data-files"), Some("1"));
+    let manifest_list = table.read_manifest_list(snapshot).await.unwrap();
+    let spec_ids: Vec<i32> = manifest_list.manifest_files().iter()
+        .map(|manifest_file| manifest_file.partition_spec_id)
+        .collect();
+    assert_eq!(spec_ids, [0]);
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert!(tasks[0].data_file().partition.values().is_empty());
+
+    let reader = table.scan().select(&["id"]).to_arrow_reader().await.unwrap();
+    let mut ids = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        ids.extend(column.iter().flatten());
+    }
+    assert_eq!(ids, [1, 3]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+/// Commits position deletes of the given rows and equality deletes of the given ids
+/// to the table, returning the paths of the delete files.
+async fn commit_deletes(
+    table: &mut IcebergTable,
+    data_file_path: &str,
+    positions: &[i64],
+    ids: Vec<i64>
+) -> (String, String) {
+    let mut position_deletes = PositionDeleteWriter::new(PartitionValues::default());
+    for pos in positions {
+        position_deletes.delete(data_file_path, *pos);
+    }
+    let position_delete_file = position_deletes.close(table).await.unwrap();
+
+    let mut equality_deletes = EqualityDeleteWriter::for_table(
+        table, vec![1], PartitionValues::default()
+    ).unwrap();
+    let keys = RecordBatch::try_new(
+        equality_deletes.arrow_schema(),
+        vec![Arc::new(Int64Array::from(ids))]
+    ).unwrap();
+    equality_deletes.write(&keys).unwrap();
+    let equality_delete_file = equality_deletes.close(table).await.unwrap();
+
+    let paths = (
+        position_delete_file.file_path.clone(),
+        equality_delete_file.file_path.clone()
+    );
+
+    let mut transaction = table.new_transaction();
+    let mut op = RowDeltaOperation::new();
+    op.add_deletes(position_delete_file);
+    op.add_deletes(equality_delete_file);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    paths
+}
+
+#[tokio::test]
+async fn read_with_deletes_from_multiple_commits() {
+    let (path, mut table) = create_table().await;
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from_iter_values(0..10)),
+        Arc::new(TimestampMicrosecondArray::from(vec![0; 10])),
+        Arc::new(StringArray::from_iter_values((0..10).map(|i| format!("row-{i}")))),
+    ]).await;
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    let data_file_path = tasks[0].data_file().file_path.clone();
+
+    let (first_positions, first_equality) =
+        commit_deletes(&mut table, &data_file_path, &[1], vec![4]).await;
+    let (second_positions, second_equality) =
+        commit_deletes(&mut table, &data_file_path, &[8, 1], vec![6]).await;
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    let delete_paths: Vec<&str> = tasks[0].deletes().iter()
+        .map(|delete_file| delete_file.file_path.as_str())
+        .collect();
+    assert_eq!(
+        delete_paths,
+        [first_positions, first_equality, second_positions, second_equality]
+    );
+
+    let reader = table.scan().select(&["id"]).to_arrow_reader().await.unwrap();
+    let mut ids = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        ids.extend(column.iter().flatten());
+    }
+
+    // Both commits' deletes take effect.
+    assert_eq!(ids, [0, 2, 3, 5, 7, 9]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_with_deletes_after_overwrite() {
+    let (path, mut table) = create_table().await;
+
+    for i in 0..2 {
+        write_batch(&mut table, vec![
+            Arc::new(Int64Array::from_iter_values(i * 10..i * 10 + 3)),
+            Arc::new(TimestampMicrosecondArray::from(vec![0; 3])),
+            Arc::new(StringArray::from(vec!["a"; 3])),
+        ]).await;
+    }
+
+    // The last appended file, with ids 10 to 12, is planned first.
+    let tasks = table.scan().plan_files().await.unwrap();
+    let deleted_path = tasks[0].data_file().file_path.clone();
+    let overwritten_path = tasks[1].data_file().file_path.clone();
+    commit_deletes(&mut table, &deleted_path, &[0], vec![12]).await;
+
+    // Overwriting the other file keeps the deletes.
+    let mut transaction = table.new_transaction();
+    let mut op = OverwriteFilesOperation::new();
+    op.delete_file(&overwritten_path);
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+
+    let reader = table.scan().select(&["id"]).to_arrow_reader().await.unwrap();
+    let mut ids = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        ids.extend(column.iter().flatten());
+    }
+    assert_eq!(ids, [11]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_with_partition_columns() {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("tbl-{}", Uuid::new_v4().to_string()));
+
+    let partition_spec = PartitionSpec::try_new(
+        0,
+        vec![
+            PartitionField::new(2, 1000, "ts_day", PartitionTransform::Day),
+            PartitionField::new(1, 1001, "id_bucket", PartitionTransform::Bucket(4)),
+        ],
+        schema()
+    ).unwrap();
+
+    let mut table = IcebergTableLoader::from_url(
+        &format!("file://{}", path.to_str().unwrap())
+    ).with_schema(schema())
+        .with_partition_spec(partition_spec)
+        .create()
+        .await
+        .unwrap();
+
+    let timestamp = |day: u32| {
+        NaiveDate::from_ymd_opt(2023, 3, day).unwrap().and_hms_opt(0, 0, 0).unwrap()
+    };
+
+    // Every file holds two rows with the same id and day.
+    let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+    for (id, day) in [(1i64, 15), (2i64, 16)] {
+        let micros = timestamp(day).timestamp_micros();
+        let batch = RecordBatch::try_new(writer.arrow_schema(), vec![
+            Arc::new(Int64Array::from(vec![id, id])),
+            Arc::new(TimestampMicrosecondArray::from(vec![micros, micros + 1])),
+            Arc::new(StringArray::from(vec!["a", "b"])),
+        ]).unwrap();
+        let source_values = HashMap::from([
+            (1, Some(Value::Long(id))),
+            (2, Some(Value::Timestamp(timestamp(day)))),
+        ]);
+        writer.write_partition(source_values, &batch).unwrap();
+    }
+    writer.commit(&mut table).await.unwrap();
+
+    let reader = table.scan()
+        .select(&["id"])
+        .with_partition_columns(true)
+        .to_arrow_reader()
+        .await
+        .unwrap();
+
+    let field_names: Vec<String> = reader.schema().fields().iter()
+        .map(|field| field.name().clone())
+        .collect();
+    assert_eq!(field_names, ["id", "ts_day", "id_bucket"]);
+
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let column = |index: usize| batch.column(index).clone();
+        let ids = column(0);
+        let ids = ids.as_any().downcast_ref::<Int64Array>().unwrap();
+        let days = column(1);
+        let days = days.as_any().downcast_ref::<Int32Array>().unwrap();
+        let buckets = column(2);
+        let buckets = buckets.as_any().downcast_ref::<Int32Array>().unwrap();
+        for row in 0..batch.num_rows() {
+            rows.push((ids.value(row), days.value(row), buckets.value(row)));
+        }
+    }
+    rows.sort();
+
+    // The partition values are the transformed values of each file's partition.
+    let partition_value = |transform: PartitionTransform, value: Value| {
+        match transform.apply(Some(value)).unwrap() {
+            Some(Value::Int(value)) => value,
+            value => panic!("unexpected partition value {value:?}"),
+        }
+    };
+    let expected_row = |id: i64, day: u32| (
+        id,
+        partition_value(PartitionTransform::Day, Value::Timestamp(timestamp(day))),
+        partition_value(PartitionTransform::Bucket(4), Value::Long(id)),
+    );
+    assert_eq!(rows, [
+        expected_row(1, 15), expected_row(1, 15), expected_row(2, 16), expected_row(2, 16)
+    ]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_with_bloom_filters() {
+    let (path, mut table) = create_table().await;
+
+    // Write a file of two row groups, with a bloom filter for each column.
+    let field = |name: &str, data_type: ArrowDataType, field_id: i32| {
+        ArrowField::new(name, data_type, false).with_metadata(HashMap::from([
+            ("PARQUET:field_id".to_string(), field_id.to_string())
+        ]))
+    };
+    let arrow_schema = Arc::new(ArrowSchema::new(vec![
+        field("id", ArrowDataType::Int64, 1),
+        field("name", ArrowDataType::Utf8, 3),
+    ]));
+    let batch = RecordBatch::try_new(arrow_schema.clone(), vec![
+        Arc::new(Int64Array::from_iter_values(0..10)),
+        Arc::new(StringArray::from_iter_values((0..10).map(|i| format!("row-{i}")))),
+    ]).unwrap();
+
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(5)
+        .set_bloom_filter_enabled(true)
+        .build();
+
+    std::fs::create_dir_all(path.join("data")).unwrap();
+    let file_path = path.join("data/bloom.parquet");
+    let file = std::fs::File::create(&file_path).unwrap();
+    let mut writer = ArrowWriter::try_new(file, arrow_schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let file_size = std::fs::metadata(&file_path).unwrap().len();
+    let data_file = DataFile::builder(
+        DataFileContent::Data,
+        &format!("file://{}", file_path.to_str().unwrap()),
+        DataFileFormat::Parquet,
+        10,
+        file_size as i64
+    ).build();
+    append(&mut table, data_file).await;
+
+    fn read_ids(reader: impl RecordBatchReader) -> Vec<i64> {
+        let mut ids = Vec::new();
+        for batch in reader {
+            let batch = batch.unwrap();
+            let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+            ids.extend(column.iter().flatten());
+        }
+        ids
+    }
+
+    // Without metrics the file can't be skipped, and without bloom filters the
+    // whole file is read.
+    let scan = table.scan().select(&["id"]).filter(col("id").equal_to(7i64));
+    let ids = read_ids(scan.to_arrow_reader().await.unwrap());
+    assert_eq!(ids, (0..10).collect::<Vec<i64>>());
+
+    // The bloom filter of the first row group shows it has no matching rows.
+    let scan = table.scan()
+        .select(&["id"])
+        .filter(col("id").equal_to(7i64))
+        .with_bloom_filters(true);
+    let ids = read_ids(scan.to_arrow_reader().await.unwrap());
+    assert_eq!(ids, (5..10).collect::<Vec<i64>>());
+
+    let scan = table.scan()
+        .select(&["id"])
+        .filter(col("name").is_in(["row-1", "row-8"]))
+        .with_bloom_filters(true);
+    let ids = read_ids(scan.to_arrow_reader().await.unwrap());
+    assert_eq!(ids, (0..10).collect::<Vec<i64>>());
+
+    // Other predicates can't be decided by bloom filters.
+    let scan = table.scan()
+        .select(&["id"])
+        .filter(col("id").greater_than(7i64))
+        .with_bloom_filters(true);
+    let ids = read_ids(scan.to_arrow_reader().await.unwrap());
+    assert_eq!(ids, (0..10).collect::<Vec<i64>>());
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_with_row_ids() {
+    let (path, mut table) = create_table().await;
+
+    std::fs::create_dir_all(path.join("data")).unwrap();
+    let write_file = |name: &str, ids: std::ops::Range<i64>| {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", ArrowDataType::Int64, false).with_metadata(
+                HashMap::from([("PARQUET:field_id".to_string(), "1".to_string())])
+            ),
+        ]));
+        let record_count = ids.end - ids.start;
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![Arc::new(Int64Array::from_iter_values(ids))]
+        ).unwrap();
+
+        let file_path = path.join(format!("data/{name}.parquet"));
+        let file = std::fs::File::create(&file_path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, arrow_schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        DataFile::builder(
+            DataFileContent::Data,
+            &format!("file://{}", file_path.to_str().unwrap()),
+            DataFileFormat::Parquet,
+            record_count,
+            std::fs::metadata(&file_path).unwrap().len() as i64
+        )
+    };
+
+    // Only the first file has row lineage.
+    let lineage_file = write_file("lineage", 0..5).with_first_row_id(100).build();
+    append(&mut table, lineage_file.clone()).await;
+    append(&mut table, write_file("no-lineage", 10..12).build()).await;
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    let first_row_ids: Vec<Option<i64>> = tasks.iter()
+        .map(|task| task.data_file().first_row_id)
+        .collect();
+    assert_eq!(first_row_ids.len(), 2);
+    assert!(first_row_ids.contains(&Some(100)));
+    assert!(first_row_ids.contains(&None));
+
+    // Deleted rows don't shift the row ids of the following rows.
+    commit_deletes(&mut table, &lineage_file.file_path, &[1], vec![3]).await;
+
+    let reader = table.scan()
+        .select(&["id"])
+        .with_row_ids(true)
+        .to_arrow_reader()
+        .await
+        .unwrap();
+    assert_eq!(reader.schema().field(1).name(), ROW_ID_COLUMN);
+
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        assert_eq!(batch.num_columns(), 2);
+        let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        let row_ids = batch.column(1).as_any().downcast_ref::<Int64Array>().unwrap();
+        rows.extend(ids.iter().flatten().zip(row_ids.iter()));
+    }
+    rows.sort();
+
+    assert_eq!(rows, [
+        (0, Some(100)), (2, Some(102)), (4, Some(104)), (10, None), (11, None)
+    ]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_avro_data_files() {
+    let (path, mut table) = create_table().await;
+
+    let set_file_format = |format: &str| {
+        let mut op = UpdatePropertiesOperation::new();
+        op.set(DEFAULT_FILE_FORMAT_PROPERTY, format);
+        op
+    };
+
+    let mut transaction = table.new_transaction();
+    transaction.add_operation(Box::new(set_file_format("avro")));
+    transaction.commit().await.unwrap();
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![1, 2, 3])),
+        Arc::new(TimestampMicrosecondArray::from(vec![Some(0), None, Some(2_000_000)])),
+        Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+    ]).await;
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    let data_file = tasks[0].data_file();
+    assert_eq!(data_file.file_format, DataFileFormat::Avro);
+    assert!(data_file.file_path.ends_with(".avro"));
+    assert_eq!(data_file.record_count, 3);
+    assert_eq!(data_file.null_value_counts.as_ref().unwrap()[&3], 1);
+
+    // Parquet and Avro files are read alike.
+    let mut transaction = table.new_transaction();
+    transaction.add_operation(Box::new(set_file_format("parquet")));
+    transaction.commit().await.unwrap();
+
+    write_batch(&mut table, vec![
+        Arc::new(Int64Array::from(vec![4])),
+        Arc::new(TimestampMicrosecondArray::from(vec![3_000_000])),
+        Arc::new(StringArray::from(vec!["d"])),
+    ]).await;
+
+    let reader = table.scan()
+        .select(&["name", "id", "ts"])
+        .to_arrow_reader()
+        .await
+        .unwrap();
+
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let names = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        let ids = batch.column(1).as_any().downcast_ref::<Int64Array>().unwrap();
+        let ts = batch.column(2).as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+
+        for i in 0..batch.num_rows() {
+            rows.push((
+                ids.value(i),
+                (!names.is_null(i)).then(|| names.value(i).to_string()),
+                (!ts.is_null(i)).then(|| ts.value(i))
+            ));
+        }
+    }
+    rows.sort();
+    assert_eq!(rows, [
+        (1, Some("a".to_string()), Some(0)),
+        (2, None, None),
+        (3, Some("c".to_string()), Some(2_000_000)),
+        (4, Some("d".to_string()), Some(3_000_000)),
+    ]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_unsupported_file_format() {
+    let (path, mut table) = create_table().await;
+
+    let data_file = DataFile::builder(
+        DataFileContent::Data,
+        path.join(format!("data/{}.orc", Uuid::new_v4())).to_str().unwrap(),
+        DataFileFormat::ORC,
+        10,
+        100
+    ).build();
+    append(&mut table, data_file).await;
+
+    // ORC files are planned, but can't be read.
+    assert_eq!(table.scan().plan_files().await.unwrap().len(), 1);
+    assert!(matches!(
+        table.scan().to_arrow_reader().await,
+        Err(IcebergError::Unsupported(_))
+    ));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn incremental_append_scan() {
+    let (path, mut table) = create_table().await;
+
+    async fn write_ids(table: &mut IcebergTable, ids: Vec<i64>) -> i64 {
+        let len = ids.len();
+        write_batch(table, vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(TimestampMicrosecondArray::from(vec![0; len])),
+            Arc::new(StringArray::from(vec!["a"; len])),
+        ]).await;
+        table.current_snapshot().unwrap().unwrap().snapshot_id
+    }
+
+    async fn read_ids(scan: icelake::scan::TableScan<'_>) -> Vec<i64> {
+        let reader = scan.select(&["id"]).to_arrow_reader().await.unwrap();
+        let mut ids = Vec::new();
+        for batch in reader {
+            let batch = batch.unwrap();
+            let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+            ids.extend(column.iter().flatten());
+        }
+        ids.sort();
+        ids
+    }
+
+    let first_snapshot_id = write_ids(&mut table, vec![1, 2]).await;
+    let second_snapshot_id = write_ids(&mut table, vec![3, 4]).await;
+
+    // Only the rows of the second append are read.
+    let scan = table.scan()
+        .from_snapshot_exclusive(first_snapshot_id)
+        .to_snapshot_inclusive(second_snapshot_id);
+    assert_eq!(read_ids(scan).await, [3, 4]);
+
+    let scan = table.scan().from_snapshot_exclusive(second_snapshot_id);
+    assert!(read_ids(scan).await.is_empty());
+
+    // The end snapshot must descend from the start snapshot.
+    assert!(matches!(
+        table.scan()
+            .from_snapshot_exclusive(second_snapshot_id)
+            .to_snapshot_inclusive(first_snapshot_id)
+            .plan_files()
+            .await,
+        Err(IcebergError::ScanError{..})
+    ));
+
+    // Deleting rewrites the first file in an overwrite snapshot.
+    let mut transaction = table.new_transaction();
+    transaction.delete(col("id").equal_to(1i64));
+    transaction.commit().await.unwrap();
+    write_ids(&mut table, vec![5]).await;
+
+    assert!(matches!(
+        table.scan().from_snapshot_exclusive(first_snapshot_id).plan_files().await,
+        Err(IcebergError::ScanError{..})
+    ));
+
+    // The rewritten file isn't scanned when allowing non-append snapshots.
+    let scan = table.scan()
+        .from_snapshot_exclusive(first_snapshot_id)
+        .allow_non_append(true);
+    assert_eq!(read_ids(scan).await, [3, 4, 5]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_as_stream() {
+    let (path, mut table) = create_table().await;
+
+    for i in 0..8 {
+        write_batch(&mut table, vec![
+            Arc::new(Int64Array::from_iter_values(i * 10..i * 10 + 10)),
+            Arc::new(TimestampMicrosecondArray::from(vec![0; 10])),
+            Arc::new(StringArray::from(vec!["a"; 10])),
+        ]).await;
+    }
+
+    async fn read_ids(
+        table: &IcebergTable,
+        concurrency: usize
+    ) -> IcebergResult<Vec<i64>> {
+        let stream = table.scan()
+            .select(&["id"])
+            .to_arrow_stream(concurrency)
+            .await?;
+        let mut stream = Box::pin(stream);
+
+        let mut ids = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+            ids.extend(column.iter().flatten());
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    let expected: Vec<i64> = (0..80).collect();
+    for concurrency in [0, 1, 3, 8, 16] {
+        assert_eq!(read_ids(&table, concurrency).await.unwrap(), expected);
+    }
+
+    // A missing data file fails the stream.
+    let tasks = table.scan().plan_files().await.unwrap();
+    std::fs::remove_file(tasks[3].data_file().file_path.trim_start_matches("file://"))
+        .unwrap();
+    for concurrency in [1, 4] {
+        assert!(read_ids(&table, concurrency).await.is_err());
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn read_with_limit() {
+    let (path, mut table) = create_table().await;
+
+    for i in 0..2 {
+        write_batch(&mut table, vec![
+            Arc::new(Int64Array::from_iter_values(i * 10..i * 10 + 3)),
+            Arc::new(TimestampMicrosecondArray::from(vec![0; 3])),
+            Arc::new(StringArray::from(vec!["a"; 3])),
+        ]).await;
+    }
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    assert_eq!(tasks.len(), 2);
+    // The last appended file, with ids 10 to 12, is planned first.
+    let first_path = tasks[0].data_file().file_path.clone();
+    commit_deletes(&mut table, &first_path, &[0], vec![]).await;
+
+    // Only the first file is needed for the limit, so the second one isn't opened.
+    let tasks = table.scan().plan_files().await.unwrap();
+    std::fs::remove_file(tasks[1].data_file().file_path.trim_start_matches("file://"))
+        .unwrap();
+
+    let reader = table.scan().select(&["id"]).with_limit(1).to_arrow_reader()
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        ids.extend(column.iter().flatten());
+    }
+
+    // The deleted first row doesn't count towards the limit.
+    assert_eq!(ids, [11]);
+
+    let stream = table.scan().select(&["id"]).with_limit(2).to_arrow_stream(1)
+        .await
+        .unwrap();
+    let mut stream = Box::pin(stream);
+    let mut ids = Vec::new();
+    while let Some(batch) = stream.next().await {
+        let batch = batch.unwrap();
+        let column = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        ids.extend(column.iter().flatten());
+    }
+    assert_eq!(ids, [11, 12]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn plan_files_with_manifest_cache() {
+    let (path, mut table) = create_table().await;
+
+    let data_file1 = data_file(&path, 100);
+    let data_file2 = data_file(&path, 200);
+    append(&mut table, data_file1.clone()).await;
+    append(&mut table, data_file2.clone()).await;
+
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    let manifest_list = table.read_manifest_list(snapshot).await.unwrap();
+    let manifest_count = manifest_list.manifest_files().len() as u64;
+
+    let cache = Arc::new(ManifestCache::new(10));
+    let tasks = table.scan()
+        .with_manifest_cache(cache.clone())
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&data_file1, &data_file2]));
+    assert_eq!((cache.hits(), cache.misses()), (0, manifest_count));
+    assert_eq!(cache.len() as u64, manifest_count);
+
+    // Remove the manifests, so planning fails if they are read again.
+    for manifest_file in manifest_list.manifest_files() {
+        std::fs::remove_file(manifest_file.manifest_path.trim_start_matches("file://"))
+            .unwrap();
+    }
+
+    let tasks = table.scan()
+        .with_manifest_cache(cache.clone())
+        .plan_files()
+        .await
+        .unwrap();
+    assert_eq!(file_paths(&tasks), file_paths_of(&[&data_file1, &data_file2]));
+    assert_eq!((cache.hits(), cache.misses()), (manifest_count, manifest_count));
+
+    assert!(table.scan().plan_files().await.is_err());
+
+    let _ = std::fs::remove_dir_all(&path);
+}
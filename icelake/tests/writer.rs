@@ -0,0 +1,408 @@
+//! Tests for writing data files with `RecordBatchWriter` and delete files.
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use arrow_array::{Array, RecordBatch, Int32Array, Int64Array, StringArray};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::basic::{Compression, ZstdLevel, GzipLevel};
+
+use icelake::{IcebergTable, IcebergTableLoader};
+use icelake::schema::{Schema, Field, PrimitiveType};
+use icelake::manifest::{DataFile, DataFileContent, ManifestReader};
+use icelake::partition::PartitionValues;
+use icelake::IcebergError;
+use icelake::transaction::UpdatePropertiesOperation;
+use icelake::writer::{
+    RecordBatchWriter, RollingDataWriter, PositionDeleteWriter, EqualityDeleteWriter,
+    CompressionCodec, DELETE_FILE_PATH_FIELD_ID, DELETE_POS_FIELD_ID,
+    PARQUET_COMPRESSION_CODEC_PROPERTY, PARQUET_COMPRESSION_LEVEL_PROPERTY,
+    METRICS_DEFAULT_PROPERTY, PARQUET_BLOOM_FILTER_ENABLED_PROPERTY_PREFIX,
+    PARQUET_BLOOM_FILTER_FPP_PROPERTY_PREFIX
+};
+
+fn schema() -> Schema {
+    Schema::new(0, vec![
+        Field::new_primitive(1, "id", true, PrimitiveType::Int),
+        Field::new_primitive(2, "name", true, PrimitiveType::String),
+        Field::new_primitive(3, "comment", false, PrimitiveType::String),
+    ])
+}
+
+async fn create_table() -> (std::path::PathBuf, IcebergTable) {
+    let mut path = std::env::temp_dir();
+    path.push("icelake");
+    path.push(format!("tbl-{}", Uuid::new_v4().to_string()));
+
+    let table = IcebergTableLoader::from_url(
+        &format!("file://{}", path.to_str().unwrap())
+    ).with_schema(schema())
+        .create()
+        .await
+        .unwrap();
+
+    (path, table)
+}
+
+/// Reads all data files of the table's current snapshot.
+async fn read_data_files(table: &IcebergTable) -> Vec<DataFile> {
+    let snapshot = table.current_snapshot().unwrap().unwrap();
+    let manifest_list = table.read_manifest_list(&snapshot).await.unwrap();
+
+    let mut data_files = Vec::new();
+    for manifest_file in manifest_list.manifest_files() {
+        let storage = table.storage();
+        let path = storage.create_path_from_url(&manifest_file.manifest_path).unwrap();
+        let bytes = storage.get(&path).await.unwrap();
+        let manifest = ManifestReader::for_manifest_file(manifest_file)
+            .read(&bytes).unwrap();
+
+        data_files.extend(
+            manifest.into_entries().map(|entry| entry.data_file)
+        );
+    }
+
+    data_files
+}
+
+#[tokio::test]
+async fn write_bounds() {
+    let (path, mut table) = create_table().await;
+
+    let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+    let batch = RecordBatch::try_new(
+        writer.arrow_schema(),
+        vec![
+            Arc::new(Int32Array::from(vec![3, -7, 42])),
+            Arc::new(StringArray::from(vec![
+                "banana",
+                "apple-apple-apple-apple",
+                "cherry-cherry-cherry-cherry"
+            ])),
+            Arc::new(StringArray::from(vec![None::<&str>, None, None])),
+        ]
+    ).unwrap();
+
+    writer.write_partition(HashMap::new(), &batch).unwrap();
+    writer.commit(&mut table).await.unwrap();
+
+    let data_files = read_data_files(&table).await;
+    assert_eq!(data_files.len(), 1);
+
+    let lower_bounds = data_files[0].lower_bounds.as_ref().unwrap();
+    let upper_bounds = data_files[0].upper_bounds.as_ref().unwrap();
+
+    assert_eq!(lower_bounds.get(&1), Some(&(-7i32).to_le_bytes().to_vec()));
+    assert_eq!(upper_bounds.get(&1), Some(&42i32.to_le_bytes().to_vec()));
+
+    // String bounds are truncated to 16 characters, with the upper bound
+    // incremented to remain a valid upper bound.
+    assert_eq!(lower_bounds.get(&2), Some(&b"apple-apple-appl".to_vec()));
+    assert_eq!(upper_bounds.get(&2), Some(&b"cherry-cherry-ci".to_vec()));
+
+    // Null-only columns have no bounds.
+    assert!(!lower_bounds.contains_key(&3));
+    assert!(!upper_bounds.contains_key(&3));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn rolling_writer() {
+    let (path, table) = create_table().await;
+
+    // A tiny target size causes every batch to be written to a separate file.
+    let mut writer = RollingDataWriter::for_table(&table, PartitionValues::default())
+        .unwrap()
+        .with_target_file_size(1);
+
+    for i in 0..5 {
+        let batch = RecordBatch::try_new(
+            writer.arrow_schema(),
+            vec![
+                Arc::new(Int32Array::from(vec![i; 100])),
+                Arc::new(StringArray::from(vec!["a"; 100])),
+                Arc::new(StringArray::from(vec![None::<&str>; 100])),
+            ]
+        ).unwrap();
+
+        writer.write(&batch).unwrap();
+    }
+
+    let data_files = writer.close(&table).await.unwrap();
+
+    assert_eq!(data_files.len(), 5);
+    assert!(data_files.iter().all(|data_file| data_file.record_count == 100));
+    assert!(data_files.iter().all(|data_file| data_file.file_size_in_bytes > 0));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+async fn set_properties(table: &mut IcebergTable, properties: &[(&str, &str)]) {
+    let mut transaction = table.new_transaction();
+    let mut op = UpdatePropertiesOperation::new();
+    for (key, value) in properties {
+        op.set(key, value);
+    }
+    transaction.add_operation(Box::new(op));
+    transaction.commit().await.unwrap();
+}
+
+#[tokio::test]
+async fn write_with_compression() {
+    let (path, mut table) = create_table().await;
+
+    let codecs = [
+        ("uncompressed", None, Compression::UNCOMPRESSED),
+        ("snappy", None, Compression::SNAPPY),
+        ("zstd", None, Compression::ZSTD(ZstdLevel::try_new(1).unwrap())),
+        ("gzip", Some("9"), Compression::GZIP(GzipLevel::try_new(9).unwrap())),
+        ("ZSTD", Some("3"), Compression::ZSTD(ZstdLevel::try_new(3).unwrap())),
+    ];
+
+    for (codec, level, expected) in codecs {
+        let mut properties = vec![(PARQUET_COMPRESSION_CODEC_PROPERTY, codec)];
+        properties.extend(level.map(|level| (PARQUET_COMPRESSION_LEVEL_PROPERTY, level)));
+        set_properties(&mut table, &properties).await;
+
+        let mut writer = RollingDataWriter::for_table(&table, PartitionValues::default())
+            .unwrap();
+        let batch = RecordBatch::try_new(
+            writer.arrow_schema(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+                Arc::new(StringArray::from(vec![None::<&str>; 3])),
+            ]
+        ).unwrap();
+        writer.write(&batch).unwrap();
+        let data_files = writer.close(&table).await.unwrap();
+
+        let storage = table.storage();
+        let file_path = storage.create_path_from_url(&data_files[0].file_path).unwrap();
+        let bytes = storage.get(&file_path).await.unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(bytes).unwrap();
+
+        let row_group = builder.metadata().row_group(0);
+        assert!(
+            row_group.columns().iter().all(|column| column.compression() == expected),
+            "codec {codec}"
+        );
+
+        let rows: usize = builder.build().unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        assert_eq!(rows, 3);
+    }
+
+    // The default codec is ZSTD.
+    assert_eq!("zstd".parse::<CompressionCodec>().unwrap(), CompressionCodec::default());
+
+    set_properties(&mut table, &[(PARQUET_COMPRESSION_CODEC_PROPERTY, "lzo-fast")]).await;
+    assert!(matches!(
+        RecordBatchWriter::for_table(&table),
+        Err(IcebergError::InvalidProperty { key, value })
+            if key == PARQUET_COMPRESSION_CODEC_PROPERTY && value == "lzo-fast"
+    ));
+
+    // Out of range levels are invalid too.
+    set_properties(&mut table, &[
+        (PARQUET_COMPRESSION_CODEC_PROPERTY, "zstd"),
+        (PARQUET_COMPRESSION_LEVEL_PROPERTY, "100"),
+    ]).await;
+    assert!(matches!(
+        RecordBatchWriter::for_table(&table),
+        Err(IcebergError::InvalidProperty { key, .. })
+            if key == PARQUET_COMPRESSION_LEVEL_PROPERTY
+    ));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn write_with_bloom_filters() {
+    let (path, mut table) = create_table().await;
+
+    let enabled_key = format!("{PARQUET_BLOOM_FILTER_ENABLED_PROPERTY_PREFIX}id");
+    let fpp_key = format!("{PARQUET_BLOOM_FILTER_FPP_PROPERTY_PREFIX}id");
+    set_properties(&mut table, &[
+        (enabled_key.as_str(), "true"),
+        (fpp_key.as_str(), "0.05"),
+    ]).await;
+
+    let mut writer = RecordBatchWriter::for_table(&table).unwrap();
+    let batch = RecordBatch::try_new(
+        writer.arrow_schema(),
+        vec![
+            Arc::new(Int32Array::from((0..100).collect::<Vec<i32>>())),
+            Arc::new(StringArray::from(vec!["a"; 100])),
+            Arc::new(StringArray::from(vec![None::<&str>; 100])),
+        ]
+    ).unwrap();
+    writer.write_partition(HashMap::new(), &batch).unwrap();
+    writer.commit(&mut table).await.unwrap();
+
+    let tasks = table.scan().plan_files().await.unwrap();
+    let storage = table.storage();
+    let file_path = storage.create_path_from_url(&tasks[0].data_file().file_path)
+        .unwrap();
+    let bytes = storage.get(&file_path).await.unwrap();
+    let builder = ParquetRecordBatchReaderBuilder::try_new(bytes).unwrap();
+
+    // Only the id column has a bloom filter.
+    let row_group = builder.metadata().row_group(0);
+    assert!(row_group.column(0).bloom_filter_offset().is_some());
+    assert!(row_group.column(1).bloom_filter_offset().is_none());
+    assert!(row_group.column(2).bloom_filter_offset().is_none());
+
+    // Probabilities must be between 0 and 1.
+    set_properties(&mut table, &[(fpp_key.as_str(), "1.5")]).await;
+    assert!(matches!(
+        RecordBatchWriter::for_table(&table),
+        Err(IcebergError::InvalidProperty { key, value })
+            if key == fpp_key && value == "1.5"
+    ));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn write_with_metrics_modes() {
+    let (path, mut table) = create_table().await;
+
+    set_properties(&mut table, &[
+        (METRICS_DEFAULT_PROPERTY, "none"),
+        ("write.metadata.metrics.column.id", "counts"),
+        ("write.metadata.metrics.column.name", "truncate(8)"),
+    ]).await;
+
+    let mut writer = RollingDataWriter::for_table(&table, PartitionValues::default())
+        .unwrap();
+    let batch = RecordBatch::try_new(
+        writer.arrow_schema(),
+        vec![
+            Arc::new(Int32Array::from(vec![3, -7, 42])),
+            Arc::new(StringArray::from(vec!["abcdefghij", "zyxwvutsrqp", "m"])),
+            Arc::new(StringArray::from(vec![Some("x"), None, Some("y")])),
+        ]
+    ).unwrap();
+    writer.write(&batch).unwrap();
+    let data_file = writer.close(&table).await.unwrap().remove(0);
+
+    let value_counts = data_file.value_counts.unwrap();
+    let null_value_counts = data_file.null_value_counts.unwrap();
+    let lower_bounds = data_file.lower_bounds.unwrap();
+    let upper_bounds = data_file.upper_bounds.unwrap();
+
+    // counts: no bounds.
+    assert_eq!(value_counts.get(&1), Some(&3));
+    assert_eq!(null_value_counts.get(&1), Some(&0));
+    assert!(!lower_bounds.contains_key(&1));
+    assert!(!upper_bounds.contains_key(&1));
+
+    // truncate(8): the upper bound's last character is incremented.
+    assert_eq!(value_counts.get(&2), Some(&3));
+    assert_eq!(lower_bounds.get(&2), Some(&b"abcdefgh".to_vec()));
+    assert_eq!(upper_bounds.get(&2), Some(&b"zyxwvutt".to_vec()));
+
+    // none: no metrics at all.
+    assert!(!value_counts.contains_key(&3));
+    assert!(!null_value_counts.contains_key(&3));
+    assert!(!lower_bounds.contains_key(&3));
+    assert!(!upper_bounds.contains_key(&3));
+
+    set_properties(&mut table, &[(METRICS_DEFAULT_PROPERTY, "truncate(0)")]).await;
+    assert!(matches!(
+        RecordBatchWriter::for_table(&table),
+        Err(IcebergError::InvalidProperty { key, .. }) if key == METRICS_DEFAULT_PROPERTY
+    ));
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn write_position_deletes() {
+    let (path, table) = create_table().await;
+
+    let mut writer = PositionDeleteWriter::new(PartitionValues::default());
+    writer.delete("file:///tmp/data/b.parquet", 7);
+    writer.delete("file:///tmp/data/a.parquet", 12);
+    writer.delete("file:///tmp/data/b.parquet", 3);
+    writer.delete("file:///tmp/data/a.parquet", 0);
+
+    let delete_file = writer.close(&table).await.unwrap();
+
+    assert_eq!(delete_file.content, DataFileContent::PositionDelete);
+    assert_eq!(delete_file.record_count, 4);
+    assert!(delete_file.lower_bounds.as_ref().unwrap()
+        .contains_key(&DELETE_FILE_PATH_FIELD_ID));
+
+    // Read the deletes back, sorted by file path and position.
+    let storage = table.storage();
+    let file_path = storage.create_path_from_url(&delete_file.file_path).unwrap();
+    let bytes = storage.get(&file_path).await.unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes).unwrap();
+
+    let field_ids: Vec<i32> = reader.parquet_schema().root_schema().get_fields().iter()
+        .map(|field| field.get_basic_info().id())
+        .collect();
+    assert_eq!(field_ids, [DELETE_FILE_PATH_FIELD_ID, DELETE_POS_FIELD_ID]);
+
+    let batches: Vec<RecordBatch> = reader.build().unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(batches.len(), 1);
+
+    let file_paths = batches[0].column(0).as_any()
+        .downcast_ref::<StringArray>().unwrap();
+    let positions = batches[0].column(1).as_any()
+        .downcast_ref::<Int64Array>().unwrap();
+    assert_eq!(
+        file_paths.iter().flatten().collect::<Vec<_>>(),
+        [
+            "file:///tmp/data/a.parquet",
+            "file:///tmp/data/a.parquet",
+            "file:///tmp/data/b.parquet",
+            "file:///tmp/data/b.parquet",
+        ]
+    );
+    assert_eq!(positions.values(), &[0, 12, 3, 7]);
+
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn write_equality_deletes() {
+    let (path, table) = create_table().await;
+
+    let mut writer = EqualityDeleteWriter::for_table(
+        &table, vec![1], PartitionValues::default()
+    ).unwrap();
+    assert_eq!(writer.arrow_schema().fields().len(), 1);
+
+    let batch = RecordBatch::try_new(
+        writer.arrow_schema(),
+        vec![Arc::new(Int32Array::from(vec![4, 8, 15]))]
+    ).unwrap();
+    writer.write(&batch).unwrap();
+
+    let delete_file = writer.close(&table).await.unwrap();
+
+    assert_eq!(delete_file.content, DataFileContent::EqualityDelete);
+    assert_eq!(delete_file.equality_ids, Some(vec![1]));
+    assert_eq!(delete_file.record_count, 3);
+    assert_eq!(delete_file.lower_bounds.as_ref().unwrap()[&1], 4i32.to_le_bytes());
+    assert_eq!(delete_file.upper_bounds.as_ref().unwrap()[&1], 15i32.to_le_bytes());
+
+    // Equality fields must exist and be required.
+    for equality_ids in [vec![], vec![42], vec![1, 3]] {
+        assert!(matches!(
+            EqualityDeleteWriter::for_table(
+                &table, equality_ids, PartitionValues::default()
+            ),
+            Err(IcebergError::SchemaError{..})
+        ));
+    }
+
+    let _ = std::fs::remove_dir_all(&path);
+}